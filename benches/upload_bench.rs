@@ -25,6 +25,36 @@ fn benchmark_upload_parse(c: &mut Criterion) {
             assert_eq!(output.stored_files.len(), 1);
         });
     });
+
+    c.bench_function("parse_and_store_64kb_file_in_16_byte_frames", |b| {
+        b.to_async(&runtime).iter(|| async {
+            let multer = Multer::new(MemoryStorage::new());
+            let frames = body
+                .chunks(16)
+                .map(|frame| Ok::<Bytes, MulterError>(Bytes::copy_from_slice(frame)))
+                .collect::<Vec<_>>();
+            let output = multer
+                .parse_and_store("BOUND", stream::iter(frames))
+                .await
+                .expect("pipeline should succeed");
+            assert_eq!(output.stored_files.len(), 1);
+        });
+    });
+
+    let many_parts = build_many_parts_body(2_000);
+    c.bench_function("parse_2000_small_text_fields", |b| {
+        b.to_async(&runtime).iter(|| async {
+            let multer = Multer::new(MemoryStorage::new());
+            let output = multer
+                .parse_and_store(
+                    "BOUND",
+                    stream::iter([Ok::<Bytes, MulterError>(Bytes::from(many_parts.clone()))]),
+                )
+                .await
+                .expect("pipeline should succeed");
+            assert_eq!(output.text_fields.len(), 2_000);
+        });
+    });
 }
 
 fn build_body(size: usize) -> Vec<u8> {
@@ -37,5 +67,16 @@ fn build_body(size: usize) -> Vec<u8> {
     out
 }
 
+fn build_many_parts_body(parts: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(parts * 64);
+    for index in 0..parts {
+        out.extend_from_slice(b"--BOUND\r\nContent-Disposition: form-data; name=\"f");
+        out.extend_from_slice(index.to_string().as_bytes());
+        out.extend_from_slice(b"\"\r\n\r\nvalue\r\n");
+    }
+    out.extend_from_slice(b"--BOUND--\r\n");
+    out
+}
+
 criterion_group!(benches, benchmark_upload_parse);
 criterion_main!(benches);
@@ -17,16 +17,14 @@ async fn upload(
 
     let mut stored = Vec::new();
 
-    while let Some(part) = match multipart.next_part().await {
+    while let Some(part) = match multipart.next_file().await {
         Ok(value) => value,
         Err(err) => return HttpResponse::BadRequest().body(err.to_string()),
     } {
-        if part.file_name().is_some() {
-            match data.store(part).await {
-                Ok(file) => stored.push(file),
-                Err(err) => return HttpResponse::BadRequest().body(err.to_string()),
-            };
-        }
+        match data.store(part).await {
+            Ok(file) => stored.push(file),
+            Err(err) => return HttpResponse::BadRequest().body(err.to_string()),
+        };
     }
 
     let mut body = format!("stored {} file(s)\n", stored.len());
@@ -22,7 +22,7 @@ async fn upload(
         Ok(value) => value,
         Err(err) => return HttpResponse::BadRequest().body(err.to_string()),
     } {
-        if part.file_name().is_some() {
+        if part.is_file() {
             match data.store(part).await {
                 Ok(file) => stored.push(file),
                 Err(err) => return HttpResponse::BadRequest().body(err.to_string()),
@@ -17,10 +17,8 @@ async fn upload(
 ) -> Result<String, (StatusCode, String)> {
     let mut stored = Vec::new();
 
-    while let Some(part) = multipart.next_part().await.map_err(err)? {
-        if part.file_name().is_some() {
-            stored.push(multer.store(part).await.map_err(err)?);
-        }
+    while let Some(part) = multipart.next_file().await.map_err(err)? {
+        stored.push(multer.store(part).await.map_err(err)?);
     }
 
     let mut body = format!("stored {} file(s)\n", stored.len());
@@ -21,7 +21,7 @@ async fn upload(
     let mut text_fields = Vec::new();
 
     while let Some(mut part) = multipart.next_part().await.map_err(err)? {
-        if part.file_name().is_some() {
+        if part.is_file() {
             stored.push(multer.store(part).await.map_err(err)?);
         } else {
             let field_name = part.field_name().to_owned();
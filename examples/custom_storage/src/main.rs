@@ -5,7 +5,9 @@ use std::{collections::HashMap, io, sync::Arc};
 use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer, Responder};
 use bytes::Bytes;
 use futures::StreamExt;
-use multigear::{BoxStream, Multer, MulterError, StorageEngine, StorageError};
+use multigear::{
+    BoxStream, FileMeta, Multer, MulterError, StorageEngine, StorageError, StoreContext,
+};
 use tokio::sync::RwLock;
 
 #[derive(Debug, Clone, Default)]
@@ -29,15 +31,14 @@ impl StorageEngine for HashMapStorage {
 
     async fn store(
         &self,
-        field_name: &str,
-        _file_name: Option<&str>,
-        _content_type: &str,
+        meta: FileMeta,
+        _ctx: &StoreContext,
         mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
     ) -> Result<Self::Output, Self::Error> {
-        let key = format!("{field_name}-{}", self.files.read().await.len());
+        let key = format!("{}-{}", meta.field_name, self.files.read().await.len());
         let mut content = Vec::new();
         while let Some(chunk) = stream.next().await {
-            let chunk = chunk.map_err(|err| StorageError::new(err.to_string()))?;
+            let chunk = chunk.map_err(StorageError::Stream)?;
             content.extend_from_slice(&chunk);
         }
 
@@ -64,7 +65,7 @@ async fn upload(
         Ok(value) => value,
         Err(err) => return HttpResponse::BadRequest().body(err.to_string()),
     } {
-        if part.file_name().is_some() {
+        if part.is_file() {
             match data.store(part).await {
                 Ok(key) => stored_keys.push(key.0),
                 Err(err) => return HttpResponse::BadRequest().body(err.to_string()),
@@ -5,11 +5,8 @@ use std::{convert::Infallible, io, net::SocketAddr, sync::Arc};
 use bytes::Bytes;
 use http_body_util::{BodyExt, Full};
 use hyper::{
-    body::Incoming,
-    header::CONTENT_TYPE,
-    server::conn::http1,
-    service::service_fn,
-    Method, Request, Response, StatusCode,
+    body::Incoming, header::CONTENT_TYPE, server::conn::http1, service::service_fn, Method,
+    Request, Response, StatusCode,
 };
 use hyper_util::rt::TokioIo;
 use multigear::{DiskStorage, FilenameStrategy, Multer};
@@ -62,7 +59,7 @@ async fn upload(
     };
 
     let mut stored = Vec::new();
-    while let Some(part) = match multipart.next_part().await {
+    while let Some(part) = match multipart.next_file().await {
         Ok(value) => value,
         Err(err) => {
             return text_response(
@@ -71,15 +68,10 @@ async fn upload(
             )
         }
     } {
-        if part.file_name().is_some() {
-            match multer.store(part).await {
-                Ok(file) => stored.push(file),
-                Err(err) => {
-                    return text_response(
-                        StatusCode::BAD_REQUEST,
-                        format!("store failed: {err}\n"),
-                    );
-                }
+        match multer.store(part).await {
+            Ok(file) => stored.push(file),
+            Err(err) => {
+                return text_response(StatusCode::BAD_REQUEST, format!("store failed: {err}\n"));
             }
         }
     }
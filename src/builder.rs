@@ -1,8 +1,18 @@
+use std::time::Duration;
+
 use crate::{
-    config::{MulterConfig, Selector, UnknownFieldPolicy},
+    config::{
+        DuplicateFilePolicy, DuplicateHeaderPolicy, EncodedWordPolicy, EpiloguePolicy,
+        FieldConstraint, LineEndingPolicy, MimeSniffPolicy, MulterConfig, ParserCompliance,
+        Selector, TransferEncodingPolicy, UnknownFieldPolicy,
+    },
     error::ConfigError,
-    limits::Limits,
+    filter::{FileFilter, SharedFileFilter},
+    limits::{ByteSize, FilenameCharset, Limits},
+    scan::{AvScanner, Scanner},
     storage::NoopStorage,
+    transform::{PartTransformer, TransformerChain},
+    validation::{PartValidator, ValidatorChain},
     Multer,
 };
 
@@ -11,6 +21,10 @@ use crate::{
 pub struct MulterBuilder<S = NoopStorage> {
     config: MulterConfig,
     storage: S,
+    validators: ValidatorChain,
+    scanner: Option<Scanner>,
+    file_filter: Option<SharedFileFilter>,
+    transformers: TransformerChain,
 }
 
 impl Default for MulterBuilder<NoopStorage> {
@@ -18,6 +32,10 @@ impl Default for MulterBuilder<NoopStorage> {
         Self {
             config: MulterConfig::default(),
             storage: NoopStorage,
+            validators: ValidatorChain::default(),
+            scanner: None,
+            file_filter: None,
+            transformers: TransformerChain::default(),
         }
     }
 }
@@ -40,6 +58,10 @@ impl<S> MulterBuilder<S> {
         MulterBuilder {
             config: self.config,
             storage,
+            validators: self.validators,
+            scanner: self.scanner,
+            file_filter: self.file_filter,
+            transformers: self.transformers,
         }
     }
 
@@ -99,6 +121,84 @@ impl<S> MulterBuilder<S> {
         self.unknown_field_policy(policy)
     }
 
+    /// Sets how bytes after the terminal boundary are handled.
+    pub fn epilogue_policy(mut self, policy: EpiloguePolicy) -> Self {
+        self.config.epilogue_policy = policy;
+        self
+    }
+
+    /// Sets which line terminators are accepted in multipart framing.
+    pub fn line_ending_policy(mut self, policy: LineEndingPolicy) -> Self {
+        self.config.line_ending_policy = policy;
+        self
+    }
+
+    /// Sets whether RFC 2047 encoded-word file names are decoded.
+    pub fn encoded_word_policy(mut self, policy: EncodedWordPolicy) -> Self {
+        self.config.encoded_word_policy = policy;
+        self
+    }
+
+    /// Sets how parts with an unsupported `Content-Transfer-Encoding` are handled.
+    pub fn transfer_encoding_policy(mut self, policy: TransferEncodingPolicy) -> Self {
+        self.config.transfer_encoding_policy = policy;
+        self
+    }
+
+    /// Sets how strictly part headers are checked against RFC 7578.
+    pub fn parser_compliance(mut self, compliance: ParserCompliance) -> Self {
+        self.config.parser_compliance = compliance;
+        self
+    }
+
+    /// Sets how repeated interpreted part headers are handled.
+    pub fn duplicate_header_policy(mut self, policy: DuplicateHeaderPolicy) -> Self {
+        self.config.duplicate_header_policy = policy;
+        self
+    }
+
+    /// Sets whether input missing the terminal boundary line ends cleanly.
+    pub fn allow_missing_terminal_boundary(mut self, allow: bool) -> Self {
+        self.config.allow_missing_terminal_boundary = allow;
+        self
+    }
+
+    /// Sets whether a leading UTF-8 byte order mark is stripped from text field values.
+    pub fn strip_text_bom(mut self, strip: bool) -> Self {
+        self.config.strip_text_bom = strip;
+        self
+    }
+
+    /// Sets how sniffed file content types are reconciled with declared ones.
+    pub fn mime_sniff_policy(mut self, policy: MimeSniffPolicy) -> Self {
+        self.config.mime_sniff_policy = policy;
+        self
+    }
+
+    /// Sets how files repeating an earlier file's content are handled.
+    pub fn duplicate_file_policy(mut self, policy: DuplicateFilePolicy) -> Self {
+        self.config.duplicate_file_policy = policy;
+        self
+    }
+
+    /// Sets whether rejected parts are reported instead of failing the request.
+    ///
+    /// When enabled, [`Multer::parse_and_store`] skips parts that break a
+    /// size, MIME, selector or validation rule, lists them in
+    /// [`ProcessedMultipart::report`](crate::ProcessedMultipart::report)
+    /// and keeps storing the rest. Request-wide failures such as body size,
+    /// total file size or malformed framing still abort.
+    pub fn collect_violations(mut self, collect: bool) -> Self {
+        self.config.collect_violations = collect;
+        self
+    }
+
+    /// Adds a cross-field rule checked once the request ends.
+    pub fn constraint(mut self, constraint: FieldConstraint) -> Self {
+        self.config.field_constraints.push(constraint);
+        self
+    }
+
     /// Sets global multipart limits.
     pub fn limits(mut self, limits: Limits) -> Self {
         self.config.limits = limits;
@@ -111,6 +211,34 @@ impl<S> MulterBuilder<S> {
         self
     }
 
+    /// Sets the maximum accepted file size from a size string such as `"64MB"` or `"1GiB"`.
+    ///
+    /// See [`ByteSize`] for the accepted syntax.
+    ///
+    /// ```rust
+    /// use multigear::MulterBuilder;
+    ///
+    /// let builder = MulterBuilder::new().max_file_size_str("64MB")?;
+    /// assert_eq!(builder.config().limits.max_file_size, Some(64_000_000));
+    /// # Ok::<(), multigear::ConfigError>(())
+    /// ```
+    pub fn max_file_size_str(self, max_file_size: &str) -> Result<Self, ConfigError> {
+        Ok(self.max_file_size(max_file_size.parse::<ByteSize>()?.as_u64()))
+    }
+
+    /// Sets the minimum accepted file size in bytes.
+    pub fn min_file_size(mut self, min_file_size: u64) -> Self {
+        self.config.limits.min_file_size = Some(min_file_size);
+        self
+    }
+
+    /// Sets the minimum accepted file size from a size string such as `"64MB"` or `"1GiB"`.
+    ///
+    /// See [`ByteSize`] for the accepted syntax.
+    pub fn min_file_size_str(self, min_file_size: &str) -> Result<Self, ConfigError> {
+        Ok(self.min_file_size(min_file_size.parse::<ByteSize>()?.as_u64()))
+    }
+
     /// Sets the maximum accepted number of files.
     pub fn max_files(mut self, max_files: usize) -> Self {
         self.config.limits.max_files = Some(max_files);
@@ -123,18 +251,99 @@ impl<S> MulterBuilder<S> {
         self
     }
 
+    /// Sets the maximum accepted text field size from a size string such as `"64MB"` or `"1GiB"`.
+    ///
+    /// See [`ByteSize`] for the accepted syntax.
+    pub fn max_field_size_str(self, max_field_size: &str) -> Result<Self, ConfigError> {
+        Ok(self.max_field_size(max_field_size.parse::<ByteSize>()?.as_u64()))
+    }
+
     /// Sets the maximum accepted number of text fields.
     pub fn max_fields(mut self, max_fields: usize) -> Self {
         self.config.limits.max_fields = Some(max_fields);
         self
     }
 
+    /// Sets the maximum accepted number of parts of any kind.
+    pub fn max_parts(mut self, max_parts: usize) -> Self {
+        self.config.limits.max_parts = Some(max_parts);
+        self
+    }
+
     /// Sets the maximum accepted multipart request size in bytes.
     pub fn max_body_size(mut self, max_body_size: u64) -> Self {
         self.config.limits.max_body_size = Some(max_body_size);
         self
     }
 
+    /// Sets the maximum accepted multipart request size from a size string such as `"64MB"` or `"1GiB"`.
+    ///
+    /// See [`ByteSize`] for the accepted syntax.
+    pub fn max_body_size_str(self, max_body_size: &str) -> Result<Self, ConfigError> {
+        Ok(self.max_body_size(max_body_size.parse::<ByteSize>()?.as_u64()))
+    }
+
+    /// Sets the maximum combined size in bytes of all accepted file parts.
+    pub fn max_total_file_size(mut self, max_total_file_size: u64) -> Self {
+        self.config.limits.max_total_file_size = Some(max_total_file_size);
+        self
+    }
+
+    /// Sets the maximum combined size of all accepted file parts from a size string such as `"64MB"` or `"1GiB"`.
+    ///
+    /// See [`ByteSize`] for the accepted syntax.
+    pub fn max_total_file_size_str(self, max_total_file_size: &str) -> Result<Self, ConfigError> {
+        Ok(self.max_total_file_size(max_total_file_size.parse::<ByteSize>()?.as_u64()))
+    }
+
+    /// Sets the maximum accepted header block size in bytes for a single part.
+    pub fn max_header_size(mut self, max_header_size: u64) -> Self {
+        self.config.limits.max_header_size = Some(max_header_size);
+        self
+    }
+
+    /// Sets the maximum accepted number of headers for a single part.
+    pub fn max_headers_per_part(mut self, max_headers_per_part: usize) -> Self {
+        self.config.limits.max_headers_per_part = Some(max_headers_per_part);
+        self
+    }
+
+    /// Sets the maximum decompressed size in bytes of a single compressed part.
+    pub fn max_decompressed_size(mut self, max_decompressed_size: u64) -> Self {
+        self.config.limits.max_decompressed_size = Some(max_decompressed_size);
+        self
+    }
+
+    /// Sets the maximum time allowed to parse a whole request.
+    pub fn max_parse_duration(mut self, max_parse_duration: Duration) -> Self {
+        self.config.limits.max_parse_duration = Some(max_parse_duration);
+        self
+    }
+
+    /// Sets the maximum time to wait for the next body chunk.
+    pub fn idle_chunk_timeout(mut self, idle_chunk_timeout: Duration) -> Self {
+        self.config.limits.idle_chunk_timeout = Some(idle_chunk_timeout);
+        self
+    }
+
+    /// Sets the maximum average rate in bytes per second at which the body is read.
+    pub fn max_bytes_per_second(mut self, max_bytes_per_second: u64) -> Self {
+        self.config.limits.max_bytes_per_second = Some(max_bytes_per_second);
+        self
+    }
+
+    /// Sets the minimum average rate in bytes per second the client must send at.
+    pub fn min_throughput_bytes_per_sec(mut self, min_throughput_bytes_per_sec: u64) -> Self {
+        self.config.limits.min_throughput_bytes_per_sec = Some(min_throughput_bytes_per_sec);
+        self
+    }
+
+    /// Sets the waiting time allowed before the minimum throughput applies.
+    pub fn min_throughput_grace(mut self, min_throughput_grace: Duration) -> Self {
+        self.config.limits.min_throughput_grace = Some(min_throughput_grace);
+        self
+    }
+
     /// Sets the global list of allowed MIME patterns.
     pub fn allowed_mime_types<I, M>(mut self, allowed_mime_types: I) -> Self
     where
@@ -146,6 +355,105 @@ impl<S> MulterBuilder<S> {
         self
     }
 
+    /// Sets the global list of denied MIME patterns, checked before any allowlist.
+    pub fn denied_mime_types<I, M>(mut self, denied_mime_types: I) -> Self
+    where
+        I: IntoIterator<Item = M>,
+        M: Into<String>,
+    {
+        self.config.limits.denied_mime_types =
+            denied_mime_types.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the global list of allowed file name extensions.
+    pub fn allowed_extensions<I, E>(mut self, allowed_extensions: I) -> Self
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<String>,
+    {
+        self.config.limits.allowed_extensions =
+            allowed_extensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets whether file names with a double extension such as `invoice.pdf.exe` are rejected.
+    pub fn reject_double_extensions(mut self, reject: bool) -> Self {
+        self.config.limits.reject_double_extensions = reject;
+        self
+    }
+
+    /// Sets the compound extensions, such as `tar.gz`, accepted despite
+    /// [`MulterBuilder::reject_double_extensions`].
+    pub fn allowed_double_extensions<I, E>(mut self, allowed_double_extensions: I) -> Self
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<String>,
+    {
+        self.config.limits.allowed_double_extensions = allowed_double_extensions
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        self
+    }
+
+    /// Sets the maximum filename length in characters.
+    pub fn max_filename_length(mut self, max_filename_length: usize) -> Self {
+        self.config.limits.max_filename_length = Some(max_filename_length);
+        self
+    }
+
+    /// Sets the characters accepted in filenames.
+    pub fn filename_charset(mut self, filename_charset: FilenameCharset) -> Self {
+        self.config.limits.filename_charset = filename_charset;
+        self
+    }
+
+    /// Sets whether file parts with an empty filename and body are rejected.
+    pub fn reject_empty_files(mut self, reject: bool) -> Self {
+        self.config.limits.reject_empty_files = reject;
+        self
+    }
+
+    /// Sets whether file parts with an empty filename and body are skipped.
+    pub fn skip_empty_files(mut self, skip: bool) -> Self {
+        self.config.limits.skip_empty_files = skip;
+        self
+    }
+
+    /// Appends a validator run on every file part before it is stored.
+    ///
+    /// Validators run in registration order; see [`PartValidator`].
+    pub fn validator(mut self, validator: impl PartValidator) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    /// Sets the antivirus scanner run on every file part as it is stored.
+    ///
+    /// Replaces any previously configured scanner; see [`AvScanner`].
+    pub fn scanner(mut self, scanner: impl AvScanner) -> Self {
+        self.scanner = Some(Scanner::new(scanner));
+        self
+    }
+
+    /// Sets the async filter deciding whether each file part is accepted,
+    /// skipped, or rejected before the selector sees it.
+    ///
+    /// Replaces any previously configured filter; see [`FileFilter`].
+    pub fn file_filter(mut self, filter: impl FileFilter) -> Self {
+        self.file_filter = Some(SharedFileFilter::new(filter));
+        self
+    }
+
+    /// Appends a transformer applied to every file body on its way to storage.
+    ///
+    /// Transformers wrap the body in registration order; see [`PartTransformer`].
+    pub fn transformer(mut self, transformer: impl PartTransformer) -> Self {
+        self.transformers.push(transformer);
+        self
+    }
+
     /// Validates builder configuration.
     pub fn validate(&self) -> Result<(), ConfigError> {
         self.config.validate()
@@ -159,6 +467,10 @@ impl<S> MulterBuilder<S> {
 
     /// Builds a fully configured `Multer` instance.
     pub fn build(self) -> Result<Multer<S>, ConfigError> {
-        Multer::with_config(self.storage, self.config)
+        Ok(Multer::with_config(self.storage, self.config)?
+            .with_validators(self.validators)
+            .with_scanner(self.scanner)
+            .with_file_filter(self.file_filter)
+            .with_transformers(self.transformers))
     }
 }
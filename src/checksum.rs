@@ -0,0 +1,221 @@
+//! Verification of client-supplied `Content-MD5` and `X-Checksum-SHA256` digests.
+
+use std::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use base64::Engine as _;
+use bytes::Bytes;
+use futures::Stream;
+use http::HeaderMap;
+use md5::{Digest, Md5};
+use sha2::Sha256;
+
+use crate::{error::ParseError, MulterError};
+
+/// Header carrying the base64 MD5 digest of a body (RFC 1864).
+pub const CONTENT_MD5: &str = "content-md5";
+/// Header carrying the SHA-256 digest of a body, base64 or hex encoded.
+pub const X_CHECKSUM_SHA256: &str = "x-checksum-sha256";
+
+/// Digests a body is expected to match.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExpectedChecksums {
+    md5: Option<[u8; 16]>,
+    sha256: Option<[u8; 32]>,
+}
+
+impl ExpectedChecksums {
+    /// Reads expected digests from `Content-MD5` and `X-Checksum-SHA256` headers.
+    ///
+    /// Digests may be base64 or hex encoded. Missing headers are not an
+    /// error; malformed ones are.
+    pub fn from_headers(headers: &HeaderMap) -> Result<Self, ParseError> {
+        Ok(Self {
+            md5: decode_header(headers, CONTENT_MD5)?,
+            sha256: decode_header(headers, X_CHECKSUM_SHA256)?,
+        })
+    }
+
+    /// Returns `true` when no digest is expected.
+    pub fn is_empty(&self) -> bool {
+        self.md5.is_none() && self.sha256.is_none()
+    }
+
+    /// Wraps a body stream so it fails with [`MulterError::ChecksumMismatch`]
+    /// after its last chunk if the body does not match.
+    ///
+    /// `field` names the part the body belongs to, or is `None` for a whole
+    /// request body.
+    pub fn verify<S>(self, field: Option<String>, stream: S) -> ChecksumStream<S> {
+        ChecksumStream {
+            inner: stream,
+            md5: self.md5.map(|expected| (Md5::new(), expected)),
+            sha256: self.sha256.map(|expected| (Sha256::new(), expected)),
+            field,
+            finished: false,
+        }
+    }
+}
+
+/// Wraps a request body so it fails with [`MulterError::ChecksumMismatch`]
+/// unless it matches the request's `Content-MD5` / `X-Checksum-SHA256`
+/// headers.
+///
+/// Pass the returned stream to [`Multer::parse_and_store`](crate::Multer::parse_and_store)
+/// or any other parse entry point. Requests without checksum headers pass
+/// through unchanged.
+///
+/// ```rust
+/// use bytes::Bytes;
+/// use futures::stream;
+/// use http::HeaderMap;
+/// use multigear::{checksum, MemoryStorage, Multer, MulterError};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let body = "--X\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nhi\r\n--X--\r\n";
+/// let mut headers = HeaderMap::new();
+/// headers.insert("content-md5", "AAAAAAAAAAAAAAAAAAAAAA==".parse().unwrap());
+///
+/// let body = stream::iter([Ok::<_, MulterError>(Bytes::from(body))]);
+/// let body = checksum::verify_body(&headers, body).expect("well-formed header");
+/// let err = Multer::new(MemoryStorage::new())
+///     .parse_and_store("X", body)
+///     .await
+///     .expect_err("digest does not match");
+/// assert!(matches!(err, MulterError::ChecksumMismatch { field: None, .. }));
+/// # }
+/// ```
+pub fn verify_body<S>(headers: &HeaderMap, stream: S) -> Result<ChecksumStream<S>, ParseError> {
+    Ok(ExpectedChecksums::from_headers(headers)?.verify(None, stream))
+}
+
+/// Body stream adapter that hashes chunks as they pass through; see
+/// [`ExpectedChecksums::verify`].
+pub struct ChecksumStream<S> {
+    inner: S,
+    md5: Option<(Md5, [u8; 16])>,
+    sha256: Option<(Sha256, [u8; 32])>,
+    field: Option<String>,
+    finished: bool,
+}
+
+impl<S> fmt::Debug for ChecksumStream<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChecksumStream")
+            .field("field", &self.field)
+            .field("md5", &self.md5.is_some())
+            .field("sha256", &self.sha256.is_some())
+            .field("finished", &self.finished)
+            .finish()
+    }
+}
+
+impl<S> ChecksumStream<S> {
+    fn finish(&mut self) -> Option<Mismatch> {
+        if let Some((hasher, expected)) = self.md5.take() {
+            let actual: [u8; 16] = hasher.finalize().into();
+            if actual != expected {
+                return Some(Mismatch::new("MD5", &expected, &actual));
+            }
+        }
+        if let Some((hasher, expected)) = self.sha256.take() {
+            let actual: [u8; 32] = hasher.finalize().into();
+            if actual != expected {
+                return Some(Mismatch::new("SHA-256", &expected, &actual));
+            }
+        }
+        None
+    }
+}
+
+impl<S> Stream for ChecksumStream<S>
+where
+    S: Stream<Item = Result<Bytes, MulterError>> + Unpin,
+{
+    type Item = Result<Bytes, MulterError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
+        match Pin::new(&mut self.inner).poll_next(cx) {
+            Poll::Ready(Some(Ok(chunk))) => {
+                if let Some((hasher, _)) = self.md5.as_mut() {
+                    hasher.update(&chunk);
+                }
+                if let Some((hasher, _)) = self.sha256.as_mut() {
+                    hasher.update(&chunk);
+                }
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            Poll::Ready(Some(Err(err))) => Poll::Ready(Some(Err(err))),
+            Poll::Ready(None) => {
+                self.finished = true;
+                let Some(mismatch) = self.finish() else {
+                    return Poll::Ready(None);
+                };
+                let field = self.field.take();
+                Poll::Ready(Some(Err(mismatch.into_error(field))))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Mismatch {
+    algorithm: &'static str,
+    expected: String,
+    actual: String,
+}
+
+impl Mismatch {
+    fn new(algorithm: &'static str, expected: &[u8], actual: &[u8]) -> Self {
+        Self {
+            algorithm,
+            expected: hex(expected),
+            actual: hex(actual),
+        }
+    }
+
+    fn into_error(self, field: Option<String>) -> MulterError {
+        MulterError::ChecksumMismatch {
+            field,
+            algorithm: self.algorithm,
+            expected: self.expected,
+            actual: self.actual,
+        }
+    }
+}
+
+fn decode_header<const N: usize>(
+    headers: &HeaderMap,
+    name: &str,
+) -> Result<Option<[u8; N]>, ParseError> {
+    let Some(value) = headers.get(name) else {
+        return Ok(None);
+    };
+    let invalid = || ParseError::new(format!("invalid {name} header"));
+    let value = value.to_str().map_err(|_| invalid())?.trim();
+
+    let digest = if value.len() == N * 2 && value.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        (0..N)
+            .map(|index| u8::from_str_radix(&value[index * 2..index * 2 + 2], 16))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?
+    } else {
+        base64::engine::general_purpose::STANDARD
+            .decode(value)
+            .map_err(|_| invalid())?
+    };
+    digest.try_into().map(Some).map_err(|_| invalid())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
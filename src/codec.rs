@@ -0,0 +1,148 @@
+//! `tokio_util::codec` integration.
+
+use std::task::{Context, Poll};
+
+use bytes::{Bytes, BytesMut};
+use futures::task::noop_waker_ref;
+use tokio_util::codec::Decoder;
+
+use crate::{
+    parser::{
+        headers::ParsedPartHeaders,
+        source::ChunkQueue,
+        stream::{MultipartStream, StreamLimits},
+    },
+    MulterError, ParseError,
+};
+
+/// Event emitted by [`MultipartDecoder`] as multipart input is decoded.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum PartEvent {
+    /// A new part started with the given headers.
+    Headers(Box<ParsedPartHeaders>),
+    /// A chunk of the current part body.
+    Data(Bytes),
+    /// The current part body ended.
+    PartEnd,
+}
+
+/// Multipart decoder for `FramedRead` pipelines over raw byte IO.
+///
+/// Each call to [`Decoder::decode`] hands buffered input to the streaming
+/// parser and yields at most one [`PartEvent`]. Input after the closing
+/// boundary is discarded.
+///
+/// ```rust
+/// use futures::StreamExt;
+/// use multigear::codec::{MultipartDecoder, PartEvent};
+/// use tokio_util::codec::FramedRead;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), multigear::MulterError> {
+/// let body: &[u8] = b"--X\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nhi\r\n--X--\r\n";
+/// let mut events = FramedRead::new(body, MultipartDecoder::new("X")?);
+///
+/// let Some(PartEvent::Headers(headers)) = events.next().await.transpose()? else {
+///     panic!("expected part headers");
+/// };
+/// assert_eq!(headers.field_name, "a");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MultipartDecoder {
+    parser: MultipartStream<ChunkQueue>,
+    in_part: bool,
+    finished: bool,
+}
+
+impl MultipartDecoder {
+    /// Creates a decoder for a known multipart boundary.
+    pub fn new(boundary: impl Into<String>) -> Result<Self, ParseError> {
+        Self::with_limits(boundary, StreamLimits::default())
+    }
+
+    /// Creates a decoder with explicit stream limits.
+    pub fn with_limits(
+        boundary: impl Into<String>,
+        limits: StreamLimits,
+    ) -> Result<Self, ParseError> {
+        Ok(Self {
+            parser: MultipartStream::with_limits(boundary, ChunkQueue::default(), limits)?,
+            in_part: false,
+            finished: false,
+        })
+    }
+
+    /// Returns `true` once the closing boundary and any epilogue have been decoded.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn feed(&mut self, src: &mut BytesMut) {
+        let chunk = src.split().freeze();
+        if !self.finished {
+            self.parser.source_mut().push(chunk);
+        }
+    }
+
+    fn next_event(&mut self) -> Result<Option<PartEvent>, MulterError> {
+        // The chunk queue never registers a waker; `Pending` means it needs more input.
+        let mut cx = Context::from_waker(noop_waker_ref());
+        match self.poll_event(&mut cx) {
+            Poll::Ready(result) => result,
+            Poll::Pending => Ok(None),
+        }
+    }
+
+    fn poll_event(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<PartEvent>, MulterError>> {
+        if self.finished {
+            return Poll::Ready(Ok(None));
+        }
+
+        while self.in_part {
+            match self.parser.poll_next_part_chunk(cx) {
+                Poll::Ready(Ok(Some(chunk))) if chunk.is_empty() => {}
+                Poll::Ready(Ok(Some(chunk))) => {
+                    return Poll::Ready(Ok(Some(PartEvent::Data(chunk))))
+                }
+                Poll::Ready(Ok(None)) => {
+                    self.in_part = false;
+                    return Poll::Ready(Ok(Some(PartEvent::PartEnd)));
+                }
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        match self.parser.poll_next_part_headers(cx) {
+            Poll::Ready(Ok(Some(headers))) => {
+                self.in_part = true;
+                Poll::Ready(Ok(Some(PartEvent::Headers(Box::new(headers)))))
+            }
+            Poll::Ready(Ok(None)) => {
+                self.finished = true;
+                Poll::Ready(Ok(None))
+            }
+            Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Decoder for MultipartDecoder {
+    type Item = PartEvent;
+    type Error = MulterError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.feed(src);
+        self.next_event()
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.feed(src);
+        self.parser.source_mut().close();
+        self.next_event()
+    }
+}
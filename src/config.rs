@@ -1,31 +1,84 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, fmt, sync::Arc, time::Duration};
 
 use crate::{error::ConfigError, limits::Limits};
 
 /// Discriminates selected field handling between file and text parts.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum SelectedFieldKind {
     /// File upload field.
+    #[default]
     File,
     /// Text field.
     Text,
 }
 
+/// How a [`SelectedField`] name is matched against incoming field names.
+///
+/// An exact name always takes priority. Otherwise the first pattern in
+/// selector order that matches the incoming name applies.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum FieldNameMatch {
+    /// The name must match exactly.
+    #[default]
+    Exact,
+    /// The name is a glob where `*` matches any run of characters and `?`
+    /// matches one character, for example `photo_*`.
+    Glob,
+    /// The name is a regular expression that must match the whole field name.
+    #[cfg(feature = "regex")]
+    Regex,
+}
+
 /// Allowed file field declaration for `fields(...)` selector mode.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SelectedField {
-    /// Logical field name.
+    /// Logical field name, or a pattern depending on `name_match`.
     pub name: String,
+    /// How `name` is matched against incoming field names.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub name_match: FieldNameMatch,
+    /// Alternative field names that map to this field.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub aliases: Vec<String>,
     /// Field kind used for selector evaluation.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub kind: SelectedFieldKind,
     /// Maximum file count accepted for this field.
     pub max_count: Option<usize>,
-    /// Maximum size accepted for this field in bytes.
+    /// Minimum number of parts the request must contain for this field.
+    pub min_count: Option<usize>,
+    /// Maximum size in bytes accepted for this field, or for each file of a
+    /// file field.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "crate::limits::serde_byte_size::deserialize"
+        )
+    )]
     pub max_size: Option<u64>,
+    /// Minimum size in bytes accepted for each file of this field.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "crate::limits::serde_byte_size::deserialize"
+        )
+    )]
+    pub min_size: Option<u64>,
+    /// Whether the request must contain this field.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub required: bool,
     /// Allowed MIME patterns for this field (for example: `image/*`).
+    #[cfg_attr(feature = "serde", serde(default))]
     pub allowed_mime_types: Vec<String>,
+    /// Allowed file name extensions for this field (for example: `pdf`).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub allowed_extensions: Vec<String>,
 }
 
 impl SelectedField {
@@ -33,10 +86,16 @@ impl SelectedField {
     pub fn new(name: impl Into<String>) -> Self {
         Self {
             name: name.into(),
+            name_match: FieldNameMatch::Exact,
+            aliases: Vec::new(),
             kind: SelectedFieldKind::File,
             max_count: None,
+            min_count: None,
             max_size: None,
+            min_size: None,
+            required: false,
             allowed_mime_types: Vec::new(),
+            allowed_extensions: Vec::new(),
         }
     }
 
@@ -44,13 +103,57 @@ impl SelectedField {
     pub fn text(name: impl Into<String>) -> Self {
         Self {
             name: name.into(),
+            name_match: FieldNameMatch::Exact,
+            aliases: Vec::new(),
             kind: SelectedFieldKind::Text,
             max_count: None,
+            min_count: None,
             max_size: None,
+            min_size: None,
+            required: false,
             allowed_mime_types: Vec::new(),
+            allowed_extensions: Vec::new(),
         }
     }
 
+    /// Sets how the field name is matched against incoming field names.
+    ///
+    /// Counts and `required` apply to a pattern as a whole: `max_count`
+    /// caps the files of all matching fields combined.
+    pub fn with_name_match(mut self, name_match: FieldNameMatch) -> Self {
+        self.name_match = name_match;
+        self
+    }
+
+    /// Alias for [`SelectedField::with_name_match`].
+    pub fn name_match(self, name_match: FieldNameMatch) -> Self {
+        self.with_name_match(name_match)
+    }
+
+    /// Sets alternative names that map to this field, for example to accept
+    /// `profile_image` from older clients as `avatar`.
+    ///
+    /// Aliases are exact names. Parts sent under an alias share the field's
+    /// rules and counts, and [`Part::field_name`](crate::Part::field_name)
+    /// reports the field's own name unless it is a pattern.
+    pub fn with_aliases<I, A>(mut self, aliases: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: Into<String>,
+    {
+        self.aliases = aliases.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Alias for [`SelectedField::with_aliases`].
+    pub fn aliases<I, A>(self, aliases: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: Into<String>,
+    {
+        self.with_aliases(aliases)
+    }
+
     /// Sets the maximum file count accepted for this field.
     pub fn with_max_count(mut self, max_count: usize) -> Self {
         self.max_count = Some(max_count);
@@ -62,7 +165,25 @@ impl SelectedField {
         self.with_max_count(max_count)
     }
 
+    /// Sets the minimum number of parts the request must contain for this field.
+    ///
+    /// [`Multipart`](crate::Multipart) fails with
+    /// [`MulterError::FieldCountBelowMinimum`](crate::MulterError::FieldCountBelowMinimum)
+    /// when the request ends with fewer.
+    pub fn with_min_count(mut self, min_count: usize) -> Self {
+        self.min_count = Some(min_count);
+        self
+    }
+
+    /// Alias for [`SelectedField::with_min_count`].
+    pub fn min_count(self, min_count: usize) -> Self {
+        self.with_min_count(min_count)
+    }
+
     /// Sets the maximum size accepted for this field in bytes.
+    ///
+    /// For file fields the limit applies to each file and can only tighten
+    /// [`Limits::max_file_size`](crate::Limits::max_file_size).
     pub fn with_max_size(mut self, max_size: u64) -> Self {
         self.max_size = Some(max_size);
         self
@@ -73,6 +194,27 @@ impl SelectedField {
         self.with_max_size(max_size)
     }
 
+    /// Sets the minimum size in bytes accepted for each file of this field.
+    pub fn with_min_size(mut self, min_size: u64) -> Self {
+        self.min_size = Some(min_size);
+        self
+    }
+
+    /// Alias for [`SelectedField::with_min_size`].
+    pub fn min_size(self, min_size: u64) -> Self {
+        self.with_min_size(min_size)
+    }
+
+    /// Marks this field as required.
+    ///
+    /// [`Multipart`](crate::Multipart) fails with
+    /// [`MulterError::MissingRequiredField`](crate::MulterError::MissingRequiredField)
+    /// when the request ends without it.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
     /// Sets MIME patterns accepted for this field.
     pub fn with_allowed_mime_types<I, M>(mut self, patterns: I) -> Self
     where
@@ -92,18 +234,64 @@ impl SelectedField {
         self.with_allowed_mime_types(patterns)
     }
 
+    /// Sets file name extensions accepted for this field.
+    ///
+    /// Extensions are compared case-insensitively against the sanitized file
+    /// name, with or without a leading dot (`pdf` or `.pdf`). They are
+    /// checked alongside the MIME allowlists, so a spoofed `Content-Type`
+    /// alone does not get a file accepted.
+    pub fn with_allowed_extensions<I, E>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<String>,
+    {
+        self.allowed_extensions = extensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Alias for [`SelectedField::with_allowed_extensions`].
+    pub fn allowed_extensions<I, E>(self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<String>,
+    {
+        self.with_allowed_extensions(extensions)
+    }
+
     /// Validates a single selected field configuration.
     pub fn validate(&self) -> Result<(), ConfigError> {
-        if self.name.trim().is_empty() {
+        if self.name.trim().is_empty() || self.aliases.iter().any(|alias| alias.trim().is_empty()) {
             return Err(ConfigError::EmptyFieldName);
         }
 
+        #[cfg(feature = "regex")]
+        if self.name_match == FieldNameMatch::Regex {
+            if let Err(err) = regex::Regex::new(&self.name) {
+                return Err(ConfigError::InvalidFieldPattern {
+                    pattern: self.name.clone(),
+                    message: err.to_string(),
+                });
+            }
+        }
+
         if matches!(self.max_count, Some(0)) {
             return Err(ConfigError::InvalidFieldMaxCount {
                 name: self.name.clone(),
             });
         }
 
+        if let Some(min_count) = self.min_count {
+            if min_count == 0
+                || self
+                    .max_count
+                    .is_some_and(|max_count| min_count > max_count)
+            {
+                return Err(ConfigError::InvalidFieldMinCount {
+                    name: self.name.clone(),
+                });
+            }
+        }
+
         if matches!(self.max_size, Some(0)) {
             return Err(ConfigError::InvalidFieldMaxSize {
                 name: self.name.clone(),
@@ -118,6 +306,10 @@ impl SelectedField {
             }
         }
 
+        for extension in &self.allowed_extensions {
+            validate_extension(extension)?;
+        }
+
         Ok(())
     }
 }
@@ -196,10 +388,10 @@ impl Selector {
                 let mut seen = HashSet::with_capacity(fields.len());
                 for field in fields {
                     field.validate()?;
-                    if !seen.insert(field.name.clone()) {
-                        return Err(ConfigError::DuplicateFieldName {
-                            name: field.name.clone(),
-                        });
+                    for name in std::iter::once(&field.name).chain(&field.aliases) {
+                        if !seen.insert(name.clone()) {
+                            return Err(ConfigError::DuplicateFieldName { name: name.clone() });
+                        }
                     }
                 }
             }
@@ -210,20 +402,317 @@ impl Selector {
     }
 }
 
+/// Cross-field rule evaluated once the request ends.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FieldConstraint {
+    /// Exactly one of the fields must be present.
+    ExactlyOneOf(Vec<String>),
+    /// At least one of the fields must be present.
+    AtLeastOneOf(Vec<String>),
+    /// At most one of the fields may be present.
+    AtMostOneOf(Vec<String>),
+    /// When `field` is present, `requires` must be present too.
+    Requires {
+        /// Field that triggers the dependency.
+        field: String,
+        /// Field that must accompany `field`.
+        requires: String,
+    },
+}
+
+impl FieldConstraint {
+    /// Requires exactly one of `fields`, for example an avatar file or URL.
+    pub fn exactly_one_of<I, N>(fields: I) -> Self
+    where
+        I: IntoIterator<Item = N>,
+        N: Into<String>,
+    {
+        Self::ExactlyOneOf(fields.into_iter().map(Into::into).collect())
+    }
+
+    /// Requires at least one of `fields`.
+    pub fn at_least_one_of<I, N>(fields: I) -> Self
+    where
+        I: IntoIterator<Item = N>,
+        N: Into<String>,
+    {
+        Self::AtLeastOneOf(fields.into_iter().map(Into::into).collect())
+    }
+
+    /// Allows at most one of `fields`.
+    pub fn at_most_one_of<I, N>(fields: I) -> Self
+    where
+        I: IntoIterator<Item = N>,
+        N: Into<String>,
+    {
+        Self::AtMostOneOf(fields.into_iter().map(Into::into).collect())
+    }
+
+    /// Requires `requires` whenever `field` is present.
+    pub fn requires(field: impl Into<String>, requires: impl Into<String>) -> Self {
+        Self::Requires {
+            field: field.into(),
+            requires: requires.into(),
+        }
+    }
+
+    /// Returns every field name the constraint refers to.
+    pub fn field_names(&self) -> Vec<&str> {
+        match self {
+            Self::ExactlyOneOf(fields) | Self::AtLeastOneOf(fields) | Self::AtMostOneOf(fields) => {
+                fields.iter().map(String::as_str).collect()
+            }
+            Self::Requires { field, requires } => vec![field.as_str(), requires.as_str()],
+        }
+    }
+
+    /// Returns `true` when the set of present fields satisfies the constraint.
+    pub fn is_satisfied_by(&self, is_present: impl Fn(&str) -> bool) -> bool {
+        match self {
+            Self::ExactlyOneOf(fields) => {
+                fields.iter().filter(|field| is_present(field)).count() == 1
+            }
+            Self::AtLeastOneOf(fields) => fields.iter().any(|field| is_present(field)),
+            Self::AtMostOneOf(fields) => {
+                fields.iter().filter(|field| is_present(field)).count() <= 1
+            }
+            Self::Requires { field, requires } => !is_present(field) || is_present(requires),
+        }
+    }
+
+    /// Validates that the constraint names at least one field and no empty names.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let names = self.field_names();
+        if names.is_empty() || names.iter().any(|name| name.trim().is_empty()) {
+            return Err(ConfigError::InvalidFieldConstraint {
+                constraint: self.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FieldConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |fields: &[String]| {
+            fields
+                .iter()
+                .map(|field| format!("`{field}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            Self::ExactlyOneOf(fields) => write!(f, "exactly one of {}", list(fields)),
+            Self::AtLeastOneOf(fields) => write!(f, "at least one of {}", list(fields)),
+            Self::AtMostOneOf(fields) => write!(f, "at most one of {}", list(fields)),
+            Self::Requires { field, requires } => write!(f, "`{field}` requires `{requires}`"),
+        }
+    }
+}
+
+type UnknownFieldFn = dyn Fn(&str) + Send + Sync;
+
 /// Policy for handling fields not described by the active selector.
+///
+/// With the `serde` feature, [`UnknownFieldPolicy::Warn`] cannot be
+/// serialized or deserialized since it holds a callback.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Clone, Default)]
 pub enum UnknownFieldPolicy {
     /// Reject unknown fields with an error.
     Reject,
     /// Ignore unknown fields.
     #[default]
     Ignore,
+    /// Buffer unknown parts in memory instead of yielding them.
+    ///
+    /// Collected parts are available from [`Multipart::unknown_parts`](crate::Multipart::unknown_parts)
+    /// and [`ProcessedMultipart::unknown_parts`](crate::ProcessedMultipart::unknown_parts).
+    /// A part that would take the collected total past `max_size` bytes is
+    /// ignored instead.
+    Collect {
+        /// Maximum total size in bytes of the collected part bodies.
+        max_size: u64,
+    },
+    /// Calls the callback with the field name, then ignores the part.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Warn(Arc<UnknownFieldFn>),
+}
+
+impl UnknownFieldPolicy {
+    /// Creates a [`UnknownFieldPolicy::Warn`] policy from a callback.
+    pub fn warn(callback: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        Self::Warn(Arc::new(callback))
+    }
+}
+
+impl fmt::Debug for UnknownFieldPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reject => f.write_str("Reject"),
+            Self::Ignore => f.write_str("Ignore"),
+            Self::Collect { max_size } => f
+                .debug_struct("Collect")
+                .field("max_size", max_size)
+                .finish(),
+            Self::Warn(_) => f.write_str("Warn(<fn>)"),
+        }
+    }
+}
+
+impl PartialEq for UnknownFieldPolicy {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Reject, Self::Reject) | (Self::Ignore, Self::Ignore) => true,
+            (Self::Collect { max_size: a }, Self::Collect { max_size: b }) => a == b,
+            (Self::Warn(a), Self::Warn(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for UnknownFieldPolicy {}
+
+/// Policy for bytes received after the terminal boundary (the MIME epilogue).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EpiloguePolicy {
+    /// Consume and discard any epilogue bytes.
+    #[default]
+    Ignore,
+    /// Fail the stream when anything follows the terminal boundary line.
+    Reject,
+}
+
+/// Line terminators accepted between boundaries and part headers.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEndingPolicy {
+    /// Require `CRLF` as mandated by RFC 2046.
+    #[default]
+    Strict,
+    /// Accept bare `LF` in addition to `CRLF`.
+    Lenient,
+}
+
+/// Handling of RFC 2047 encoded-words (`=?charset?B?...?=`) in part file names.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EncodedWordPolicy {
+    /// Keep the file name exactly as sent.
+    #[default]
+    Preserve,
+    /// Decode encoded-words into a human-readable file name.
+    Decode,
+}
+
+/// Handling of part headers that appear more than once, such as two
+/// `Content-Disposition` or `Content-Type` lines in one part.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateHeaderPolicy {
+    /// Fail the stream when a part repeats a header the parser interprets.
+    Reject,
+    /// Use the first occurrence and ignore later ones.
+    #[default]
+    UseFirst,
+    /// Use the last occurrence and ignore earlier ones.
+    UseLast,
+}
+
+/// Handling of parts whose `Content-Transfer-Encoding` cannot be decoded.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransferEncodingPolicy {
+    /// Pass the body through in its encoded form.
+    #[default]
+    Passthrough,
+    /// Fail the stream with [`crate::MulterError::UnsupportedTransferEncoding`].
+    Reject,
+}
+
+/// How strictly part headers are checked against RFC 7578.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParserCompliance {
+    /// Tolerate common client deviations: nameless parts (given an empty
+    /// field name), whitespace around header names, folded header lines,
+    /// and dispositions other than `form-data`.
+    #[default]
+    Lenient,
+    /// Reject any of the deviations tolerated by [`ParserCompliance::Lenient`],
+    /// as well as repeated `Content-Disposition` headers.
+    Strict,
+}
+
+/// How a file's sniffed content type is reconciled with its declared one.
+///
+/// Content is sniffed from magic bytes by [`Multer::store`](crate::Multer::store)
+/// with the `sniff` feature; without it the policy has no effect. A declared
+/// `application/octet-stream` never counts as a mismatch.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum MimeSniffPolicy {
+    /// Keep the declared content type and only record the sniffed one.
+    #[default]
+    TrustDeclared,
+    /// Replace the declared content type with the sniffed one when known,
+    /// and check it against the global MIME allow- and denylists.
+    PreferSniffed,
+    /// Fail with [`MulterError::MimeTypeMismatch`](crate::MulterError::MimeTypeMismatch)
+    /// when the sniffed content type differs from the declared one.
+    RejectMismatch,
+}
+
+/// Handling of file parts whose body is identical to an earlier file in the
+/// same request, as sent by clients with buggy retry logic.
+///
+/// Applies to [`Multer::parse_and_store`](crate::Multer::parse_and_store),
+/// which hashes file bodies as they stream to storage.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum DuplicateFilePolicy {
+    /// Store every file; bodies are not hashed.
+    #[default]
+    Allow,
+    /// Discard duplicates and report them in
+    /// [`ProcessedMultipart::duplicates`](crate::ProcessedMultipart::duplicates).
+    Skip,
+    /// Fail with [`MulterError::DuplicateFile`](crate::MulterError::DuplicateFile).
+    Reject,
 }
 
 /// Top-level multipart configuration model.
+///
+/// With the `serde` feature the configuration can be loaded from application
+/// config files; omitted fields keep their default values.
+///
+/// ```rust
+/// # #[cfg(all(feature = "serde", feature = "json"))]
+/// # {
+/// use multigear::{MulterConfig, Selector, UnknownFieldPolicy};
+///
+/// let config: MulterConfig = serde_json::from_str(
+///     r#"{
+///         "selector": { "Single": { "name": "avatar" } },
+///         "unknown_field_policy": "Reject",
+///         "limits": { "max_file_size": "5MiB", "allowed_mime_types": ["image/*"] }
+///     }"#,
+/// )
+/// .unwrap();
+/// assert_eq!(config.selector, Selector::single("avatar"));
+/// assert_eq!(config.unknown_field_policy, UnknownFieldPolicy::Reject);
+/// assert_eq!(config.limits.max_file_size, Some(5 * 1024 * 1024));
+/// # }
+/// ```
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
 pub struct MulterConfig {
     /// Selector strategy for file fields.
     pub selector: Selector,
@@ -231,6 +720,35 @@ pub struct MulterConfig {
     pub unknown_field_policy: UnknownFieldPolicy,
     /// Global request limits.
     pub limits: Limits,
+    /// Behavior when bytes follow the terminal boundary.
+    pub epilogue_policy: EpiloguePolicy,
+    /// Line terminators accepted in multipart framing.
+    pub line_ending_policy: LineEndingPolicy,
+    /// Handling of RFC 2047 encoded-words in part file names.
+    pub encoded_word_policy: EncodedWordPolicy,
+    /// Handling of parts with an unsupported `Content-Transfer-Encoding`.
+    pub transfer_encoding_policy: TransferEncodingPolicy,
+    /// How strictly part headers are checked against RFC 7578.
+    pub parser_compliance: ParserCompliance,
+    /// Handling of repeated `Content-Disposition`, `Content-Type`, and other
+    /// interpreted part headers.
+    pub duplicate_header_policy: DuplicateHeaderPolicy,
+    /// Ends the stream cleanly when input stops at a part boundary without the
+    /// terminal `--boundary--` line, instead of failing as incomplete.
+    pub allow_missing_terminal_boundary: bool,
+    /// Strips a leading UTF-8 byte order mark from text field values
+    /// returned by `Part::text`.
+    pub strip_text_bom: bool,
+    /// Cross-field rules checked once the request ends.
+    pub field_constraints: Vec<FieldConstraint>,
+    /// How sniffed file content types are reconciled with declared ones.
+    pub mime_sniff_policy: MimeSniffPolicy,
+    /// Handling of files repeating an earlier file's content in one request.
+    pub duplicate_file_policy: DuplicateFilePolicy,
+    /// Records per-part violations in a
+    /// [`ValidationReport`](crate::ValidationReport) instead of failing
+    /// `Multer::parse_and_store` at the first one.
+    pub collect_violations: bool,
 }
 
 impl MulterConfig {
@@ -239,14 +757,118 @@ impl MulterConfig {
         Self::default()
     }
 
+    /// Sets the selector strategy for file fields.
+    pub fn selector(mut self, selector: Selector) -> Self {
+        self.selector = selector;
+        self
+    }
+
+    /// Sets how unknown fields should be handled.
+    pub fn unknown_field_policy(mut self, policy: UnknownFieldPolicy) -> Self {
+        self.unknown_field_policy = policy;
+        self
+    }
+
+    /// Sets global multipart limits.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets how bytes after the terminal boundary are handled.
+    pub fn epilogue_policy(mut self, policy: EpiloguePolicy) -> Self {
+        self.epilogue_policy = policy;
+        self
+    }
+
+    /// Sets which line terminators are accepted in multipart framing.
+    pub fn line_ending_policy(mut self, policy: LineEndingPolicy) -> Self {
+        self.line_ending_policy = policy;
+        self
+    }
+
+    /// Sets whether RFC 2047 encoded-word file names are decoded.
+    pub fn encoded_word_policy(mut self, policy: EncodedWordPolicy) -> Self {
+        self.encoded_word_policy = policy;
+        self
+    }
+
+    /// Sets how parts with an unsupported `Content-Transfer-Encoding` are handled.
+    pub fn transfer_encoding_policy(mut self, policy: TransferEncodingPolicy) -> Self {
+        self.transfer_encoding_policy = policy;
+        self
+    }
+
+    /// Sets how strictly part headers are checked against RFC 7578.
+    pub fn parser_compliance(mut self, compliance: ParserCompliance) -> Self {
+        self.parser_compliance = compliance;
+        self
+    }
+
+    /// Sets how repeated interpreted part headers are handled.
+    pub fn duplicate_header_policy(mut self, policy: DuplicateHeaderPolicy) -> Self {
+        self.duplicate_header_policy = policy;
+        self
+    }
+
+    /// Sets whether input missing the terminal boundary line ends cleanly.
+    pub fn allow_missing_terminal_boundary(mut self, allow: bool) -> Self {
+        self.allow_missing_terminal_boundary = allow;
+        self
+    }
+
+    /// Sets whether a leading UTF-8 byte order mark is stripped from text field values.
+    pub fn strip_text_bom(mut self, strip: bool) -> Self {
+        self.strip_text_bom = strip;
+        self
+    }
+
+    /// Sets how sniffed file content types are reconciled with declared ones.
+    pub fn mime_sniff_policy(mut self, policy: MimeSniffPolicy) -> Self {
+        self.mime_sniff_policy = policy;
+        self
+    }
+
+    /// Sets how files repeating an earlier file's content are handled.
+    pub fn duplicate_file_policy(mut self, policy: DuplicateFilePolicy) -> Self {
+        self.duplicate_file_policy = policy;
+        self
+    }
+
+    /// Sets whether rejected parts are reported instead of failing the request.
+    pub fn collect_violations(mut self, collect: bool) -> Self {
+        self.collect_violations = collect;
+        self
+    }
+
+    /// Adds a cross-field rule checked once the request ends.
+    pub fn constraint(mut self, constraint: FieldConstraint) -> Self {
+        self.field_constraints.push(constraint);
+        self
+    }
+
     /// Validates selector and limit configuration.
     pub fn validate(&self) -> Result<(), ConfigError> {
         self.selector.validate()?;
+        for constraint in &self.field_constraints {
+            constraint.validate()?;
+        }
         validate_limits(&self.limits)?;
         Ok(())
     }
 }
 
+fn validate_extension(extension: &str) -> Result<(), ConfigError> {
+    let trimmed = extension.strip_prefix('.').unwrap_or(extension);
+    if trimmed.trim().is_empty() || trimmed.contains(['/', '\\']) {
+        return Err(ConfigError::InvalidFileExtension {
+            extension: extension.to_owned(),
+        });
+    }
+
+    Ok(())
+}
+
 fn validate_field_name(name: &str) -> Result<(), ConfigError> {
     if name.trim().is_empty() {
         return Err(ConfigError::EmptyFieldName);
@@ -260,7 +882,30 @@ fn validate_limits(limits: &Limits) -> Result<(), ConfigError> {
     validate_positive_usize("max_files", limits.max_files)?;
     validate_positive_u64("max_field_size", limits.max_field_size)?;
     validate_positive_usize("max_fields", limits.max_fields)?;
+    validate_positive_usize("max_parts", limits.max_parts)?;
     validate_positive_u64("max_body_size", limits.max_body_size)?;
+    validate_positive_u64("max_total_file_size", limits.max_total_file_size)?;
+    validate_positive_u64("max_header_size", limits.max_header_size)?;
+    validate_positive_usize("max_headers_per_part", limits.max_headers_per_part)?;
+    validate_positive_usize("max_filename_length", limits.max_filename_length)?;
+    validate_positive_u64("max_decompressed_size", limits.max_decompressed_size)?;
+    validate_positive_duration("max_parse_duration", limits.max_parse_duration)?;
+    validate_positive_duration("idle_chunk_timeout", limits.idle_chunk_timeout)?;
+    validate_positive_u64("max_bytes_per_second", limits.max_bytes_per_second)?;
+    validate_positive_u64(
+        "min_throughput_bytes_per_sec",
+        limits.min_throughput_bytes_per_sec,
+    )?;
+
+    if let (Some(min_file_size), Some(max_file_size)) = (limits.min_file_size, limits.max_file_size)
+    {
+        if min_file_size > max_file_size {
+            return Err(ConfigError::MinFileSizeExceedsMax {
+                min_file_size,
+                max_file_size,
+            });
+        }
+    }
 
     if let Some(max_body_size) = limits.max_body_size {
         if let Some(max_file_size) = limits.max_file_size {
@@ -282,9 +927,23 @@ fn validate_limits(limits: &Limits) -> Result<(), ConfigError> {
                 });
             }
         }
+
+        if let Some(max_total_file_size) = limits.max_total_file_size {
+            if max_total_file_size > max_body_size {
+                return Err(ConfigError::LimitExceedsBodySize {
+                    limit: "max_total_file_size",
+                    value: max_total_file_size,
+                    max_body_size,
+                });
+            }
+        }
     }
 
-    for pattern in &limits.allowed_mime_types {
+    for pattern in limits
+        .allowed_mime_types
+        .iter()
+        .chain(&limits.denied_mime_types)
+    {
         if !is_valid_mime_pattern(pattern) {
             return Err(ConfigError::InvalidMimePattern {
                 pattern: pattern.clone(),
@@ -292,6 +951,14 @@ fn validate_limits(limits: &Limits) -> Result<(), ConfigError> {
         }
     }
 
+    for extension in limits
+        .allowed_extensions
+        .iter()
+        .chain(&limits.allowed_double_extensions)
+    {
+        validate_extension(extension)?;
+    }
+
     Ok(())
 }
 
@@ -303,6 +970,17 @@ fn validate_positive_u64(limit: &'static str, value: Option<u64>) -> Result<(),
     Ok(())
 }
 
+fn validate_positive_duration(
+    limit: &'static str,
+    value: Option<Duration>,
+) -> Result<(), ConfigError> {
+    if matches!(value, Some(duration) if duration.is_zero()) {
+        return Err(ConfigError::InvalidLimitValue { limit });
+    }
+
+    Ok(())
+}
+
 fn validate_positive_usize(limit: &'static str, value: Option<usize>) -> Result<(), ConfigError> {
     if matches!(value, Some(0)) {
         return Err(ConfigError::InvalidLimitValue { limit });
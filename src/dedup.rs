@@ -0,0 +1,78 @@
+//! Detection of identical file bodies uploaded twice in one request.
+
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::{BuildHasher, Hasher},
+    sync::Mutex,
+};
+
+use bytes::Bytes;
+use futures::StreamExt;
+
+use crate::{
+    storage::{BoxStream, FileMeta},
+    MulterError,
+};
+
+/// Hashes file bodies of one request as they stream to storage.
+///
+/// Bodies are keyed by length and a randomly keyed SipHash digest, so
+/// clients cannot craft colliding files in advance.
+#[derive(Debug, Default)]
+pub(crate) struct DuplicateTracker {
+    hasher: RandomState,
+    seen: Mutex<HashMap<(u64, u64), FileRef>>,
+}
+
+/// Field name and client filename of a file seen earlier in the request.
+type FileRef = (String, Option<String>);
+
+impl DuplicateTracker {
+    /// Wraps a body so it fails after its last chunk when an earlier file
+    /// in the request had the same content.
+    ///
+    /// Storage engines see the [`MulterError::DuplicateFile`] as a stream
+    /// error and discard the file.
+    pub(crate) fn watch<'a>(
+        &'a self,
+        meta: &FileMeta,
+        body: BoxStream<'a, Result<Bytes, MulterError>>,
+    ) -> BoxStream<'a, Result<Bytes, MulterError>> {
+        let file = (meta.field_name.clone(), meta.file_name.clone());
+        let hasher = self.hasher.build_hasher();
+        Box::pin(futures::stream::unfold(
+            (body.fuse(), hasher, 0u64, Some(file)),
+            move |(mut body, mut hasher, mut len, mut file)| async move {
+                let file_ref = file.take()?;
+                match body.next().await {
+                    Some(Ok(chunk)) => {
+                        hasher.write(&chunk);
+                        len += chunk.len() as u64;
+                        Some((Ok(chunk), (body, hasher, len, Some(file_ref))))
+                    }
+                    Some(Err(err)) => Some((Err(err), (body, hasher, len, Some(file_ref)))),
+                    None => {
+                        let err = self.record((hasher.finish(), len), file_ref)?;
+                        Some((Err(err), (body, hasher, len, None)))
+                    }
+                }
+            },
+        ))
+    }
+
+    fn record(&self, key: (u64, u64), file: FileRef) -> Option<MulterError> {
+        let mut seen = self.seen.lock().ok()?;
+        let Some((original_field, original_file_name)) = seen.get(&key).cloned() else {
+            seen.insert(key, file);
+            return None;
+        };
+
+        let (field, file_name) = file;
+        Some(MulterError::DuplicateFile {
+            field,
+            file_name,
+            original_field,
+            original_file_name,
+        })
+    }
+}
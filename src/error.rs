@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::config::FieldConstraint;
+
 /// Configuration-time validation errors.
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
 #[non_exhaustive]
@@ -13,6 +15,18 @@ pub enum ConfigError {
         /// Name of the field with an invalid count.
         name: String,
     },
+    /// A field has a `min_count` of zero or above its `max_count`.
+    #[error("field `{name}` has invalid min_count; it must be between 1 and max_count")]
+    InvalidFieldMinCount {
+        /// Name of the field with an invalid count.
+        name: String,
+    },
+    /// A field constraint names no fields or an empty field name.
+    #[error("invalid field constraint: {constraint}")]
+    InvalidFieldConstraint {
+        /// The invalid constraint, as displayed.
+        constraint: String,
+    },
     /// A field has an invalid `max_size` of zero.
     #[error("field `{name}` has invalid max_size of 0")]
     InvalidFieldMaxSize {
@@ -50,6 +64,45 @@ pub enum ConfigError {
         /// Configured `max_body_size`.
         max_body_size: u64,
     },
+    /// `min_file_size` is larger than `max_file_size`.
+    #[error(
+        "limit `min_file_size` ({min_file_size}) cannot exceed `max_file_size` ({max_file_size})"
+    )]
+    MinFileSizeExceedsMax {
+        /// Configured `min_file_size`.
+        min_file_size: u64,
+        /// Configured `max_file_size`.
+        max_file_size: u64,
+    },
+    /// A selector field name pattern failed to compile.
+    #[error("invalid field name pattern `{pattern}`: {message}")]
+    InvalidFieldPattern {
+        /// The invalid pattern value.
+        pattern: String,
+        /// Compiler error message.
+        message: String,
+    },
+    /// An environment variable read by [`Limits::from_env`](crate::Limits::from_env)
+    /// holds a value that could not be parsed.
+    #[error("invalid value `{value}` for environment variable `{name}`")]
+    InvalidEnvValue {
+        /// Full name of the environment variable.
+        name: String,
+        /// The rejected value.
+        value: String,
+    },
+    /// A size string such as `"64MB"` could not be parsed.
+    #[error("invalid byte size `{value}`")]
+    InvalidByteSize {
+        /// The rejected size string.
+        value: String,
+    },
+    /// An allowed file extension is empty or contains a path separator.
+    #[error("invalid file extension `{extension}`")]
+    InvalidFileExtension {
+        /// The invalid extension value.
+        extension: String,
+    },
     /// An allowed MIME pattern is malformed.
     #[error("invalid MIME pattern `{pattern}`")]
     InvalidMimePattern {
@@ -59,13 +112,28 @@ pub enum ConfigError {
 }
 
 /// Parser-level multipart failures.
-#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum ParseError {
     /// Generic parser failure with message context.
-    #[error("{message}")]
+    #[error("{message}{}", format_location(*.part_index, .field_name.as_deref(), *.offset))]
+    #[non_exhaustive]
     Message {
         /// Parser failure message.
         message: String,
+        /// Zero-based index of the part being parsed when the failure occurred.
+        part_index: Option<usize>,
+        /// Field name of the part being parsed, once its headers were read.
+        field_name: Option<String>,
+        /// Approximate byte offset into the multipart body where parsing stopped.
+        offset: Option<u64>,
+    },
+    /// The input body stream failed.
+    #[error("body stream error: {source}")]
+    Input {
+        /// Error returned by the input stream.
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
     },
 }
 
@@ -74,12 +142,107 @@ impl ParseError {
     pub fn new(message: impl Into<String>) -> Self {
         Self::Message {
             message: message.into(),
+            part_index: None,
+            field_name: None,
+            offset: None,
         }
     }
+
+    /// Creates a parser error for a failed input stream, keeping the failure
+    /// as the source.
+    pub fn input(source: impl Into<Box<dyn std::error::Error + Send + Sync + 'static>>) -> Self {
+        Self::Input {
+            source: source.into(),
+        }
+    }
+
+    /// Returns the failure message without location context.
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Message { message, .. } => message,
+            Self::Input { .. } => "body stream error",
+        }
+    }
+
+    /// Returns the zero-based index of the part being parsed, when known.
+    pub fn part_index(&self) -> Option<usize> {
+        match self {
+            Self::Message { part_index, .. } => *part_index,
+            Self::Input { .. } => None,
+        }
+    }
+
+    /// Returns the field name of the part being parsed, when known.
+    pub fn field_name(&self) -> Option<&str> {
+        match self {
+            Self::Message { field_name, .. } => field_name.as_deref(),
+            Self::Input { .. } => None,
+        }
+    }
+
+    /// Returns the approximate byte offset into the body where parsing stopped, when known.
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            Self::Message { offset, .. } => *offset,
+            Self::Input { .. } => None,
+        }
+    }
+
+    /// Fills in location context that is not already recorded.
+    pub(crate) fn with_location(
+        self,
+        location_part_index: Option<usize>,
+        location_field_name: Option<&str>,
+        location_offset: u64,
+    ) -> Self {
+        match self {
+            Self::Message {
+                message,
+                part_index,
+                field_name,
+                offset,
+            } => Self::Message {
+                message,
+                part_index: part_index.or(location_part_index),
+                field_name: field_name.or_else(|| location_field_name.map(str::to_owned)),
+                offset: offset.or(Some(location_offset)),
+            },
+            err @ Self::Input { .. } => err,
+        }
+    }
+}
+
+/// Formats parser location context as a message suffix.
+fn format_location(
+    part_index: Option<usize>,
+    field_name: Option<&str>,
+    offset: Option<u64>,
+) -> String {
+    let mut context = Vec::new();
+    if let Some(part_index) = part_index {
+        context.push(format!("part {part_index}"));
+    }
+    if let Some(field_name) = field_name {
+        context.push(format!("field `{field_name}`"));
+    }
+    if let Some(offset) = offset {
+        context.push(format!("near byte {offset}"));
+    }
+
+    if context.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", context.join(", "))
+    }
 }
 
 /// Storage backend failures.
-#[derive(Debug, Clone, PartialEq, Eq, Error)]
+///
+/// Variants other than [`StorageError::Message`] keep the underlying error as
+/// their [`source`](std::error::Error::source), so callers can downcast to the
+/// I/O or SDK error that caused the failure.
+#[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum StorageError {
     /// Generic storage failure with message context.
     #[error("{message}")]
@@ -87,6 +250,27 @@ pub enum StorageError {
         /// Storage failure message.
         message: String,
     },
+    /// Filesystem or other I/O failure.
+    #[error("{context}: {source}")]
+    Io {
+        /// What the backend was doing when the error occurred.
+        context: String,
+        /// Underlying I/O error.
+        #[source]
+        source: std::io::Error,
+    },
+    /// The part's body stream failed while it was being stored.
+    #[error("stream read failed: {0}")]
+    Stream(#[source] MulterError),
+    /// Failure reported by a backend SDK, driver or wrapped storage engine.
+    #[error("{context}: {source}")]
+    Backend {
+        /// What the backend was doing when the error occurred.
+        context: String,
+        /// Underlying backend error.
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
 }
 
 impl StorageError {
@@ -96,6 +280,66 @@ impl StorageError {
             message: message.into(),
         }
     }
+
+    /// Creates a storage error from an I/O failure.
+    pub fn io(context: impl Into<String>, source: std::io::Error) -> Self {
+        Self::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    /// Creates a storage error from a backend failure, keeping it as the source.
+    pub fn backend(
+        context: impl Into<String>,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    ) -> Self {
+        Self::Backend {
+            context: context.into(),
+            source: source.into(),
+        }
+    }
+}
+
+/// Rejection reported by a [`PartValidator`](crate::PartValidator).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ValidationError {
+    /// Generic validation failure with message context.
+    #[error("{message}")]
+    Message {
+        /// Validation failure message.
+        message: String,
+    },
+}
+
+impl ValidationError {
+    /// Creates a validation error from a message.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self::Message {
+            message: message.into(),
+        }
+    }
+}
+
+/// Failure reported by an [`AvScanner`](crate::scan::AvScanner) that could not
+/// reach a verdict.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ScanError {
+    /// Generic scanner failure with message context.
+    #[error("{message}")]
+    Message {
+        /// Scanner failure message.
+        message: String,
+    },
+}
+
+impl ScanError {
+    /// Creates a scanner error from a message.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self::Message {
+            message: message.into(),
+        }
+    }
 }
 
 /// Runtime error type used by `multigear`.
@@ -109,14 +353,102 @@ pub enum MulterError {
     #[error(transparent)]
     Parse(#[from] ParseError),
     /// Storage backend failure.
+    ///
+    /// Holds the error returned by the storage engine as is, so callers can
+    /// downcast it to the engine's error type, such as [`StorageError`]. A
+    /// body error the engine kept as [`StorageError::Stream`] is returned as
+    /// that error instead.
     #[error(transparent)]
-    Storage(#[from] StorageError),
+    Storage(Box<dyn std::error::Error + Send + Sync + 'static>),
+    /// A file part was rejected by a [`PartValidator`](crate::PartValidator).
+    #[error("file field `{field}` failed validation: {source}")]
+    Validation {
+        /// Field name of the rejected file.
+        field: String,
+        /// Error returned by the validator.
+        #[source]
+        source: ValidationError,
+    },
+    /// A file part was rejected by the configured [`FileFilter`](crate::FileFilter).
+    #[error("file field `{field}` rejected: {message}")]
+    FileRejected {
+        /// Field name of the rejected file.
+        field: String,
+        /// Message returned by the filter.
+        message: String,
+    },
+    /// A file part repeated the content of an earlier file under
+    /// [`DuplicateFilePolicy::Reject`](crate::DuplicateFilePolicy::Reject).
+    #[error("file field `{field}` duplicates a file uploaded in field `{original_field}`")]
+    DuplicateFile {
+        /// Field name of the duplicate file.
+        field: String,
+        /// Client-supplied filename of the duplicate file.
+        file_name: Option<String>,
+        /// Field name of the earlier file with the same content.
+        original_field: String,
+        /// Client-supplied filename of the earlier file.
+        original_file_name: Option<String>,
+    },
+    /// A file part was vetoed by the configured [`AvScanner`](crate::scan::AvScanner).
+    #[error("file field `{field}` rejected by scanner: {signature}")]
+    ScanRejected {
+        /// Field name of the rejected file.
+        field: String,
+        /// Name of the detected signature.
+        signature: String,
+    },
+    /// The configured [`AvScanner`](crate::scan::AvScanner) failed to scan a file part.
+    #[error("file field `{field}` could not be scanned: {source}")]
+    Scan {
+        /// Field name of the unscanned file.
+        field: String,
+        /// Error returned by the scanner.
+        #[source]
+        source: ScanError,
+    },
+    /// A body did not match the digest declared in its `Content-MD5` or
+    /// `X-Checksum-SHA256` header.
+    #[error(
+        "{algorithm} checksum mismatch{}: expected {expected}, got {actual}",
+        .field.as_deref().map(|field| format!(" for field `{field}`")).unwrap_or_default()
+    )]
+    ChecksumMismatch {
+        /// Field name of the part, or `None` for a whole request body.
+        field: Option<String>,
+        /// Digest algorithm, `MD5` or `SHA-256`.
+        algorithm: &'static str,
+        /// Hex digest declared by the client.
+        expected: String,
+        /// Hex digest of the received bytes.
+        actual: String,
+    },
     /// Incoming field does not match active selector configuration.
     #[error("unexpected field `{field}`")]
     UnexpectedField {
         /// Field name encountered in the stream.
         field: String,
     },
+    /// The request ended without a field marked as required.
+    #[error("required field `{field}` is missing")]
+    MissingRequiredField {
+        /// Name of the missing field.
+        field: String,
+    },
+    /// The request ended with fewer parts for a field than its `min_count`.
+    #[error("field `{field}` requires at least {min_count} parts")]
+    FieldCountBelowMinimum {
+        /// Name of the field.
+        field: String,
+        /// Configured minimum part count.
+        min_count: usize,
+    },
+    /// The request ended in violation of a configured field constraint.
+    #[error("field constraint violated: {constraint}")]
+    FieldConstraintViolated {
+        /// The violated constraint.
+        constraint: FieldConstraint,
+    },
     /// File count for a field exceeded the active selector limit.
     #[error("field `{field}` exceeded max count of {max_count}")]
     FieldCountLimitExceeded {
@@ -133,6 +465,14 @@ pub enum MulterError {
         /// Maximum allowed file size in bytes.
         max_file_size: u64,
     },
+    /// A file part ended below the configured minimum size.
+    #[error("file field `{field}` is smaller than min file size of {min_file_size} bytes")]
+    FileSizeBelowMinimum {
+        /// Field name of the undersized file.
+        field: String,
+        /// Minimum required file size in bytes.
+        min_file_size: u64,
+    },
     /// A text part exceeded the configured size limit.
     #[error("text field `{field}` exceeded max field size of {max_field_size} bytes")]
     FieldSizeLimitExceeded {
@@ -153,12 +493,36 @@ pub enum MulterError {
         /// Maximum allowed number of text parts.
         max_fields: usize,
     },
+    /// The total number of parts exceeded the configured limit.
+    #[error("multipart request exceeded max parts limit of {max_parts}")]
+    PartsLimitExceeded {
+        /// Maximum allowed number of parts of any kind.
+        max_parts: usize,
+    },
+    /// The accepted file parts together exceeded the configured size limit.
+    #[error("multipart request exceeded max total file size of {max_total_file_size} bytes")]
+    TotalFileSizeLimitExceeded {
+        /// Maximum allowed combined file size in bytes.
+        max_total_file_size: u64,
+    },
     /// The request body exceeded the configured body-size limit.
     #[error("multipart request exceeded max body size of {max_body_size} bytes")]
     BodySizeLimitExceeded {
         /// Maximum allowed request body size in bytes.
         max_body_size: u64,
     },
+    /// A part header block exceeded the configured size limit.
+    #[error("multipart part headers exceeded max header size of {max_header_size} bytes")]
+    HeaderSizeLimitExceeded {
+        /// Maximum allowed header block size in bytes.
+        max_header_size: u64,
+    },
+    /// A part declared more headers than the configured limit.
+    #[error("multipart part exceeded max headers limit of {max_headers_per_part}")]
+    HeaderCountLimitExceeded {
+        /// Maximum allowed number of headers per part.
+        max_headers_per_part: usize,
+    },
     /// A file MIME type is not permitted by the configured allowlist.
     #[error("file field `{field}` has disallowed MIME type `{mime}`")]
     MimeTypeNotAllowed {
@@ -167,7 +531,157 @@ pub enum MulterError {
         /// MIME type encountered for the file part.
         mime: String,
     },
+    /// A file's sniffed content type differs from its declared one under
+    /// [`MimeSniffPolicy::RejectMismatch`](crate::MimeSniffPolicy::RejectMismatch).
+    #[error("file field `{field}` declared `{declared}` but its content looks like `{sniffed}`")]
+    MimeTypeMismatch {
+        /// File field name.
+        field: String,
+        /// Content type declared by the part headers.
+        declared: String,
+        /// Content type sniffed from the file's magic bytes.
+        sniffed: String,
+    },
+    /// A file extension is not permitted by the field's extension allowlist.
+    #[error("file field `{field}` has disallowed extension `{extension}`")]
+    ExtensionNotAllowed {
+        /// File field name.
+        field: String,
+        /// Lowercased file name extension, empty when the file name has none.
+        extension: String,
+    },
+    /// A filename has a double extension under
+    /// [`Limits::reject_double_extensions`](crate::Limits::reject_double_extensions).
+    #[error("file field `{field}` has a suspicious double extension `{extension}`")]
+    DoubleExtension {
+        /// File field name.
+        field: String,
+        /// Lowercased compound extension, such as `pdf.exe`.
+        extension: String,
+    },
+    /// A filename is longer than [`Limits::max_filename_length`](crate::Limits::max_filename_length).
+    #[error("file field `{field}` has a filename longer than {max_filename_length} characters")]
+    FilenameTooLong {
+        /// File field name.
+        field: String,
+        /// Configured maximum filename length.
+        max_filename_length: usize,
+    },
+    /// A filename contains characters rejected by
+    /// [`Limits::filename_charset`](crate::Limits::filename_charset).
+    #[error("file field `{field}` has a filename with disallowed characters: {file_name:?}")]
+    InvalidFilename {
+        /// File field name.
+        field: String,
+        /// The rejected filename.
+        file_name: String,
+    },
+    /// A file input was submitted without a file while empty files are rejected.
+    #[error("file field `{field}` was submitted without a file")]
+    EmptyFile {
+        /// File field name.
+        field: String,
+    },
+    /// A compressed part decompressed past the configured limit.
+    #[error("field `{field}` exceeded max decompressed size of {max_decompressed_size} bytes")]
+    DecompressedSizeLimitExceeded {
+        /// Field name of the compressed part.
+        field: String,
+        /// Maximum allowed decompressed size in bytes.
+        max_decompressed_size: u64,
+    },
+    /// A part declared a `Content-Transfer-Encoding` the parser cannot decode.
+    #[error("field `{field}` uses unsupported transfer encoding `{encoding}`")]
+    UnsupportedTransferEncoding {
+        /// Field name of the rejected part.
+        field: String,
+        /// Declared transfer encoding token.
+        encoding: String,
+    },
+    /// Parsing took longer than a configured timeout.
+    #[error("multipart `{limit}` of {duration:?} elapsed")]
+    Timeout {
+        /// Name of the elapsed limit (`max_parse_duration` or `idle_chunk_timeout`).
+        limit: &'static str,
+        /// Configured duration of the elapsed limit.
+        duration: std::time::Duration,
+    },
+    /// The client sent the body slower than the configured minimum throughput.
+    #[error("multipart upload fell below min throughput of {min_throughput_bytes_per_sec} bytes per second")]
+    ThroughputTooLow {
+        /// Configured minimum throughput in bytes per second.
+        min_throughput_bytes_per_sec: u64,
+    },
+    /// The parser buffered too much input without finding a boundary or header terminator.
+    #[error("multipart parser exceeded max buffered size of {max_buffered_bytes} bytes")]
+    BufferLimitExceeded {
+        /// Maximum number of unconsumed bytes the parser may hold.
+        max_buffered_bytes: usize,
+    },
     /// Multipart stream ended before a complete terminal boundary.
     #[error("multipart stream ended unexpectedly")]
     IncompleteStream,
+    /// Parsing was stopped through a cancellation token.
+    #[error("multipart parsing was cancelled")]
+    Cancelled,
+    /// Parsing was stopped through [`Multipart::abort`](crate::Multipart::abort).
+    #[error("multipart parsing was aborted")]
+    Aborted,
+}
+
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+impl MulterError {
+    /// Wraps a storage engine failure, surfacing the body error that caused
+    /// it when the engine kept that error in its source chain.
+    pub(crate) fn from_storage(err: BoxError) -> Self {
+        take_body_error(err).unwrap_or_else(Self::Storage)
+    }
+}
+
+/// Walks the owned source chain of `err` for the [`MulterError`] a storage
+/// engine reported through [`StorageError::Stream`].
+fn take_body_error(err: BoxError) -> Result<MulterError, BoxError> {
+    let err = match err.downcast::<MulterError>() {
+        Ok(err) => return Ok(*err),
+        Err(err) => err,
+    };
+    match err.downcast::<StorageError>().map(|err| *err) {
+        Ok(StorageError::Stream(err)) => Ok(err),
+        Ok(StorageError::Backend { context, source }) => take_body_error(source)
+            .map_err(|source| Box::new(StorageError::Backend { context, source }) as BoxError),
+        Ok(err) => Err(Box::new(err)),
+        Err(err) => Err(err),
+    }
+}
+
+impl From<StorageError> for MulterError {
+    fn from(err: StorageError) -> Self {
+        Self::Storage(Box::new(err))
+    }
+}
+
+impl From<std::io::Error> for MulterError {
+    fn from(err: std::io::Error) -> Self {
+        ParseError::input(err).into()
+    }
+}
+
+impl From<Box<dyn std::error::Error + Send + Sync>> for MulterError {
+    fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        ParseError::input(err).into()
+    }
+}
+
+impl From<std::convert::Infallible> for MulterError {
+    fn from(err: std::convert::Infallible) -> Self {
+        match err {}
+    }
+}
+
+#[cfg(feature = "hyper")]
+impl From<hyper::Error> for MulterError {
+    fn from(err: hyper::Error) -> Self {
+        ParseError::input(err).into()
+    }
 }
@@ -1,4 +1,4 @@
-use crate::config::{SelectedField, SelectedFieldKind};
+use crate::config::{FieldNameMatch, SelectedField, SelectedFieldKind};
 
 /// Multipart field model.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -28,6 +28,29 @@ impl Field {
         Self::Text(TextField::new(name))
     }
 
+    /// Sets how the field name is matched against incoming field names.
+    pub fn name_match(mut self, name_match: FieldNameMatch) -> Self {
+        match &mut self {
+            Self::File(field) => field.name_match = name_match,
+            Self::Text(field) => field.name_match = name_match,
+        }
+        self
+    }
+
+    /// Sets alternative names that map to this field.
+    pub fn aliases<I, A>(mut self, aliases: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: Into<String>,
+    {
+        let aliases = aliases.into_iter().map(Into::into).collect();
+        match &mut self {
+            Self::File(field) => field.aliases = aliases,
+            Self::Text(field) => field.aliases = aliases,
+        }
+        self
+    }
+
     /// Sets the maximum number of file parts accepted for this field.
     pub fn max_count(mut self, max_count: usize) -> Self {
         if let Self::File(field) = &mut self {
@@ -36,6 +59,14 @@ impl Field {
         self
     }
 
+    /// Sets the minimum number of file parts required for this field.
+    pub fn min_count(mut self, min_count: usize) -> Self {
+        if let Self::File(field) = &mut self {
+            field.min_count = Some(min_count);
+        }
+        self
+    }
+
     /// Sets MIME patterns accepted for this file field.
     pub fn allowed_mime_types<I, M>(mut self, patterns: I) -> Self
     where
@@ -48,10 +79,41 @@ impl Field {
         self
     }
 
-    /// Sets the maximum accepted text length in bytes for this text field.
+    /// Sets file name extensions accepted for this file field.
+    pub fn allowed_extensions<I, E>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<String>,
+    {
+        if let Self::File(field) = &mut self {
+            field.allowed_extensions = extensions.into_iter().map(Into::into).collect();
+        }
+        self
+    }
+
+    /// Sets the minimum accepted size in bytes for this file field.
+    pub fn min_size(mut self, min_size: u64) -> Self {
+        if let Self::File(field) = &mut self {
+            field.min_size = Some(min_size);
+        }
+        self
+    }
+
+    /// Marks this field as required.
+    pub fn required(mut self) -> Self {
+        match &mut self {
+            Self::File(field) => field.required = true,
+            Self::Text(field) => field.required = true,
+        }
+        self
+    }
+
+    /// Sets the maximum accepted size in bytes for this field, per file for
+    /// file fields.
     pub fn max_size(mut self, max_size: u64) -> Self {
-        if let Self::Text(field) = &mut self {
-            field.max_size = Some(max_size);
+        match &mut self {
+            Self::File(field) => field.max_size = Some(max_size),
+            Self::Text(field) => field.max_size = Some(max_size),
         }
         self
     }
@@ -85,12 +147,26 @@ pub enum FieldKind {
 /// File field metadata and constraints.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FileField {
-    /// Logical field name.
+    /// Logical field name, or a pattern depending on `name_match`.
     pub name: String,
+    /// How `name` is matched against incoming field names.
+    pub name_match: FieldNameMatch,
+    /// Alternative field names that map to this field.
+    pub aliases: Vec<String>,
     /// Maximum number of file parts accepted for this field.
     pub max_count: Option<usize>,
+    /// Minimum number of file parts required for this field.
+    pub min_count: Option<usize>,
+    /// Maximum accepted size in bytes for each file of this field.
+    pub max_size: Option<u64>,
+    /// Minimum accepted size in bytes for each file of this field.
+    pub min_size: Option<u64>,
+    /// Whether the request must contain this field.
+    pub required: bool,
     /// Allowed MIME patterns for this field.
     pub allowed_mime_types: Vec<String>,
+    /// Allowed file name extensions for this field.
+    pub allowed_extensions: Vec<String>,
 }
 
 impl FileField {
@@ -98,11 +174,48 @@ impl FileField {
     pub fn new(name: impl Into<String>) -> Self {
         Self {
             name: name.into(),
+            name_match: FieldNameMatch::Exact,
+            aliases: Vec::new(),
             max_count: None,
+            min_count: None,
+            max_size: None,
+            min_size: None,
+            required: false,
             allowed_mime_types: Vec::new(),
+            allowed_extensions: Vec::new(),
         }
     }
 
+    /// Sets how the field name is matched against incoming field names.
+    pub fn with_name_match(mut self, name_match: FieldNameMatch) -> Self {
+        self.name_match = name_match;
+        self
+    }
+
+    /// Alias for [`FileField::with_name_match`].
+    pub fn name_match(self, name_match: FieldNameMatch) -> Self {
+        self.with_name_match(name_match)
+    }
+
+    /// Sets alternative names that map to this field.
+    pub fn with_aliases<I, A>(mut self, aliases: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: Into<String>,
+    {
+        self.aliases = aliases.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Alias for [`FileField::with_aliases`].
+    pub fn aliases<I, A>(self, aliases: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: Into<String>,
+    {
+        self.with_aliases(aliases)
+    }
+
     /// Sets the maximum number of file parts for this field.
     pub fn with_max_count(mut self, max_count: usize) -> Self {
         self.max_count = Some(max_count);
@@ -114,6 +227,45 @@ impl FileField {
         self.with_max_count(max_count)
     }
 
+    /// Sets the minimum number of file parts required for this field.
+    pub fn with_min_count(mut self, min_count: usize) -> Self {
+        self.min_count = Some(min_count);
+        self
+    }
+
+    /// Alias for [`FileField::with_min_count`].
+    pub fn min_count(self, min_count: usize) -> Self {
+        self.with_min_count(min_count)
+    }
+
+    /// Sets the maximum size in bytes for each file of this field.
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Alias for [`FileField::with_max_size`].
+    pub fn max_size(self, max_size: u64) -> Self {
+        self.with_max_size(max_size)
+    }
+
+    /// Sets the minimum size in bytes for each file of this field.
+    pub fn with_min_size(mut self, min_size: u64) -> Self {
+        self.min_size = Some(min_size);
+        self
+    }
+
+    /// Alias for [`FileField::with_min_size`].
+    pub fn min_size(self, min_size: u64) -> Self {
+        self.with_min_size(min_size)
+    }
+
+    /// Marks this field as required.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
     /// Sets MIME patterns accepted for this file field.
     pub fn with_allowed_mime_types<I, M>(mut self, patterns: I) -> Self
     where
@@ -132,15 +284,40 @@ impl FileField {
     {
         self.with_allowed_mime_types(patterns)
     }
+
+    /// Sets file name extensions accepted for this file field.
+    pub fn with_allowed_extensions<I, E>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<String>,
+    {
+        self.allowed_extensions = extensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Alias for [`FileField::with_allowed_extensions`].
+    pub fn allowed_extensions<I, E>(self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<String>,
+    {
+        self.with_allowed_extensions(extensions)
+    }
 }
 
 /// Text field metadata and constraints.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TextField {
-    /// Logical field name.
+    /// Logical field name, or a pattern depending on `name_match`.
     pub name: String,
+    /// How `name` is matched against incoming field names.
+    pub name_match: FieldNameMatch,
+    /// Alternative field names that map to this field.
+    pub aliases: Vec<String>,
     /// Maximum accepted text size in bytes.
     pub max_size: Option<u64>,
+    /// Whether the request must contain this field.
+    pub required: bool,
 }
 
 impl TextField {
@@ -148,10 +325,43 @@ impl TextField {
     pub fn new(name: impl Into<String>) -> Self {
         Self {
             name: name.into(),
+            name_match: FieldNameMatch::Exact,
+            aliases: Vec::new(),
             max_size: None,
+            required: false,
         }
     }
 
+    /// Sets how the field name is matched against incoming field names.
+    pub fn with_name_match(mut self, name_match: FieldNameMatch) -> Self {
+        self.name_match = name_match;
+        self
+    }
+
+    /// Alias for [`TextField::with_name_match`].
+    pub fn name_match(self, name_match: FieldNameMatch) -> Self {
+        self.with_name_match(name_match)
+    }
+
+    /// Sets alternative names that map to this field.
+    pub fn with_aliases<I, A>(mut self, aliases: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: Into<String>,
+    {
+        self.aliases = aliases.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Alias for [`TextField::with_aliases`].
+    pub fn aliases<I, A>(self, aliases: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: Into<String>,
+    {
+        self.with_aliases(aliases)
+    }
+
     /// Sets the maximum text size in bytes for this field.
     pub fn with_max_size(mut self, max_size: u64) -> Self {
         self.max_size = Some(max_size);
@@ -163,6 +373,12 @@ impl TextField {
         self.with_max_size(max_size)
     }
 
+    /// Marks this field as required.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
     /// Backward-compatible alias for [`TextField::with_max_size`].
     pub fn with_max_length(self, max_length: usize) -> Self {
         self.with_max_size(max_length as u64)
@@ -182,10 +398,16 @@ impl From<FileField> for SelectedField {
     fn from(value: FileField) -> Self {
         SelectedField {
             name: value.name,
+            name_match: value.name_match,
+            aliases: value.aliases,
             kind: SelectedFieldKind::File,
             max_count: value.max_count,
-            max_size: None,
+            min_count: value.min_count,
+            max_size: value.max_size,
+            min_size: value.min_size,
+            required: value.required,
             allowed_mime_types: value.allowed_mime_types,
+            allowed_extensions: value.allowed_extensions,
         }
     }
 }
@@ -194,10 +416,16 @@ impl From<TextField> for SelectedField {
     fn from(value: TextField) -> Self {
         SelectedField {
             name: value.name,
+            name_match: value.name_match,
+            aliases: value.aliases,
             kind: SelectedFieldKind::Text,
             max_count: None,
+            min_count: None,
             max_size: value.max_size,
+            min_size: None,
+            required: value.required,
             allowed_mime_types: Vec::new(),
+            allowed_extensions: Vec::new(),
         }
     }
 }
@@ -0,0 +1,98 @@
+//! Async accept/skip/reject decisions for incoming file parts.
+
+use std::{fmt, future::Future, sync::Arc};
+
+use crate::storage::FileMeta;
+
+/// Outcome of a [`FileFilter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FilterDecision {
+    /// Hand the part on to the selector and limit checks.
+    Accept,
+    /// Drain the part silently and continue with the next one.
+    Skip,
+    /// Fail the request with [`MulterError::FileRejected`](crate::MulterError::FileRejected).
+    Reject {
+        /// Message reported to the caller.
+        message: String,
+    },
+}
+
+impl FilterDecision {
+    /// Creates a [`FilterDecision::Reject`] with a custom message.
+    pub fn reject(message: impl Into<String>) -> Self {
+        Self::Reject {
+            message: message.into(),
+        }
+    }
+}
+
+/// Async check deciding whether a file part is accepted, skipped, or
+/// rejected, like multer's `fileFilter`.
+///
+/// The filter registered with [`MulterBuilder::file_filter`](crate::MulterBuilder::file_filter)
+/// sees each file part's metadata before the selector accepts it, so skipped
+/// parts do not count toward selector or file limits. Text fields are not
+/// filtered.
+///
+/// Closures taking `&FileMeta` and returning a future implement this trait:
+///
+/// ```rust
+/// use multigear::{FileMeta, FilterDecision, MemoryStorage, Multer};
+///
+/// let multer = Multer::builder()
+///     .storage(MemoryStorage::new())
+///     .file_filter(|meta: &FileMeta| {
+///         let is_hidden = meta
+///             .file_name
+///             .as_deref()
+///             .is_some_and(|name| name.starts_with('.'));
+///         async move {
+///             if is_hidden {
+///                 FilterDecision::reject("hidden files are not accepted")
+///             } else {
+///                 FilterDecision::Accept
+///             }
+///         }
+///     })
+///     .build()
+///     .expect("valid config");
+/// # drop(multer);
+/// ```
+#[async_trait::async_trait]
+pub trait FileFilter: Send + Sync + 'static {
+    /// Decides what to do with a file part.
+    async fn filter(&self, meta: &FileMeta) -> FilterDecision;
+}
+
+#[async_trait::async_trait]
+impl<F, Fut> FileFilter for F
+where
+    F: Fn(&FileMeta) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = FilterDecision> + Send,
+{
+    async fn filter(&self, meta: &FileMeta) -> FilterDecision {
+        self(meta).await
+    }
+}
+
+/// File filter shared by a builder, the `Multer` it builds, and its parsers.
+#[derive(Clone)]
+pub(crate) struct SharedFileFilter(Arc<dyn FileFilter>);
+
+impl SharedFileFilter {
+    pub(crate) fn new(filter: impl FileFilter) -> Self {
+        Self(Arc::new(filter))
+    }
+
+    pub(crate) async fn filter(&self, meta: &FileMeta) -> FilterDecision {
+        self.0.filter(meta).await
+    }
+}
+
+impl fmt::Debug for SharedFileFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedFileFilter").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,110 @@
+use bytes::Bytes;
+use futures::Stream;
+
+pub use multimap::MultiMap;
+
+use crate::{MulterError, Multipart};
+
+/// File part read fully into memory by [`Multipart::collect_form`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InMemoryFile {
+    /// Multipart field name.
+    pub field_name: String,
+    /// Original filename from the multipart part, when present.
+    pub file_name: Option<String>,
+    /// Content type declared on the file part.
+    pub content_type: mime::Mime,
+    /// File contents.
+    pub data: Bytes,
+}
+
+impl InMemoryFile {
+    /// Returns the file size in bytes.
+    pub fn size(&self) -> u64 {
+        self.data.len() as u64
+    }
+}
+
+/// Unknown part buffered under [`UnknownFieldPolicy::Collect`](crate::UnknownFieldPolicy::Collect).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownPart {
+    /// Multipart field name.
+    pub field_name: String,
+    /// Original filename from the multipart part, when present.
+    pub file_name: Option<String>,
+    /// Content type declared on the part.
+    pub content_type: mime::Mime,
+    /// Part body.
+    pub data: Bytes,
+}
+
+/// Whole multipart form collected in memory by [`Multipart::collect_form`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormData {
+    /// File parts in request order.
+    pub files: Vec<InMemoryFile>,
+    /// Text field values keyed by field name, in request order per field.
+    pub fields: MultiMap<String, String>,
+}
+
+impl FormData {
+    /// Returns the first value of a text field, if present.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+
+    /// Returns the first file uploaded for a field, if present.
+    pub fn file(&self, name: &str) -> Option<&InMemoryFile> {
+        self.files.iter().find(|file| file.field_name == name)
+    }
+}
+
+impl<S, E> Multipart<S>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin + Send,
+    E: Into<MulterError>,
+{
+    /// Consumes the stream and collects every part into memory.
+    ///
+    /// No storage engine is involved: file bodies are buffered as [`Bytes`],
+    /// so this suits small forms. Configure [`Limits`](crate::Limits) to
+    /// bound the memory a single request can claim.
+    ///
+    /// ```rust
+    /// use futures::stream;
+    /// use multigear::{MulterError, Multipart};
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), MulterError> {
+    /// let body = concat!(
+    ///     "--X\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nhello\r\n",
+    ///     "--X\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n\r\nabc\r\n",
+    ///     "--X--\r\n",
+    /// );
+    /// let input = stream::iter([Ok::<_, MulterError>(bytes::Bytes::from(body))]);
+    /// let form = Multipart::new("X", input)?.collect_form().await?;
+    ///
+    /// assert_eq!(form.field("title"), Some("hello"));
+    /// assert_eq!(form.file("doc").map(|file| file.size()), Some(3));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn collect_form(mut self) -> Result<FormData, MulterError> {
+        let mut form = FormData::default();
+        while let Some(mut part) = self.next_part().await? {
+            if part.is_file() {
+                let data = part.bytes().await?;
+                form.files.push(InMemoryFile {
+                    field_name: part.headers.field_name,
+                    file_name: part.headers.file_name,
+                    content_type: part.headers.content_type,
+                    data,
+                });
+            } else {
+                let value = part.text().await?;
+                form.fields.insert(part.headers.field_name, value);
+            }
+        }
+        Ok(form)
+    }
+}
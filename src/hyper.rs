@@ -2,12 +2,12 @@
 
 use std::{future::Future, pin::Pin, sync::Arc};
 
-use bytes::Bytes;
-use futures::{Stream, StreamExt};
-use http_body_util::BodyExt;
-use hyper::{header, service::Service, Request, Response};
+use bytes::{Buf, Bytes};
+use futures::{future, Stream, StreamExt};
+use http_body_util::BodyStream;
+use hyper::{body::Body, header, service::Service, Request, Response};
 
-use crate::{parser, Multer, MulterError, ParseError, StorageEngine};
+use crate::{parser, Multer, MulterError, Multipart, ParseError, StorageEngine};
 
 /// Boxed error type used by [`MulterService`].
 pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
@@ -41,7 +41,7 @@ impl<S, H> MulterService<S, H> {
 impl<S, H, ReqBody, ResBody, Fut, E> Service<Request<ReqBody>> for MulterService<S, H>
 where
     S: StorageEngine,
-    ReqBody: hyper::body::Body<Data = Bytes> + Send + 'static,
+    ReqBody: Body<Data = Bytes> + Send + 'static,
     ReqBody::Error: std::error::Error + Send + Sync + 'static,
     H: Fn(Vec<S::Output>) -> Fut + Clone + Send + Sync + 'static,
     Fut: Future<Output = Result<Response<ResBody>, E>> + Send + 'static,
@@ -49,7 +49,10 @@ where
 {
     type Response = Response<ResBody>;
     type Error = BoxError;
+    #[cfg(not(feature = "unsend"))]
     type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+    #[cfg(feature = "unsend")]
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
 
     fn call(&self, request: Request<ReqBody>) -> Self::Future {
         let multer = Arc::clone(&self.multer);
@@ -67,11 +70,9 @@ where
                 .map_err(into_box_error)?;
 
             let mut saved_files = Vec::new();
-            while let Some(part) = multipart.next_part().await.map_err(into_box_error)? {
-                if part.file_name().is_some() {
-                    let stored = multer.store(part).await.map_err(into_box_error)?;
-                    saved_files.push(stored);
-                }
+            while let Some(part) = multipart.next_file().await.map_err(into_box_error)? {
+                let stored = multer.store(part).await.map_err(into_box_error)?;
+                saved_files.push(stored);
             }
 
             handler(saved_files).await.map_err(into_box_error)
@@ -90,23 +91,72 @@ pub fn content_type_from_request<B>(request: &Request<B>) -> Result<&str, Multer
         .map_err(|_| ParseError::new("Content-Type header must be ASCII").into())
 }
 
+/// Creates a [`Multipart`] stream directly from a Hyper request.
+///
+/// The boundary is taken from the request `Content-Type` header. Data frames
+/// are forwarded to the parser as they arrive, trailer frames are skipped, and
+/// body errors surface as [`MulterError::Parse`].
+///
+/// ```rust
+/// use bytes::Bytes;
+/// use http_body_util::Full;
+/// use multigear::hyper::multipart_from_request;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), multigear::MulterError> {
+/// let request = hyper::Request::builder()
+///     .header("content-type", "multipart/form-data; boundary=X")
+///     .body(Full::new(Bytes::from_static(
+///         b"--X\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nhi\r\n--X--\r\n",
+///     )))
+///     .expect("request");
+///
+/// let mut multipart = multipart_from_request(request)?;
+/// while let Some(mut part) = multipart.next_part().await? {
+///     assert_eq!(part.text().await?, "hi");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn multipart_from_request<B>(
+    request: Request<B>,
+) -> Result<Multipart<HyperBodyBoxStream>, MulterError>
+where
+    B: Body + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    let boundary = parser::extract_multipart_boundary(content_type_from_request(&request)?)?;
+    Ok(Multipart::new(
+        boundary,
+        map_body_stream(request.into_body()),
+    )?)
+}
+
 /// Maps a Hyper body into the stream shape expected by `multigear`.
+///
+/// Trailer frames are skipped and empty data frames are dropped.
 pub fn map_body_stream<B>(body: B) -> HyperBodyBoxStream
 where
-    B: hyper::body::Body<Data = Bytes> + Send + 'static,
-    B::Error: std::error::Error + Send + Sync + 'static,
+    B: Body + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
 {
-    let stream = body
-        .into_data_stream()
-        .map(hyper_item_to_multer::<B::Error>);
+    let stream = BodyStream::new(body).filter_map(|frame| {
+        future::ready(match frame {
+            Ok(frame) => frame
+                .into_data()
+                .ok()
+                .map(|mut data| Ok(data.copy_to_bytes(data.remaining())))
+                .filter(|chunk| !matches!(chunk, Ok(bytes) if bytes.is_empty())),
+            Err(err) => Some(Err(hyper_error_to_multer(err.into()))),
+        })
+    });
     Box::pin(stream)
 }
 
-fn hyper_item_to_multer<E>(item: Result<Bytes, E>) -> Result<Bytes, MulterError>
-where
-    E: std::error::Error + Send + Sync + 'static,
-{
-    item.map_err(|err| ParseError::new(format!("hyper body stream error: {err}")).into())
+fn hyper_error_to_multer(err: BoxError) -> MulterError {
+    ParseError::new(format!("hyper body stream error: {err}")).into()
 }
 
 fn into_box_error<E>(err: E) -> BoxError
@@ -0,0 +1,175 @@
+//! Image dimension and integrity checks for file parts.
+//!
+//! The `image-validation` feature needs Rust 1.88 or newer, above the crate's
+//! 1.75 MSRV, because of the `image` crate.
+
+use std::io::Cursor;
+
+use image::ImageReader;
+
+use crate::{error::ValidationError, storage::FileMeta, validation::PartValidator};
+
+/// Leading bytes read to find image dimensions when decoding is not verified.
+///
+/// Large enough for JPEG files that carry EXIF or ICC segments before the
+/// frame header.
+pub const DEFAULT_IMAGE_HEADER_LEN: usize = 64 * 1024;
+
+/// [`PartValidator`] that enforces image dimensions and, optionally, that the
+/// file fully decodes.
+///
+/// Applies to parts whose declared or sniffed content type is `image/*`;
+/// other parts pass untouched. PNG, JPEG, GIF and WebP are recognized.
+///
+/// ```rust
+/// use multigear::{image_validation::ImageValidator, MemoryStorage, Multer};
+///
+/// let multer = Multer::builder()
+///     .storage(MemoryStorage::new())
+///     .validator(
+///         ImageValidator::new()
+///             .max_width(4096)
+///             .max_height(4096)
+///             .max_pixels(16_000_000)
+///             .verify_decode(10 * 1024 * 1024),
+///     )
+///     .build()
+///     .expect("valid config");
+/// # drop(multer);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ImageValidator {
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+    max_pixels: Option<u64>,
+    verify_decode: Option<usize>,
+}
+
+impl ImageValidator {
+    /// Creates a validator that only requires a readable image header.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum image width in pixels.
+    pub fn max_width(mut self, max_width: u32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Sets the maximum image height in pixels.
+    pub fn max_height(mut self, max_height: u32) -> Self {
+        self.max_height = Some(max_height);
+        self
+    }
+
+    /// Sets the maximum pixel count (width times height).
+    ///
+    /// Guards against decompression bombs: small files declaring huge canvases.
+    pub fn max_pixels(mut self, max_pixels: u64) -> Self {
+        self.max_pixels = Some(max_pixels);
+        self
+    }
+
+    /// Requires every image to decode completely.
+    ///
+    /// Files are buffered for decoding, so images larger than `max_bytes`
+    /// are rejected. Decoding runs on Tokio's blocking thread pool.
+    pub fn verify_decode(mut self, max_bytes: usize) -> Self {
+        self.verify_decode = Some(max_bytes);
+        self
+    }
+
+    fn decode_limits(&self) -> image::Limits {
+        let mut limits = image::Limits::default();
+        limits.max_image_width = self.max_width;
+        limits.max_image_height = self.max_height;
+        limits
+    }
+
+    fn check_dimensions(&self, width: u32, height: u32) -> Result<(), ValidationError> {
+        if let Some(max_width) = self.max_width.filter(|max| width > *max) {
+            return Err(ValidationError::new(format!(
+                "image width {width} exceeds {max_width} pixels"
+            )));
+        }
+        if let Some(max_height) = self.max_height.filter(|max| height > *max) {
+            return Err(ValidationError::new(format!(
+                "image height {height} exceeds {max_height} pixels"
+            )));
+        }
+        let pixels = u64::from(width) * u64::from(height);
+        if let Some(max_pixels) = self.max_pixels.filter(|max| pixels > *max) {
+            return Err(ValidationError::new(format!(
+                "image has {pixels} pixels, more than {max_pixels}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl PartValidator for ImageValidator {
+    async fn validate(&self, meta: &FileMeta, head: &[u8]) -> Result<(), ValidationError> {
+        if !is_image(meta) {
+            return Ok(());
+        }
+
+        let (width, height) = reader(head, self.decode_limits())?
+            .into_dimensions()
+            .map_err(|err| ValidationError::new(format!("unreadable image header: {err}")))?;
+        self.check_dimensions(width, height)?;
+
+        let Some(max_bytes) = self.verify_decode else {
+            return Ok(());
+        };
+        if head.len() > max_bytes {
+            return Err(ValidationError::new(format!(
+                "image is larger than {max_bytes} bytes and cannot be verified"
+            )));
+        }
+
+        let body = head.to_vec();
+        let limits = self.decode_limits();
+        tokio::task::spawn_blocking(move || {
+            reader(&body, limits)?
+                .decode()
+                .map(drop)
+                .map_err(|err| ValidationError::new(format!("image does not decode: {err}")))
+        })
+        .await
+        .map_err(|err| ValidationError::new(format!("image decoding task failed: {err}")))?
+    }
+
+    fn head_len(&self) -> usize {
+        // One byte past the limit reveals files too large to verify.
+        self.verify_decode
+            .map_or(DEFAULT_IMAGE_HEADER_LEN, |max_bytes| {
+                max_bytes.saturating_add(1)
+            })
+    }
+}
+
+fn is_image(meta: &FileMeta) -> bool {
+    std::iter::once(meta.content_type.as_str())
+        .chain(meta.sniffed_content_type.as_deref())
+        .any(|content_type| {
+            content_type
+                .get(..6)
+                .is_some_and(|kind| kind.eq_ignore_ascii_case("image/"))
+        })
+}
+
+fn reader(
+    bytes: &[u8],
+    limits: image::Limits,
+) -> Result<ImageReader<Cursor<&[u8]>>, ValidationError> {
+    let mut reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|err| ValidationError::new(format!("unreadable image: {err}")))?;
+    if reader.format().is_none() {
+        return Err(ValidationError::new("unrecognized image format"));
+    }
+    reader.limits(limits);
+    Ok(reader)
+}
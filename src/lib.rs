@@ -6,7 +6,16 @@
 use bytes::Bytes;
 use futures::{Stream, StreamExt};
 use tokio::io::AsyncRead;
-use tokio_util::io::ReaderStream;
+use tokio_util::{io::ReaderStream, sync::CancellationToken};
+
+use crate::{
+    dedup::DuplicateTracker,
+    filter::SharedFileFilter,
+    report::{is_part_violation, is_request_end_violation},
+    scan::Scanner,
+    transform::TransformerChain,
+    validation::ValidatorChain,
+};
 
 /// Fluent builder API.
 pub mod builder;
@@ -16,6 +25,10 @@ pub mod config;
 pub mod error;
 /// Field selection and matching models.
 pub mod field;
+/// Async file part filtering.
+pub mod filter;
+/// In-memory form collection.
+pub mod form;
 /// Request and field limits.
 pub mod limits;
 /// High-level multipart stream type.
@@ -24,30 +37,82 @@ pub mod multipart;
 pub mod parser;
 /// Parsed multipart part API.
 pub mod part;
+/// Per-part violation reports.
+pub mod report;
+/// Antivirus scanning hook.
+pub mod scan;
 /// Runtime selector engine.
 pub mod selector;
 /// Storage engine traits and implementations.
 pub mod storage;
+/// Streaming file body transformers.
+pub mod transform;
+/// Pre-storage validation of file parts.
+pub mod validation;
+
+mod dedup;
+#[cfg(feature = "sniff")]
+mod sniff;
+#[cfg(feature = "json")]
+mod structured;
 
 #[cfg(feature = "actix")]
 pub mod actix;
 #[cfg(feature = "axum")]
 pub mod axum;
+#[cfg(feature = "checksum")]
+pub mod checksum;
+#[cfg(feature = "codec")]
+pub mod codec;
 #[cfg(feature = "hyper")]
 pub mod hyper;
+#[cfg(feature = "image-validation")]
+pub mod image_validation;
 
 pub use builder::MulterBuilder;
-pub use config::{MulterConfig, SelectedField, SelectedFieldKind, Selector, UnknownFieldPolicy};
-pub use error::{ConfigError, MulterError, ParseError, StorageError};
+pub use config::{
+    DuplicateFilePolicy, DuplicateHeaderPolicy, EncodedWordPolicy, EpiloguePolicy, FieldConstraint,
+    FieldNameMatch, LineEndingPolicy, MimeSniffPolicy, MulterConfig, ParserCompliance,
+    SelectedField, SelectedFieldKind, Selector, TransferEncodingPolicy, UnknownFieldPolicy,
+};
+pub use error::{ConfigError, MulterError, ParseError, ScanError, StorageError, ValidationError};
 pub use field::{Field, FieldKind, FileField, TextField};
-pub use limits::Limits;
-pub use multipart::Multipart;
+pub use filter::{FileFilter, FilterDecision};
+pub use form::{FormData, InMemoryFile, UnknownPart};
+pub use limits::{ByteSize, FilenameCharset, Limits};
+pub use multipart::{AbortHandle, DrainOnDrop, Multipart, MultipartEvent, PartGroup};
+pub use parser::ParserStats;
 pub use part::Part;
+pub use report::{PartViolation, ValidationReport};
+pub use scan::AvScanner;
 pub use selector::{SelectorAction, SelectorEngine};
+#[cfg(feature = "checksum")]
+pub use storage::DigestAlgorithm;
+#[cfg(feature = "azure")]
+pub use storage::{AzureBlobStorage, AzureBlobStorageBuilder, AzureCredential};
+pub use storage::{
+    BoxStream, DiscardStorage, DiskStorage, DiskStorageBuilder, FallbackFile, FallbackStorage,
+    FileMeta, FileMetadata, FilenamePolicy, FilenameStrategy, FnStorage, HybridFile, HybridStorage,
+    InstrumentedStorage, MemoryStorage, MetricsSink, MetricsSnapshot, NoopStorage, StorageEngine,
+    StorageEngineExt, StorageMetrics, StoreContext, StoreEvent, StoreOutcome, StoredFile,
+    TeeCleanup, TeeFile, TeeHalf, TeeStorage, ThrottledStorage, ThrottledStorageBuilder,
+};
+#[cfg(feature = "compression")]
 pub use storage::{
-    BoxStream, DiskStorage, DiskStorageBuilder, FileMeta, FilenameStrategy, MemoryStorage,
-    NoopStorage, StorageEngine, StoredFile,
+    CompressedFile, CompressedStorage, CompressedStorageBuilder, CompressionAlgorithm,
 };
+#[cfg(feature = "gridfs")]
+pub use storage::{GridFsStorage, GridFsStorageBuilder};
+#[cfg(feature = "sqlx-postgres")]
+pub use storage::{PostgresStorage, PostgresStorageBuilder};
+#[cfg(feature = "s3")]
+pub use storage::{S3Storage, S3StorageBuilder};
+#[cfg(feature = "sqlx-sqlite")]
+pub use storage::{SqliteStorage, SqliteStorageBuilder};
+#[cfg(feature = "tempfile")]
+pub use storage::{TempFile, TempfileStorage};
+pub use transform::PartTransformer;
+pub use validation::PartValidator;
 
 /// `AsyncRead` adapter stream used by [`Multer::parse_reader`].
 pub type AsyncReadStream<R> = futures::stream::Map<
@@ -64,12 +129,35 @@ pub fn extract_boundary(content_type: &str) -> Result<String, ParseError> {
 }
 
 /// Processed multipart output returned by [`Multer::parse_and_store`].
+///
+/// New fields may be added; outside the crate start from
+/// [`ProcessedMultipart::default`] and set fields afterwards.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct ProcessedMultipart<O = StoredFile> {
     /// File parts persisted through the configured storage engine.
     pub stored_files: Vec<O>,
     /// Text field values collected from the stream.
     pub text_fields: Vec<(String, String)>,
+    /// Unknown parts buffered under [`UnknownFieldPolicy::Collect`].
+    pub unknown_parts: Vec<UnknownPart>,
+    /// Files discarded under [`DuplicateFilePolicy::Skip`].
+    pub duplicates: Vec<DuplicateFile>,
+    /// Parts skipped under [`MulterConfig::collect_violations`].
+    pub report: ValidationReport,
+}
+
+/// File discarded because its body repeated an earlier file in the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateFile {
+    /// Field name of the discarded file.
+    pub field_name: String,
+    /// Original filename of the discarded file, when present.
+    pub file_name: Option<String>,
+    /// Field name of the earlier file with the same content.
+    pub original_field_name: String,
+    /// Original filename of the earlier file, when present.
+    pub original_file_name: Option<String>,
 }
 
 impl<O> Default for ProcessedMultipart<O> {
@@ -77,15 +165,60 @@ impl<O> Default for ProcessedMultipart<O> {
         Self {
             stored_files: Vec::new(),
             text_fields: Vec::new(),
+            unknown_parts: Vec::new(),
+            duplicates: Vec::new(),
+            report: ValidationReport::default(),
         }
     }
 }
 
+#[cfg(feature = "json")]
+impl<O> ProcessedMultipart<O> {
+    /// Returns the text fields as nested JSON, reading qs-style bracket
+    /// notation in field names.
+    ///
+    /// `user[address][street]` nests objects, `tags[]` appends to an array,
+    /// and numeric indexes (`items[0][name]`) build arrays ordered by index.
+    /// Repeated plain names collect into an array. Values stay strings, so
+    /// deserialize into structs whose leaf fields are strings or use
+    /// string-parsing helpers.
+    ///
+    /// ```rust
+    /// use multigear::ProcessedMultipart;
+    ///
+    /// let mut processed = ProcessedMultipart::<()>::default();
+    /// processed.text_fields = vec![
+    ///     ("user[name]".to_owned(), "Ada".to_owned()),
+    ///     ("user[address][city]".to_owned(), "London".to_owned()),
+    ///     ("tags[]".to_owned(), "math".to_owned()),
+    /// ];
+    ///
+    /// assert_eq!(
+    ///     processed.structured_fields(),
+    ///     serde_json::json!({
+    ///         "user": { "name": "Ada", "address": { "city": "London" } },
+    ///         "tags": ["math"],
+    ///     })
+    /// );
+    /// ```
+    pub fn structured_fields(&self) -> serde_json::Value {
+        structured::structure_fields(
+            self.text_fields
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        )
+    }
+}
+
 /// Main `multigear` entry point.
 #[derive(Debug)]
 pub struct Multer<S = NoopStorage> {
     config: MulterConfig,
     storage: S,
+    validators: ValidatorChain,
+    scanner: Option<Scanner>,
+    file_filter: Option<SharedFileFilter>,
+    transformers: TransformerChain,
 }
 
 impl<S> Multer<S> {
@@ -101,13 +234,44 @@ impl<S> Multer<S> {
         Self {
             config: MulterConfig::default(),
             storage,
+            validators: ValidatorChain::default(),
+            scanner: None,
+            file_filter: None,
+            transformers: TransformerChain::default(),
         }
     }
 
     /// Creates a new multer instance with explicit validated configuration.
     pub fn with_config(storage: S, config: MulterConfig) -> Result<Self, ConfigError> {
         config.validate()?;
-        Ok(Self { config, storage })
+        Ok(Self {
+            config,
+            storage,
+            validators: ValidatorChain::default(),
+            scanner: None,
+            file_filter: None,
+            transformers: TransformerChain::default(),
+        })
+    }
+
+    pub(crate) fn with_validators(mut self, validators: ValidatorChain) -> Self {
+        self.validators = validators;
+        self
+    }
+
+    pub(crate) fn with_scanner(mut self, scanner: Option<Scanner>) -> Self {
+        self.scanner = scanner;
+        self
+    }
+
+    pub(crate) fn with_file_filter(mut self, file_filter: Option<SharedFileFilter>) -> Self {
+        self.file_filter = file_filter;
+        self
+    }
+
+    pub(crate) fn with_transformers(mut self, transformers: TransformerChain) -> Self {
+        self.transformers = transformers;
+        self
     }
 
     /// Returns an immutable reference to the active configuration.
@@ -126,46 +290,206 @@ where
     S: StorageEngine,
 {
     /// Stores a file part through the configured storage backend.
-    pub async fn store(&self, mut part: Part<'_>) -> Result<S::Output, MulterError> {
-        let field_name = part.field_name().to_owned();
-        let file_name = part.file_name().map(ToOwned::to_owned);
-        let content_type = part.content_type().to_string();
+    ///
+    /// Validators registered with [`MulterBuilder::validator`] run first; a
+    /// rejection fails with [`MulterError::Validation`] before storage sees
+    /// any bytes. A scanner registered with [`MulterBuilder::scanner`] sees
+    /// the body as it is stored and can veto it with
+    /// [`MulterError::ScanRejected`], aborting the file if the engine already
+    /// stored it. With the `checksum` feature, parts
+    /// carrying `Content-MD5` or `X-Checksum-SHA256` headers fail with
+    /// [`MulterError::ChecksumMismatch`] when the received bytes do not match.
+    /// Transformers registered with [`MulterBuilder::transformer`] then
+    /// rewrite the body on its way to the scanner and storage.
+    pub async fn store(&self, part: Part<'_>) -> Result<S::Output, MulterError> {
+        self.store_part(part, None, &StoreContext::new()).await
+    }
+
+    /// Returns a handle storing uploads with `ctx` passed to the storage
+    /// engine.
+    ///
+    /// Build one per request to hand request-scoped values, such as the
+    /// authenticated user or tenant, to [`StorageEngine::store`].
+    ///
+    /// ```rust
+    /// use bytes::Bytes;
+    /// use futures::stream;
+    /// use multigear::{MemoryStorage, Multer, MulterError, StoreContext};
+    ///
+    /// #[derive(Clone)]
+    /// struct UserId(u64);
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), MulterError> {
+    /// let multer = Multer::new(MemoryStorage::new());
+    /// let body = "--X\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n\r\nhello\r\n--X--\r\n";
+    /// let output = multer
+    ///     .with_context(StoreContext::new().with(UserId(7)))
+    ///     .parse_and_store("X", stream::iter([Ok::<_, MulterError>(Bytes::from(body))]))
+    ///     .await?;
+    ///
+    /// assert_eq!(output.stored_files.len(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_context(&self, ctx: StoreContext) -> WithContext<'_, S> {
+        WithContext { multer: self, ctx }
+    }
+
+    async fn store_part(
+        &self,
+        mut part: Part<'_>,
+        duplicates: Option<&DuplicateTracker>,
+        ctx: &StoreContext,
+    ) -> Result<S::Output, MulterError> {
+        let mut meta = FileMeta {
+            field_name: part.field_name().to_owned(),
+            file_name: part.file_name().map(ToOwned::to_owned),
+            content_type: part.content_type().to_string(),
+            size_hint: part.size_hint(),
+            sniffed_content_type: None,
+            headers: part.headers().clone(),
+        };
+
+        #[cfg(feature = "sniff")]
+        {
+            let head = part.peek(sniff::SNIFF_LEN).await?;
+            sniff::apply_mime_sniffing(
+                self.config.mime_sniff_policy,
+                &self.config.limits,
+                &mut meta,
+                &head,
+            )?;
+        }
+
+        if !self.validators.is_empty() {
+            let head = part.peek(self.validators.head_len()).await?;
+            if let Err(source) = self.validators.run(&meta, &head).await {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    field_name = meta.field_name.as_str(),
+                    error = %source,
+                    "multer: part rejected by validator"
+                );
+                return Err(MulterError::Validation {
+                    field: meta.field_name,
+                    source,
+                });
+            }
+        }
+        #[cfg(feature = "checksum")]
+        let checksums = checksum::ExpectedChecksums::from_headers(part.headers())?;
         let stream = part.stream();
+        #[cfg(feature = "checksum")]
+        let stream: BoxStream<'_, Result<Bytes, MulterError>> =
+            Box::pin(checksums.verify(Some(meta.field_name.clone()), stream));
+        let stream = match duplicates {
+            Some(tracker) => tracker.watch(&meta, stream),
+            None => stream,
+        };
+        let stream = self.transformers.apply(&mut meta, stream);
 
         #[cfg(feature = "tracing")]
         tracing::debug!(
-            field_name = field_name.as_str(),
-            file_name = file_name.as_deref().unwrap_or("<none>"),
-            content_type = content_type.as_str(),
+            field_name = meta.field_name.as_str(),
+            file_name = meta.file_name.as_deref().unwrap_or("<none>"),
+            content_type = meta.content_type.as_str(),
             "multer: dispatching part to storage engine"
         );
 
+        match &self.scanner {
+            Some(scanner) => {
+                let (stored, rejection) = scanner
+                    .scan_while_storing(meta.clone(), stream, |stream| {
+                        self.store_stream(meta, ctx, stream)
+                    })
+                    .await;
+                match rejection {
+                    Some(err) => {
+                        // An engine that stops reading early can finish
+                        // storing before the verdict arrives.
+                        if let Ok(output) = stored {
+                            self.abort(output).await;
+                        }
+                        Err(err)
+                    }
+                    None => stored,
+                }
+            }
+            None => self.store_stream(meta, ctx, stream).await,
+        }
+    }
+
+    async fn store_stream(
+        &self,
+        meta: FileMeta,
+        ctx: &StoreContext,
+        stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<S::Output, MulterError> {
         self.storage
-            .store(&field_name, file_name.as_deref(), &content_type, stream)
+            .store(meta, ctx, stream)
             .await
-            .map_err(|err| MulterError::Storage(StorageError::new(err.to_string())))
+            .map_err(|err| MulterError::from_storage(Box::new(err)))
     }
 
     /// Creates a configured multipart parser from a raw multipart boundary.
-    pub fn multipart_from_boundary<T>(
+    pub fn multipart_from_boundary<T, E>(
         &self,
         boundary: impl Into<String>,
         stream: T,
     ) -> Result<Multipart<T>, MulterError>
     where
-        T: Stream<Item = Result<Bytes, MulterError>> + Unpin,
+        T: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: Into<MulterError>,
     {
         Multipart::with_config(boundary, stream, self.config.clone())
+            .map(|multipart| multipart.with_file_filter(self.file_filter.clone()))
+    }
+
+    /// Creates a multipart parser from a raw boundary with per-request limit overrides.
+    ///
+    /// `configure` receives a copy of the shared limits; the adjusted limits
+    /// apply to this request only and are validated like any other config.
+    ///
+    /// ```rust
+    /// use bytes::Bytes;
+    /// use futures::stream;
+    /// use multigear::{MemoryStorage, Multer, MulterError};
+    ///
+    /// let multer = Multer::new(MemoryStorage::new());
+    /// let body = stream::iter([Ok::<_, MulterError>(Bytes::new())]);
+    /// let multipart = multer
+    ///     .multipart_from_boundary_with("BOUND", body, |limits| limits.max_file_size(1024))
+    ///     .expect("limits should validate");
+    /// # drop(multipart);
+    /// assert_eq!(multer.config().limits.max_file_size, None);
+    /// ```
+    pub fn multipart_from_boundary_with<T, E, F>(
+        &self,
+        boundary: impl Into<String>,
+        stream: T,
+        configure: F,
+    ) -> Result<Multipart<T>, MulterError>
+    where
+        T: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: Into<MulterError>,
+        F: FnOnce(Limits) -> Limits,
+    {
+        let mut config = self.config.clone();
+        config.limits = configure(config.limits);
+        Multipart::with_config(boundary, stream, config)
+            .map(|multipart| multipart.with_file_filter(self.file_filter.clone()))
     }
 
     /// Creates a configured multipart parser from an HTTP `Content-Type` value.
-    pub fn multipart_from_content_type<T>(
+    pub fn multipart_from_content_type<T, E>(
         &self,
         content_type: &str,
         stream: T,
     ) -> Result<Multipart<T>, MulterError>
     where
-        T: Stream<Item = Result<Bytes, MulterError>> + Unpin,
+        T: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: Into<MulterError>,
     {
         let boundary = parser::extract_multipart_boundary(content_type)?;
         self.multipart_from_boundary(boundary, stream)
@@ -215,6 +539,9 @@ where
 
     /// Parses multipart input and stores all file parts using the active storage backend.
     ///
+    /// When the request fails, files already stored for it are handed to
+    /// [`StorageEngine::abort`] so nothing is left behind.
+    ///
     /// ```rust
     /// use bytes::Bytes;
     /// use futures::stream;
@@ -242,26 +569,157 @@ where
     /// assert_eq!(output.stored_files.len(), 1);
     /// # }
     /// ```
-    pub async fn parse_and_store<T>(
+    pub async fn parse_and_store<T, E>(
         &self,
         boundary: impl Into<String>,
         stream: T,
     ) -> Result<ProcessedMultipart<S::Output>, MulterError>
     where
-        T: Stream<Item = Result<Bytes, MulterError>> + Unpin + Send,
+        T: Stream<Item = Result<Bytes, E>> + Unpin + Send,
+        E: Into<MulterError>,
+    {
+        let multipart = self.multipart_from_boundary(boundary, stream)?;
+        self.store_all(multipart, &StoreContext::new()).await
+    }
+
+    /// Like [`Multer::parse_and_store`], but stops once `token` is cancelled.
+    ///
+    /// Cancellation fails with [`MulterError::Cancelled`] and aborts any
+    /// in-flight storage write; [`DiskStorage`] removes the partial file.
+    pub async fn parse_and_store_with_cancellation<T, E>(
+        &self,
+        boundary: impl Into<String>,
+        stream: T,
+        token: CancellationToken,
+    ) -> Result<ProcessedMultipart<S::Output>, MulterError>
+    where
+        T: Stream<Item = Result<Bytes, E>> + Unpin + Send,
+        E: Into<MulterError>,
+    {
+        self.store_all_with_cancellation(boundary, stream, token, &StoreContext::new())
+            .await
+    }
+
+    async fn store_all_with_cancellation<T, E>(
+        &self,
+        boundary: impl Into<String>,
+        stream: T,
+        token: CancellationToken,
+        ctx: &StoreContext,
+    ) -> Result<ProcessedMultipart<S::Output>, MulterError>
+    where
+        T: Stream<Item = Result<Bytes, E>> + Unpin + Send,
+        E: Into<MulterError>,
+    {
+        let multipart = self
+            .multipart_from_boundary(boundary, stream)?
+            .with_cancellation(token.clone());
+
+        // Storage backends report the interrupted body read as their own error.
+        self.store_all(multipart, ctx).await.map_err(|err| {
+            if token.is_cancelled() {
+                MulterError::Cancelled
+            } else {
+                err
+            }
+        })
+    }
+
+    async fn store_all<T, E>(
+        &self,
+        mut multipart: Multipart<T>,
+        ctx: &StoreContext,
+    ) -> Result<ProcessedMultipart<S::Output>, MulterError>
+    where
+        T: Stream<Item = Result<Bytes, E>> + Unpin + Send,
+        E: Into<MulterError>,
     {
-        let mut multipart = self.multipart_from_boundary(boundary, stream)?;
         let mut out = ProcessedMultipart::default();
+        if let Err(err) = self.store_parts(&mut multipart, ctx, &mut out).await {
+            self.abort_all(out.stored_files).await;
+            return Err(err);
+        }
+        out.unknown_parts = multipart.take_unknown_parts();
+        Ok(out)
+    }
+
+    async fn store_parts<T, E>(
+        &self,
+        multipart: &mut Multipart<T>,
+        ctx: &StoreContext,
+        out: &mut ProcessedMultipart<S::Output>,
+    ) -> Result<(), MulterError>
+    where
+        T: Stream<Item = Result<Bytes, E>> + Unpin + Send,
+        E: Into<MulterError>,
+    {
+        let policy = self.config.duplicate_file_policy;
+        let duplicates = (policy != DuplicateFilePolicy::Allow).then(DuplicateTracker::default);
+        let collect = self.config.collect_violations;
 
-        while let Some(mut part) = multipart.next_part().await? {
-            if part.file_name().is_some() {
+        loop {
+            let mut part = match multipart.next_part().await {
+                Ok(Some(part)) => part,
+                Ok(None) => break,
+                // These repeat on every later call, so the request ends here.
+                Err(err) if collect && is_request_end_violation(&err) => {
+                    out.report.push(PartViolation::new(err));
+                    break;
+                }
+                Err(err) if collect && is_part_violation(&err) => {
+                    out.report.push(PartViolation::new(err));
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            if part.is_file() {
                 #[cfg(feature = "tracing")]
                 tracing::trace!(field_name = part.field_name(), "multer: storing file part");
-                let stored = self.store(part).await?;
-                out.stored_files.push(stored);
+                let file_name = part.file_name().map(ToOwned::to_owned);
+                let err = match self.store_part(part, duplicates.as_ref(), ctx).await {
+                    Ok(stored) => {
+                        out.stored_files.push(stored);
+                        continue;
+                    }
+                    Err(err) => err,
+                };
+                match err {
+                    MulterError::DuplicateFile {
+                        field,
+                        file_name,
+                        original_field,
+                        original_file_name,
+                    } if policy == DuplicateFilePolicy::Skip => {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(
+                            field_name = field.as_str(),
+                            original_field_name = original_field.as_str(),
+                            "multer: skipped duplicate file"
+                        );
+                        out.duplicates.push(DuplicateFile {
+                            field_name: field,
+                            file_name,
+                            original_field_name: original_field,
+                            original_file_name,
+                        });
+                    }
+                    err if collect && is_part_violation(&err) => {
+                        out.report
+                            .push(PartViolation::new(err).with_file_name(file_name));
+                    }
+                    err => return Err(err),
+                }
             } else {
                 let field_name = part.field_name().to_owned();
-                let text = part.text().await?;
+                let text = match part.text().await {
+                    Ok(text) => text,
+                    Err(err) if collect && is_part_violation(&err) => {
+                        out.report.push(PartViolation::new(err));
+                        continue;
+                    }
+                    Err(err) => return Err(err),
+                };
                 #[cfg(feature = "tracing")]
                 tracing::trace!(
                     field_name = field_name.as_str(),
@@ -270,8 +728,80 @@ where
                 out.text_fields.push((field_name, text));
             }
         }
+        Ok(())
+    }
 
-        Ok(out)
+    /// Hands files stored for a failed request back to the storage engine.
+    async fn abort_all(&self, outputs: Vec<S::Output>) {
+        for output in outputs {
+            self.abort(output).await;
+        }
+    }
+
+    async fn abort(&self, output: S::Output) {
+        if let Err(err) = self.storage.abort(output).await {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %err, "multer: failed to abort stored file");
+            #[cfg(not(feature = "tracing"))]
+            let _ = err;
+        }
+    }
+}
+
+/// Handle returned by [`Multer::with_context`] storing uploads with a
+/// per-request [`StoreContext`].
+#[derive(Debug)]
+pub struct WithContext<'a, S> {
+    multer: &'a Multer<S>,
+    ctx: StoreContext,
+}
+
+impl<S> WithContext<'_, S> {
+    /// Returns the context passed to the storage engine.
+    pub fn context(&self) -> &StoreContext {
+        &self.ctx
+    }
+}
+
+impl<S> WithContext<'_, S>
+where
+    S: StorageEngine,
+{
+    /// Like [`Multer::store`], passing the context to the storage engine.
+    pub async fn store(&self, part: Part<'_>) -> Result<S::Output, MulterError> {
+        self.multer.store_part(part, None, &self.ctx).await
+    }
+
+    /// Like [`Multer::parse_and_store`], passing the context to the storage
+    /// engine.
+    pub async fn parse_and_store<T, E>(
+        &self,
+        boundary: impl Into<String>,
+        stream: T,
+    ) -> Result<ProcessedMultipart<S::Output>, MulterError>
+    where
+        T: Stream<Item = Result<Bytes, E>> + Unpin + Send,
+        E: Into<MulterError>,
+    {
+        let multipart = self.multer.multipart_from_boundary(boundary, stream)?;
+        self.multer.store_all(multipart, &self.ctx).await
+    }
+
+    /// Like [`Multer::parse_and_store_with_cancellation`], passing the context
+    /// to the storage engine.
+    pub async fn parse_and_store_with_cancellation<T, E>(
+        &self,
+        boundary: impl Into<String>,
+        stream: T,
+        token: CancellationToken,
+    ) -> Result<ProcessedMultipart<S::Output>, MulterError>
+    where
+        T: Stream<Item = Result<Bytes, E>> + Unpin + Send,
+        E: Into<MulterError>,
+    {
+        self.multer
+            .store_all_with_cancellation(boundary, stream, token, &self.ctx)
+            .await
     }
 }
 
@@ -1,19 +1,326 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use crate::error::ConfigError;
+
+/// A size in bytes parsed from a human-readable string such as `"64MB"` or `"1GiB"`.
+///
+/// Decimal units (`KB`, `MB`, `GB`, `TB`) are powers of 1000 and binary units
+/// (`KiB`, `MiB`, `GiB`, `TiB`) powers of 1024. Units are case-insensitive,
+/// may follow the number after whitespace, and a bare number is a byte count.
+/// Fractions such as `"1.5GB"` are rounded down to whole bytes.
+///
+/// ```rust
+/// use multigear::limits::ByteSize;
+///
+/// assert_eq!("64MB".parse::<ByteSize>().unwrap().as_u64(), 64_000_000);
+/// assert_eq!("1 GiB".parse::<ByteSize>().unwrap().as_u64(), 1 << 30);
+/// assert_eq!("512".parse::<ByteSize>().unwrap().as_u64(), 512);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    /// Returns the size in bytes.
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(bytes: u64) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> Self {
+        size.0
+    }
+}
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}B", self.0)
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = ConfigError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || ConfigError::InvalidByteSize {
+            value: value.to_owned(),
+        };
+
+        let trimmed = value.trim();
+        let split = trimmed
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(trimmed.len());
+        let (number, unit) = trimmed.split_at(split);
+        let multiplier: u128 = match unit.trim_start().to_ascii_lowercase().as_str() {
+            "" | "b" => 1,
+            "k" | "kb" => 1_000,
+            "m" | "mb" => 1_000_000,
+            "g" | "gb" => 1_000_000_000,
+            "t" | "tb" => 1_000_000_000_000,
+            "kib" => 1 << 10,
+            "mib" => 1 << 20,
+            "gib" => 1 << 30,
+            "tib" => 1 << 40,
+            _ => return Err(invalid()),
+        };
+
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if (whole.is_empty() && fraction.is_empty()) || fraction.contains('.') {
+            return Err(invalid());
+        }
+        let whole: u128 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().map_err(|_| invalid())?
+        };
+        let mut bytes = whole.checked_mul(multiplier).ok_or_else(invalid)?;
+        if !fraction.is_empty() {
+            let scale = 10u128
+                .checked_pow(u32::try_from(fraction.len()).map_err(|_| invalid())?)
+                .ok_or_else(invalid)?;
+            let fraction: u128 = fraction.parse().map_err(|_| invalid())?;
+            bytes = fraction
+                .checked_mul(multiplier)
+                .map(|scaled| scaled / scale)
+                .and_then(|scaled| bytes.checked_add(scaled))
+                .ok_or_else(invalid)?;
+        }
+
+        u64::try_from(bytes).map(Self).map_err(|_| invalid())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ByteSize {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ByteSize {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ByteSizeVisitor;
+
+        impl serde::de::Visitor<'_> for ByteSizeVisitor {
+            type Value = ByteSize;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a byte count or a size string such as \"64MB\"")
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, bytes: u64) -> Result<ByteSize, E> {
+                Ok(ByteSize(bytes))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, bytes: i64) -> Result<ByteSize, E> {
+                u64::try_from(bytes)
+                    .map(ByteSize)
+                    .map_err(|_| E::custom("byte size cannot be negative"))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<ByteSize, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(ByteSizeVisitor)
+    }
+}
+
+/// Serde adapter for optional byte limits that also accept size strings.
+#[cfg(feature = "serde")]
+pub(crate) mod serde_byte_size {
+    use serde::{Deserialize, Deserializer};
+
+    use super::ByteSize;
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<ByteSize>::deserialize(deserializer)?.map(ByteSize::as_u64))
+    }
+}
+
+/// Characters accepted in file part filenames.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum FilenameCharset {
+    /// Accept any characters.
+    #[default]
+    Any,
+    /// Reject control characters such as `\0`, `\n`, or `\u{7f}`.
+    NoControl,
+    /// Accept only ASCII letters, digits, spaces, `.`, `_`, and `-`.
+    Portable,
+}
+
+impl FilenameCharset {
+    /// Returns `true` when every character of `file_name` is accepted.
+    pub fn allows(self, file_name: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::NoControl => !file_name.chars().any(char::is_control),
+            Self::Portable => file_name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '.' | '_' | '-')),
+        }
+    }
+}
+
+/// Compound archive extensions commonly allowed under
+/// [`Limits::reject_double_extensions`].
+///
+/// ```rust
+/// use multigear::{limits::DEFAULT_ALLOWED_DOUBLE_EXTENSIONS, Limits};
+///
+/// let limits = Limits::new()
+///     .reject_double_extensions(true)
+///     .allowed_double_extensions(DEFAULT_ALLOWED_DOUBLE_EXTENSIONS.iter().copied());
+/// assert!(limits.allowed_double_extensions.contains(&"tar.gz".to_owned()));
+/// ```
+pub const DEFAULT_ALLOWED_DOUBLE_EXTENSIONS: &[&str] = &["tar.gz", "tar.bz2", "tar.xz", "tar.zst"];
+
 /// Request and field limits enforced during multipart parsing.
+///
+/// With the `serde` feature, omitted fields keep their default values and
+/// byte limits accept either a number or a [`ByteSize`] string.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
 pub struct Limits {
     /// Maximum accepted file size in bytes for a single file part.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "serde_byte_size::deserialize")
+    )]
     pub max_file_size: Option<u64>,
+    /// Minimum accepted file size in bytes for a single file part.
+    ///
+    /// Checked once the part body ends, so undersized uploads fail before
+    /// storage finalizes them.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "serde_byte_size::deserialize")
+    )]
+    pub min_file_size: Option<u64>,
     /// Maximum total number of file parts in a request.
     pub max_files: Option<usize>,
     /// Maximum accepted size in bytes for a text field.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "serde_byte_size::deserialize")
+    )]
     pub max_field_size: Option<u64>,
     /// Maximum number of text fields in a request.
     pub max_fields: Option<usize>,
+    /// Maximum total number of parts (files and fields) in a request.
+    pub max_parts: Option<usize>,
     /// Maximum request body size in bytes.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "serde_byte_size::deserialize")
+    )]
     pub max_body_size: Option<u64>,
+    /// Maximum combined size in bytes of all accepted file parts.
+    ///
+    /// Unlike [`Limits::max_body_size`], text fields, part headers and
+    /// boundaries do not count. Ignored or skipped file parts are not counted.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "serde_byte_size::deserialize")
+    )]
+    pub max_total_file_size: Option<u64>,
+    /// Maximum size in bytes of a single part's header block.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "serde_byte_size::deserialize")
+    )]
+    pub max_header_size: Option<u64>,
+    /// Maximum number of headers accepted on a single part.
+    pub max_headers_per_part: Option<usize>,
+    /// Maximum decompressed size in bytes of a single `Content-Encoding` part.
+    ///
+    /// Only enforced with the `decompression` feature.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "serde_byte_size::deserialize")
+    )]
+    pub max_decompressed_size: Option<u64>,
+    /// Maximum time from the first read until the request is fully parsed.
+    pub max_parse_duration: Option<Duration>,
+    /// Maximum time to wait for the next body chunk from the client.
+    pub idle_chunk_timeout: Option<Duration>,
+    /// Maximum average rate in bytes per second at which the body is read.
+    ///
+    /// The parser pauses reading from upstream while it is ahead of this
+    /// rate. Paused time counts toward [`Limits::max_parse_duration`] but not
+    /// [`Limits::idle_chunk_timeout`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "serde_byte_size::deserialize")
+    )]
+    pub max_bytes_per_second: Option<u64>,
+    /// Minimum average rate in bytes per second at which the client must send the body.
+    ///
+    /// Measured over the time the parser spends waiting on the client, so a
+    /// slow consumer is not held against it. Once the waiting time exceeds
+    /// [`Limits::min_throughput_grace`] and the bytes received so far, the
+    /// request fails with [`MulterError::ThroughputTooLow`](crate::MulterError::ThroughputTooLow).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "serde_byte_size::deserialize")
+    )]
+    pub min_throughput_bytes_per_sec: Option<u64>,
+    /// Waiting time allowed before [`Limits::min_throughput_bytes_per_sec`] applies.
+    ///
+    /// Defaults to
+    /// [`DEFAULT_MIN_THROUGHPUT_GRACE`](crate::parser::stream::DEFAULT_MIN_THROUGHPUT_GRACE).
+    pub min_throughput_grace: Option<Duration>,
     /// Allowed MIME patterns (for example: `image/png`, `image/*`).
     pub allowed_mime_types: Vec<String>,
+    /// Denied MIME patterns, checked before any allowlist.
+    ///
+    /// A file part whose type matches is rejected even when an allowlist
+    /// would accept it.
+    pub denied_mime_types: Vec<String>,
+    /// Allowed file name extensions (for example: `png`, `.jpg`).
+    ///
+    /// Compared case-insensitively against the sanitized file name of every
+    /// file part. Per-field extension lists apply in addition to this one.
+    pub allowed_extensions: Vec<String>,
+    /// Rejects file names with a double extension such as `invoice.pdf.exe`
+    /// or `photo.jpg.php`.
+    ///
+    /// A name counts as a double extension when the segment before its
+    /// extension is itself extension-like: one to four ASCII letters and
+    /// digits, at least one a letter. `report.final.pdf` and `v1.2.zip` pass.
+    pub reject_double_extensions: bool,
+    /// Compound extensions accepted under [`Limits::reject_double_extensions`]
+    /// (for example: `tar.gz`, `.tar.bz2`).
+    ///
+    /// See [`DEFAULT_ALLOWED_DOUBLE_EXTENSIONS`] for common archive formats.
+    pub allowed_double_extensions: Vec<String>,
+    /// Maximum filename length in characters for a file part.
+    pub max_filename_length: Option<usize>,
+    /// Characters accepted in file part filenames.
+    pub filename_charset: FilenameCharset,
+    /// Rejects file parts with an empty filename and an empty body.
+    ///
+    /// Browsers submit such parts for file inputs left blank. Takes
+    /// precedence over [`Limits::skip_empty_files`].
+    pub reject_empty_files: bool,
+    /// Silently skips file parts with an empty filename and an empty body.
+    pub skip_empty_files: bool,
 }
 
 impl Limits {
@@ -22,6 +329,233 @@ impl Limits {
         Self::default()
     }
 
+    /// Reads limits from `{prefix}_*` environment variables.
+    ///
+    /// Each field maps to its upper-cased name, for example
+    /// `MULTIGEAR_MAX_FILE_SIZE` for the prefix `MULTIGEAR`. Unset variables
+    /// keep their defaults. Values are parsed as follows:
+    ///
+    /// - byte limits accept a number or a [`ByteSize`] string such as `64MB`;
+    /// - counts and `MAX_FILENAME_LENGTH` accept a number;
+    /// - `MAX_PARSE_DURATION` and `IDLE_CHUNK_TIMEOUT` accept seconds, such as `30` or `0.5`;
+    /// - `ALLOWED_MIME_TYPES`, `DENIED_MIME_TYPES`, `ALLOWED_EXTENSIONS` and
+    ///   `ALLOWED_DOUBLE_EXTENSIONS` accept comma-separated lists;
+    /// - `FILENAME_CHARSET` accepts `any`, `no_control` or `portable`;
+    /// - `REJECT_DOUBLE_EXTENSIONS`, `REJECT_EMPTY_FILES` and `SKIP_EMPTY_FILES`
+    ///   accept `true`/`false` or `1`/`0`.
+    ///
+    /// Values are only parsed here; call
+    /// [`MulterConfig::validate`](crate::MulterConfig::validate) to check them.
+    pub fn from_env(prefix: &str) -> Result<Self, ConfigError> {
+        let env = EnvReader { prefix };
+        Ok(Self {
+            max_file_size: env.byte_size("MAX_FILE_SIZE")?,
+            min_file_size: env.byte_size("MIN_FILE_SIZE")?,
+            max_files: env.parse("MAX_FILES")?,
+            max_field_size: env.byte_size("MAX_FIELD_SIZE")?,
+            max_fields: env.parse("MAX_FIELDS")?,
+            max_parts: env.parse("MAX_PARTS")?,
+            max_body_size: env.byte_size("MAX_BODY_SIZE")?,
+            max_total_file_size: env.byte_size("MAX_TOTAL_FILE_SIZE")?,
+            max_header_size: env.byte_size("MAX_HEADER_SIZE")?,
+            max_headers_per_part: env.parse("MAX_HEADERS_PER_PART")?,
+            max_decompressed_size: env.byte_size("MAX_DECOMPRESSED_SIZE")?,
+            max_parse_duration: env.seconds("MAX_PARSE_DURATION")?,
+            idle_chunk_timeout: env.seconds("IDLE_CHUNK_TIMEOUT")?,
+            max_bytes_per_second: env.byte_size("MAX_BYTES_PER_SECOND")?,
+            min_throughput_bytes_per_sec: env.byte_size("MIN_THROUGHPUT_BYTES_PER_SEC")?,
+            min_throughput_grace: env.seconds("MIN_THROUGHPUT_GRACE")?,
+            allowed_mime_types: env.list("ALLOWED_MIME_TYPES")?,
+            denied_mime_types: env.list("DENIED_MIME_TYPES")?,
+            allowed_extensions: env.list("ALLOWED_EXTENSIONS")?,
+            reject_double_extensions: env.flag("REJECT_DOUBLE_EXTENSIONS")?,
+            allowed_double_extensions: env.list("ALLOWED_DOUBLE_EXTENSIONS")?,
+            max_filename_length: env.parse("MAX_FILENAME_LENGTH")?,
+            filename_charset: env
+                .value("FILENAME_CHARSET", |value| {
+                    match value.to_ascii_lowercase().as_str() {
+                        "any" => Some(FilenameCharset::Any),
+                        "no_control" => Some(FilenameCharset::NoControl),
+                        "portable" => Some(FilenameCharset::Portable),
+                        _ => None,
+                    }
+                })?
+                .unwrap_or_default(),
+            reject_empty_files: env.flag("REJECT_EMPTY_FILES")?,
+            skip_empty_files: env.flag("SKIP_EMPTY_FILES")?,
+        })
+    }
+
+    /// Sets the maximum accepted file size in bytes.
+    pub fn max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = Some(max_file_size);
+        self
+    }
+
+    /// Sets the minimum accepted file size in bytes.
+    pub fn min_file_size(mut self, min_file_size: u64) -> Self {
+        self.min_file_size = Some(min_file_size);
+        self
+    }
+
+    /// Sets the maximum accepted number of files.
+    pub fn max_files(mut self, max_files: usize) -> Self {
+        self.max_files = Some(max_files);
+        self
+    }
+
+    /// Sets the maximum accepted text field size in bytes.
+    pub fn max_field_size(mut self, max_field_size: u64) -> Self {
+        self.max_field_size = Some(max_field_size);
+        self
+    }
+
+    /// Sets the maximum accepted number of text fields.
+    pub fn max_fields(mut self, max_fields: usize) -> Self {
+        self.max_fields = Some(max_fields);
+        self
+    }
+
+    /// Sets the maximum accepted number of parts of any kind.
+    pub fn max_parts(mut self, max_parts: usize) -> Self {
+        self.max_parts = Some(max_parts);
+        self
+    }
+
+    /// Sets the maximum accepted multipart request size in bytes.
+    pub fn max_body_size(mut self, max_body_size: u64) -> Self {
+        self.max_body_size = Some(max_body_size);
+        self
+    }
+
+    /// Sets the maximum combined size in bytes of all accepted file parts.
+    pub fn max_total_file_size(mut self, max_total_file_size: u64) -> Self {
+        self.max_total_file_size = Some(max_total_file_size);
+        self
+    }
+
+    /// Sets the maximum accepted header block size in bytes for a single part.
+    pub fn max_header_size(mut self, max_header_size: u64) -> Self {
+        self.max_header_size = Some(max_header_size);
+        self
+    }
+
+    /// Sets the maximum accepted number of headers for a single part.
+    pub fn max_headers_per_part(mut self, max_headers_per_part: usize) -> Self {
+        self.max_headers_per_part = Some(max_headers_per_part);
+        self
+    }
+
+    /// Sets the maximum decompressed size in bytes of a single compressed part.
+    pub fn max_decompressed_size(mut self, max_decompressed_size: u64) -> Self {
+        self.max_decompressed_size = Some(max_decompressed_size);
+        self
+    }
+
+    /// Sets the maximum time allowed to parse a whole request.
+    pub fn max_parse_duration(mut self, max_parse_duration: Duration) -> Self {
+        self.max_parse_duration = Some(max_parse_duration);
+        self
+    }
+
+    /// Sets the maximum time to wait for the next body chunk.
+    pub fn idle_chunk_timeout(mut self, idle_chunk_timeout: Duration) -> Self {
+        self.idle_chunk_timeout = Some(idle_chunk_timeout);
+        self
+    }
+
+    /// Sets the maximum average rate in bytes per second at which the body is read.
+    pub fn max_bytes_per_second(mut self, max_bytes_per_second: u64) -> Self {
+        self.max_bytes_per_second = Some(max_bytes_per_second);
+        self
+    }
+
+    /// Sets the minimum average rate in bytes per second the client must send at.
+    pub fn min_throughput_bytes_per_sec(mut self, min_throughput_bytes_per_sec: u64) -> Self {
+        self.min_throughput_bytes_per_sec = Some(min_throughput_bytes_per_sec);
+        self
+    }
+
+    /// Sets the waiting time allowed before the minimum throughput applies.
+    pub fn min_throughput_grace(mut self, min_throughput_grace: Duration) -> Self {
+        self.min_throughput_grace = Some(min_throughput_grace);
+        self
+    }
+
+    /// Sets the global list of allowed MIME patterns.
+    pub fn allowed_mime_types<I, M>(mut self, allowed_mime_types: I) -> Self
+    where
+        I: IntoIterator<Item = M>,
+        M: Into<String>,
+    {
+        self.allowed_mime_types = allowed_mime_types.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the global list of denied MIME patterns.
+    pub fn denied_mime_types<I, M>(mut self, denied_mime_types: I) -> Self
+    where
+        I: IntoIterator<Item = M>,
+        M: Into<String>,
+    {
+        self.denied_mime_types = denied_mime_types.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the global list of allowed file name extensions.
+    pub fn allowed_extensions<I, E>(mut self, allowed_extensions: I) -> Self
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<String>,
+    {
+        self.allowed_extensions = allowed_extensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets whether file names with a double extension are rejected.
+    pub fn reject_double_extensions(mut self, reject: bool) -> Self {
+        self.reject_double_extensions = reject;
+        self
+    }
+
+    /// Sets the compound extensions accepted despite
+    /// [`Limits::reject_double_extensions`].
+    pub fn allowed_double_extensions<I, E>(mut self, allowed_double_extensions: I) -> Self
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<String>,
+    {
+        self.allowed_double_extensions = allowed_double_extensions
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        self
+    }
+
+    /// Sets the maximum filename length in characters.
+    pub fn max_filename_length(mut self, max_filename_length: usize) -> Self {
+        self.max_filename_length = Some(max_filename_length);
+        self
+    }
+
+    /// Sets the characters accepted in filenames.
+    pub fn filename_charset(mut self, filename_charset: FilenameCharset) -> Self {
+        self.filename_charset = filename_charset;
+        self
+    }
+
+    /// Sets whether file parts with an empty filename and body are rejected.
+    pub fn reject_empty_files(mut self, reject: bool) -> Self {
+        self.reject_empty_files = reject;
+        self
+    }
+
+    /// Sets whether file parts with an empty filename and body are skipped.
+    pub fn skip_empty_files(mut self, skip: bool) -> Self {
+        self.skip_empty_files = skip;
+        self
+    }
+
     /// Returns `true` when `mime` is allowed by the configured allowlist.
     ///
     /// When no allowlist is configured, all MIME types are accepted.
@@ -46,6 +580,92 @@ impl Limits {
 
         allowed
     }
+
+    /// Returns `true` when `mime` matches the configured denylist.
+    pub fn is_mime_denied(&self, mime: &mime::Mime) -> bool {
+        let denied = self
+            .denied_mime_types
+            .iter()
+            .any(|pattern| mime_matches_pattern(mime, pattern));
+
+        #[cfg(feature = "tracing")]
+        if denied {
+            tracing::debug!(
+                mime = mime.essence_str(),
+                denied_patterns = ?self.denied_mime_types,
+                "limits: MIME rejected by global denylist"
+            );
+        }
+
+        denied
+    }
+}
+
+/// Reads `{prefix}_{name}` environment variables for [`Limits::from_env`].
+struct EnvReader<'a> {
+    prefix: &'a str,
+}
+
+impl EnvReader<'_> {
+    fn value<T>(
+        &self,
+        name: &str,
+        parse: impl FnOnce(&str) -> Option<T>,
+    ) -> Result<Option<T>, ConfigError> {
+        let name = format!("{}_{name}", self.prefix);
+        let Some(raw) = std::env::var_os(&name) else {
+            return Ok(None);
+        };
+
+        let value = raw.to_str().map(str::trim);
+        match value.and_then(parse) {
+            Some(parsed) => Ok(Some(parsed)),
+            None => Err(ConfigError::InvalidEnvValue {
+                value: raw.to_string_lossy().into_owned(),
+                name,
+            }),
+        }
+    }
+
+    fn parse<T: FromStr>(&self, name: &str) -> Result<Option<T>, ConfigError> {
+        self.value(name, |value| value.parse().ok())
+    }
+
+    fn byte_size(&self, name: &str) -> Result<Option<u64>, ConfigError> {
+        self.value(name, |value| value.parse().ok().map(ByteSize::as_u64))
+    }
+
+    fn seconds(&self, name: &str) -> Result<Option<Duration>, ConfigError> {
+        self.value(name, |value| {
+            value
+                .parse()
+                .ok()
+                .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        })
+    }
+
+    fn list(&self, name: &str) -> Result<Vec<String>, ConfigError> {
+        let list = self.value(name, |value| {
+            Some(
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(str::to_owned)
+                    .collect(),
+            )
+        })?;
+        Ok(list.unwrap_or_default())
+    }
+
+    fn flag(&self, name: &str) -> Result<bool, ConfigError> {
+        let flag = self.value(name, |value| match value.to_ascii_lowercase().as_str() {
+            "true" | "1" => Some(true),
+            "false" | "0" => Some(false),
+            _ => None,
+        })?;
+        Ok(flag.unwrap_or_default())
+    }
 }
 
 fn mime_matches_pattern(mime: &mime::Mime, pattern: &str) -> bool {
@@ -1,15 +1,64 @@
-use std::task::{Context, Poll};
+use std::{
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    task::{Context, Poll},
+    time::Duration,
+};
 
-use bytes::Bytes;
-use futures::{future::poll_fn, Stream};
+use bytes::{Bytes, BytesMut};
+use encoding_rs::Encoding;
+use futures::{future::poll_fn, stream, Stream};
+use tokio::io::AsyncRead;
+use tokio_util::{io::ReaderStream, sync::CancellationToken};
 
 use crate::{
-    parser::stream::{MultipartStream, StreamLimits},
-    part::PartBodyReader,
+    filter::{FilterDecision, SharedFileFilter},
+    form::UnknownPart,
+    parser::{
+        headers::ParsedPartHeaders,
+        source::ChunkQueue,
+        stream::{MultipartStream, ParserStats, StreamLimits, DEFAULT_MIN_THROUGHPUT_GRACE},
+    },
+    part::{double_extension, file_extension, BufferedBody, PartBodyReader},
     selector::{SelectorAction, SelectorEngine},
-    Limits, MulterConfig, MulterError, ParseError, Part, Selector, UnknownFieldPolicy,
+    FileMeta, Limits, MulterConfig, MulterError, ParseError, Part, Selector, UnknownFieldPolicy,
 };
 
+/// Name of the RFC 7578 form field carrying the default charset for text fields.
+const CHARSET_FIELD: &str = "_charset_";
+
+/// Default number of bytes requested per read by [`Multipart::from_async_read`].
+pub const DEFAULT_READ_CHUNK_SIZE: usize = 8 * 1024;
+
+/// Default number of bytes a [`DrainOnDrop`] guard discards before giving up.
+pub const DEFAULT_DRAIN_MAX_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Default time a [`DrainOnDrop`] guard spends draining before giving up.
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Event yielded by [`Multipart::events`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum MultipartEvent {
+    /// An accepted part started with the given headers.
+    PartStart(Box<ParsedPartHeaders>),
+    /// A chunk of the current part body.
+    Chunk(Bytes),
+    /// The current part body ended.
+    PartEnd,
+    /// The closing boundary was reached; no further events follow.
+    Finished,
+}
+
+/// Position of [`Multipart::events`] within the multipart body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EventState {
+    BetweenParts,
+    InPart,
+    Finished,
+}
+
 /// High-level multipart stream abstraction.
 #[derive(Debug)]
 pub struct Multipart<S> {
@@ -18,6 +67,48 @@ pub struct Multipart<S> {
     limits: Limits,
     file_count: usize,
     field_count: usize,
+    default_charset: Option<&'static Encoding>,
+    strip_text_bom: bool,
+    parts_read: usize,
+    replay: BufferedBody,
+    nested: Option<NestedBody>,
+    abort: Option<CancellationToken>,
+    pending: Option<ParsedPartHeaders>,
+    unknown_parts: Vec<UnknownPart>,
+    file_filter: Option<SharedFileFilter>,
+}
+
+/// Cloneable handle returned by [`Multipart::abort_handle`].
+#[derive(Debug, Clone)]
+pub struct AbortHandle {
+    token: CancellationToken,
+}
+
+impl AbortHandle {
+    /// Aborts the multipart stream this handle was created from.
+    pub fn abort(&self) {
+        self.token.cancel();
+    }
+
+    /// Returns `true` once [`AbortHandle::abort`] has been called.
+    pub fn is_aborted(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
+
+/// Part kinds returned by [`Multipart::next_part_of`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PartFilter {
+    Any,
+    Files,
+    Text,
+}
+
+/// A `multipart/mixed` body nested inside one form-data part (RFC 2388).
+#[derive(Debug)]
+struct NestedBody {
+    parser: MultipartStream<ChunkQueue>,
+    field_name: String,
 }
 
 impl<S> Multipart<S> {
@@ -29,6 +120,15 @@ impl<S> Multipart<S> {
             limits: Limits::default(),
             file_count: 0,
             field_count: 0,
+            default_charset: None,
+            strip_text_bom: false,
+            parts_read: 0,
+            replay: BufferedBody::default(),
+            nested: None,
+            abort: None,
+            pending: None,
+            unknown_parts: Vec::new(),
+            file_filter: None,
         })
     }
 
@@ -43,37 +143,283 @@ impl<S> Multipart<S> {
             max_file_size: config.limits.max_file_size,
             max_field_size: config.limits.max_field_size,
             max_body_size: config.limits.max_body_size,
+            max_total_file_size: config.limits.max_total_file_size,
+            max_parts: config.limits.max_parts,
+            max_header_size: config.limits.max_header_size,
+            max_headers_per_part: config.limits.max_headers_per_part,
+            max_decompressed_size: config.limits.max_decompressed_size,
+            max_parse_duration: config.limits.max_parse_duration,
+            idle_chunk_timeout: config.limits.idle_chunk_timeout,
+            max_bytes_per_second: config.limits.max_bytes_per_second,
+            min_throughput_bytes_per_sec: config.limits.min_throughput_bytes_per_sec,
+            min_throughput_grace: config
+                .limits
+                .min_throughput_grace
+                .unwrap_or(DEFAULT_MIN_THROUGHPUT_GRACE),
+            ..StreamLimits::default()
         };
-        let selector = SelectorEngine::new(config.selector, config.unknown_field_policy);
+        let selector = SelectorEngine::new(config.selector, config.unknown_field_policy)
+            .with_constraints(config.field_constraints);
         Ok(Self {
-            inner: MultipartStream::with_limits(boundary, stream, stream_limits)?,
+            inner: MultipartStream::with_limits(boundary, stream, stream_limits)?
+                .with_epilogue_policy(config.epilogue_policy)
+                .with_line_ending_policy(config.line_ending_policy)
+                .with_encoded_word_policy(config.encoded_word_policy)
+                .with_transfer_encoding_policy(config.transfer_encoding_policy)
+                .with_parser_compliance(config.parser_compliance)
+                .with_duplicate_header_policy(config.duplicate_header_policy)
+                .with_allow_missing_terminal_boundary(config.allow_missing_terminal_boundary)
+                .with_recoverable_part_limits(config.collect_violations),
             selector,
             limits: config.limits,
             file_count: 0,
             field_count: 0,
+            default_charset: None,
+            strip_text_bom: config.strip_text_bom,
+            parts_read: 0,
+            replay: BufferedBody::default(),
+            nested: None,
+            abort: None,
+            pending: None,
+            unknown_parts: Vec::new(),
+            file_filter: None,
         })
     }
+
+    /// Returns the parts buffered so far under [`UnknownFieldPolicy::Collect`].
+    pub fn unknown_parts(&self) -> &[UnknownPart] {
+        &self.unknown_parts
+    }
+
+    /// Takes the parts buffered so far under [`UnknownFieldPolicy::Collect`].
+    pub fn take_unknown_parts(&mut self) -> Vec<UnknownPart> {
+        std::mem::take(&mut self.unknown_parts)
+    }
+
+    /// Returns parser throughput counters collected so far.
+    ///
+    /// A nested `multipart/mixed` body counts as a single part of the
+    /// enclosing form.
+    pub fn stats(&self) -> ParserStats {
+        self.inner.stats()
+    }
+
+    /// Consumes the multipart stream, returning the wrapped source and the
+    /// bytes already read from it but not parsed yet.
+    ///
+    /// Prepending the returned bytes to the source yields the rest of the
+    /// request body, starting where parsing stopped. Once a part body has been
+    /// read to the end, the boundary line after it is already consumed, so
+    /// the remainder starts at the next part's headers; otherwise it can
+    /// start mid-body. Input buffered for an active nested `multipart/mixed`
+    /// body is not included.
+    ///
+    /// ```rust
+    /// use bytes::Bytes;
+    /// use futures::stream;
+    /// use multigear::{MulterError, Multipart};
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), MulterError> {
+    /// let body = "--X\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nhi\r\n\
+    /// --X\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nrest\r\n--X--\r\n";
+    /// let input = stream::iter([Ok::<_, MulterError>(Bytes::from(body))]);
+    /// let mut multipart = Multipart::new("X", input)?;
+    ///
+    /// let mut first = multipart.next_part().await?.expect("part");
+    /// assert_eq!(first.text().await?, "hi");
+    ///
+    /// let (_source, buffered) = multipart.into_inner();
+    /// assert!(buffered.starts_with(b"Content-Disposition: form-data; name=\"b\""));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_inner(self) -> (S, Bytes) {
+        self.inner.into_inner()
+    }
+
+    /// Stops parsing for good and discards buffered input.
+    ///
+    /// Upstream is not polled again; later calls to [`Multipart::next_part`]
+    /// fail with [`MulterError::Aborted`]. A fully parsed stream is left
+    /// untouched.
+    pub fn abort(&mut self) {
+        if let Some(token) = &self.abort {
+            token.cancel();
+        }
+        self.replay.take();
+        self.pending = None;
+        self.nested = None;
+        self.inner.abort();
+    }
+
+    /// Returns a handle that aborts this stream from another task.
+    ///
+    /// Aborting through the handle interrupts a part body that is being read:
+    /// the body stream yields [`MulterError::Aborted`], so an in-progress
+    /// [`DiskStorage`](crate::DiskStorage) write fails and deletes its
+    /// partial file.
+    pub fn abort_handle(&mut self) -> AbortHandle {
+        let token = self.abort.get_or_insert_with(|| {
+            let token = CancellationToken::new();
+            self.inner.set_abort_token(token.clone());
+            token
+        });
+        AbortHandle {
+            token: token.clone(),
+        }
+    }
+
+    /// Stops parsing with [`MulterError::Cancelled`] once `token` is cancelled.
+    ///
+    /// Part bodies being read, including those streamed into storage, fail
+    /// with the same error.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.inner = self.inner.with_cancellation(token);
+        self
+    }
+
+    pub(crate) fn with_file_filter(mut self, file_filter: Option<SharedFileFilter>) -> Self {
+        self.file_filter = file_filter;
+        self
+    }
 }
 
-impl<S> Multipart<S>
+impl<R> Multipart<ReaderStream<R>>
 where
-    S: Stream<Item = Result<Bytes, MulterError>> + Unpin + Send,
+    R: AsyncRead,
+{
+    /// Creates a multipart stream that reads from any `AsyncRead` source.
+    ///
+    /// ```rust
+    /// use multigear::Multipart;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let body: &[u8] = b"--BOUND\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nhi\r\n--BOUND--\r\n";
+    /// let mut multipart = Multipart::from_async_read("BOUND", body).expect("multipart");
+    /// let mut part = multipart.next_part().await.expect("next part").expect("part");
+    /// assert_eq!(part.text().await.expect("text"), "hi");
+    /// # }
+    /// ```
+    pub fn from_async_read(boundary: impl Into<String>, reader: R) -> Result<Self, ParseError> {
+        Self::from_async_read_with_chunk_size(boundary, reader, DEFAULT_READ_CHUNK_SIZE)
+    }
+
+    /// Creates a multipart stream from an `AsyncRead` source, reading up to
+    /// `chunk_size` bytes at a time.
+    pub fn from_async_read_with_chunk_size(
+        boundary: impl Into<String>,
+        reader: R,
+        chunk_size: usize,
+    ) -> Result<Self, ParseError> {
+        if chunk_size == 0 {
+            return Err(ParseError::new("read chunk size must be greater than 0"));
+        }
+
+        Self::new(boundary, ReaderStream::with_capacity(reader, chunk_size))
+    }
+}
+
+impl<S, E> Multipart<S>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin + Send,
+    E: Into<MulterError>,
 {
     /// Returns the next multipart part, if available.
     pub async fn next_part(&mut self) -> Result<Option<Part<'_>>, MulterError> {
-        loop {
-            if self.inner.is_reading_part_body() {
-                self.inner.drain_current_part().await?;
-            }
+        self.next_part_of(PartFilter::Any, None).await
+    }
 
-            let headers = poll_fn(|cx| self.inner.poll_next_part_headers(cx)).await?;
-            let Some(headers) = headers else {
-                #[cfg(feature = "tracing")]
-                tracing::debug!("multipart: reached end of stream");
-                return Ok(None);
+    /// Returns the next file part, draining any text fields before it.
+    ///
+    /// Skipped text fields still pass the selector and limit checks of
+    /// [`Multipart::next_part`], and a `_charset_` field still sets the
+    /// default text charset.
+    pub async fn next_file(&mut self) -> Result<Option<Part<'_>>, MulterError> {
+        self.next_part_of(PartFilter::Files, None).await
+    }
+
+    /// Returns the next text field, draining any file parts before it.
+    ///
+    /// Skipped file parts still pass the selector and limit checks of
+    /// [`Multipart::next_part`].
+    pub async fn next_text(&mut self) -> Result<Option<Part<'_>>, MulterError> {
+        self.next_part_of(PartFilter::Text, None).await
+    }
+
+    /// Returns the next run of consecutive parts sharing one field name.
+    ///
+    /// Useful for array uploads such as repeated `files` parts. The group
+    /// yields parts through [`PartGroup::next_part`] until a part with a
+    /// different field name starts; that part opens the next group. Parts
+    /// still pass the selector and limit checks of [`Multipart::next_part`].
+    ///
+    /// ```rust
+    /// use multigear::Multipart;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), multigear::MulterError> {
+    /// let body: &[u8] = b"--X\r\nContent-Disposition: form-data; name=\"files\"; filename=\"a.txt\"\r\n\r\na\r\n\
+    /// --X\r\nContent-Disposition: form-data; name=\"files\"; filename=\"b.txt\"\r\n\r\nb\r\n--X--\r\n";
+    /// let mut multipart = Multipart::from_async_read("X", body)?;
+    ///
+    /// let mut group = multipart.next_group().await?.expect("group");
+    /// assert_eq!(group.field_name(), "files");
+    /// let mut count = 0;
+    /// while let Some(mut part) = group.next_part().await? {
+    ///     part.bytes().await?;
+    ///     count += 1;
+    /// }
+    /// assert_eq!(count, 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn next_group(&mut self) -> Result<Option<PartGroup<'_, S>>, MulterError> {
+        self.replay.take();
+        if self.pending.is_none() {
+            self.pending = self.next_part_headers().await?;
+        }
+        let Some(headers) = self.pending.as_ref() else {
+            return Ok(None);
+        };
+
+        let field_name = headers.field_name.clone();
+        Ok(Some(PartGroup {
+            multipart: self,
+            field_name,
+        }))
+    }
+
+    async fn next_part_of(
+        &mut self,
+        filter: PartFilter,
+        group: Option<&str>,
+    ) -> Result<Option<Part<'_>>, MulterError> {
+        // A replayed `_charset_` body left unread belongs to the previous part.
+        self.replay.take();
+        loop {
+            let headers = match self.pending.take() {
+                Some(headers) => headers,
+                None => match self.next_part_headers().await? {
+                    Some(headers) => headers,
+                    None => return Ok(None),
+                },
             };
+            if group.is_some_and(|field_name| headers.field_name != field_name) {
+                self.pending = Some(headers);
+                return Ok(None);
+            }
+            let index = self.parts_read;
+            self.parts_read += 1;
 
             if headers.file_name.is_none() {
+                let charset_body = if self.nested.is_none() && headers.field_name == CHARSET_FIELD {
+                    Some(self.read_charset_field().await?)
+                } else {
+                    None
+                };
+
                 match self.selector.evaluate_text_field(&headers.field_name) {
                     Ok(SelectorAction::Accept) => {}
                     Ok(SelectorAction::Ignore) => {
@@ -82,14 +428,18 @@ where
                             field_name = headers.field_name.as_str(),
                             "multipart: ignoring unmatched text field"
                         );
-                        self.inner.drain_current_part().await?;
+                        self.drain_current_part().await?;
+                        continue;
+                    }
+                    Ok(SelectorAction::Collect) => {
+                        self.collect_unknown_part(headers, charset_body).await?;
                         continue;
                     }
                     Err(err) => return Err(err),
                 }
 
                 if let Some(max_size) = self.selector.field_text_max_size(&headers.field_name) {
-                    self.inner.tighten_current_part_max_size(Some(max_size));
+                    self.tighten_current_part_max_size(Some(max_size));
                 }
 
                 self.field_count += 1;
@@ -105,16 +455,115 @@ where
                     }
                 }
 
+                self.selector.mark_received(&headers.field_name);
+                if filter == PartFilter::Files {
+                    self.replay.take();
+                    self.drain_current_part().await?;
+                    continue;
+                }
+
                 #[cfg(feature = "tracing")]
                 tracing::debug!(
                     field_name = headers.field_name.as_str(),
                     "multipart: yielding text part"
                 );
-                return Ok(Some(Part::new(headers, &mut self.inner)));
+                let default_charset = self.default_charset;
+                let strip_bom = self.strip_text_bom;
+                let part = match charset_body {
+                    Some(body) => {
+                        self.replay.replace(body);
+                        Part::new(headers, &mut self.replay)
+                    }
+                    None => self.new_part(headers),
+                };
+                return Ok(Some(
+                    part.with_index(index)
+                        .with_default_charset(default_charset)
+                        .with_strip_bom(strip_bom),
+                ));
+            }
+
+            if let Some(file_filter) = &self.file_filter {
+                let meta = FileMeta {
+                    field_name: headers.field_name.clone(),
+                    file_name: headers.file_name.clone(),
+                    content_type: headers.content_type.to_string(),
+                    size_hint: headers.content_length,
+                    sniffed_content_type: None,
+                    headers: headers.headers.clone(),
+                };
+                match file_filter.filter(&meta).await {
+                    FilterDecision::Accept => {}
+                    FilterDecision::Skip => {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(
+                            field_name = headers.field_name.as_str(),
+                            "multipart: file part skipped by filter"
+                        );
+                        self.drain_current_part().await?;
+                        continue;
+                    }
+                    FilterDecision::Reject { message } => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(
+                            field_name = headers.field_name.as_str(),
+                            message = message.as_str(),
+                            "multipart: file part rejected by filter"
+                        );
+                        return Err(MulterError::FileRejected {
+                            field: headers.field_name,
+                            message,
+                        });
+                    }
+                }
             }
 
             match self.selector.evaluate_file_field(&headers.field_name) {
                 Ok(SelectorAction::Accept) => {
+                    if let Some(max_size) = self.selector.field_file_max_size(&headers.field_name) {
+                        self.tighten_current_part_max_size(Some(max_size));
+                    }
+
+                    let mut first_chunk = None;
+                    if headers.file_name.as_deref() == Some("")
+                        && (self.limits.reject_empty_files || self.limits.skip_empty_files)
+                    {
+                        match self.first_body_chunk().await? {
+                            Some(chunk) => first_chunk = Some(chunk),
+                            None if self.limits.reject_empty_files => {
+                                #[cfg(feature = "tracing")]
+                                tracing::warn!(
+                                    field_name = headers.field_name.as_str(),
+                                    "multipart: rejected empty file part"
+                                );
+                                return Err(MulterError::EmptyFile {
+                                    field: headers.field_name,
+                                });
+                            }
+                            None => {
+                                #[cfg(feature = "tracing")]
+                                tracing::debug!(
+                                    field_name = headers.field_name.as_str(),
+                                    "multipart: skipping empty file part"
+                                );
+                                continue;
+                            }
+                        }
+                    }
+
+                    if self.limits.is_mime_denied(&headers.content_type) {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(
+                            field_name = headers.field_name.as_str(),
+                            mime = headers.content_type.essence_str(),
+                            "multipart: rejected by global MIME denylist"
+                        );
+                        return Err(MulterError::MimeTypeNotAllowed {
+                            field: headers.field_name.clone(),
+                            mime: headers.content_type.essence_str().to_owned(),
+                        });
+                    }
+
                     if let Some(patterns) =
                         self.selector.field_allowed_mime_types(&headers.field_name)
                     {
@@ -134,6 +583,46 @@ where
                         }
                     }
 
+                    self.check_filename(&headers)?;
+
+                    let extension = headers
+                        .file_name
+                        .as_deref()
+                        .and_then(file_extension)
+                        .unwrap_or_default();
+                    if let Some(extensions) =
+                        self.selector.field_allowed_extensions(&headers.field_name)
+                    {
+                        if !extensions.is_empty() && !extension_matches_any(&extension, extensions)
+                        {
+                            #[cfg(feature = "tracing")]
+                            tracing::warn!(
+                                field_name = headers.field_name.as_str(),
+                                extension = extension.as_str(),
+                                "multipart: rejected by per-field extension allowlist"
+                            );
+                            return Err(MulterError::ExtensionNotAllowed {
+                                field: headers.field_name.clone(),
+                                extension,
+                            });
+                        }
+                    }
+
+                    if !self.limits.allowed_extensions.is_empty()
+                        && !extension_matches_any(&extension, &self.limits.allowed_extensions)
+                    {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(
+                            field_name = headers.field_name.as_str(),
+                            extension = extension.as_str(),
+                            "multipart: rejected by global extension allowlist"
+                        );
+                        return Err(MulterError::ExtensionNotAllowed {
+                            field: headers.field_name.clone(),
+                            extension,
+                        });
+                    }
+
                     if !self.limits.is_mime_allowed(&headers.content_type) {
                         #[cfg(feature = "tracing")]
                         tracing::warn!(
@@ -160,6 +649,14 @@ where
                         }
                     }
 
+                    self.apply_min_file_size(&headers.field_name, first_chunk.as_ref())?;
+                    self.selector.mark_received(&headers.field_name);
+
+                    if filter == PartFilter::Text {
+                        self.drain_current_part().await?;
+                        continue;
+                    }
+
                     #[cfg(feature = "tracing")]
                     tracing::debug!(
                         field_name = headers.field_name.as_str(),
@@ -167,7 +664,14 @@ where
                         mime = headers.content_type.essence_str(),
                         "multipart: yielding file part"
                     );
-                    return Ok(Some(Part::new(headers, &mut self.inner)));
+                    let part = match first_chunk {
+                        Some(chunk) => {
+                            self.replay.replace(chunk);
+                            Part::new(headers, self)
+                        }
+                        None => self.new_part(headers),
+                    };
+                    return Ok(Some(part.with_index(index)));
                 }
                 Ok(SelectorAction::Ignore) => {
                     #[cfg(feature = "tracing")]
@@ -175,7 +679,11 @@ where
                         field_name = headers.field_name.as_str(),
                         "multipart: ignoring unmatched file field"
                     );
-                    self.inner.drain_current_part().await?;
+                    self.drain_current_part().await?;
+                    continue;
+                }
+                Ok(SelectorAction::Collect) => {
+                    self.collect_unknown_part(headers, None).await?;
                     continue;
                 }
                 Err(err) => return Err(err),
@@ -184,9 +692,474 @@ where
     }
 }
 
-impl<S> PartBodyReader for MultipartStream<S>
+impl<S, E> Multipart<S>
 where
-    S: Stream<Item = Result<Bytes, MulterError>> + Unpin + Send,
+    S: Stream<Item = Result<Bytes, E>> + Unpin + Send,
+    E: Into<MulterError>,
+{
+    /// Reads and discards the rest of the request body.
+    ///
+    /// Handlers that stop early, for example after a limit error, can call
+    /// this so the unread body does not break HTTP keep-alive. No further
+    /// parts are yielded afterwards. Limits are not applied to the discarded
+    /// input, so pair this with a server-level body timeout. Returns the
+    /// number of bytes discarded.
+    pub async fn drain(&mut self) -> Result<u64, MulterError> {
+        self.drain_up_to(None).await
+    }
+
+    async fn drain_up_to(&mut self, max_bytes: Option<u64>) -> Result<u64, MulterError> {
+        self.replay.take();
+        self.pending = None;
+        self.nested = None;
+        self.inner.drain_upstream_up_to(max_bytes).await
+    }
+
+    /// Wraps the stream so any unread body is drained once it is dropped.
+    ///
+    /// See [`DrainOnDrop`].
+    pub fn drain_on_drop(self) -> DrainOnDrop<S, E>
+    where
+        S: 'static,
+    {
+        DrainOnDrop {
+            multipart: Some(self),
+            max_bytes: DEFAULT_DRAIN_MAX_BYTES,
+            timeout: DEFAULT_DRAIN_TIMEOUT,
+            _error: PhantomData,
+        }
+    }
+
+    /// Returns the remaining multipart body as a stream of [`MultipartEvent`]s.
+    ///
+    /// Parts go through the same selector and limit checks as
+    /// [`Multipart::next_part`], but their bodies are yielded chunk by chunk
+    /// without materializing a [`Part`], which suits proxies forwarding uploads
+    /// downstream. The stream ends after [`MultipartEvent::Finished`] or the
+    /// first error.
+    ///
+    /// ```rust
+    /// use futures::{stream, StreamExt};
+    /// use multigear::{Multipart, MultipartEvent};
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), multigear::MulterError> {
+    /// let body = "--X\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nhi\r\n--X--\r\n";
+    /// let input = stream::iter([Ok::<_, multigear::MulterError>(bytes::Bytes::from(body))]);
+    /// let mut multipart = Multipart::new("X", input)?;
+    ///
+    /// let mut events = Box::pin(multipart.events());
+    /// while let Some(event) = events.next().await.transpose()? {
+    ///     if let MultipartEvent::Chunk(chunk) = event {
+    ///         assert_eq!(&chunk[..], b"hi");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn events(
+        &mut self,
+    ) -> impl Stream<Item = Result<MultipartEvent, MulterError>> + Send + '_ {
+        stream::try_unfold(
+            (self, EventState::BetweenParts),
+            |(multipart, state)| async move {
+                let (event, next) = match state {
+                    EventState::Finished => return Ok(None),
+                    EventState::BetweenParts => match multipart.next_part().await? {
+                        Some(part) => (
+                            MultipartEvent::PartStart(Box::new(part.headers.clone())),
+                            EventState::InPart,
+                        ),
+                        None => (MultipartEvent::Finished, EventState::Finished),
+                    },
+                    EventState::InPart => {
+                        match poll_fn(|cx| multipart.poll_current_chunk(cx)).await? {
+                            Some(chunk) => (MultipartEvent::Chunk(chunk), EventState::InPart),
+                            None => (MultipartEvent::PartEnd, EventState::BetweenParts),
+                        }
+                    }
+                };
+                Ok(Some((event, (multipart, next))))
+            },
+        )
+    }
+
+    /// Reads the next body chunk of the part most recently returned by `next_part`.
+    fn poll_current_chunk(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<Bytes>, MulterError>> {
+        if let Some(body) = self.replay.take().filter(|body| !body.is_empty()) {
+            return Poll::Ready(Ok(Some(body)));
+        }
+
+        if self.nested.is_some() {
+            self.poll_nested(cx, |parser, cx| parser.poll_next_part_chunk(cx))
+        } else {
+            self.inner.poll_next_part_chunk(cx)
+        }
+    }
+
+    /// Applies the filename length and character limits to a file part.
+    fn check_filename(&self, headers: &ParsedPartHeaders) -> Result<(), MulterError> {
+        let Some(file_name) = headers.file_name.as_deref() else {
+            return Ok(());
+        };
+
+        if let Some(max_filename_length) = self.limits.max_filename_length {
+            if file_name.chars().count() > max_filename_length {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    field_name = headers.field_name.as_str(),
+                    max_filename_length,
+                    "multipart: filename too long"
+                );
+                return Err(MulterError::FilenameTooLong {
+                    field: headers.field_name.clone(),
+                    max_filename_length,
+                });
+            }
+        }
+
+        if !self.limits.filename_charset.allows(file_name) {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                field_name = headers.field_name.as_str(),
+                "multipart: filename has disallowed characters"
+            );
+            return Err(MulterError::InvalidFilename {
+                field: headers.field_name.clone(),
+                file_name: file_name.to_owned(),
+            });
+        }
+
+        if self.limits.reject_double_extensions {
+            if let Some(extension) = double_extension(file_name).filter(|extension| {
+                !extension_matches_any(extension, &self.limits.allowed_double_extensions)
+            }) {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    field_name = headers.field_name.as_str(),
+                    extension = extension.as_str(),
+                    "multipart: rejected double extension"
+                );
+                return Err(MulterError::DoubleExtension {
+                    field: headers.field_name.clone(),
+                    extension,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Buffers an unknown part under [`UnknownFieldPolicy::Collect`].
+    ///
+    /// `body` is a body already read ahead, such as a `_charset_` value. A
+    /// part that does not fit in the remaining budget is drained and dropped.
+    async fn collect_unknown_part(
+        &mut self,
+        headers: ParsedPartHeaders,
+        body: Option<Bytes>,
+    ) -> Result<(), MulterError> {
+        let max_size = self.selector.unknown_parts_max_size().unwrap_or(0);
+        let collected: u64 = self
+            .unknown_parts
+            .iter()
+            .map(|part| part.data.len() as u64)
+            .sum();
+        let remaining = max_size.saturating_sub(collected);
+
+        if let Some(body) = body {
+            self.replay.replace(body);
+        }
+        let mut data = BytesMut::new();
+        let mut fits = true;
+        while let Some(chunk) = poll_fn(|cx| self.poll_current_chunk(cx)).await? {
+            if fits && (data.len() + chunk.len()) as u64 > remaining {
+                fits = false;
+                data = BytesMut::new();
+            }
+            if fits {
+                data.extend_from_slice(&chunk);
+            }
+        }
+
+        if !fits {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                field_name = headers.field_name.as_str(),
+                "multipart: unknown part exceeds collection budget; ignoring"
+            );
+            return Ok(());
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = headers.field_name.as_str(),
+            "multipart: collected unknown part"
+        );
+        self.unknown_parts.push(UnknownPart {
+            field_name: headers.field_name,
+            file_name: headers.file_name,
+            content_type: headers.content_type,
+            data: data.freeze(),
+        });
+        Ok(())
+    }
+
+    /// Reads the first non-empty body chunk of the current part, or `None` when the body is empty.
+    async fn first_body_chunk(&mut self) -> Result<Option<Bytes>, MulterError> {
+        loop {
+            match poll_fn(|cx| self.poll_current_chunk(cx)).await? {
+                Some(chunk) if chunk.is_empty() => {}
+                chunk => return Ok(chunk),
+            }
+        }
+    }
+
+    /// Creates a part reading from the nested body when one is active.
+    fn new_part(&mut self, headers: ParsedPartHeaders) -> Part<'_> {
+        if self.nested.is_some() {
+            Part::new(headers, self)
+        } else {
+            Part::new(headers, &mut self.inner)
+        }
+    }
+
+    fn tighten_current_part_max_size(&mut self, limit: Option<u64>) {
+        match self.nested.as_mut() {
+            Some(nested) => nested.parser.tighten_current_part_max_size(limit),
+            None => self.inner.tighten_current_part_max_size(limit),
+        }
+    }
+
+    /// Applies the global and per-field minimum file size to the current part.
+    ///
+    /// `first_chunk` is a body chunk already read ahead; when it ended the
+    /// part, the minimum is checked against it directly.
+    fn apply_min_file_size(
+        &mut self,
+        field_name: &str,
+        first_chunk: Option<&Bytes>,
+    ) -> Result<(), MulterError> {
+        let Some(min_file_size) = self
+            .limits
+            .min_file_size
+            .max(self.selector.field_file_min_size(field_name))
+        else {
+            return Ok(());
+        };
+
+        let in_body = match self.nested.as_mut() {
+            Some(nested) => {
+                nested.parser.set_current_part_min_size(Some(min_file_size));
+                nested.parser.is_reading_part_body()
+            }
+            None => {
+                self.inner.set_current_part_min_size(Some(min_file_size));
+                self.inner.is_reading_part_body()
+            }
+        };
+        if in_body {
+            return Ok(());
+        }
+
+        let size = first_chunk.map_or(0, |chunk| chunk.len() as u64);
+        if size < min_file_size {
+            return Err(MulterError::FileSizeBelowMinimum {
+                field: field_name.to_owned(),
+                min_file_size,
+            });
+        }
+        Ok(())
+    }
+
+    async fn drain_current_part(&mut self) -> Result<(), MulterError> {
+        if self.nested.is_none() {
+            return self.inner.drain_current_part().await;
+        }
+
+        poll_fn(|cx| self.poll_nested(cx, poll_drain_part)).await
+    }
+
+    /// Returns the headers of the next part, entering nested bodies as needed.
+    async fn next_part_headers(&mut self) -> Result<Option<ParsedPartHeaders>, MulterError> {
+        loop {
+            if self.nested.is_some() {
+                match self.next_nested_part_headers().await? {
+                    Some(headers) => return Ok(Some(self.canonicalize_field_name(headers))),
+                    None => continue,
+                }
+            }
+
+            if self.inner.is_reading_part_body() {
+                self.inner.drain_current_part().await?;
+            }
+
+            let headers = poll_fn(|cx| self.inner.poll_next_part_headers(cx)).await?;
+            let Some(headers) = headers else {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("multipart: reached end of stream");
+                if let Some(field) = self.selector.missing_required_field() {
+                    return Err(MulterError::MissingRequiredField {
+                        field: field.to_owned(),
+                    });
+                }
+                if let Some((field, min_count)) = self.selector.field_below_min_count() {
+                    return Err(MulterError::FieldCountBelowMinimum {
+                        field: field.to_owned(),
+                        min_count,
+                    });
+                }
+                if let Some(constraint) = self.selector.violated_constraint() {
+                    return Err(MulterError::FieldConstraintViolated {
+                        constraint: constraint.clone(),
+                    });
+                }
+                return Ok(None);
+            };
+
+            if let Some(boundary) = nested_multipart_boundary(&headers) {
+                self.start_nested(boundary, headers.field_name)?;
+                continue;
+            }
+
+            return Ok(Some(self.canonicalize_field_name(headers)));
+        }
+    }
+
+    /// Replaces a selector alias with the name of the field it maps to.
+    fn canonicalize_field_name(&self, mut headers: ParsedPartHeaders) -> ParsedPartHeaders {
+        if let Some(name) = self.selector.canonical_field_name(&headers.field_name) {
+            headers.field_name = name.to_owned();
+        }
+        headers
+    }
+
+    /// Enters the `multipart/mixed` body of the current part.
+    fn start_nested(&mut self, boundary: String, field_name: String) -> Result<(), MulterError> {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = field_name.as_str(),
+            "multipart: entering nested multipart/mixed body"
+        );
+        // The container is not a text field; its nested parts are limited individually.
+        self.inner.set_current_part_max_size(None);
+        let mut parser = self
+            .inner
+            .nested(boundary, field_name.clone(), ChunkQueue::default())?;
+        parser.set_total_file_size(self.inner.total_file_size());
+        self.nested = Some(NestedBody { parser, field_name });
+        Ok(())
+    }
+
+    /// Returns the next nested part's headers, or `None` once the nested body ends.
+    async fn next_nested_part_headers(&mut self) -> Result<Option<ParsedPartHeaders>, MulterError> {
+        self.drain_current_part().await?;
+        let headers =
+            poll_fn(|cx| self.poll_nested(cx, |parser, cx| parser.poll_next_part_headers(cx)))
+                .await?;
+
+        match headers {
+            Some(mut headers) => {
+                if let Some(nested) = self.nested.as_ref() {
+                    headers.field_name.clone_from(&nested.field_name);
+                }
+                Ok(Some(headers))
+            }
+            None => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("multipart: leaving nested multipart/mixed body");
+                if let Some(nested) = self.nested.take() {
+                    self.inner
+                        .set_total_file_size(nested.parser.total_file_size());
+                }
+                self.inner.drain_current_part().await?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Drives the nested parser, feeding it chunks of the enclosing part body on demand.
+    fn poll_nested<T>(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut poll: impl FnMut(
+            &mut MultipartStream<ChunkQueue>,
+            &mut Context<'_>,
+        ) -> Poll<Result<T, MulterError>>,
+    ) -> Poll<Result<T, MulterError>> {
+        let Some(nested) = self.nested.as_mut() else {
+            return Poll::Ready(Err(
+                ParseError::new("no nested multipart body is active").into()
+            ));
+        };
+
+        loop {
+            if let Poll::Ready(result) = poll(&mut nested.parser, cx) {
+                return Poll::Ready(result);
+            }
+
+            let source = nested.parser.source_mut();
+            if source.is_closed() {
+                return Poll::Ready(Err(MulterError::IncompleteStream));
+            }
+
+            match self.inner.poll_next_part_chunk(cx) {
+                Poll::Ready(Ok(Some(chunk))) => source.push(chunk),
+                Poll::Ready(Ok(None)) => source.close(),
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    /// Reads the `_charset_` field body and records it as the form-wide text charset.
+    async fn read_charset_field(&mut self) -> Result<Bytes, MulterError> {
+        let mut body = BytesMut::new();
+        while let Some(chunk) = poll_fn(|cx| self.inner.poll_next_part_chunk(cx)).await? {
+            body.extend_from_slice(&chunk);
+        }
+
+        let body = body.freeze();
+        let label = std::str::from_utf8(&body)
+            .map(str::trim)
+            .unwrap_or_default();
+        let encoding = Encoding::for_label(label.as_bytes());
+        #[cfg(feature = "tracing")]
+        match encoding {
+            Some(encoding) => tracing::debug!(
+                charset = encoding.name(),
+                "multipart: applying _charset_ to text fields"
+            ),
+            None => tracing::debug!(label, "multipart: ignoring unknown _charset_ value"),
+        }
+        if encoding.is_some() {
+            self.default_charset = encoding;
+        }
+
+        Ok(body)
+    }
+}
+
+impl<S, E> PartBodyReader for Multipart<S>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin + Send,
+    E: Into<MulterError>,
+{
+    fn poll_next_chunk(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<Bytes>, MulterError>> {
+        self.poll_current_chunk(cx)
+    }
+}
+
+impl<S, E> PartBodyReader for MultipartStream<S>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin + Send,
+    E: Into<MulterError>,
 {
     fn poll_next_chunk(
         &mut self,
@@ -196,12 +1169,50 @@ where
     }
 }
 
+/// Returns the boundary of a nameless-file part carrying a nested `multipart/mixed` body.
+fn nested_multipart_boundary(headers: &ParsedPartHeaders) -> Option<String> {
+    if headers.file_name.is_some() || headers.content_type.essence_str() != "multipart/mixed" {
+        return None;
+    }
+
+    headers
+        .content_type
+        .get_param(mime::BOUNDARY)
+        .map(|boundary| boundary.as_str().to_owned())
+}
+
+/// Discards the rest of the current nested part body.
+fn poll_drain_part(
+    parser: &mut MultipartStream<ChunkQueue>,
+    cx: &mut Context<'_>,
+) -> Poll<Result<(), MulterError>> {
+    while parser.is_reading_part_body() {
+        match parser.poll_next_part_chunk(cx) {
+            Poll::Ready(Ok(_)) => {}
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+            Poll::Pending => return Poll::Pending,
+        }
+    }
+
+    Poll::Ready(Ok(()))
+}
+
 fn mime_matches_any(mime: &mime::Mime, patterns: &[String]) -> bool {
     patterns
         .iter()
         .any(|pattern| mime_matches_pattern(mime, pattern))
 }
 
+fn extension_matches_any(extension: &str, allowed: &[String]) -> bool {
+    !extension.is_empty()
+        && allowed.iter().any(|allowed| {
+            allowed
+                .strip_prefix('.')
+                .unwrap_or(allowed)
+                .eq_ignore_ascii_case(extension)
+        })
+}
+
 fn mime_matches_pattern(mime: &mime::Mime, pattern: &str) -> bool {
     if let Some((kind, subtype)) = pattern.split_once('/') {
         if subtype == "*" {
@@ -211,3 +1222,143 @@ fn mime_matches_pattern(mime: &mime::Mime, pattern: &str) -> bool {
 
     mime.essence_str().eq_ignore_ascii_case(pattern)
 }
+
+/// Consecutive parts sharing one field name, returned by [`Multipart::next_group`].
+#[derive(Debug)]
+pub struct PartGroup<'a, S> {
+    multipart: &'a mut Multipart<S>,
+    field_name: String,
+}
+
+impl<S> PartGroup<'_, S> {
+    /// Returns the field name shared by every part in the group.
+    pub fn field_name(&self) -> &str {
+        &self.field_name
+    }
+}
+
+impl<S, E> PartGroup<'_, S>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin + Send,
+    E: Into<MulterError>,
+{
+    /// Returns the next part of the group, or `None` once the group ends.
+    pub async fn next_part(&mut self) -> Result<Option<Part<'_>>, MulterError> {
+        self.multipart
+            .next_part_of(PartFilter::Any, Some(&self.field_name))
+            .await
+    }
+}
+
+/// [`Multipart`] guard that drains the unread request body when dropped.
+///
+/// Created by [`Multipart::drain_on_drop`]. The guard dereferences to the
+/// wrapped [`Multipart`]. On drop, the rest of the body is read and discarded
+/// on a spawned Tokio task; outside a Tokio runtime the body is left unread.
+/// The task gives up after [`DrainOnDrop::max_bytes`] bytes or
+/// [`DrainOnDrop::timeout`], whichever comes first, so a slow or endless body
+/// cannot keep it alive.
+pub struct DrainOnDrop<S, E>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin + Send + 'static,
+    E: Into<MulterError>,
+{
+    multipart: Option<Multipart<S>>,
+    max_bytes: u64,
+    timeout: Duration,
+    _error: PhantomData<fn() -> E>,
+}
+
+impl<S, E> DrainOnDrop<S, E>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin + Send + 'static,
+    E: Into<MulterError>,
+{
+    /// Sets how many bytes the drain discards before giving up.
+    ///
+    /// Defaults to [`DEFAULT_DRAIN_MAX_BYTES`].
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Sets how long the drain runs before giving up.
+    ///
+    /// Defaults to [`DEFAULT_DRAIN_TIMEOUT`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the wrapped multipart stream without draining it.
+    pub fn into_inner(mut self) -> Multipart<S> {
+        self.multipart
+            .take()
+            .expect("multipart is only taken on drop or into_inner")
+    }
+}
+
+impl<S, E> fmt::Debug for DrainOnDrop<S, E>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin + Send + 'static,
+    E: Into<MulterError>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DrainOnDrop")
+            .field("max_bytes", &self.max_bytes)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, E> Deref for DrainOnDrop<S, E>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin + Send + 'static,
+    E: Into<MulterError>,
+{
+    type Target = Multipart<S>;
+
+    fn deref(&self) -> &Self::Target {
+        self.multipart
+            .as_ref()
+            .expect("multipart is only taken on drop or into_inner")
+    }
+}
+
+impl<S, E> DerefMut for DrainOnDrop<S, E>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin + Send + 'static,
+    E: Into<MulterError>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.multipart
+            .as_mut()
+            .expect("multipart is only taken on drop or into_inner")
+    }
+}
+
+impl<S, E> Drop for DrainOnDrop<S, E>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin + Send + 'static,
+    E: Into<MulterError>,
+{
+    fn drop(&mut self) {
+        let Some(mut multipart) = self.multipart.take() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let (max_bytes, timeout) = (self.max_bytes, self.timeout);
+        runtime.spawn(async move {
+            let _drained =
+                tokio::time::timeout(timeout, multipart.drain_up_to(Some(max_bytes))).await;
+            #[cfg(feature = "tracing")]
+            match _drained {
+                Ok(Ok(_)) => {}
+                Ok(Err(err)) => tracing::debug!(error = %err, "multipart: drain on drop failed"),
+                Err(_) => tracing::debug!("multipart: drain on drop timed out"),
+            }
+        });
+    }
+}
@@ -5,16 +5,36 @@ const MAX_BOUNDARY_LEN: usize = 70;
 
 /// Extracts and validates the `boundary` parameter from a `Content-Type` value.
 pub fn extract_multipart_boundary(content_type: &str) -> Result<String, ParseError> {
-    let mime = content_type
-        .parse::<mime::Mime>()
-        .map_err(|_| ParseError::new("invalid Content-Type header"))?;
+    let mime = parse_content_type(content_type)?;
 
     if mime.essence_str() != MULTIPART_FORM_DATA {
         return Err(ParseError::new("Content-Type must be multipart/form-data"));
     }
 
+    boundary_param(&mime)
+}
+
+/// Extracts and validates the `boundary` parameter from any `multipart/*`
+/// `Content-Type` value, such as `multipart/related` or `multipart/mixed`.
+pub fn extract_any_multipart_boundary(content_type: &str) -> Result<String, ParseError> {
+    let mime = parse_content_type(content_type)?;
+
+    if mime.type_() != mime::MULTIPART {
+        return Err(ParseError::new("Content-Type must be a multipart type"));
+    }
+
+    boundary_param(&mime)
+}
+
+fn parse_content_type(content_type: &str) -> Result<mime::Mime, ParseError> {
+    content_type
+        .parse::<mime::Mime>()
+        .map_err(|_| ParseError::new("invalid Content-Type header"))
+}
+
+fn boundary_param(mime: &mime::Mime) -> Result<String, ParseError> {
     let boundary = mime
-        .get_param("boundary")
+        .get_param(mime::BOUNDARY)
         .map(|value| value.as_str())
         .ok_or_else(|| ParseError::new("missing multipart boundary parameter"))?;
 
@@ -0,0 +1,125 @@
+use std::io::{self, Write};
+
+use brotli_decompressor::DecompressorWriter;
+use bytes::Bytes;
+use flate2::write::{GzDecoder, ZlibDecoder};
+
+use crate::{error::ParseError, parser::headers::ContentEncoding};
+
+/// Output buffer size used by the brotli decoder.
+const BROTLI_BUFFER_SIZE: usize = 4096;
+
+/// Outcome of a failed decompression step.
+#[derive(Debug)]
+pub(crate) enum DecompressError {
+    /// The decompressed output exceeded the configured limit.
+    LimitExceeded,
+    /// The compressed input was malformed.
+    Invalid(ParseError),
+}
+
+/// Incremental decompressor applied to part body chunks.
+pub(crate) enum Decompressor {
+    Gzip(GzDecoder<LimitedSink>),
+    Deflate(ZlibDecoder<LimitedSink>),
+    Brotli(Box<DecompressorWriter<LimitedSink>>),
+}
+
+impl std::fmt::Debug for Decompressor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Gzip(_) => "Gzip",
+            Self::Deflate(_) => "Deflate",
+            Self::Brotli(_) => "Brotli",
+        };
+        f.debug_tuple("Decompressor").field(&name).finish()
+    }
+}
+
+impl Decompressor {
+    /// Creates a decompressor for a part's `Content-Encoding`, if one applies.
+    pub(crate) fn for_encoding(encoding: &ContentEncoding, limit: Option<u64>) -> Option<Self> {
+        let sink = LimitedSink {
+            output: Vec::new(),
+            remaining: limit,
+            exceeded: false,
+        };
+
+        match encoding {
+            ContentEncoding::Gzip => Some(Self::Gzip(GzDecoder::new(sink))),
+            ContentEncoding::Deflate => Some(Self::Deflate(ZlibDecoder::new(sink))),
+            ContentEncoding::Brotli => Some(Self::Brotli(Box::new(DecompressorWriter::new(
+                sink,
+                BROTLI_BUFFER_SIZE,
+            )))),
+            _ => None,
+        }
+    }
+
+    /// Feeds one compressed chunk and returns the bytes it decompressed to.
+    pub(crate) fn decompress(&mut self, chunk: &[u8]) -> Result<Bytes, DecompressError> {
+        let result = match self {
+            Self::Gzip(decoder) => decoder.write_all(chunk),
+            Self::Deflate(decoder) => decoder.write_all(chunk),
+            Self::Brotli(decoder) => decoder.write_all(chunk),
+        };
+        self.take_output(result)
+    }
+
+    /// Flushes the decoder once the compressed body has ended.
+    pub(crate) fn finish(&mut self) -> Result<Bytes, DecompressError> {
+        let result = match self {
+            Self::Gzip(decoder) => decoder.try_finish(),
+            Self::Deflate(decoder) => decoder.try_finish(),
+            Self::Brotli(decoder) => decoder.close(),
+        };
+        self.take_output(result)
+    }
+
+    fn take_output(&mut self, result: io::Result<()>) -> Result<Bytes, DecompressError> {
+        let sink = match self {
+            Self::Gzip(decoder) => decoder.get_mut(),
+            Self::Deflate(decoder) => decoder.get_mut(),
+            Self::Brotli(decoder) => decoder.get_mut(),
+        };
+
+        if sink.exceeded {
+            return Err(DecompressError::LimitExceeded);
+        }
+        if result.is_err() {
+            return Err(DecompressError::Invalid(ParseError::new(
+                "invalid compressed part body",
+            )));
+        }
+
+        Ok(Bytes::from(std::mem::take(&mut sink.output)))
+    }
+}
+
+/// Decoder output buffer that refuses writes past the decompressed-size limit.
+#[derive(Debug)]
+pub(crate) struct LimitedSink {
+    output: Vec<u8>,
+    remaining: Option<u64>,
+    exceeded: bool,
+}
+
+impl Write for LimitedSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(remaining) = self.remaining.as_mut() {
+            let len = buf.len() as u64;
+            if len > *remaining {
+                self.exceeded = true;
+                return Err(io::Error::other("decompressed size limit exceeded"));
+            }
+            *remaining -= len;
+        }
+
+        self.output.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
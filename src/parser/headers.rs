@@ -1,6 +1,10 @@
-use http::{header, HeaderMap};
+use base64::Engine as _;
+use http::{HeaderMap, HeaderValue};
 
-use crate::error::ParseError;
+use crate::{
+    config::{DuplicateHeaderPolicy, ParserCompliance},
+    error::ParseError,
+};
 
 const DEFAULT_PART_CONTENT_TYPE: &str = "application/octet-stream";
 
@@ -28,10 +32,69 @@ pub struct ParsedPartHeaders {
     pub file_name: Option<String>,
     /// Parsed part-level content type.
     pub content_type: mime::Mime,
+    /// Parsed part-level `Content-Transfer-Encoding`.
+    pub transfer_encoding: ContentTransferEncoding,
+    /// Parsed part-level `Content-Encoding`.
+    pub content_encoding: ContentEncoding,
+    /// Body length declared by a part-level `Content-Length`, when valid.
+    pub content_length: Option<u64>,
+}
+
+/// Part-level `Content-Transfer-Encoding` (RFC 2045).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum ContentTransferEncoding {
+    /// Body is not encoded (`7bit`, `8bit`, `binary`, or no header).
+    #[default]
+    Identity,
+    /// Body is base64-encoded and is decoded while streaming.
+    Base64,
+    /// Body is quoted-printable encoded and is decoded while streaming.
+    QuotedPrintable,
+    /// Any other encoding, passed through untouched (lowercased token) unless
+    /// [`crate::TransferEncodingPolicy::Reject`] is configured.
+    Other(String),
+}
+
+/// Part-level `Content-Encoding` compression.
+///
+/// Compressed bodies are decompressed while streaming when the
+/// `decompression` feature is enabled.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum ContentEncoding {
+    /// Body is not compressed (`identity` or no header).
+    #[default]
+    Identity,
+    /// `gzip` compression.
+    Gzip,
+    /// `deflate` (zlib-wrapped) compression.
+    Deflate,
+    /// `br` (Brotli) compression.
+    Brotli,
+    /// Any other encoding, passed through untouched (lowercased token).
+    Other(String),
 }
 
 /// Parses a multipart part `Content-Disposition` value.
 pub fn parse_content_disposition(value: &str) -> Result<ContentDisposition, ParseError> {
+    let content_disposition = parse_disposition_params(value)?;
+
+    if content_disposition.disposition == "form-data"
+        && matches!(content_disposition.name.as_deref(), None | Some(""))
+    {
+        return Err(missing_form_data_name());
+    }
+
+    Ok(content_disposition)
+}
+
+fn missing_form_data_name() -> ParseError {
+    ParseError::new("form-data Content-Disposition must include non-empty `name`")
+}
+
+/// Parses a `Content-Disposition` value without requiring a field name.
+fn parse_disposition_params(value: &str) -> Result<ContentDisposition, ParseError> {
     let mut segments = split_semicolon_aware(value).into_iter();
     let disposition = segments
         .next()
@@ -42,6 +105,7 @@ pub fn parse_content_disposition(value: &str) -> Result<ContentDisposition, Pars
     let mut name: Option<String> = None;
     let mut filename: Option<String> = None;
     let mut filename_star: Option<String> = None;
+    let mut filename_sections: Vec<FilenameSection> = Vec::new();
 
     for segment in segments {
         let trimmed = segment.trim();
@@ -61,17 +125,30 @@ pub fn parse_content_disposition(value: &str) -> Result<ContentDisposition, Pars
         match key.as_str() {
             "name" => name = Some(decoded),
             "filename" => filename = Some(parse_filename_value(&decoded)?),
-            "filename*" => filename_star = Some(parse_rfc5987_value(&decoded)?),
-            _ => {}
+            "filename*" => filename_star = Some(decoded),
+            _ => {
+                if let Some(section) = parse_filename_section(&key, decoded)? {
+                    filename_sections.push(section);
+                }
+            }
         }
     }
 
-    if disposition == "form-data" && matches!(name.as_deref(), None | Some("")) {
-        return Err(ParseError::new(
-            "form-data Content-Disposition must include non-empty `name`",
-        ));
+    if filename_star.is_none() && !filename_sections.is_empty() {
+        filename_star = Some(join_rfc2231_sections(filename_sections)?);
     }
 
+    let filename_star = match filename_star {
+        Some(value) => match parse_rfc5987_value(&value) {
+            Ok(decoded) => Some(decoded),
+            // RFC 6266: fall back to `filename` when `filename*` uses a
+            // charset we cannot decode.
+            Err(_) if filename.is_some() && is_unsupported_charset(&value) => None,
+            Err(err) => return Err(err),
+        },
+        None => None,
+    };
+
     Ok(ContentDisposition {
         disposition,
         name,
@@ -86,24 +163,84 @@ pub fn parse_part_content_type(value: Option<&str>) -> Result<mime::Mime, ParseE
         .map_err(|_| ParseError::new("invalid part Content-Type header"))
 }
 
+/// Parses a part-level `Content-Transfer-Encoding` value.
+pub fn parse_content_transfer_encoding(value: Option<&str>) -> ContentTransferEncoding {
+    let Some(value) = value.map(str::trim) else {
+        return ContentTransferEncoding::Identity;
+    };
+
+    let value = value.to_ascii_lowercase();
+    match value.as_str() {
+        "" | "7bit" | "8bit" | "binary" => ContentTransferEncoding::Identity,
+        "base64" => ContentTransferEncoding::Base64,
+        "quoted-printable" => ContentTransferEncoding::QuotedPrintable,
+        _ => ContentTransferEncoding::Other(value),
+    }
+}
+
+/// Parses a part-level `Content-Encoding` value.
+pub fn parse_content_encoding(value: Option<&str>) -> ContentEncoding {
+    let Some(value) = value.map(str::trim) else {
+        return ContentEncoding::Identity;
+    };
+
+    let value = value.to_ascii_lowercase();
+    match value.as_str() {
+        "" | "identity" => ContentEncoding::Identity,
+        "gzip" | "x-gzip" => ContentEncoding::Gzip,
+        "deflate" => ContentEncoding::Deflate,
+        "br" => ContentEncoding::Brotli,
+        _ => ContentEncoding::Other(value),
+    }
+}
+
 /// Parses multipart part headers needed by higher-level parser stages.
 pub fn parse_part_headers(headers: &HeaderMap) -> Result<ParsedPartHeaders, ParseError> {
-    let disposition_raw = headers
-        .get(header::CONTENT_DISPOSITION)
+    parse_part_headers_with_policy(headers, DuplicateHeaderPolicy::default())
+}
+
+/// Parses multipart part headers, resolving repeated interpreted headers with `duplicates`.
+pub fn parse_part_headers_with_policy(
+    headers: &HeaderMap,
+    duplicates: DuplicateHeaderPolicy,
+) -> Result<ParsedPartHeaders, ParseError> {
+    parse_part_headers_with_fallback_name(headers, None, ParserCompliance::Strict, duplicates)
+}
+
+/// Parses part headers, using `fallback_name` when the part carries no `name`.
+///
+/// Parts nested in a `multipart/mixed` body are attributed to the enclosing
+/// field and usually only declare a `filename`. Under
+/// [`ParserCompliance::Lenient`] a part with neither gets an empty field name.
+pub(crate) fn parse_part_headers_with_fallback_name(
+    headers: &HeaderMap,
+    fallback_name: Option<&str>,
+    compliance: ParserCompliance,
+    duplicates: DuplicateHeaderPolicy,
+) -> Result<ParsedPartHeaders, ParseError> {
+    let disposition_raw = single_header(headers, "Content-Disposition", duplicates)?
         .ok_or_else(|| ParseError::new("missing Content-Disposition header"))?;
 
     let disposition_raw = disposition_raw
         .to_str()
         .map_err(|_| ParseError::new("Content-Disposition header must be ASCII"))?;
-    let content_disposition = parse_content_disposition(disposition_raw)?;
+    let content_disposition = parse_disposition_params(disposition_raw)?;
 
-    let field_name = content_disposition
+    let field_name = match content_disposition
         .name
-        .clone()
-        .ok_or_else(|| ParseError::new("missing part field name"))?;
+        .as_deref()
+        .filter(|name| !name.is_empty())
+        .or(fallback_name)
+    {
+        Some(name) => name.to_owned(),
+        None if compliance == ParserCompliance::Lenient => String::new(),
+        None if content_disposition.disposition == "form-data" => {
+            return Err(missing_form_data_name())
+        }
+        None => return Err(ParseError::new("missing part field name")),
+    };
 
-    let content_type_raw = headers
-        .get(header::CONTENT_TYPE)
+    let content_type_raw = single_header(headers, "Content-Type", duplicates)?
         .map(|value| {
             value
                 .to_str()
@@ -113,15 +250,153 @@ pub fn parse_part_headers(headers: &HeaderMap) -> Result<ParsedPartHeaders, Pars
 
     let content_type = parse_part_content_type(content_type_raw)?;
 
+    let transfer_encoding_raw = single_header(headers, "Content-Transfer-Encoding", duplicates)?
+        .map(|value| {
+            value
+                .to_str()
+                .map_err(|_| ParseError::new("Content-Transfer-Encoding header must be ASCII"))
+        })
+        .transpose()?;
+    let transfer_encoding = parse_content_transfer_encoding(transfer_encoding_raw);
+
+    let content_encoding_raw = single_header(headers, "Content-Encoding", duplicates)?
+        .map(|value| {
+            value
+                .to_str()
+                .map_err(|_| ParseError::new("Content-Encoding header must be ASCII"))
+        })
+        .transpose()?;
+    let content_encoding = parse_content_encoding(content_encoding_raw);
+
+    let content_length = single_header(headers, "Content-Length", duplicates)?
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+
     Ok(ParsedPartHeaders {
         headers: headers.clone(),
         file_name: content_disposition.filename.clone(),
         content_disposition,
         field_name,
         content_type,
+        transfer_encoding,
+        content_encoding,
+        content_length,
     })
 }
 
+/// Returns the occurrence of header `name` selected by `duplicates`.
+fn single_header<'a>(
+    headers: &'a HeaderMap,
+    name: &'static str,
+    duplicates: DuplicateHeaderPolicy,
+) -> Result<Option<&'a HeaderValue>, ParseError> {
+    let mut values = headers.get_all(name).iter();
+    let first = values.next();
+    match duplicates {
+        DuplicateHeaderPolicy::UseFirst => Ok(first),
+        DuplicateHeaderPolicy::UseLast => Ok(values.next_back().or(first)),
+        DuplicateHeaderPolicy::Reject if values.next().is_some() => {
+            Err(ParseError::new(format!("duplicate {name} header")))
+        }
+        DuplicateHeaderPolicy::Reject => Ok(first),
+    }
+}
+
+/// Decodes RFC 2047 encoded-words (`=?charset?B|Q?text?=`) embedded in `value`.
+///
+/// UTF-8, ISO-8859-1, and US-ASCII charsets are supported. Words that are
+/// malformed or use another charset are left untouched, and whitespace
+/// between adjacent encoded-words is dropped as the RFC requires.
+pub fn decode_encoded_words(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    let mut after_word = false;
+
+    while let Some(start) = rest.find("=?") {
+        let (before, candidate) = rest.split_at(start);
+        match decode_encoded_word(candidate) {
+            Some((decoded, consumed)) => {
+                if !(after_word && before.chars().all(char::is_whitespace)) {
+                    out.push_str(before);
+                }
+                out.push_str(&decoded);
+                rest = &candidate[consumed..];
+                after_word = true;
+            }
+            None => {
+                out.push_str(before);
+                out.push_str("=?");
+                rest = &candidate[2..];
+                after_word = false;
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Decodes one encoded-word at the start of `candidate`, returning the text and bytes consumed.
+fn decode_encoded_word(candidate: &str) -> Option<(String, usize)> {
+    let body = candidate.strip_prefix("=?")?;
+    let (charset, body) = body.split_once('?')?;
+    let (encoding, body) = body.split_once('?')?;
+    let end = body.find("?=")?;
+    let text = &body[..end];
+
+    if charset.is_empty() || text.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let bytes = if encoding.eq_ignore_ascii_case("b") {
+        base64::engine::general_purpose::STANDARD
+            .decode(text)
+            .ok()?
+    } else if encoding.eq_ignore_ascii_case("q") {
+        decode_q_encoding(text)?
+    } else {
+        return None;
+    };
+
+    // RFC 2231 allows a `*language` suffix on the charset.
+    let charset = charset
+        .split_once('*')
+        .map_or(charset, |(charset, _)| charset);
+    let decoded = if charset.eq_ignore_ascii_case("utf-8") {
+        String::from_utf8(bytes).ok()?
+    } else if charset.eq_ignore_ascii_case("iso-8859-1") || charset.eq_ignore_ascii_case("us-ascii")
+    {
+        bytes.into_iter().map(char::from).collect()
+    } else {
+        return None;
+    };
+
+    let consumed = candidate.len() - body.len() + end + 2;
+    Some((decoded, consumed))
+}
+
+fn decode_q_encoding(text: &str) -> Option<Vec<u8>> {
+    let raw = text.as_bytes();
+    let mut bytes = Vec::with_capacity(raw.len());
+    let mut index = 0;
+
+    while index < raw.len() {
+        match raw[index] {
+            b'_' => bytes.push(b' '),
+            b'=' => {
+                let hi = char::from(*raw.get(index + 1)?).to_digit(16)?;
+                let lo = char::from(*raw.get(index + 2)?).to_digit(16)?;
+                bytes.push(((hi << 4) | lo) as u8);
+                index += 2;
+            }
+            byte => bytes.push(byte),
+        }
+        index += 1;
+    }
+
+    Some(bytes)
+}
+
 fn parse_parameter_value(raw: &str) -> Result<String, ParseError> {
     if let Some(stripped) = raw.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
         return unescape_quoted_string(stripped);
@@ -152,20 +427,102 @@ fn unescape_quoted_string(value: &str) -> Result<String, ParseError> {
     Ok(out)
 }
 
+/// One `filename*N` / `filename*N*` section of an RFC 2231 continuation.
+struct FilenameSection {
+    index: usize,
+    encoded: bool,
+    value: String,
+}
+
+fn parse_filename_section(key: &str, value: String) -> Result<Option<FilenameSection>, ParseError> {
+    let Some(rest) = key.strip_prefix("filename*") else {
+        return Ok(None);
+    };
+
+    let (digits, encoded) = match rest.strip_suffix('*') {
+        Some(digits) => (digits, true),
+        None => (rest, false),
+    };
+
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Ok(None);
+    }
+
+    if digits.len() > 1 && digits.starts_with('0') {
+        return Err(ParseError::new("invalid filename* continuation index"));
+    }
+
+    let index = digits
+        .parse::<usize>()
+        .map_err(|_| ParseError::new("invalid filename* continuation index"))?;
+
+    Ok(Some(FilenameSection {
+        index,
+        encoded,
+        value,
+    }))
+}
+
+/// Reassembles RFC 2231 continuation sections into a single extended value.
+///
+/// The result uses the `charset'language'percent-encoded` form so it can be
+/// decoded by [`parse_rfc5987_value`]; literal sections are percent-encoded.
+fn join_rfc2231_sections(mut sections: Vec<FilenameSection>) -> Result<String, ParseError> {
+    sections.sort_by_key(|section| section.index);
+
+    for (expected, section) in sections.iter().enumerate() {
+        if section.index != expected {
+            return Err(ParseError::new("incomplete filename* continuation"));
+        }
+    }
+
+    let mut joined = String::new();
+    if !sections[0].encoded {
+        joined.push_str("utf-8''");
+    }
+
+    for section in &sections {
+        if section.encoded {
+            joined.push_str(&section.value);
+        } else {
+            for byte in section.value.bytes() {
+                if byte.is_ascii_alphanumeric() {
+                    joined.push(byte as char);
+                } else {
+                    joined.push_str(&format!("%{byte:02X}"));
+                }
+            }
+        }
+    }
+
+    Ok(joined)
+}
+
 fn parse_rfc5987_value(value: &str) -> Result<String, ParseError> {
     let Some((charset, encoded)) = split_rfc5987(value) else {
         return Err(ParseError::new("invalid filename* parameter encoding"));
     };
 
-    if !charset.eq_ignore_ascii_case("utf-8") {
-        return Err(ParseError::new("only UTF-8 filename* charset is supported"));
+    let bytes = percent_decode(encoded, "invalid percent-encoding in filename*")?;
+
+    if charset.eq_ignore_ascii_case("utf-8") {
+        return String::from_utf8(bytes)
+            .map_err(|_| ParseError::new("filename* is not valid UTF-8"));
     }
 
-    percent_decode_utf8(
-        encoded,
-        "invalid percent-encoding in filename*",
-        "filename* is not valid UTF-8",
-    )
+    if charset.eq_ignore_ascii_case("iso-8859-1") {
+        return Ok(bytes.into_iter().map(char::from).collect());
+    }
+
+    Err(ParseError::new(
+        "only UTF-8 and ISO-8859-1 filename* charsets are supported",
+    ))
+}
+
+fn is_unsupported_charset(value: &str) -> bool {
+    split_rfc5987(value).is_some_and(|(charset, _)| {
+        !charset.eq_ignore_ascii_case("utf-8") && !charset.eq_ignore_ascii_case("iso-8859-1")
+    })
 }
 
 fn split_rfc5987(value: &str) -> Option<(&str, &str)> {
@@ -191,6 +548,14 @@ fn percent_decode_utf8(
     invalid_encoding_message: &'static str,
     invalid_utf8_message: &'static str,
 ) -> Result<String, ParseError> {
+    let bytes = percent_decode(value, invalid_encoding_message)?;
+    String::from_utf8(bytes).map_err(|_| ParseError::new(invalid_utf8_message))
+}
+
+fn percent_decode(
+    value: &str,
+    invalid_encoding_message: &'static str,
+) -> Result<Vec<u8>, ParseError> {
     let mut bytes = Vec::with_capacity(value.len());
     let raw = value.as_bytes();
     let mut index = 0;
@@ -211,7 +576,7 @@ fn percent_decode_utf8(
         index += 1;
     }
 
-    String::from_utf8(bytes).map_err(|_| ParseError::new(invalid_utf8_message))
+    Ok(bytes)
 }
 
 fn hex_value(byte: u8, invalid_encoding_message: &'static str) -> Result<u8, ParseError> {
@@ -1,16 +1,22 @@
 /// Multipart boundary parsing helpers.
 pub mod boundary;
+#[cfg(feature = "decompression")]
+mod decompress;
 /// Multipart part header parsing helpers.
 pub mod headers;
+pub(crate) mod source;
 /// Streaming multipart parser state machine.
 pub mod stream;
+mod transfer;
 
-pub use boundary::extract_multipart_boundary;
+pub use boundary::{extract_any_multipart_boundary, extract_multipart_boundary};
 pub use headers::{
-    parse_content_disposition, parse_part_content_type, parse_part_headers, ContentDisposition,
+    decode_encoded_words, parse_content_disposition, parse_content_encoding,
+    parse_content_transfer_encoding, parse_part_content_type, parse_part_headers,
+    parse_part_headers_with_policy, ContentDisposition, ContentEncoding, ContentTransferEncoding,
     ParsedPartHeaders,
 };
-pub use stream::MultipartStream;
+pub use stream::{MultipartStream, ParserStats};
 
 /// Low-level multipart parser entry type.
 #[derive(Debug, Clone, Default)]
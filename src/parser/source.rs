@@ -0,0 +1,53 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::Stream;
+
+use crate::MulterError;
+
+/// Push-fed chunk source for parsers driven by something other than a stream.
+///
+/// Yields queued chunks in order, then `Pending` until more are pushed or the
+/// queue is closed. Owners refill the queue and poll the parser again rather
+/// than relying on a wake-up.
+#[derive(Debug, Default)]
+pub(crate) struct ChunkQueue {
+    chunks: VecDeque<Bytes>,
+    closed: bool,
+}
+
+impl ChunkQueue {
+    /// Appends a chunk for the parser to consume.
+    pub(crate) fn push(&mut self, chunk: Bytes) {
+        if !chunk.is_empty() {
+            self.chunks.push_back(chunk);
+        }
+    }
+
+    /// Marks the input as complete; the stream ends once queued chunks drain.
+    pub(crate) fn close(&mut self) {
+        self.closed = true;
+    }
+
+    /// Returns `true` once [`ChunkQueue::close`] has been called.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
+
+impl Stream for ChunkQueue {
+    type Item = Result<Bytes, MulterError>;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        match this.chunks.pop_front() {
+            Some(chunk) => Poll::Ready(Some(Ok(chunk))),
+            None if this.closed => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
@@ -1,14 +1,33 @@
-use std::task::{Context, Poll};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
 
-use bytes::Bytes;
-use futures::{future::poll_fn, Stream};
+use bytes::{Buf, Bytes, BytesMut};
+use futures::{future::poll_fn, Future, Stream};
 use http::{
     header::{self},
     HeaderMap, HeaderName, HeaderValue,
 };
+use memchr::memmem::Finder;
+use tokio::time::{Instant, Sleep};
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
 
+#[cfg(feature = "decompression")]
+use crate::parser::decompress::{DecompressError, Decompressor};
 use crate::{
-    parser::headers::{parse_part_headers, ParsedPartHeaders},
+    config::{
+        DuplicateHeaderPolicy, EncodedWordPolicy, EpiloguePolicy, LineEndingPolicy,
+        ParserCompliance, TransferEncodingPolicy,
+    },
+    parser::{
+        headers::{
+            decode_encoded_words, parse_part_headers_with_fallback_name, ContentEncoding,
+            ContentTransferEncoding, ParsedPartHeaders,
+        },
+        transfer::BodyDecoder,
+    },
     MulterError, ParseError,
 };
 
@@ -21,8 +40,27 @@ enum ParseState {
     Failed,
 }
 
+/// Default cap on bytes the parser holds while searching for a boundary or header terminator.
+pub const DEFAULT_MAX_BUFFERED_BYTES: usize = 1024 * 1024;
+
+/// Default waiting time allowed before a minimum throughput applies.
+pub const DEFAULT_MIN_THROUGHPUT_GRACE: Duration = Duration::from_secs(10);
+
+/// Buffered input up to which the parser keeps pulling chunks that upstream
+/// has ready, so tiny frames (common with HTTP/2) are scanned in batches.
+const COALESCE_TARGET: usize = 8 * 1024;
+
+/// Longest run of transport padding accepted after a boundary delimiter.
+///
+/// Body input is not subject to `max_buffered_bytes`, so the padding the
+/// parser waits on is bounded here instead.
+const MAX_TRANSPORT_PADDING: usize = 256;
+
 /// Stream-level limits enforced while parsing multipart input.
-#[derive(Debug, Clone, Copy, Default)]
+///
+/// Start from [`StreamLimits::default`] and adjust it with the `with_*` setters.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
 pub struct StreamLimits {
     /// Maximum accepted file size in bytes for a single file part.
     pub max_file_size: Option<u64>,
@@ -30,23 +68,210 @@ pub struct StreamLimits {
     pub max_field_size: Option<u64>,
     /// Maximum request body size in bytes.
     pub max_body_size: Option<u64>,
+    /// Maximum combined size in bytes of all file part bodies.
+    ///
+    /// Bodies discarded with [`MultipartStream::drain_current_part`] are not counted.
+    pub max_total_file_size: Option<u64>,
+    /// Maximum total number of parts in the stream.
+    pub max_parts: Option<usize>,
+    /// Maximum size in bytes of a single part's header block.
+    pub max_header_size: Option<u64>,
+    /// Maximum number of headers accepted on a single part.
+    pub max_headers_per_part: Option<usize>,
+    /// Maximum decompressed size in bytes of a single `Content-Encoding` part.
+    pub max_decompressed_size: Option<u64>,
+    /// Maximum time from the first read until the stream is fully parsed.
+    pub max_parse_duration: Option<Duration>,
+    /// Maximum time spent waiting for the next chunk from upstream.
+    pub idle_chunk_timeout: Option<Duration>,
+    /// Maximum average rate in bytes per second at which upstream is read.
+    pub max_bytes_per_second: Option<u64>,
+    /// Minimum average rate in bytes per second at which upstream must deliver,
+    /// measured over the time spent waiting on it.
+    pub min_throughput_bytes_per_sec: Option<u64>,
+    /// Waiting time allowed before `min_throughput_bytes_per_sec` applies.
+    pub min_throughput_grace: Duration,
+    /// Maximum unconsumed bytes held while looking for a boundary or the end of a header block.
+    ///
+    /// Defaults to [`DEFAULT_MAX_BUFFERED_BYTES`]; `None` disables the cap.
+    pub max_buffered_bytes: Option<usize>,
+}
+
+impl Default for StreamLimits {
+    fn default() -> Self {
+        Self {
+            max_file_size: None,
+            max_field_size: None,
+            max_body_size: None,
+            max_total_file_size: None,
+            max_parts: None,
+            max_header_size: None,
+            max_headers_per_part: None,
+            max_decompressed_size: None,
+            max_parse_duration: None,
+            idle_chunk_timeout: None,
+            max_bytes_per_second: None,
+            min_throughput_bytes_per_sec: None,
+            min_throughput_grace: DEFAULT_MIN_THROUGHPUT_GRACE,
+            max_buffered_bytes: Some(DEFAULT_MAX_BUFFERED_BYTES),
+        }
+    }
+}
+
+impl StreamLimits {
+    /// Sets the maximum accepted size in bytes for a single file part.
+    pub fn with_max_file_size(mut self, limit: u64) -> Self {
+        self.max_file_size = Some(limit);
+        self
+    }
+
+    /// Sets the maximum accepted size in bytes for a text field.
+    pub fn with_max_field_size(mut self, limit: u64) -> Self {
+        self.max_field_size = Some(limit);
+        self
+    }
+
+    /// Sets the maximum request body size in bytes.
+    pub fn with_max_body_size(mut self, limit: u64) -> Self {
+        self.max_body_size = Some(limit);
+        self
+    }
+
+    /// Sets the maximum combined size in bytes of all file part bodies.
+    pub fn with_max_total_file_size(mut self, limit: u64) -> Self {
+        self.max_total_file_size = Some(limit);
+        self
+    }
+
+    /// Sets the maximum total number of parts in the stream.
+    pub fn with_max_parts(mut self, limit: usize) -> Self {
+        self.max_parts = Some(limit);
+        self
+    }
+
+    /// Sets the maximum size in bytes of a single part's header block.
+    pub fn with_max_header_size(mut self, limit: u64) -> Self {
+        self.max_header_size = Some(limit);
+        self
+    }
+
+    /// Sets the maximum number of headers accepted on a single part.
+    pub fn with_max_headers_per_part(mut self, limit: usize) -> Self {
+        self.max_headers_per_part = Some(limit);
+        self
+    }
+
+    /// Sets the maximum decompressed size in bytes of a single encoded part.
+    pub fn with_max_decompressed_size(mut self, limit: u64) -> Self {
+        self.max_decompressed_size = Some(limit);
+        self
+    }
+
+    /// Sets the maximum time from the first read until the stream is fully parsed.
+    pub fn with_max_parse_duration(mut self, limit: Duration) -> Self {
+        self.max_parse_duration = Some(limit);
+        self
+    }
+
+    /// Sets the maximum time spent waiting for the next chunk from upstream.
+    pub fn with_idle_chunk_timeout(mut self, limit: Duration) -> Self {
+        self.idle_chunk_timeout = Some(limit);
+        self
+    }
+
+    /// Sets the maximum average rate in bytes per second at which upstream is read.
+    pub fn with_max_bytes_per_second(mut self, limit: u64) -> Self {
+        self.max_bytes_per_second = Some(limit);
+        self
+    }
+
+    /// Sets the minimum average rate in bytes per second upstream must deliver.
+    pub fn with_min_throughput_bytes_per_sec(mut self, limit: u64) -> Self {
+        self.min_throughput_bytes_per_sec = Some(limit);
+        self
+    }
+
+    /// Sets the waiting time allowed before the minimum throughput applies.
+    pub fn with_min_throughput_grace(mut self, grace: Duration) -> Self {
+        self.min_throughput_grace = grace;
+        self
+    }
+
+    /// Sets the cap on unconsumed bytes held while looking for a boundary;
+    /// `None` disables it.
+    pub fn with_max_buffered_bytes(mut self, limit: Option<usize>) -> Self {
+        self.max_buffered_bytes = limit;
+        self
+    }
+}
+
+/// Throughput counters collected while parsing, for spotting pathological payloads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParserStats {
+    /// Bytes the parser has processed, excluding input still buffered.
+    pub bytes_consumed: u64,
+    /// Non-empty chunks received from the upstream source.
+    pub chunks_received: u64,
+    /// Parts whose headers were parsed and returned.
+    pub parts_emitted: u64,
+    /// Largest number of bytes held in the parse buffer at once.
+    pub buffer_high_water_mark: usize,
+    /// Calls to the header and body polling entry points.
+    pub poll_count: u64,
 }
 
 /// Incremental multipart parser over a chunked byte stream.
 #[derive(Debug)]
 pub struct MultipartStream<S> {
     stream: S,
+    boundary: String,
     boundary_line: Vec<u8>,
     boundary_end_line: Vec<u8>,
     delimiter: Vec<u8>,
-    buffer: Vec<u8>,
+    line_ending: LineEndingPolicy,
+    finders: Finders,
+    buffer: BytesMut,
+    scan_offset: usize,
+    dash_line_scan_offset: usize,
     state: ParseState,
     current_headers: Option<ParsedPartHeaders>,
     current_part_max_size: Option<u64>,
+    current_part_min_size: Option<u64>,
     current_part_size: u64,
     current_part_is_file: bool,
+    current_part_discarded: bool,
+    total_file_size: u64,
+    decoder: BodyDecoder,
+    #[cfg(feature = "decompression")]
+    decompressor: Option<Decompressor>,
     limits: StreamLimits,
+    epilogue_policy: EpiloguePolicy,
+    encoded_word_policy: EncodedWordPolicy,
+    transfer_encoding_policy: TransferEncodingPolicy,
+    compliance: ParserCompliance,
+    duplicate_header_policy: DuplicateHeaderPolicy,
+    allow_missing_terminal_boundary: bool,
+    recoverable_part_limits: bool,
+    fallback_field_name: Option<String>,
+    close_line_pending: bool,
+    parse_deadline: Option<Pin<Box<Sleep>>>,
+    idle_deadline: Option<Pin<Box<Sleep>>>,
+    idle_waiting: bool,
+    throttle_start: Option<Instant>,
+    throttle: Option<Pin<Box<Sleep>>>,
+    upstream_wait_start: Option<Instant>,
+    upstream_waited: Duration,
+    throughput_deadline: Option<Pin<Box<Sleep>>>,
+    cancellation: Option<Pin<Box<WaitForCancellationFutureOwned>>>,
+    abort_signal: Option<Pin<Box<WaitForCancellationFutureOwned>>>,
+    aborted: bool,
     received_body_bytes: u64,
+    received_bytes: u64,
+    stats: ParserStats,
+    part_field_name: Option<String>,
+    parts_seen: usize,
+    deferred_error: Option<MulterError>,
     upstream_done: bool,
 }
 
@@ -67,25 +292,229 @@ impl<S> MultipartStream<S> {
 
         let boundary_line = format!("--{boundary}").into_bytes();
         let boundary_end_line = format!("--{boundary}--").into_bytes();
-        let delimiter = format!("\r\n--{boundary}").into_bytes();
+        let line_ending = LineEndingPolicy::default();
+        let delimiter = delimiter_for(&boundary, line_ending);
 
         Ok(Self {
             stream,
+            boundary,
             boundary_line,
             boundary_end_line,
+            finders: Finders::new(&delimiter, line_ending),
             delimiter,
-            buffer: Vec::new(),
+            line_ending,
+            buffer: BytesMut::new(),
+            scan_offset: 0,
+            dash_line_scan_offset: 0,
             state: ParseState::StartBoundary,
             current_headers: None,
             current_part_max_size: None,
+            current_part_min_size: None,
             current_part_size: 0,
             current_part_is_file: false,
+            current_part_discarded: false,
+            total_file_size: 0,
+            decoder: BodyDecoder::Identity,
+            #[cfg(feature = "decompression")]
+            decompressor: None,
             limits,
+            epilogue_policy: EpiloguePolicy::default(),
+            encoded_word_policy: EncodedWordPolicy::default(),
+            transfer_encoding_policy: TransferEncodingPolicy::default(),
+            compliance: ParserCompliance::default(),
+            duplicate_header_policy: DuplicateHeaderPolicy::default(),
+            allow_missing_terminal_boundary: false,
+            recoverable_part_limits: false,
+            fallback_field_name: None,
+            close_line_pending: false,
+            parse_deadline: None,
+            idle_deadline: None,
+            idle_waiting: false,
+            throttle_start: None,
+            throttle: None,
+            upstream_wait_start: None,
+            upstream_waited: Duration::ZERO,
+            throughput_deadline: None,
+            cancellation: None,
+            abort_signal: None,
+            aborted: false,
             received_body_bytes: 0,
+            received_bytes: 0,
+            stats: ParserStats::default(),
+            part_field_name: None,
+            parts_seen: 0,
+            deferred_error: None,
             upstream_done: false,
         })
     }
 
+    /// Sets how bytes following the terminal boundary are handled.
+    pub fn with_epilogue_policy(mut self, policy: EpiloguePolicy) -> Self {
+        self.epilogue_policy = policy;
+        self
+    }
+
+    /// Sets whether RFC 2047 encoded-word file names are decoded.
+    pub fn with_encoded_word_policy(mut self, policy: EncodedWordPolicy) -> Self {
+        self.encoded_word_policy = policy;
+        self
+    }
+
+    /// Sets how parts with an unsupported `Content-Transfer-Encoding` are handled.
+    pub fn with_transfer_encoding_policy(mut self, policy: TransferEncodingPolicy) -> Self {
+        self.transfer_encoding_policy = policy;
+        self
+    }
+
+    /// Sets how strictly part headers are checked against RFC 7578.
+    pub fn with_parser_compliance(mut self, compliance: ParserCompliance) -> Self {
+        self.compliance = compliance;
+        self
+    }
+
+    /// Sets how repeated interpreted part headers are handled.
+    pub fn with_duplicate_header_policy(mut self, policy: DuplicateHeaderPolicy) -> Self {
+        self.duplicate_header_policy = policy;
+        self
+    }
+
+    /// Sets whether input ending without the terminal `--boundary--` line ends cleanly.
+    ///
+    /// When enabled, completed parts are yielded and the stream ends instead of
+    /// failing with [`MulterError::IncompleteStream`], as long as input stops
+    /// at a part boundary. Input truncated inside a part body still fails.
+    pub fn with_allow_missing_terminal_boundary(mut self, allow: bool) -> Self {
+        self.allow_missing_terminal_boundary = allow;
+        self
+    }
+
+    /// Sets whether per-part size limit violations leave the parser usable.
+    ///
+    /// When enabled, a part exceeding its size limit or ending below its
+    /// minimum size still fails, but the rest of its body is discarded on
+    /// the next [`MultipartStream::poll_next_part_headers`] and parsing
+    /// continues with the following part. Total size limits stay fatal.
+    pub fn with_recoverable_part_limits(mut self, recoverable: bool) -> Self {
+        self.recoverable_part_limits = recoverable;
+        self
+    }
+
+    /// Stops parsing with [`MulterError::Cancelled`] once `token` is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(Box::pin(token.cancelled_owned()));
+        self
+    }
+
+    /// Aborts the parser once `token` is cancelled, as if [`MultipartStream::abort`] was called.
+    pub(crate) fn set_abort_token(&mut self, token: CancellationToken) {
+        self.abort_signal = Some(Box::pin(token.cancelled_owned()));
+    }
+
+    /// Stops parsing for good without reading any further input.
+    ///
+    /// Buffered input is discarded and every later read fails with
+    /// [`MulterError::Aborted`]. A fully parsed stream is left untouched.
+    pub fn abort(&mut self) {
+        if self.state == ParseState::End {
+            return;
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!("multipart parser: aborted");
+        self.aborted = true;
+        self.state = ParseState::Failed;
+        self.buffer.clear();
+        self.scan_offset = 0;
+        self.dash_line_scan_offset = 0;
+        self.reset_current_part();
+        self.deferred_error = None;
+        self.upstream_done = true;
+    }
+
+    /// Sets which line terminators are accepted in multipart framing.
+    pub fn with_line_ending_policy(mut self, policy: LineEndingPolicy) -> Self {
+        self.line_ending = policy;
+        self.delimiter = delimiter_for(&self.boundary, policy);
+        self.finders = Finders::new(&self.delimiter, policy);
+        self
+    }
+
+    /// Creates a parser for a multipart body nested inside the current part.
+    ///
+    /// The nested parser inherits this parser's policies and per-part limits,
+    /// and attributes nameless parts to `field_name`. The request-wide body
+    /// limit, timeouts and rate limits stay with this parser, which supplies the
+    /// nested bytes.
+    pub(crate) fn nested<T>(
+        &self,
+        boundary: impl Into<String>,
+        field_name: impl Into<String>,
+        stream: T,
+    ) -> Result<MultipartStream<T>, ParseError> {
+        let limits = StreamLimits {
+            max_body_size: None,
+            max_parse_duration: None,
+            idle_chunk_timeout: None,
+            max_bytes_per_second: None,
+            min_throughput_bytes_per_sec: None,
+            ..self.limits
+        };
+        let mut nested = MultipartStream::with_limits(boundary, stream, limits)?
+            .with_epilogue_policy(self.epilogue_policy)
+            .with_line_ending_policy(self.line_ending)
+            .with_encoded_word_policy(self.encoded_word_policy)
+            .with_transfer_encoding_policy(self.transfer_encoding_policy)
+            .with_parser_compliance(self.compliance)
+            .with_duplicate_header_policy(self.duplicate_header_policy)
+            .with_allow_missing_terminal_boundary(self.allow_missing_terminal_boundary)
+            .with_recoverable_part_limits(self.recoverable_part_limits);
+        nested.fallback_field_name = Some(field_name.into());
+        Ok(nested)
+    }
+
+    /// Returns the combined size of the file part bodies read so far.
+    pub(crate) fn total_file_size(&self) -> u64 {
+        self.total_file_size
+    }
+
+    /// Carries over file bytes counted by another parser, such as an enclosing one.
+    pub(crate) fn set_total_file_size(&mut self, total_file_size: u64) {
+        self.total_file_size = total_file_size;
+    }
+
+    /// Returns throughput counters collected so far.
+    pub fn stats(&self) -> ParserStats {
+        ParserStats {
+            bytes_consumed: self.received_bytes.saturating_sub(self.buffer.len() as u64),
+            ..self.stats
+        }
+    }
+
+    /// Consumes the parser, returning the upstream source and the input
+    /// buffered from it that has not been parsed yet.
+    pub fn into_inner(self) -> (S, Bytes) {
+        (self.stream, self.buffer.freeze())
+    }
+
+    /// Returns the upstream chunk source.
+    pub(crate) fn source_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Replaces the active part size limit while a part body is being read.
+    pub(crate) fn set_current_part_max_size(&mut self, limit: Option<u64>) {
+        if self.state == ParseState::Body {
+            self.current_part_max_size = limit;
+        }
+    }
+
+    /// Sets the minimum size the active file part must reach before its body ends.
+    pub(crate) fn set_current_part_min_size(&mut self, limit: Option<u64>) {
+        if self.state == ParseState::Body {
+            self.current_part_min_size = limit;
+        }
+    }
+
     /// Returns `true` when the parser is currently positioned in a part body.
     pub fn is_reading_part_body(&self) -> bool {
         self.state == ParseState::Body
@@ -105,17 +534,39 @@ impl<S> MultipartStream<S> {
     }
 
     /// Polls until the next part headers are available.
-    pub fn poll_next_part_headers(
+    ///
+    /// Parse errors carry the index and field name of the part being parsed
+    /// and the approximate byte offset where parsing stopped.
+    pub fn poll_next_part_headers<E>(
         &mut self,
         cx: &mut Context<'_>,
     ) -> Poll<Result<Option<ParsedPartHeaders>, MulterError>>
     where
-        S: Stream<Item = Result<Bytes, MulterError>> + Unpin,
+        S: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: Into<MulterError>,
     {
+        self.stats.poll_count += 1;
+        let poll = self.poll_part_headers(cx);
+        if let Poll::Ready(Ok(Some(_))) = &poll {
+            self.stats.parts_emitted += 1;
+        }
+        poll.map_err(|err| self.with_location(err))
+    }
+
+    fn poll_part_headers<E>(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<ParsedPartHeaders>, MulterError>>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: Into<MulterError>,
+    {
+        self.poll_cancellation(cx)?;
+
         loop {
             match self.state {
                 ParseState::StartBoundary => {
-                    let Some(line) = take_line(&mut self.buffer) else {
+                    let Some(line) = self.take_line() else {
                         if self.upstream_done {
                             self.state = ParseState::Failed;
                             return Poll::Ready(Err(
@@ -129,6 +580,7 @@ impl<S> MultipartStream<S> {
                         }
                     };
 
+                    let line = trim_transport_padding(&line);
                     if line == self.boundary_line {
                         #[cfg(feature = "tracing")]
                         tracing::trace!("multipart parser: opening boundary detected");
@@ -143,14 +595,28 @@ impl<S> MultipartStream<S> {
                         continue;
                     }
 
+                    // RFC 2046 allows arbitrary preamble before the first
+                    // delimiter; it carries no meaning and is discarded.
                     #[cfg(feature = "tracing")]
-                    tracing::warn!("multipart parser: malformed opening boundary");
-                    self.state = ParseState::Failed;
-                    return Poll::Ready(Err(ParseError::new("malformed opening boundary").into()));
+                    tracing::trace!(len = line.len(), "multipart parser: skipping preamble line");
+                    continue;
                 }
                 ParseState::Headers => {
-                    let Some(split) = find_subslice(&self.buffer, b"\r\n\r\n") else {
+                    let Some((split, terminator_len)) = self.find_header_end() else {
+                        // Up to three bytes of a partially received terminator may be buffered.
+                        let pending = self.buffer.len().saturating_sub(3);
+                        if let Err(err) = self.ensure_header_size(pending) {
+                            return Poll::Ready(Err(err));
+                        }
+
                         if self.upstream_done {
+                            if self.allow_missing_terminal_boundary
+                                && self.buffer.iter().all(u8::is_ascii_whitespace)
+                            {
+                                self.end_without_terminal_boundary();
+                                return Poll::Ready(Ok(None));
+                            }
+
                             self.state = ParseState::Failed;
                             return Poll::Ready(Err(MulterError::IncompleteStream));
                         }
@@ -161,13 +627,71 @@ impl<S> MultipartStream<S> {
                         }
                     };
 
-                    let raw = self.buffer[..split].to_vec();
-                    self.buffer.drain(..split + 4);
+                    if let Err(err) = self.ensure_header_size(split) {
+                        return Poll::Ready(Err(err));
+                    }
+
+                    self.parts_seen += 1;
+                    self.part_field_name = None;
+                    if let Some(max_parts) = self.limits.max_parts {
+                        if self.parts_seen > max_parts {
+                            #[cfg(feature = "tracing")]
+                            tracing::warn!(
+                                max_parts = max_parts,
+                                "multipart parser: part count limit exceeded"
+                            );
+                            self.state = ParseState::Failed;
+                            return Poll::Ready(Err(MulterError::PartsLimitExceeded { max_parts }));
+                        }
+                    }
+
+                    let raw = self.buffer.split_to(split);
+                    self.consume(terminator_len);
 
-                    let headers = match parse_header_block(&raw)
-                        .and_then(|h| parse_part_headers(&h))
+                    let header_map = match parse_header_block(
+                        &raw,
+                        self.line_ending,
+                        self.compliance,
+                    ) {
+                        Ok(header_map) => header_map,
+                        Err(err) => {
+                            #[cfg(feature = "tracing")]
+                            tracing::warn!(error = %err, "multipart parser: failed to parse part headers");
+                            self.state = ParseState::Failed;
+                            return Poll::Ready(Err(err.into()));
+                        }
+                    };
+
+                    if let Some(max_headers_per_part) = self.limits.max_headers_per_part {
+                        if header_map.len() > max_headers_per_part {
+                            #[cfg(feature = "tracing")]
+                            tracing::warn!(
+                                headers = header_map.len(),
+                                max_headers_per_part = max_headers_per_part,
+                                "multipart parser: part header count limit exceeded"
+                            );
+                            self.state = ParseState::Failed;
+                            return Poll::Ready(Err(MulterError::HeaderCountLimitExceeded {
+                                max_headers_per_part,
+                            }));
+                        }
+                    }
+
+                    let headers = match parse_part_headers_with_fallback_name(
+                        &header_map,
+                        self.fallback_field_name.as_deref(),
+                        self.compliance,
+                        self.duplicate_header_policy,
+                    )
+                    .and_then(|headers| self.check_disposition(headers))
                     {
-                        Ok(headers) => headers,
+                        Ok(mut headers) => {
+                            if self.encoded_word_policy == EncodedWordPolicy::Decode {
+                                decode_file_name(&mut headers);
+                            }
+                            self.part_field_name = Some(headers.field_name.clone());
+                            headers
+                        }
                         Err(err) => {
                             #[cfg(feature = "tracing")]
                             tracing::warn!(error = %err, "multipart parser: failed to parse part headers");
@@ -176,6 +700,22 @@ impl<S> MultipartStream<S> {
                         }
                     };
 
+                    if let ContentTransferEncoding::Other(encoding) = &headers.transfer_encoding {
+                        if self.transfer_encoding_policy == TransferEncodingPolicy::Reject {
+                            #[cfg(feature = "tracing")]
+                            tracing::warn!(
+                                field_name = headers.field_name.as_str(),
+                                encoding = encoding.as_str(),
+                                "multipart parser: unsupported transfer encoding"
+                            );
+                            self.state = ParseState::Failed;
+                            return Poll::Ready(Err(MulterError::UnsupportedTransferEncoding {
+                                field: headers.field_name,
+                                encoding: encoding.clone(),
+                            }));
+                        }
+                    }
+
                     self.current_part_is_file = headers.file_name.is_some();
                     self.current_part_max_size = if self.current_part_is_file {
                         self.limits.max_file_size
@@ -183,7 +723,20 @@ impl<S> MultipartStream<S> {
                         self.limits.max_field_size
                     };
                     self.current_part_size = 0;
+                    self.decoder = BodyDecoder::for_encoding(&headers.transfer_encoding);
+                    #[cfg(feature = "decompression")]
+                    {
+                        self.decompressor = Decompressor::for_encoding(
+                            &headers.content_encoding,
+                            self.limits.max_decompressed_size,
+                        );
+                    }
                     self.current_headers = Some(headers.clone());
+                    if let Err(err) = self.ensure_declared_length() {
+                        self.state = ParseState::Body;
+                        self.fail_current_part(false);
+                        return Poll::Ready(Err(err));
+                    }
                     self.state = ParseState::Body;
                     #[cfg(feature = "tracing")]
                     tracing::trace!(
@@ -199,28 +752,204 @@ impl<S> MultipartStream<S> {
                     )
                     .into()));
                 }
-                ParseState::End => return Poll::Ready(Ok(None)),
+                ParseState::End => {
+                    if self.close_line_pending {
+                        let padding = transport_padding_len(&self.buffer);
+                        let rest = &self.buffer[padding..];
+                        if (rest.is_empty() || rest == b"\r") && !self.upstream_done {
+                            match self.poll_fill_buffer(cx)? {
+                                Poll::Ready(()) => continue,
+                                Poll::Pending => return Poll::Pending,
+                            }
+                        }
+                        if let Some(len) = self.line_break_len(rest) {
+                            self.consume(padding + len);
+                        }
+                        self.close_line_pending = false;
+                    }
+
+                    if !self.buffer.is_empty() {
+                        if self.epilogue_policy == EpiloguePolicy::Reject {
+                            #[cfg(feature = "tracing")]
+                            tracing::warn!("multipart parser: unexpected epilogue rejected");
+                            self.state = ParseState::Failed;
+                            return Poll::Ready(Err(ParseError::new(
+                                "unexpected data after terminal boundary",
+                            )
+                            .into()));
+                        }
+
+                        #[cfg(feature = "tracing")]
+                        tracing::trace!(
+                            len = self.buffer.len(),
+                            "multipart parser: discarding epilogue bytes"
+                        );
+                        self.buffer.clear();
+                        self.scan_offset = 0;
+                        self.dash_line_scan_offset = 0;
+                    }
+
+                    if self.upstream_done {
+                        return Poll::Ready(Ok(None));
+                    }
+
+                    match self.poll_fill_buffer(cx)? {
+                        Poll::Ready(()) => continue,
+                        Poll::Pending => return Poll::Pending,
+                    }
+                }
                 ParseState::Failed => return Poll::Ready(Ok(None)),
             }
         }
     }
 
     /// Polls the next chunk for the currently active part body.
-    pub fn poll_next_part_chunk(
+    ///
+    /// Bodies declaring a `Content-Transfer-Encoding` the parser understands
+    /// are decoded before size limits are applied. Parse errors carry the
+    /// same location context as [`MultipartStream::poll_next_part_headers`].
+    pub fn poll_next_part_chunk<E>(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<Bytes>, MulterError>>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: Into<MulterError>,
+    {
+        self.stats.poll_count += 1;
+        self.poll_part_chunk(cx)
+            .map_err(|err| self.with_location(err))
+    }
+
+    fn poll_part_chunk<E>(
         &mut self,
         cx: &mut Context<'_>,
     ) -> Poll<Result<Option<Bytes>, MulterError>>
     where
-        S: Stream<Item = Result<Bytes, MulterError>> + Unpin,
+        S: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: Into<MulterError>,
+    {
+        if self.current_headers.is_some() {
+            if let Err(err) = self.poll_cancellation(cx) {
+                self.reset_current_part();
+                return Poll::Ready(Err(err));
+            }
+        }
+
+        loop {
+            if self.current_headers.is_none() {
+                return Poll::Ready(Ok(None));
+            }
+
+            let raw = match self.poll_next_raw_chunk(cx) {
+                Poll::Ready(Ok(raw)) => raw,
+                Poll::Ready(Err(err)) => {
+                    self.reset_current_part();
+                    return Poll::Ready(Err(err));
+                }
+                Poll::Pending => return Poll::Pending,
+            };
+            let part_ended = self.state != ParseState::Body;
+
+            let decoded = match self.decode_body_chunk(raw, part_ended) {
+                Ok(decoded) => decoded,
+                Err(err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(error = %err, "multipart parser: failed to decode part body");
+                    self.state = ParseState::Failed;
+                    self.reset_current_part();
+                    return Poll::Ready(Err(err));
+                }
+            };
+
+            if let Err(err) = self.ensure_part_limit(decoded.len() as u64) {
+                self.fail_current_part(part_ended);
+                return Poll::Ready(Err(err));
+            }
+            if let Err(err) = self.ensure_total_file_limit(decoded.len() as u64) {
+                self.state = ParseState::Failed;
+                self.reset_current_part();
+                return Poll::Ready(Err(err));
+            }
+            self.current_part_size = self.current_part_size.saturating_add(decoded.len() as u64);
+            if self.counts_toward_total_file_size() {
+                self.total_file_size = self.total_file_size.saturating_add(decoded.len() as u64);
+            }
+
+            if part_ended {
+                if let Err(err) = self.ensure_part_min_size() {
+                    self.fail_current_part(true);
+                    return Poll::Ready(Err(err));
+                }
+                self.reset_current_part();
+                return Poll::Ready(Ok((!decoded.is_empty()).then_some(decoded)));
+            }
+
+            if !decoded.is_empty() {
+                return Poll::Ready(Ok(Some(decoded)));
+            }
+        }
+    }
+
+    /// Applies transfer decoding and, when enabled, decompression to a raw body chunk.
+    ///
+    /// When `part_ended` is set the decoders are flushed as well.
+    fn decode_body_chunk(
+        &mut self,
+        raw: Option<Bytes>,
+        part_ended: bool,
+    ) -> Result<Bytes, MulterError> {
+        let mut decoded = match raw {
+            Some(raw) => self.decoder.decode(raw)?,
+            None => Bytes::new(),
+        };
+        if part_ended {
+            decoded = concat_bytes(decoded, self.decoder.finish()?);
+        }
+
+        #[cfg(feature = "decompression")]
+        if let Some(decompressor) = self.decompressor.as_mut() {
+            let mut result = decompressor.decompress(&decoded);
+            if part_ended {
+                result = result.and_then(|head| Ok(concat_bytes(head, decompressor.finish()?)));
+            }
+            decoded = result.map_err(|err| match err {
+                DecompressError::Invalid(err) => MulterError::from(err),
+                DecompressError::LimitExceeded => MulterError::DecompressedSizeLimitExceeded {
+                    field: self
+                        .current_headers
+                        .as_ref()
+                        .map(|headers| headers.field_name.clone())
+                        .unwrap_or_else(|| "<unknown>".to_owned()),
+                    max_decompressed_size: self.limits.max_decompressed_size.unwrap_or_default(),
+                },
+            })?;
+        }
+
+        Ok(decoded)
+    }
+
+    /// Polls the next undecoded body chunk of the current part.
+    fn poll_next_raw_chunk<E>(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<Bytes>, MulterError>>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: Into<MulterError>,
     {
         loop {
             if self.state != ParseState::Body {
                 return Poll::Ready(Ok(None));
             }
 
-            if let Some(split) = find_subslice(&self.buffer, &self.delimiter) {
+            if let Some(split) =
+                find_from(&self.finders.delimiter, &self.buffer, &mut self.scan_offset)
+            {
                 let suffix_start = split + self.delimiter.len();
                 let Some(boundary_suffix) = self.buffer.get(suffix_start..) else {
+                    // The delimiter cannot start earlier once more input arrives.
+                    self.scan_offset = split;
                     if self.upstream_done {
                         self.state = ParseState::Failed;
                         return Poll::Ready(Err(MulterError::IncompleteStream));
@@ -232,12 +961,32 @@ impl<S> MultipartStream<S> {
                     }
                 };
 
-                let (consumed, is_terminal) = if boundary_suffix.starts_with(b"--\r\n") {
-                    (suffix_start + 4, true)
-                } else if boundary_suffix.starts_with(b"\r\n") {
-                    (suffix_start + 2, false)
-                } else if self.upstream_done && boundary_suffix == b"--" {
+                // RFC 2046 transport padding (SP/HTAB) may precede the line break.
+                let padding = transport_padding_len(boundary_suffix);
+                let (consumed, is_terminal) = if boundary_suffix.starts_with(b"--") {
+                    // The close line (padding, line break, epilogue) is handled in `End`.
+                    self.close_line_pending = true;
                     (suffix_start + 2, true)
+                } else if let Some(len) = self.line_break_len(&boundary_suffix[padding..]) {
+                    (suffix_start + padding + len, false)
+                } else if padding > MAX_TRANSPORT_PADDING {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(padding, "multipart parser: boundary padding too long");
+                    self.state = ParseState::Failed;
+                    return Poll::Ready(
+                        Err(ParseError::new("malformed multipart boundary").into()),
+                    );
+                } else if boundary_suffix.len() - padding < 2 && !self.upstream_done {
+                    self.scan_offset = split;
+                    match self.poll_fill_buffer(cx)? {
+                        Poll::Ready(()) => continue,
+                        Poll::Pending => return Poll::Pending,
+                    }
+                } else if self.allow_missing_terminal_boundary
+                    && matches!(&boundary_suffix[padding..], b"" | b"-")
+                {
+                    // Input stopped after the delimiter; treat it as the terminal boundary.
+                    (self.buffer.len(), true)
                 } else {
                     self.state = ParseState::Failed;
                     return Poll::Ready(
@@ -245,24 +994,23 @@ impl<S> MultipartStream<S> {
                     );
                 };
 
-                if let Err(err) = self.ensure_part_limit(split as u64) {
-                    self.state = ParseState::Failed;
-                    return Poll::Ready(Err(err));
-                }
+                // A lenient `LF` delimiter still owns a preceding `CR`.
+                let body_end = if self.line_ending == LineEndingPolicy::Lenient
+                    && split > 0
+                    && self.buffer[split - 1] == b'\r'
+                {
+                    split - 1
+                } else {
+                    split
+                };
 
-                let emit_chunk = if split == 0 {
+                let emit_chunk = if body_end == 0 {
                     None
                 } else {
-                    let bytes = Bytes::copy_from_slice(&self.buffer[..split]);
-                    self.current_part_size = self.current_part_size.saturating_add(split as u64);
-                    Some(bytes)
+                    Some(self.buffer.split_to(body_end).freeze())
                 };
 
-                self.buffer.drain(..consumed);
-                self.current_headers = None;
-                self.current_part_max_size = None;
-                self.current_part_size = 0;
-                self.current_part_is_file = false;
+                self.consume(consumed - body_end);
                 self.state = if is_terminal {
                     #[cfg(feature = "tracing")]
                     tracing::trace!("multipart parser: terminal boundary reached");
@@ -276,32 +1024,27 @@ impl<S> MultipartStream<S> {
                 return Poll::Ready(Ok(emit_chunk));
             }
 
-            if has_malformed_boundary_line(
-                &self.buffer,
-                &self.boundary_line,
-                &self.boundary_end_line,
-            ) {
+            if self.has_malformed_boundary_line() {
                 #[cfg(feature = "tracing")]
                 tracing::warn!("multipart parser: malformed boundary line detected");
                 self.state = ParseState::Failed;
                 return Poll::Ready(Err(ParseError::new("malformed multipart boundary").into()));
             }
 
-            let max_tail = self.delimiter.len().saturating_sub(1);
-            let safe_len = self.buffer.len().saturating_sub(max_tail);
+            let safe_len = self.safe_body_len();
             if safe_len > 0 {
-                if let Err(err) = self.ensure_part_limit(safe_len as u64) {
-                    self.state = ParseState::Failed;
-                    return Poll::Ready(Err(err));
-                }
-
-                let bytes = Bytes::copy_from_slice(&self.buffer[..safe_len]);
-                self.buffer.drain(..safe_len);
-                self.current_part_size = self.current_part_size.saturating_add(safe_len as u64);
+                let bytes = self.buffer.split_to(safe_len).freeze();
+                self.scan_offset = 0;
+                self.dash_line_scan_offset = 0;
                 return Poll::Ready(Ok(Some(bytes)));
             }
 
             if self.upstream_done {
+                if self.allow_missing_terminal_boundary && self.truncated_at_delimiter() {
+                    self.end_without_terminal_boundary();
+                    return Poll::Ready(Ok(None));
+                }
+
                 #[cfg(feature = "tracing")]
                 tracing::warn!("multipart parser: upstream ended before terminal boundary");
                 self.state = ParseState::Failed;
@@ -315,15 +1058,75 @@ impl<S> MultipartStream<S> {
         }
     }
 
+    /// Discards buffered input and reads the upstream source to its end.
+    ///
+    /// Parsing stops for good: no further parts are yielded. Limits are not
+    /// applied to the discarded input. Returns the number of bytes discarded,
+    /// including input that was already buffered.
+    pub async fn drain_upstream<E>(&mut self) -> Result<u64, MulterError>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: Into<MulterError>,
+    {
+        self.drain_upstream_up_to(None).await
+    }
+
+    /// Like [`MultipartStream::drain_upstream`], but stops reading once more
+    /// than `max_bytes` have been discarded.
+    pub(crate) async fn drain_upstream_up_to<E>(
+        &mut self,
+        max_bytes: Option<u64>,
+    ) -> Result<u64, MulterError>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: Into<MulterError>,
+    {
+        let mut discarded = self.buffer.len() as u64;
+        self.buffer.clear();
+        self.scan_offset = 0;
+        self.dash_line_scan_offset = 0;
+        self.reset_current_part();
+        self.deferred_error = None;
+        if !self.aborted {
+            self.state = ParseState::End;
+        }
+
+        while !self.upstream_done {
+            if max_bytes.is_some_and(|max_bytes| discarded > max_bytes) {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(discarded, "multipart parser: stopped draining at byte cap");
+                return Ok(discarded);
+            }
+            match poll_fn(|cx| Pin::new(&mut self.stream).poll_next(cx)).await {
+                Some(chunk) => discarded += chunk.map_err(Into::into)?.len() as u64,
+                None => self.upstream_done = true,
+            }
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(discarded, "multipart parser: drained upstream body");
+        Ok(discarded)
+    }
+
     /// Drains and discards the currently active part body, if any.
-    pub async fn drain_current_part(&mut self) -> Result<(), MulterError>
+    pub async fn drain_current_part<E>(&mut self) -> Result<(), MulterError>
     where
-        S: Stream<Item = Result<Bytes, MulterError>> + Unpin,
+        S: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: Into<MulterError>,
     {
         if !self.is_reading_part_body() {
             return Ok(());
         }
 
+        // Discarded bodies are not decoded, need no minimum size and do not
+        // count toward the total file size.
+        self.decoder = BodyDecoder::Identity;
+        self.current_part_min_size = None;
+        self.current_part_discarded = true;
+        #[cfg(feature = "decompression")]
+        {
+            self.decompressor = None;
+        }
         loop {
             let next = poll_fn(|cx| self.poll_next_part_chunk(cx)).await?;
             if next.is_none() {
@@ -332,44 +1135,560 @@ impl<S> MultipartStream<S> {
         }
     }
 
-    fn poll_fill_buffer(&mut self, cx: &mut Context<'_>) -> Result<Poll<()>, MulterError>
+    fn poll_fill_buffer<E>(&mut self, cx: &mut Context<'_>) -> Result<Poll<()>, MulterError>
     where
-        S: Stream<Item = Result<Bytes, MulterError>> + Unpin,
+        S: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: Into<MulterError>,
     {
-        match std::pin::Pin::new(&mut self.stream).poll_next(cx) {
-            Poll::Pending => Ok(Poll::Pending),
-            Poll::Ready(Some(Ok(chunk))) => {
-                if !chunk.is_empty() {
-                    if let Some(max_body_size) = self.limits.max_body_size {
-                        let next = self.received_body_bytes.saturating_add(chunk.len() as u64);
-                        if next > max_body_size {
-                            #[cfg(feature = "tracing")]
-                            tracing::warn!(
-                                max_body_size = max_body_size,
-                                received = next,
-                                "multipart parser: body size limit exceeded"
-                            );
-                            self.state = ParseState::Failed;
-                            return Err(MulterError::BodySizeLimitExceeded { max_body_size });
-                        }
-                        self.received_body_bytes = next;
-                    }
+        // Body bytes are streamed out as they arrive; only boundary and header
+        // searches can accumulate input without making progress.
+        if self.state != ParseState::Body {
+            if let Some(max_buffered_bytes) = self.limits.max_buffered_bytes {
+                if self.buffer.len() >= max_buffered_bytes {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        max_buffered_bytes = max_buffered_bytes,
+                        buffered = self.buffer.len(),
+                        "multipart parser: buffer limit exceeded"
+                    );
+                    self.state = ParseState::Failed;
+                    return Err(MulterError::BufferLimitExceeded { max_buffered_bytes });
+                }
+            }
+        }
 
-                    self.buffer.extend_from_slice(&chunk);
+        if let Some(err) = self.deferred_error.take() {
+            self.state = ParseState::Failed;
+            return Err(err);
+        }
+
+        self.poll_parse_deadline(cx)?;
+        if self.poll_throttle(cx).is_pending() {
+            return Ok(Poll::Pending);
+        }
+
+        // Chunks that are already available are coalesced; upstream is only
+        // waited on when nothing has been received yet.
+        let target = COALESCE_TARGET.min(self.limits.max_buffered_bytes.unwrap_or(usize::MAX));
+        let mut received = false;
+        loop {
+            match Pin::new(&mut self.stream).poll_next(cx) {
+                Poll::Pending if received => return Ok(Poll::Ready(())),
+                Poll::Pending => {
+                    self.poll_idle_timeout(cx)?;
+                    self.poll_min_throughput(cx)?;
+                    return Ok(Poll::Pending);
+                }
+                Poll::Ready(Some(Ok(chunk))) => {
+                    self.idle_waiting = false;
+                    if let Some(wait_start) = self.upstream_wait_start.take() {
+                        self.upstream_waited += wait_start.elapsed();
+                    }
+                    received = true;
+                    self.append_chunk(chunk)?;
+                    // A rate-limited parser re-checks its budget after every chunk.
+                    if self.buffer.len() >= target || self.limits.max_bytes_per_second.is_some() {
+                        return Ok(Poll::Ready(()));
+                    }
+                }
+                Poll::Ready(Some(Err(err))) if received => {
+                    // Let the parser consume what arrived before the failure.
+                    self.deferred_error = Some(err.into());
+                    return Ok(Poll::Ready(()));
+                }
+                Poll::Ready(Some(Err(err))) => {
+                    self.state = ParseState::Failed;
+                    return Err(err.into());
+                }
+                Poll::Ready(None) => {
+                    self.upstream_done = true;
+                    return Ok(Poll::Ready(()));
                 }
-                Ok(Poll::Ready(()))
             }
-            Poll::Ready(Some(Err(err))) => {
+        }
+    }
+
+    /// Appends an upstream chunk to the buffer, enforcing the body size limit.
+    fn append_chunk(&mut self, chunk: Bytes) -> Result<(), MulterError> {
+        if chunk.is_empty() {
+            return Ok(());
+        }
+
+        self.received_bytes = self.received_bytes.saturating_add(chunk.len() as u64);
+        self.stats.chunks_received += 1;
+
+        if let Some(max_body_size) = self.limits.max_body_size {
+            let next = self.received_body_bytes.saturating_add(chunk.len() as u64);
+            if next > max_body_size {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    max_body_size = max_body_size,
+                    received = next,
+                    "multipart parser: body size limit exceeded"
+                );
                 self.state = ParseState::Failed;
-                Err(err)
+                return Err(MulterError::BodySizeLimitExceeded { max_body_size });
+            }
+            self.received_body_bytes = next;
+        }
+
+        if self.buffer.is_empty() {
+            // Adopt the chunk allocation when nothing else references it;
+            // shared chunks are copied like any other appended input.
+            match chunk.try_into_mut() {
+                Ok(owned) => self.buffer = owned,
+                Err(shared) => self.buffer.extend_from_slice(&shared),
+            }
+        } else {
+            self.buffer.extend_from_slice(&chunk);
+        }
+        self.stats.buffer_high_water_mark =
+            self.stats.buffer_high_water_mark.max(self.buffer.len());
+        Ok(())
+    }
+
+    /// Fails once the configured cancellation token has been cancelled.
+    fn poll_cancellation(&mut self, cx: &mut Context<'_>) -> Result<(), MulterError> {
+        // A fully parsed stream stays complete even if cancelled afterwards.
+        if self.state == ParseState::End {
+            return Ok(());
+        }
+        if let Some(signal) = self.abort_signal.as_mut() {
+            if !self.aborted && signal.as_mut().poll(cx).is_ready() {
+                self.abort();
             }
-            Poll::Ready(None) => {
-                self.upstream_done = true;
-                Ok(Poll::Ready(()))
+        }
+        if self.aborted {
+            return Err(MulterError::Aborted);
+        }
+        let Some(cancelled) = self.cancellation.as_mut() else {
+            return Ok(());
+        };
+
+        if cancelled.as_mut().poll(cx).is_ready() {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("multipart parser: cancelled");
+            self.state = ParseState::Failed;
+            return Err(MulterError::Cancelled);
+        }
+
+        Ok(())
+    }
+
+    /// Fails once `max_parse_duration` has elapsed since the first read.
+    fn poll_parse_deadline(&mut self, cx: &mut Context<'_>) -> Result<(), MulterError> {
+        let Some(max_parse_duration) = self.limits.max_parse_duration else {
+            return Ok(());
+        };
+
+        let deadline = self
+            .parse_deadline
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep(max_parse_duration)));
+        if deadline.as_mut().poll(cx).is_ready() {
+            return Err(self.timed_out("max_parse_duration", max_parse_duration));
+        }
+
+        Ok(())
+    }
+
+    /// Waits while more bytes have been read than `max_bytes_per_second` allows so far.
+    fn poll_throttle(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let Some(max_bytes_per_second) = self.limits.max_bytes_per_second else {
+            return Poll::Ready(());
+        };
+
+        let start = *self.throttle_start.get_or_insert_with(Instant::now);
+        let nanos = u128::from(self.received_bytes) * 1_000_000_000
+            / u128::from(max_bytes_per_second.max(1));
+        let behind = Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX));
+        let now = Instant::now();
+        // Deadlines beyond what `Instant` can hold are re-evaluated hourly.
+        let resume_at = start
+            .checked_add(behind)
+            .unwrap_or_else(|| now + Duration::from_secs(3600));
+        if resume_at <= now {
+            return Poll::Ready(());
+        }
+
+        let throttle = self
+            .throttle
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(resume_at)));
+        throttle.as_mut().reset(resume_at);
+        throttle.as_mut().poll(cx)
+    }
+
+    /// Fails once upstream has been pending for longer than `idle_chunk_timeout`.
+    fn poll_idle_timeout(&mut self, cx: &mut Context<'_>) -> Result<(), MulterError> {
+        let Some(idle_chunk_timeout) = self.limits.idle_chunk_timeout else {
+            return Ok(());
+        };
+
+        // The idle clock starts when the parser begins waiting, so time spent
+        // by a slow consumer between polls is not counted against upstream.
+        let idle = self
+            .idle_deadline
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep(idle_chunk_timeout)));
+        if !self.idle_waiting {
+            idle.as_mut().reset(Instant::now() + idle_chunk_timeout);
+            self.idle_waiting = true;
+        }
+        if idle.as_mut().poll(cx).is_ready() {
+            return Err(self.timed_out("idle_chunk_timeout", idle_chunk_timeout));
+        }
+
+        Ok(())
+    }
+
+    /// Fails once the time spent waiting on upstream exceeds both the grace
+    /// period and the time the bytes received so far allow at the minimum rate.
+    fn poll_min_throughput(&mut self, cx: &mut Context<'_>) -> Result<(), MulterError> {
+        let Some(min_throughput_bytes_per_sec) = self.limits.min_throughput_bytes_per_sec else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        let wait_start = *self.upstream_wait_start.get_or_insert(now);
+        let nanos = u128::from(self.received_bytes) * 1_000_000_000
+            / u128::from(min_throughput_bytes_per_sec.max(1));
+        let earned = Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX));
+        let allowed = self
+            .limits
+            .min_throughput_grace
+            .max(earned)
+            .saturating_sub(self.upstream_waited);
+        // Deadlines beyond what `Instant` can hold are re-evaluated hourly.
+        let fail_at = wait_start
+            .checked_add(allowed)
+            .unwrap_or_else(|| now + Duration::from_secs(3600));
+
+        let deadline = self
+            .throughput_deadline
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(fail_at)));
+        deadline.as_mut().reset(fail_at);
+        if fail_at <= now || deadline.as_mut().poll(cx).is_ready() {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                min_throughput_bytes_per_sec,
+                received = self.received_bytes,
+                waited = ?self.upstream_waited + now.saturating_duration_since(wait_start),
+                "multipart parser: throughput below minimum"
+            );
+            self.state = ParseState::Failed;
+            return Err(MulterError::ThroughputTooLow {
+                min_throughput_bytes_per_sec,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn timed_out(&mut self, limit: &'static str, duration: Duration) -> MulterError {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            limit = limit,
+            duration = ?duration,
+            "multipart parser: timeout elapsed"
+        );
+        self.state = ParseState::Failed;
+        MulterError::Timeout { limit, duration }
+    }
+
+    fn take_line(&mut self) -> Option<BytesMut> {
+        let split = find_from(&self.finders.line_end, &self.buffer, &mut self.scan_offset)?;
+        let mut line = self.buffer.split_to(split);
+        self.consume(self.finders.line_end.needle().len());
+        if self.line_ending == LineEndingPolicy::Lenient && line.ends_with(b"\r") {
+            line.truncate(line.len() - 1);
+        }
+        Some(line)
+    }
+
+    /// Locates the blank line ending a header block, returning its offset and
+    /// the length of the terminator to consume.
+    fn find_header_end(&mut self) -> Option<(usize, usize)> {
+        if self.line_ending == LineEndingPolicy::Strict {
+            let split = find_from(
+                &self.finders.header_end,
+                &self.buffer,
+                &mut self.scan_offset,
+            )?;
+            return Some((split, 4));
+        }
+
+        loop {
+            let split = find_from(&self.finders.line_end, &self.buffer, &mut self.scan_offset)?;
+            let rest = &self.buffer[split + 1..];
+            if rest.starts_with(b"\n") {
+                return Some((split, 2));
+            }
+            if rest.starts_with(b"\r\n") {
+                return Some((split, 3));
+            }
+            if rest.is_empty() || rest == b"\r" {
+                // The blank line may still be arriving.
+                self.scan_offset = split;
+                return None;
             }
+            self.scan_offset = split + 1;
         }
     }
 
+    /// Fails the stream when a part header block of `len` bytes exceeds `max_header_size`.
+    fn ensure_header_size(&mut self, len: usize) -> Result<(), MulterError> {
+        let Some(max_header_size) = self.limits.max_header_size else {
+            return Ok(());
+        };
+
+        if len as u64 > max_header_size {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                len = len,
+                max_header_size = max_header_size,
+                "multipart parser: part header size limit exceeded"
+            );
+            self.state = ParseState::Failed;
+            return Err(MulterError::HeaderSizeLimitExceeded { max_header_size });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the length of the line break at the start of `bytes`, if any.
+    fn line_break_len(&self, bytes: &[u8]) -> Option<usize> {
+        if bytes.starts_with(b"\r\n") {
+            Some(2)
+        } else if self.line_ending == LineEndingPolicy::Lenient && bytes.starts_with(b"\n") {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    /// Advances the read cursor past `len` consumed bytes in O(1).
+    fn consume(&mut self, len: usize) {
+        self.buffer.advance(len);
+        self.scan_offset = 0;
+        self.dash_line_scan_offset = 0;
+    }
+
+    /// Returns how many leading buffer bytes can be emitted as body data
+    /// without splitting a delimiter that may still be arriving.
+    ///
+    /// Only a tail starting with a line-break byte can be a delimiter prefix,
+    /// so the retained tail is trimmed to the first such byte.
+    fn safe_body_len(&self) -> usize {
+        let (max_tail, found) = match self.line_ending {
+            LineEndingPolicy::Strict => {
+                let max_tail = self.delimiter.len().saturating_sub(1);
+                let tail_start = self.buffer.len().saturating_sub(max_tail);
+                (max_tail, memchr::memchr(b'\r', &self.buffer[tail_start..]))
+            }
+            LineEndingPolicy::Lenient => {
+                // Keep one extra byte for a `CR` preceding the `LF` delimiter.
+                let max_tail = self.delimiter.len();
+                let tail_start = self.buffer.len().saturating_sub(max_tail);
+                (
+                    max_tail,
+                    memchr::memchr2(b'\r', b'\n', &self.buffer[tail_start..]),
+                )
+            }
+        };
+        let tail_start = self.buffer.len().saturating_sub(max_tail);
+        match found {
+            Some(index) => tail_start + index,
+            None => self.buffer.len(),
+        }
+    }
+
+    fn has_malformed_boundary_line(&mut self) -> bool {
+        let Some(prefix) = find_from(
+            &self.finders.dash_line,
+            &self.buffer,
+            &mut self.dash_line_scan_offset,
+        ) else {
+            return false;
+        };
+        // Re-check this line, not the whole buffer, once more input arrives.
+        self.dash_line_scan_offset = prefix;
+
+        let line_start = prefix + self.finders.dash_line.needle().len() - 2;
+        let Some(relative_end) = self.finders.line_end.find(&self.buffer[line_start..]) else {
+            return false;
+        };
+        let mut line = &self.buffer[line_start..line_start + relative_end];
+        if self.line_ending == LineEndingPolicy::Lenient {
+            line = line.strip_suffix(b"\r").unwrap_or(line);
+        }
+        let line = trim_transport_padding(line);
+        line != self.boundary_line && line != self.boundary_end_line
+    }
+
+    /// Rejects top-level parts whose disposition is not `form-data` in strict mode.
+    fn check_disposition(
+        &self,
+        headers: ParsedPartHeaders,
+    ) -> Result<ParsedPartHeaders, ParseError> {
+        if self.compliance == ParserCompliance::Strict
+            && self.fallback_field_name.is_none()
+            && headers.content_disposition.disposition != "form-data"
+        {
+            return Err(ParseError::new(
+                "part Content-Disposition must be form-data",
+            ));
+        }
+
+        Ok(headers)
+    }
+
+    /// Returns `true` when the unconsumed input is a prefix of the next delimiter
+    /// that covers at least its leading line break.
+    fn truncated_at_delimiter(&self) -> bool {
+        let tail = &self.buffer[..];
+        let (tail, line_break_len) = match self.line_ending {
+            LineEndingPolicy::Lenient => (tail.strip_prefix(b"\r").unwrap_or(tail), 1),
+            LineEndingPolicy::Strict => (tail, 2),
+        };
+        tail.len() >= line_break_len && self.delimiter.starts_with(tail)
+    }
+
+    /// Ends the stream cleanly at a part boundary when the terminal boundary never arrived.
+    fn end_without_terminal_boundary(&mut self) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!("multipart parser: upstream ended without terminal boundary");
+        self.buffer.clear();
+        self.scan_offset = 0;
+        self.dash_line_scan_offset = 0;
+        self.close_line_pending = false;
+        self.state = ParseState::End;
+    }
+
+    /// Attaches the current part and approximate body offset to parse errors.
+    fn with_location(&self, err: MulterError) -> MulterError {
+        match err {
+            MulterError::Parse(err) => {
+                let offset = self.received_bytes.saturating_sub(self.buffer.len() as u64);
+                MulterError::Parse(err.with_location(
+                    self.parts_seen.checked_sub(1),
+                    self.part_field_name.as_deref(),
+                    offset,
+                ))
+            }
+            err => err,
+        }
+    }
+
+    /// Clears per-part bookkeeping once the current part ends or fails.
+    fn reset_current_part(&mut self) {
+        self.current_headers = None;
+        self.current_part_max_size = None;
+        self.current_part_min_size = None;
+        self.current_part_size = 0;
+        self.current_part_is_file = false;
+        self.current_part_discarded = false;
+        self.decoder = BodyDecoder::Identity;
+        #[cfg(feature = "decompression")]
+        {
+            self.decompressor = None;
+        }
+    }
+
+    /// Stops the current part after it broke a per-part size limit.
+    ///
+    /// With recoverable part limits the rest of an unfinished body is
+    /// discarded like [`MultipartStream::drain_current_part`] does;
+    /// otherwise the parser fails.
+    fn fail_current_part(&mut self, part_ended: bool) {
+        if !self.recoverable_part_limits {
+            self.state = ParseState::Failed;
+            self.reset_current_part();
+            return;
+        }
+        if part_ended {
+            self.reset_current_part();
+            return;
+        }
+
+        self.current_part_max_size = None;
+        self.current_part_min_size = None;
+        self.current_part_discarded = true;
+        self.decoder = BodyDecoder::Identity;
+        #[cfg(feature = "decompression")]
+        {
+            self.decompressor = None;
+        }
+    }
+
+    /// Fails fast when an unencoded part declares a `Content-Length` above its size limit.
+    fn ensure_declared_length(&self) -> Result<(), MulterError> {
+        let Some(headers) = self.current_headers.as_ref() else {
+            return Ok(());
+        };
+
+        // Encoded bodies decode to a different length than the one declared.
+        if headers.transfer_encoding != ContentTransferEncoding::Identity
+            || headers.content_encoding != ContentEncoding::Identity
+        {
+            return Ok(());
+        }
+
+        match headers.content_length {
+            Some(declared) => self.ensure_part_limit(declared),
+            None => Ok(()),
+        }
+    }
+
+    fn ensure_part_min_size(&self) -> Result<(), MulterError> {
+        let Some(min_file_size) = self.current_part_min_size else {
+            return Ok(());
+        };
+
+        if self.current_part_size >= min_file_size {
+            return Ok(());
+        }
+
+        let field = self
+            .current_headers
+            .as_ref()
+            .map(|headers| headers.field_name.clone())
+            .unwrap_or_else(|| "<unknown>".to_owned());
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            field = field.as_str(),
+            min_file_size,
+            size = self.current_part_size,
+            "multipart parser: file below min file size"
+        );
+        Err(MulterError::FileSizeBelowMinimum {
+            field,
+            min_file_size,
+        })
+    }
+
+    fn counts_toward_total_file_size(&self) -> bool {
+        self.current_part_is_file && !self.current_part_discarded
+    }
+
+    fn ensure_total_file_limit(&self, additional: u64) -> Result<(), MulterError> {
+        let Some(max_total_file_size) = self.limits.max_total_file_size else {
+            return Ok(());
+        };
+
+        if !self.counts_toward_total_file_size()
+            || self.total_file_size.saturating_add(additional) <= max_total_file_size
+        {
+            return Ok(());
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            max_total_file_size,
+            "multipart parser: total file size limit exceeded"
+        );
+        Err(MulterError::TotalFileSizeLimitExceeded {
+            max_total_file_size,
+        })
+    }
+
     fn ensure_part_limit(&self, additional: u64) -> Result<(), MulterError> {
         let Some(limit) = self.current_part_max_size else {
             return Ok(());
@@ -411,22 +1730,57 @@ impl<S> MultipartStream<S> {
     }
 }
 
-fn parse_header_block(raw: &[u8]) -> Result<HeaderMap, ParseError> {
+fn parse_header_block(
+    raw: &[u8],
+    line_ending: LineEndingPolicy,
+    compliance: ParserCompliance,
+) -> Result<HeaderMap, ParseError> {
     let text =
         std::str::from_utf8(raw).map_err(|_| ParseError::new("part headers must be UTF-8"))?;
     let mut headers = HeaderMap::new();
 
-    for line in text.split("\r\n") {
+    let lines: Box<dyn Iterator<Item = &str>> = match line_ending {
+        LineEndingPolicy::Strict => Box::new(text.split("\r\n")),
+        LineEndingPolicy::Lenient => Box::new(
+            text.split('\n')
+                .map(|line| line.strip_suffix('\r').unwrap_or(line)),
+        ),
+    };
+
+    // Join obsolete folded continuation lines onto the header they continue.
+    let mut unfolded: Vec<String> = Vec::new();
+    for line in lines {
         if line.is_empty() {
             continue;
         }
 
+        if line.starts_with([' ', '\t']) {
+            if compliance == ParserCompliance::Strict {
+                return Err(ParseError::new("folded part header lines are not allowed"));
+            }
+            // A continuation line needs a header to continue.
+            let Some(previous) = unfolded.last_mut() else {
+                return Err(ParseError::new("invalid part header line"));
+            };
+            previous.push(' ');
+            previous.push_str(line.trim());
+            continue;
+        }
+
+        unfolded.push(line.to_owned());
+    }
+
+    for line in &unfolded {
         let Some((raw_name, raw_value)) = line.split_once(':') else {
             return Err(ParseError::new("invalid part header line"));
         };
 
-        let name = raw_name
-            .trim()
+        let trimmed_name = raw_name.trim();
+        if compliance == ParserCompliance::Strict && trimmed_name.len() != raw_name.len() {
+            return Err(ParseError::new("invalid part header name"));
+        }
+
+        let name = trimmed_name
             .parse::<HeaderName>()
             .map_err(|_| ParseError::new("invalid part header name"))?;
         let value = HeaderValue::from_str(raw_value.trim())
@@ -434,45 +1788,102 @@ fn parse_header_block(raw: &[u8]) -> Result<HeaderMap, ParseError> {
         headers.append(name, value);
     }
 
-    if !headers.contains_key(header::CONTENT_DISPOSITION) {
-        return Err(ParseError::new("missing Content-Disposition header"));
+    match headers.get_all(header::CONTENT_DISPOSITION).iter().count() {
+        0 => Err(ParseError::new("missing Content-Disposition header")),
+        1 => Ok(headers),
+        _ if compliance == ParserCompliance::Strict => {
+            Err(ParseError::new("duplicate Content-Disposition header"))
+        }
+        _ => Ok(headers),
+    }
+}
+
+/// Replaces RFC 2047 encoded-words in the part's file name with their decoded text.
+fn decode_file_name(headers: &mut ParsedPartHeaders) {
+    if let Some(file_name) = headers.file_name.as_deref() {
+        let decoded = decode_encoded_words(file_name);
+        headers.content_disposition.filename = Some(decoded.clone());
+        headers.file_name = Some(decoded);
+    }
+}
+
+/// Joins two decoded slices, copying only when both are non-empty.
+fn concat_bytes(head: Bytes, tail: Bytes) -> Bytes {
+    if tail.is_empty() {
+        return head;
+    }
+    if head.is_empty() {
+        return tail;
     }
 
-    Ok(headers)
+    let mut joined = BytesMut::with_capacity(head.len() + tail.len());
+    joined.extend_from_slice(&head);
+    joined.extend_from_slice(&tail);
+    joined.freeze()
 }
 
-fn take_line(buffer: &mut Vec<u8>) -> Option<Vec<u8>> {
-    let split = find_subslice(buffer, b"\r\n")?;
-    let line = buffer[..split].to_vec();
-    buffer.drain(..split + 2);
-    Some(line)
+/// Searches `haystack` for the finder's needle starting at `scan_offset`.
+///
+/// On a miss, `scan_offset` is advanced to the last position where a needle
+/// could still begin once more input is appended, so the next search only
+/// rescans the overlap instead of the whole buffer.
+fn find_from(finder: &Finder<'_>, haystack: &[u8], scan_offset: &mut usize) -> Option<usize> {
+    let start = (*scan_offset).min(haystack.len());
+    match finder.find(&haystack[start..]) {
+        Some(index) => Some(start + index),
+        None => {
+            let overlap = finder.needle().len().saturating_sub(1);
+            *scan_offset = haystack.len().saturating_sub(overlap);
+            None
+        }
+    }
 }
 
-fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
-    if needle.is_empty() {
-        return Some(0);
+/// Precomputed substring searchers used by the parser hot paths.
+#[derive(Debug, Clone)]
+struct Finders {
+    delimiter: Finder<'static>,
+    header_end: Finder<'static>,
+    line_end: Finder<'static>,
+    dash_line: Finder<'static>,
+}
+
+impl Finders {
+    fn new(delimiter: &[u8], line_ending: LineEndingPolicy) -> Self {
+        let (line_end, dash_line): (&[u8], &[u8]) = match line_ending {
+            LineEndingPolicy::Strict => (b"\r\n", b"\r\n--"),
+            LineEndingPolicy::Lenient => (b"\n", b"\n--"),
+        };
+
+        Self {
+            delimiter: Finder::new(delimiter).into_owned(),
+            header_end: Finder::new(b"\r\n\r\n").into_owned(),
+            line_end: Finder::new(line_end).into_owned(),
+            dash_line: Finder::new(dash_line).into_owned(),
+        }
     }
+}
 
-    haystack
-        .windows(needle.len())
-        .position(|window| window == needle)
+fn transport_padding_len(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .take_while(|byte| matches!(byte, b' ' | b'\t'))
+        .count()
 }
 
-fn has_malformed_boundary_line(
-    buffer: &[u8],
-    boundary_line: &[u8],
-    boundary_end_line: &[u8],
-) -> bool {
-    let Some(prefix) = find_subslice(buffer, b"\r\n--") else {
-        return false;
-    };
+fn trim_transport_padding(line: &[u8]) -> &[u8] {
+    let end = line
+        .iter()
+        .rposition(|byte| !matches!(byte, b' ' | b'\t'))
+        .map_or(0, |index| index + 1);
+    &line[..end]
+}
 
-    let line_start = prefix + 2;
-    let Some(relative_end) = find_subslice(&buffer[line_start..], b"\r\n") else {
-        return false;
-    };
-    let line = &buffer[line_start..line_start + relative_end];
-    line != boundary_line && line != boundary_end_line
+fn delimiter_for(boundary: &str, line_ending: LineEndingPolicy) -> Vec<u8> {
+    match line_ending {
+        LineEndingPolicy::Strict => format!("\r\n--{boundary}").into_bytes(),
+        LineEndingPolicy::Lenient => format!("\n--{boundary}").into_bytes(),
+    }
 }
 
 fn validate_boundary_input(boundary: &str) -> Result<(), ParseError> {
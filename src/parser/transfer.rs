@@ -0,0 +1,168 @@
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine as _,
+};
+use bytes::Bytes;
+
+use crate::{error::ParseError, parser::headers::ContentTransferEncoding};
+
+/// Base64 engine that accepts both padded and unpadded final groups.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Incremental decoder applied to part body chunks.
+#[derive(Debug, Default)]
+pub(crate) enum BodyDecoder {
+    /// Chunks are passed through untouched.
+    #[default]
+    Identity,
+    /// Chunks are base64-decoded; `pending` holds an incomplete 4-char group.
+    Base64 { pending: Vec<u8> },
+    /// Chunks are quoted-printable decoded; `pending` holds an incomplete escape.
+    QuotedPrintable { pending: Vec<u8> },
+}
+
+impl BodyDecoder {
+    /// Creates the decoder for a part's declared transfer encoding.
+    pub(crate) fn for_encoding(encoding: &ContentTransferEncoding) -> Self {
+        match encoding {
+            ContentTransferEncoding::Base64 => Self::Base64 {
+                pending: Vec::new(),
+            },
+            ContentTransferEncoding::QuotedPrintable => Self::QuotedPrintable {
+                pending: Vec::new(),
+            },
+            _ => Self::Identity,
+        }
+    }
+
+    /// Decodes one raw body chunk, possibly buffering a partial trailing group.
+    pub(crate) fn decode(&mut self, chunk: Bytes) -> Result<Bytes, ParseError> {
+        match self {
+            Self::Identity => Ok(chunk),
+            Self::Base64 { pending } => {
+                pending.extend(chunk.iter().filter(|byte| !byte.is_ascii_whitespace()));
+                let ready = pending.len() - pending.len() % 4;
+                if ready == 0 {
+                    return Ok(Bytes::new());
+                }
+
+                let decoded = decode_base64(&pending[..ready])?;
+                pending.drain(..ready);
+                Ok(decoded)
+            }
+            Self::QuotedPrintable { pending } => {
+                pending.extend_from_slice(&chunk);
+                let (decoded, consumed) = decode_quoted_printable(pending, false)?;
+                pending.drain(..consumed);
+                Ok(decoded)
+            }
+        }
+    }
+
+    /// Flushes any buffered input once the part body has ended.
+    pub(crate) fn finish(&mut self) -> Result<Bytes, ParseError> {
+        match self {
+            Self::Identity => Ok(Bytes::new()),
+            Self::Base64 { pending } => {
+                let tail = std::mem::take(pending);
+                if tail.is_empty() {
+                    return Ok(Bytes::new());
+                }
+
+                decode_base64(&tail)
+            }
+            Self::QuotedPrintable { pending } => {
+                let tail = std::mem::take(pending);
+                decode_quoted_printable(&tail, true).map(|(decoded, _)| decoded)
+            }
+        }
+    }
+}
+
+fn decode_base64(input: &[u8]) -> Result<Bytes, ParseError> {
+    BASE64
+        .decode(input)
+        .map(Bytes::from)
+        .map_err(|_| ParseError::new("invalid base64 part body"))
+}
+
+/// Decodes quoted-printable `input`, returning the output and bytes consumed.
+///
+/// Unless `last` is set, an escape or trailing whitespace run that may still
+/// be completed by the next chunk is left unconsumed.
+fn decode_quoted_printable(input: &[u8], last: bool) -> Result<(Bytes, usize), ParseError> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut index = 0;
+
+    while index < input.len() {
+        match input[index] {
+            b'=' => {
+                let rest = &input[index + 1..];
+                if let (Some(hi), Some(lo)) = (
+                    rest.first().and_then(|b| char::from(*b).to_digit(16)),
+                    rest.get(1).and_then(|b| char::from(*b).to_digit(16)),
+                ) {
+                    out.push(((hi << 4) | lo) as u8);
+                    index += 3;
+                    continue;
+                }
+
+                // Soft line break, optionally preceded by transport whitespace.
+                let padding = whitespace_len(rest);
+                match line_break_len(&rest[padding..]) {
+                    Some(len) => index += 1 + padding + len,
+                    // A soft break ending the body precedes the delimiter's CRLF.
+                    None if last && rest[padding..].is_empty() => index += 1 + padding,
+                    None if !last && incomplete_line_break(&rest[padding..]) => break,
+                    None if !last && rest.len() < 2 => break,
+                    None => return Err(ParseError::new("invalid quoted-printable part body")),
+                }
+            }
+            b' ' | b'\t' => {
+                // Whitespace before a line break was added in transport.
+                let padding = whitespace_len(&input[index..]);
+                let after = &input[index + padding..];
+                if line_break_len(after).is_some() {
+                    index += padding;
+                } else if !last && incomplete_line_break(after) {
+                    break;
+                } else {
+                    out.extend_from_slice(&input[index..index + padding]);
+                    index += padding;
+                }
+            }
+            byte => {
+                out.push(byte);
+                index += 1;
+            }
+        }
+    }
+
+    Ok((Bytes::from(out), index))
+}
+
+fn whitespace_len(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .take_while(|byte| matches!(byte, b' ' | b'\t'))
+        .count()
+}
+
+fn line_break_len(bytes: &[u8]) -> Option<usize> {
+    if bytes.starts_with(b"\r\n") {
+        Some(2)
+    } else if bytes.starts_with(b"\n") {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+/// Returns `true` when `bytes` may still become a line break with more input.
+fn incomplete_line_break(bytes: &[u8]) -> bool {
+    bytes.is_empty() || bytes == b"\r"
+}
@@ -1,32 +1,80 @@
 use std::{
+    collections::VecDeque,
     fmt,
+    path::Path,
     pin::Pin,
     task::{Context, Poll},
 };
 
-use bytes::Bytes;
-use futures::{stream, Stream, StreamExt};
-use http::{header, HeaderMap};
+use bytes::{Buf, Bytes, BytesMut};
+use encoding_rs::{Encoding, UTF_8};
+use futures::{future::poll_fn, Stream, StreamExt};
+use http::HeaderMap;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
 
-use crate::{parser::headers::ParsedPartHeaders, BoxStream, MulterError, ParseError};
+use crate::{
+    parser::headers::ParsedPartHeaders,
+    storage::disk::{sanitize_filename, PartialFileGuard},
+    BoxStream, MulterError, ParseError, StorageError,
+};
 
 pub(crate) trait PartBodyReader: Send {
     fn poll_next_chunk(&mut self, cx: &mut Context<'_>)
         -> Poll<Result<Option<Bytes>, MulterError>>;
 }
 
+/// Part body already read into memory, replayed once through [`PartBodyReader`].
+#[derive(Debug, Default)]
+pub(crate) struct BufferedBody {
+    body: Option<Bytes>,
+}
+
+impl BufferedBody {
+    /// Stores `body` to be yielded by the next read.
+    pub(crate) fn replace(&mut self, body: Bytes) {
+        self.body = Some(body);
+    }
+
+    /// Takes the stored body, leaving nothing to replay.
+    pub(crate) fn take(&mut self) -> Option<Bytes> {
+        self.body.take()
+    }
+}
+
+impl PartBodyReader for BufferedBody {
+    fn poll_next_chunk(
+        &mut self,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<Bytes>, MulterError>> {
+        Poll::Ready(Ok(self.body.take().filter(|body| !body.is_empty())))
+    }
+}
+
+/// Byte order mark optionally stripped from UTF-8 text fields.
+const UTF_8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
 /// Parsed multipart part.
 pub struct Part<'a> {
     /// Parsed part headers.
     pub headers: ParsedPartHeaders,
+    index: usize,
     body_reader: Option<&'a mut dyn PartBodyReader>,
+    default_charset: Option<&'static Encoding>,
+    strip_bom: bool,
+    peeked: VecDeque<Bytes>,
+    peek_ended: bool,
 }
 
 impl fmt::Debug for Part<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Part")
             .field("headers", &self.headers)
+            .field("index", &self.index)
             .field("consumed", &self.body_reader.is_none())
+            .field("default_charset", &self.default_charset.map(Encoding::name))
+            .field("strip_bom", &self.strip_bom)
+            .field("peeked", &self.peeked.iter().map(Bytes::len).sum::<usize>())
             .finish()
     }
 }
@@ -36,10 +84,47 @@ impl<'a> Part<'a> {
     pub(crate) fn new(headers: ParsedPartHeaders, body_reader: &'a mut dyn PartBodyReader) -> Self {
         Self {
             headers,
+            index: 0,
             body_reader: Some(body_reader),
+            default_charset: None,
+            strip_bom: false,
+            peeked: VecDeque::new(),
+            peek_ended: false,
         }
     }
 
+    /// Sets the position reported by [`Part::index`].
+    pub(crate) fn with_index(mut self, index: usize) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Sets the form-wide charset used by [`Part::text`] when the part declares none.
+    pub(crate) fn with_default_charset(mut self, charset: Option<&'static Encoding>) -> Self {
+        self.default_charset = charset;
+        self
+    }
+
+    /// Sets whether [`Part::text`] strips a leading UTF-8 byte order mark.
+    pub(crate) fn with_strip_bom(mut self, strip: bool) -> Self {
+        self.strip_bom = strip;
+        self
+    }
+
+    /// Returns the zero-based position of this part in the request.
+    ///
+    /// Parts skipped by the selector still occupy a position, and parts of a
+    /// nested `multipart/mixed` body are numbered in sequence with the outer
+    /// parts.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns `true` when this part carries a file name.
+    pub fn is_file(&self) -> bool {
+        self.headers.file_name.is_some()
+    }
+
     /// Returns the logical field name for this part.
     pub fn field_name(&self) -> &str {
         &self.headers.field_name
@@ -50,6 +135,22 @@ impl<'a> Part<'a> {
         self.headers.file_name.as_deref()
     }
 
+    /// Returns the lowercased extension of the sanitized file name, if any.
+    ///
+    /// Path components are stripped before the extension is read, so
+    /// `../evil.PNG` yields `png`.
+    pub fn extension(&self) -> Option<String> {
+        file_extension(self.file_name()?)
+    }
+
+    /// Guesses a MIME type from [`Part::extension`].
+    ///
+    /// The guess is based on the file name only; it does not inspect the
+    /// declared `Content-Type` or the body.
+    pub fn guessed_mime(&self) -> Option<mime::Mime> {
+        mime_guess::from_ext(&self.extension()?).first()
+    }
+
     /// Returns the parsed content type for this part.
     pub fn content_type(&self) -> &str {
         self.headers.content_type.as_ref()
@@ -73,51 +174,271 @@ impl<'a> Part<'a> {
     /// The hint may be `None` when the incoming part does not declare a
     /// `Content-Length` header.
     pub fn size_hint(&self) -> Option<u64> {
-        self.headers
-            .headers
-            .get(header::CONTENT_LENGTH)
-            .and_then(|value| value.to_str().ok())
-            .and_then(|value| value.parse::<u64>().ok())
+        self.headers.content_length
     }
 
     /// Reads the full part body as bytes.
+    ///
+    /// Bodies delivered as a single chunk are returned without copying.
     pub async fn bytes(&mut self) -> Result<Bytes, MulterError> {
         let mut stream = self.stream();
-        let mut out = Vec::new();
+        let Some(first) = stream.next().await.transpose()? else {
+            return Ok(Bytes::new());
+        };
+        let Some(second) = stream.next().await.transpose()? else {
+            return Ok(first);
+        };
+
+        let mut out = BytesMut::with_capacity(first.len() + second.len());
+        out.extend_from_slice(&first);
+        out.extend_from_slice(&second);
         while let Some(chunk) = stream.next().await {
             out.extend_from_slice(&chunk?);
         }
-        Ok(Bytes::from(out))
+        Ok(out.freeze())
     }
 
-    /// Reads the full part body and decodes it as UTF-8 text.
+    /// Reads the full part body, failing once it grows past `max` bytes.
+    ///
+    /// The error is [`MulterError::FileSizeLimitExceeded`] for file parts and
+    /// [`MulterError::FieldSizeLimitExceeded`] for text parts, as if `max` had
+    /// been configured as the matching limit. Reading stops at the first
+    /// chunk that crosses the cap.
+    pub async fn bytes_limited(&mut self, max: u64) -> Result<Bytes, MulterError> {
+        let mut out = BytesMut::new();
+        let mut stream = self.stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            if out.len() as u64 + chunk.len() as u64 > max {
+                drop(stream);
+                return Err(self.size_limit_error(max));
+            }
+            out.extend_from_slice(&chunk);
+        }
+        Ok(out.freeze())
+    }
+
+    /// Reads the full part body and decodes it as text.
+    ///
+    /// The charset is taken from the part's `Content-Type`, then from a
+    /// preceding RFC 7578 `_charset_` form field, and defaults to UTF-8.
+    /// Text fields parsed with `MulterConfig::strip_text_bom` set have a
+    /// leading UTF-8 byte order mark removed.
     pub async fn text(&mut self) -> Result<String, MulterError> {
+        let encoding = match self.headers.content_type.get_param(mime::CHARSET) {
+            Some(label) => Encoding::for_label(label.as_str().as_bytes())
+                .ok_or_else(|| ParseError::new("unsupported part charset"))?,
+            None => self.default_charset.unwrap_or(UTF_8),
+        };
+
+        let mut bytes = self.bytes().await?;
+        if encoding == UTF_8 {
+            if self.strip_bom && bytes.starts_with(UTF_8_BOM) {
+                bytes.advance(UTF_8_BOM.len());
+            }
+            return String::from_utf8(bytes.to_vec())
+                .map_err(|_| ParseError::new("part body is not valid UTF-8").into());
+        }
+
+        encoding
+            .decode_without_bom_handling_and_without_replacement(&bytes)
+            .map(|text| text.into_owned())
+            .ok_or_else(|| ParseError::new("part body is not valid in its declared charset").into())
+    }
+
+    /// Reads the full part body and deserializes it as JSON.
+    ///
+    /// The part must declare an `application/json` or `+json` content type.
+    ///
+    /// ```rust
+    /// use bytes::Bytes;
+    /// use futures::stream;
+    /// use multigear::{MulterError, Multipart};
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Meta {
+    ///     title: String,
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), MulterError> {
+    /// let body = "--X\r\nContent-Disposition: form-data; name=\"meta\"\r\nContent-Type: application/json\r\n\r\n{\"title\":\"hi\"}\r\n--X--\r\n";
+    /// let mut multipart = Multipart::new("X", stream::iter([Ok::<_, MulterError>(Bytes::from(body))]))?;
+    /// let mut part = multipart.next_part().await?.expect("part");
+    /// let meta: Meta = part.json().await?;
+    /// assert_eq!(meta.title, "hi");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "json")]
+    pub async fn json<T>(&mut self) -> Result<T, MulterError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let content_type = &self.headers.content_type;
+        let is_json = content_type.type_() == mime::APPLICATION
+            && (content_type.subtype() == mime::JSON || content_type.suffix() == Some(mime::JSON));
+        if !is_json {
+            return Err(ParseError::new(format!(
+                "part Content-Type `{}` is not JSON",
+                content_type.essence_str()
+            ))
+            .into());
+        }
+
         let bytes = self.bytes().await?;
-        String::from_utf8(bytes.to_vec())
-            .map_err(|_| ParseError::new("part body is not valid UTF-8").into())
+        serde_json::from_slice(&bytes)
+            .map_err(|err| ParseError::new(format!("invalid JSON part body: {err}")).into())
+    }
+
+    /// Streams the part body to `path` and returns the number of bytes written.
+    ///
+    /// Missing parent directories are created. The body is written to a
+    /// temporary file next to `path` and renamed into place once complete, so
+    /// `path` never holds a partial upload; an existing file is replaced.
+    pub async fn save_to(&mut self, path: impl AsRef<Path>) -> Result<u64, MulterError> {
+        let path = path.as_ref();
+        let Some(file_name) = path.file_name() else {
+            return Err(StorageError::new(format!(
+                "save path `{}` has no file name",
+                path.display()
+            ))
+            .into());
+        };
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|err| StorageError::io("failed to create parent directory", err))?;
+        }
+
+        let temp_path = path.with_file_name(format!(
+            ".{}.{}.tmp",
+            file_name.to_string_lossy(),
+            Uuid::new_v4().simple()
+        ));
+
+        // Declared before the file so the handle is closed before cleanup runs.
+        let mut partial = PartialFileGuard::default();
+        let mut file = tokio::fs::File::create(&temp_path)
+            .await
+            .map_err(|err| StorageError::io("failed to create output file", err))?;
+        partial.arm(temp_path.clone());
+
+        let mut written = 0u64;
+        let mut stream = self.stream();
+        while let Some(chunk) = stream.next().await {
+            let bytes = chunk?;
+            file.write_all(&bytes)
+                .await
+                .map_err(|err| StorageError::io("failed to write output file", err))?;
+            written = written.saturating_add(bytes.len() as u64);
+        }
+        drop(stream);
+
+        file.flush()
+            .await
+            .map_err(|err| StorageError::io("failed to flush output file", err))?;
+        drop(file);
+
+        tokio::fs::rename(&temp_path, path)
+            .await
+            .map_err(|err| StorageError::io("failed to move output file", err))?;
+        partial.disarm();
+
+        Ok(written)
+    }
+
+    /// Returns up to the first `n` body bytes without consuming them.
+    ///
+    /// Fewer bytes are returned only when the body is shorter. Peeked bytes
+    /// are replayed by every later read, so a part can be sniffed for magic
+    /// numbers and still be passed untouched to `Multer::store`.
+    pub async fn peek(&mut self, n: usize) -> Result<Bytes, MulterError> {
+        let mut buffered: usize = self.peeked.iter().map(Bytes::len).sum();
+        while buffered < n && !self.peek_ended {
+            let Some(body_reader) = self.body_reader.as_mut() else {
+                return Err(ParseError::new("part body was already consumed").into());
+            };
+            match poll_fn(|cx| body_reader.poll_next_chunk(cx)).await? {
+                Some(chunk) => {
+                    buffered += chunk.len();
+                    self.peeked.push_back(chunk);
+                }
+                None => self.peek_ended = true,
+            }
+        }
+
+        match self.peeked.front() {
+            Some(first) if first.len() >= n => Ok(first.slice(..n)),
+            _ => {
+                let mut out = BytesMut::with_capacity(n.min(buffered));
+                for chunk in &self.peeked {
+                    let take = chunk.len().min(n - out.len());
+                    out.extend_from_slice(&chunk[..take]);
+                }
+                Ok(out.freeze())
+            }
+        }
+    }
+
+    fn size_limit_error(&self, max: u64) -> MulterError {
+        let field = self.headers.field_name.clone();
+        if self.is_file() {
+            MulterError::FileSizeLimitExceeded {
+                field,
+                max_file_size: max,
+            }
+        } else {
+            MulterError::FieldSizeLimitExceeded {
+                field,
+                max_field_size: max,
+            }
+        }
     }
 
     /// Returns a one-shot body stream for this part.
     ///
     /// The returned stream can only be created once; subsequent calls return a
-    /// stream that yields a single "already consumed" error item.
+    /// stream that yields a single "already consumed" error item. Bytes read
+    /// by [`Part::peek`] are yielded first.
     pub fn stream(&mut self) -> BoxStream<'_, Result<Bytes, MulterError>> {
-        let Some(body_reader) = self.body_reader.take() else {
-            return Box::pin(stream::once(async {
-                Err(ParseError::new("part body was already consumed").into())
-            }));
-        };
+        Box::pin(self.take_body_stream())
+    }
+
+    /// Consumes the part and returns its body stream.
+    ///
+    /// Unlike [`Part::stream`], the stream is not tied to a borrow of the
+    /// part, so it can be moved into a storage future or helper. It still
+    /// borrows the parent [`Multipart`](crate::Multipart) and must be drained
+    /// or dropped before the next part is requested.
+    pub fn into_stream(self) -> PartBodyStream<'a> {
+        self.into_parts().1
+    }
 
-        Box::pin(PartBodyStream {
+    /// Consumes the part and returns its owned headers plus its body stream.
+    ///
+    /// See [`Part::into_stream`] for the lifetime of the returned stream.
+    pub fn into_parts(mut self) -> (ParsedPartHeaders, PartBodyStream<'a>) {
+        let body = self.take_body_stream();
+        (self.headers, body)
+    }
+
+    fn take_body_stream(&mut self) -> PartBodyStream<'a> {
+        let body_reader = self.body_reader.take();
+        PartBodyStream {
+            // A consumed body must still surface the "already consumed" error.
+            finished: self.peek_ended && body_reader.is_some(),
             body_reader,
-            finished: false,
-        })
+            peeked: std::mem::take(&mut self.peeked),
+        }
     }
 }
 
-/// One-shot stream returned by [`Part::stream`].
+/// One-shot stream returned by [`Part::stream`] and [`Part::into_stream`].
 pub struct PartBodyStream<'a> {
-    body_reader: &'a mut dyn PartBodyReader,
+    body_reader: Option<&'a mut dyn PartBodyReader>,
+    peeked: VecDeque<Bytes>,
     finished: bool,
 }
 
@@ -133,11 +454,22 @@ impl Stream for PartBodyStream<'_> {
     type Item = Result<Bytes, MulterError>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(chunk) = self.peeked.pop_front() {
+            return Poll::Ready(Some(Ok(chunk)));
+        }
+
         if self.finished {
             return Poll::Ready(None);
         }
 
-        match self.body_reader.poll_next_chunk(cx) {
+        let Some(body_reader) = self.body_reader.as_mut() else {
+            self.finished = true;
+            return Poll::Ready(Some(Err(
+                ParseError::new("part body was already consumed").into()
+            )));
+        };
+
+        match body_reader.poll_next_chunk(cx) {
             Poll::Ready(Ok(Some(bytes))) => Poll::Ready(Some(Ok(bytes))),
             Poll::Ready(Ok(None)) => {
                 self.finished = true;
@@ -151,3 +483,27 @@ impl Stream for PartBodyStream<'_> {
         }
     }
 }
+
+/// Returns the lowercased compound extension, such as `pdf.exe`, when the
+/// sanitized `file_name` has an extension-like segment before its extension.
+pub(crate) fn double_extension(file_name: &str) -> Option<String> {
+    let sanitized = sanitize_filename(file_name);
+    let mut segments = sanitized.trim_start_matches('.').rsplit('.');
+    let extension = segments.next()?;
+    let inner = segments.next()?;
+    // A name like `pdf.exe` has a stem and a single extension.
+    segments.next()?;
+
+    let extension_like = (1..=4).contains(&inner.len())
+        && inner.bytes().all(|byte| byte.is_ascii_alphanumeric())
+        && inner.bytes().any(|byte| byte.is_ascii_alphabetic());
+    (extension_like && !extension.is_empty())
+        .then(|| format!("{inner}.{extension}").to_ascii_lowercase())
+}
+
+/// Returns the lowercased extension of the sanitized `file_name`, if any.
+pub(crate) fn file_extension(file_name: &str) -> Option<String> {
+    let sanitized = sanitize_filename(file_name);
+    let extension = Path::new(&sanitized).extension()?.to_str()?;
+    Some(extension.to_ascii_lowercase())
+}
@@ -0,0 +1,181 @@
+//! Violations recorded instead of failing a request.
+
+use std::sync::Arc;
+
+use crate::MulterError;
+
+/// Parts rejected while storing a request under
+/// [`MulterConfig::collect_violations`](crate::MulterConfig::collect_violations).
+///
+/// ```rust
+/// use bytes::Bytes;
+/// use futures::stream;
+/// use multigear::{MemoryStorage, Multer, MulterError};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), MulterError> {
+/// let multer = Multer::builder()
+///     .storage(MemoryStorage::new())
+///     .any()
+///     .max_file_size(4)
+///     .collect_violations(true)
+///     .build()?;
+/// let body = concat!(
+///     "--X\r\nContent-Disposition: form-data; name=\"big\"; filename=\"a.txt\"\r\n\r\ntoo large\r\n",
+///     "--X\r\nContent-Disposition: form-data; name=\"small\"; filename=\"b.txt\"\r\n\r\nok\r\n",
+///     "--X--\r\n",
+/// );
+///
+/// let output = multer
+///     .parse_and_store("X", stream::iter([Ok::<_, MulterError>(Bytes::from(body))]))
+///     .await?;
+/// assert_eq!(output.stored_files.len(), 1);
+/// assert_eq!(output.report.len(), 1);
+/// assert_eq!(output.report.violations()[0].field_name.as_deref(), Some("big"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    violations: Vec<PartViolation>,
+}
+
+impl ValidationReport {
+    /// Returns `true` when no part was rejected.
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Returns the number of recorded violations.
+    pub fn len(&self) -> usize {
+        self.violations.len()
+    }
+
+    /// Returns the recorded violations in stream order.
+    pub fn violations(&self) -> &[PartViolation] {
+        &self.violations
+    }
+
+    /// Iterates over the recorded violations in stream order.
+    pub fn iter(&self) -> std::slice::Iter<'_, PartViolation> {
+        self.violations.iter()
+    }
+
+    pub(crate) fn push(&mut self, violation: PartViolation) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = violation.field_name.as_deref().unwrap_or("<none>"),
+            error = %violation.error,
+            "multer: recorded part violation"
+        );
+        self.violations.push(violation);
+    }
+}
+
+impl<'a> IntoIterator for &'a ValidationReport {
+    type Item = &'a PartViolation;
+    type IntoIter = std::slice::Iter<'a, PartViolation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A part skipped because it broke a configured rule.
+#[derive(Debug, Clone)]
+pub struct PartViolation {
+    /// Field name of the rejected part, or `None` for rules checked once the
+    /// request ends, such as field constraints.
+    pub field_name: Option<String>,
+    /// Original filename of the rejected part, when it was read.
+    pub file_name: Option<String>,
+    /// The error the part would have failed the request with.
+    pub error: Arc<MulterError>,
+}
+
+impl PartViolation {
+    pub(crate) fn new(error: MulterError) -> Self {
+        Self {
+            field_name: error_field(&error).map(ToOwned::to_owned),
+            file_name: None,
+            error: Arc::new(error),
+        }
+    }
+
+    pub(crate) fn with_file_name(mut self, file_name: Option<String>) -> Self {
+        self.file_name = file_name;
+        self
+    }
+}
+
+impl PartialEq for PartViolation {
+    fn eq(&self, other: &Self) -> bool {
+        self.field_name == other.field_name
+            && self.file_name == other.file_name
+            && self.error.to_string() == other.error.to_string()
+    }
+}
+
+impl Eq for PartViolation {}
+
+/// Returns `true` for errors confined to one part, after which parsing can
+/// continue with the next part.
+pub(crate) fn is_part_violation(err: &MulterError) -> bool {
+    matches!(
+        err,
+        MulterError::Validation { .. }
+            | MulterError::FileRejected { .. }
+            | MulterError::DuplicateFile { .. }
+            | MulterError::ScanRejected { .. }
+            | MulterError::ChecksumMismatch { .. }
+            | MulterError::UnexpectedField { .. }
+            | MulterError::FieldCountLimitExceeded { .. }
+            | MulterError::FileSizeLimitExceeded { .. }
+            | MulterError::FileSizeBelowMinimum { .. }
+            | MulterError::FieldSizeLimitExceeded { .. }
+            | MulterError::FilesLimitExceeded { .. }
+            | MulterError::FieldsLimitExceeded { .. }
+            | MulterError::MimeTypeNotAllowed { .. }
+            | MulterError::MimeTypeMismatch { .. }
+            | MulterError::ExtensionNotAllowed { .. }
+            | MulterError::DoubleExtension { .. }
+            | MulterError::FilenameTooLong { .. }
+            | MulterError::InvalidFilename { .. }
+            | MulterError::EmptyFile { .. }
+    )
+}
+
+/// Returns `true` for form rules checked once the stream has ended.
+pub(crate) fn is_request_end_violation(err: &MulterError) -> bool {
+    matches!(
+        err,
+        MulterError::MissingRequiredField { .. }
+            | MulterError::FieldCountBelowMinimum { .. }
+            | MulterError::FieldConstraintViolated { .. }
+    )
+}
+
+fn error_field(err: &MulterError) -> Option<&str> {
+    match err {
+        MulterError::Validation { field, .. }
+        | MulterError::FileRejected { field, .. }
+        | MulterError::DuplicateFile { field, .. }
+        | MulterError::ScanRejected { field, .. }
+        | MulterError::UnexpectedField { field }
+        | MulterError::MissingRequiredField { field }
+        | MulterError::FieldCountBelowMinimum { field, .. }
+        | MulterError::FieldCountLimitExceeded { field, .. }
+        | MulterError::FileSizeLimitExceeded { field, .. }
+        | MulterError::FileSizeBelowMinimum { field, .. }
+        | MulterError::FieldSizeLimitExceeded { field, .. }
+        | MulterError::MimeTypeNotAllowed { field, .. }
+        | MulterError::MimeTypeMismatch { field, .. }
+        | MulterError::ExtensionNotAllowed { field, .. }
+        | MulterError::DoubleExtension { field, .. }
+        | MulterError::FilenameTooLong { field, .. }
+        | MulterError::InvalidFilename { field, .. }
+        | MulterError::EmptyFile { field } => Some(field),
+        MulterError::ChecksumMismatch { field, .. } => field.as_deref(),
+        _ => None,
+    }
+}
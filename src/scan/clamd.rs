@@ -0,0 +1,166 @@
+//! [`AvScanner`] backed by a running clamd daemon.
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::StreamExt;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    error::ScanError,
+    scan::{AvScanner, ScanVerdict},
+    storage::{BoxStream, FileMeta},
+};
+
+/// Default time allowed for one clamd scan, including streaming the body.
+pub const DEFAULT_CLAMD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Largest clamd reply read before the connection is abandoned.
+const MAX_REPLY_LEN: u64 = 4096;
+
+#[derive(Debug, Clone)]
+enum ClamdAddress {
+    Tcp(String),
+    #[cfg(unix)]
+    Unix(std::path::PathBuf),
+}
+
+/// [`AvScanner`] that streams file bodies to clamd with the `INSTREAM`
+/// command.
+///
+/// Every scan opens a new connection. Bodies larger than clamd's
+/// `StreamMaxLength` make clamd end the stream early and fail the scan with
+/// [`ScanError`].
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use multigear::{scan::ClamdScanner, MemoryStorage, Multer};
+///
+/// let multer = Multer::builder()
+///     .storage(MemoryStorage::new())
+///     .scanner(ClamdScanner::tcp("127.0.0.1:3310").timeout(Duration::from_secs(30)))
+///     .build()
+///     .expect("valid config");
+/// # drop(multer);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClamdScanner {
+    address: ClamdAddress,
+    timeout: Duration,
+}
+
+impl ClamdScanner {
+    /// Creates a scanner for clamd listening on a TCP address such as `127.0.0.1:3310`.
+    pub fn tcp(address: impl Into<String>) -> Self {
+        Self {
+            address: ClamdAddress::Tcp(address.into()),
+            timeout: DEFAULT_CLAMD_TIMEOUT,
+        }
+    }
+
+    /// Creates a scanner for clamd listening on a Unix domain socket.
+    #[cfg(unix)]
+    pub fn unix(path: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            address: ClamdAddress::Unix(path.into()),
+            timeout: DEFAULT_CLAMD_TIMEOUT,
+        }
+    }
+
+    /// Sets the time allowed for one scan.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    async fn scan_body(&self, body: BoxStream<'_, Bytes>) -> Result<ScanVerdict, ScanError> {
+        match &self.address {
+            ClamdAddress::Tcp(address) => {
+                let connection = tokio::net::TcpStream::connect(address)
+                    .await
+                    .map_err(|err| ScanError::new(format!("cannot connect to clamd: {err}")))?;
+                instream(connection, body).await
+            }
+            #[cfg(unix)]
+            ClamdAddress::Unix(path) => {
+                let connection = tokio::net::UnixStream::connect(path)
+                    .await
+                    .map_err(|err| ScanError::new(format!("cannot connect to clamd: {err}")))?;
+                instream(connection, body).await
+            }
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl AvScanner for ClamdScanner {
+    async fn scan(
+        &self,
+        _meta: &FileMeta,
+        body: BoxStream<'_, Bytes>,
+    ) -> Result<ScanVerdict, ScanError> {
+        tokio::time::timeout(self.timeout, self.scan_body(body))
+            .await
+            .map_err(|_| ScanError::new("clamd scan timed out"))?
+    }
+}
+
+async fn instream<C>(
+    mut connection: C,
+    mut body: BoxStream<'_, Bytes>,
+) -> Result<ScanVerdict, ScanError>
+where
+    C: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut sent = send_body(&mut connection, &mut body).await;
+    if sent.is_ok() {
+        sent = connection.flush().await;
+    }
+
+    // clamd replies and closes early when the stream exceeds its size limit,
+    // so read the reply even after a failed write.
+    let mut reply = Vec::new();
+    let read = (&mut connection)
+        .take(MAX_REPLY_LEN)
+        .read_to_end(&mut reply)
+        .await;
+    if reply.is_empty() {
+        sent.and(read)
+            .map_err(|err| ScanError::new(format!("clamd connection failed: {err}")))?;
+    }
+    parse_reply(&reply)
+}
+
+async fn send_body<C>(connection: &mut C, body: &mut BoxStream<'_, Bytes>) -> std::io::Result<()>
+where
+    C: AsyncWrite + Unpin,
+{
+    connection.write_all(b"zINSTREAM\0").await?;
+    while let Some(chunk) = body.next().await {
+        for piece in chunk.chunks(u32::MAX as usize) {
+            connection
+                .write_all(&(piece.len() as u32).to_be_bytes())
+                .await?;
+            connection.write_all(piece).await?;
+        }
+    }
+    connection.write_all(&0u32.to_be_bytes()).await
+}
+
+fn parse_reply(reply: &[u8]) -> Result<ScanVerdict, ScanError> {
+    let reply = String::from_utf8_lossy(reply);
+    let reply = reply.trim_end_matches(['\0', '\n']).trim();
+    let result = reply.strip_prefix("stream:").unwrap_or(reply).trim();
+
+    if result == "OK" {
+        return Ok(ScanVerdict::Clean);
+    }
+    if let Some(signature) = result.strip_suffix(" FOUND") {
+        return Ok(ScanVerdict::Infected {
+            signature: signature.trim().to_owned(),
+        });
+    }
+    Err(ScanError::new(format!("clamd error: {reply}")))
+}
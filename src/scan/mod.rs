@@ -0,0 +1,190 @@
+//! Antivirus scanning of file parts while they are stored.
+
+use std::{fmt, future::Future, sync::Arc};
+
+use bytes::Bytes;
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt, StreamExt,
+};
+
+use crate::{
+    error::ScanError,
+    storage::{BoxStream, FileMeta},
+    MulterError,
+};
+
+/// clamd scanner speaking the `INSTREAM` protocol.
+#[cfg(feature = "clamav")]
+pub mod clamd;
+#[cfg(feature = "clamav")]
+pub use clamd::ClamdScanner;
+
+/// Body chunks buffered between storage and a scanner before storage waits.
+const SCAN_CHANNEL_CAPACITY: usize = 8;
+
+/// Outcome of scanning a file part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ScanVerdict {
+    /// No threat found.
+    Clean,
+    /// A threat was found; the upload is vetoed.
+    Infected {
+        /// Name of the detected signature.
+        signature: String,
+    },
+}
+
+/// Async antivirus hook that sees every file body as it streams to storage.
+///
+/// The scanner registered with [`MulterBuilder::scanner`](crate::MulterBuilder::scanner)
+/// receives a tee of the part body while [`Multer::store`](crate::Multer::store)
+/// writes it. Storage does not complete until the scanner returns: a
+/// [`ScanVerdict::Infected`] verdict fails the upload with
+/// [`MulterError::ScanRejected`] and a scanner error with
+/// [`MulterError::Scan`]. Storage engines observe either as an error at the
+/// end of the body stream, so backends that clean up after stream errors,
+/// such as [`DiskStorage`](crate::DiskStorage), discard the vetoed file.
+///
+/// Scanners may return before reading the whole body; the remaining bytes
+/// still reach storage.
+///
+/// ```rust
+/// # #[cfg(not(feature = "unsend"))]
+/// # {
+/// use bytes::Bytes;
+/// use futures::StreamExt;
+/// use multigear::{
+///     scan::{AvScanner, ScanVerdict},
+///     BoxStream, FileMeta, MemoryStorage, Multer, ScanError,
+/// };
+///
+/// struct EicarScanner;
+///
+/// #[async_trait::async_trait]
+/// impl AvScanner for EicarScanner {
+///     async fn scan(
+///         &self,
+///         _meta: &FileMeta,
+///         mut body: BoxStream<'_, Bytes>,
+///     ) -> Result<ScanVerdict, ScanError> {
+///         while let Some(chunk) = body.next().await {
+///             if chunk.starts_with(b"X5O!P%@AP") {
+///                 return Ok(ScanVerdict::Infected {
+///                     signature: "Eicar-Test-Signature".to_owned(),
+///                 });
+///             }
+///         }
+///         Ok(ScanVerdict::Clean)
+///     }
+/// }
+///
+/// let multer = Multer::builder()
+///     .storage(MemoryStorage::new())
+///     .scanner(EicarScanner)
+///     .build()
+///     .expect("valid config");
+/// # drop(multer);
+/// # }
+/// ```
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+pub trait AvScanner: Send + Sync + 'static {
+    /// Scans a file body, streamed in the chunks storage receives.
+    async fn scan(
+        &self,
+        meta: &FileMeta,
+        body: BoxStream<'_, Bytes>,
+    ) -> Result<ScanVerdict, ScanError>;
+}
+
+/// Scanner shared by a builder and the `Multer` it builds.
+#[derive(Clone)]
+pub(crate) struct Scanner(Arc<dyn AvScanner>);
+
+impl Scanner {
+    pub(crate) fn new(scanner: impl AvScanner) -> Self {
+        Self(Arc::new(scanner))
+    }
+
+    /// Runs `store` on a tee of `body` while scanning it, holding back the end
+    /// of the stream until the scan finishes.
+    ///
+    /// Returns the store result together with the scanner's rejection, so a
+    /// file stored before the verdict arrived can be aborted.
+    pub(crate) async fn scan_while_storing<'a, O, F, Fut>(
+        &self,
+        meta: FileMeta,
+        body: BoxStream<'a, Result<Bytes, MulterError>>,
+        store: F,
+    ) -> (Result<O, MulterError>, Option<MulterError>)
+    where
+        F: FnOnce(BoxStream<'a, Result<Bytes, MulterError>>) -> Fut,
+        Fut: Future<Output = Result<O, MulterError>>,
+    {
+        let (chunk_tx, chunk_rx) = mpsc::channel::<Bytes>(SCAN_CHANNEL_CAPACITY);
+        let (verdict_tx, verdict_rx) = oneshot::channel::<Option<MulterError>>();
+
+        let tee = futures::stream::unfold(
+            (body.fuse(), Some(chunk_tx), Some(verdict_rx)),
+            |(mut body, mut chunk_tx, mut verdict_rx)| async move {
+                match body.next().await {
+                    Some(Ok(chunk)) => {
+                        if let Some(tx) = chunk_tx.as_mut() {
+                            // A closed channel means the scanner is done reading.
+                            if tx.send(chunk.clone()).await.is_err() {
+                                chunk_tx = None;
+                            }
+                        }
+                        Some((Ok(chunk), (body, chunk_tx, verdict_rx)))
+                    }
+                    Some(Err(err)) => Some((Err(err), (body, chunk_tx, verdict_rx))),
+                    None => {
+                        drop(chunk_tx.take());
+                        let rejection = verdict_rx.take()?.await.ok()??;
+                        Some((Err(rejection), (body, None, None)))
+                    }
+                }
+            },
+        );
+
+        let scan = async {
+            let result = self.0.scan(&meta, Box::pin(chunk_rx)).await;
+            let _ = verdict_tx.send(rejection(&meta.field_name, &result));
+            result
+        };
+
+        let (stored, scanned) = futures::join!(store(Box::pin(tee)), scan);
+        let rejection = rejection(&meta.field_name, &scanned);
+        #[cfg(feature = "tracing")]
+        if let Some(err) = &rejection {
+            tracing::warn!(
+                field_name = meta.field_name.as_str(),
+                error = %err,
+                "multer: part rejected by scanner"
+            );
+        }
+        (stored, rejection)
+    }
+}
+
+impl fmt::Debug for Scanner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scanner").finish_non_exhaustive()
+    }
+}
+
+fn rejection(field: &str, result: &Result<ScanVerdict, ScanError>) -> Option<MulterError> {
+    match result {
+        Ok(ScanVerdict::Clean) => None,
+        Ok(ScanVerdict::Infected { signature }) => Some(MulterError::ScanRejected {
+            field: field.to_owned(),
+            signature: signature.clone(),
+        }),
+        Err(source) => Some(MulterError::Scan {
+            field: field.to_owned(),
+            source: source.clone(),
+        }),
+    }
+}
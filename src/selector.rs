@@ -1,6 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::{MulterError, SelectedField, SelectedFieldKind, Selector, UnknownFieldPolicy};
+use crate::{
+    FieldConstraint, FieldNameMatch, MulterError, SelectedField, SelectedFieldKind, Selector,
+    UnknownFieldPolicy,
+};
 
 /// Runtime decision for a candidate incoming file part.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -9,6 +12,8 @@ pub enum SelectorAction {
     Accept,
     /// Ignore and skip this part.
     Ignore,
+    /// Buffer this unknown part under [`UnknownFieldPolicy::Collect`].
+    Collect,
 }
 
 /// Stateful runtime selector engine.
@@ -18,20 +23,34 @@ pub struct SelectorEngine {
     unknown_field_policy: UnknownFieldPolicy,
     counts: HashMap<String, usize>,
     fields: HashMap<String, FieldRules>,
+    patterns: Vec<(NamePattern, FieldRules)>,
+    received: HashMap<String, usize>,
+    constraints: Vec<FieldConstraint>,
+    present: HashSet<String>,
 }
 
 impl SelectorEngine {
     /// Creates a selector engine with runtime counters.
     pub fn new(selector: Selector, unknown_field_policy: UnknownFieldPolicy) -> Self {
-        let fields = build_fields_map(&selector);
+        let (fields, patterns) = build_field_rules(&selector);
         Self {
             selector,
             unknown_field_policy,
             counts: HashMap::new(),
             fields,
+            patterns,
+            received: HashMap::new(),
+            constraints: Vec::new(),
+            present: HashSet::new(),
         }
     }
 
+    /// Adds cross-field rules checked by [`SelectorEngine::violated_constraint`].
+    pub fn with_constraints(mut self, constraints: Vec<FieldConstraint>) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
     /// Applies selector rules for a file field and returns the action.
     pub fn evaluate_file_field(&mut self, field_name: &str) -> Result<SelectorAction, MulterError> {
         match &self.selector {
@@ -39,24 +58,25 @@ impl SelectorEngine {
                 if field_name != name {
                     return self.handle_unknown_field(field_name);
                 }
-                self.record_with_limit(field_name, Some(1))?;
+                self.record_with_limit(field_name, field_name, Some(1))?;
                 Ok(SelectorAction::Accept)
             }
             Selector::Array { name, max_count } => {
                 if field_name != name {
                     return self.handle_unknown_field(field_name);
                 }
-                self.record_with_limit(field_name, *max_count)?;
+                self.record_with_limit(field_name, field_name, *max_count)?;
                 Ok(SelectorAction::Accept)
             }
             Selector::Fields(_) => {
-                let Some(rules) = self.fields.get(field_name).cloned() else {
+                let Some(rules) = self.rules(field_name) else {
                     return self.handle_unknown_field(field_name);
                 };
                 if rules.kind != SelectedFieldKind::File {
                     return self.handle_unknown_field(field_name);
                 }
-                self.record_with_limit(field_name, rules.max_count)?;
+                let (key, max_count) = (rules.name.clone(), rules.max_count);
+                self.record_with_limit(&key, field_name, max_count)?;
                 Ok(SelectorAction::Accept)
             }
             Selector::None => self.handle_unknown_field(field_name),
@@ -68,7 +88,7 @@ impl SelectorEngine {
     pub fn evaluate_text_field(&self, field_name: &str) -> Result<SelectorAction, MulterError> {
         match &self.selector {
             Selector::Fields(_) => {
-                let Some(rules) = self.fields.get(field_name) else {
+                let Some(rules) = self.rules(field_name) else {
                     return self.handle_unknown_field(field_name);
                 };
                 if rules.kind != SelectedFieldKind::Text {
@@ -82,21 +102,105 @@ impl SelectorEngine {
         }
     }
 
-    fn handle_unknown_field(&self, field_name: &str) -> Result<SelectorAction, MulterError> {
+    /// Returns the total size cap of [`UnknownFieldPolicy::Collect`], if active.
+    pub fn unknown_parts_max_size(&self) -> Option<u64> {
         match self.unknown_field_policy {
+            UnknownFieldPolicy::Collect { max_size } => Some(max_size),
+            _ => None,
+        }
+    }
+
+    /// Records that an accepted part for `field_name` was received.
+    pub fn mark_received(&mut self, field_name: &str) {
+        if self
+            .constraints
+            .iter()
+            .any(|constraint| constraint.field_names().contains(&field_name))
+        {
+            self.present.insert(field_name.to_owned());
+        }
+
+        if let Some(rules) = self
+            .rules(field_name)
+            .filter(|rules| rules.required || rules.min_count.is_some())
+        {
+            let key = rules.name.clone();
+            *self.received.entry(key).or_default() += 1;
+        }
+    }
+
+    /// Returns the first required field, in selector order, not yet received.
+    pub fn missing_required_field(&self) -> Option<&str> {
+        let Selector::Fields(fields) = &self.selector else {
+            return None;
+        };
+        fields
+            .iter()
+            .find(|field| field.required && !self.received.contains_key(&field.name))
+            .map(|field| field.name.as_str())
+    }
+
+    /// Returns the first configured constraint the received fields violate.
+    pub fn violated_constraint(&self) -> Option<&FieldConstraint> {
+        self.constraints
+            .iter()
+            .find(|constraint| !constraint.is_satisfied_by(|field| self.present.contains(field)))
+    }
+
+    /// Returns the first field, in selector order, received fewer times than
+    /// its `min_count`, along with that minimum.
+    pub fn field_below_min_count(&self) -> Option<(&str, usize)> {
+        let Selector::Fields(fields) = &self.selector else {
+            return None;
+        };
+        fields.iter().find_map(|field| {
+            let min_count = field.min_count?;
+            let received = self.received.get(&field.name).copied().unwrap_or(0);
+            (received < min_count).then_some((field.name.as_str(), min_count))
+        })
+    }
+
+    /// Returns the selected field name for `field_name` when it is an alias
+    /// of an exactly named field.
+    pub fn canonical_field_name(&self, field_name: &str) -> Option<&str> {
+        self.fields
+            .get(field_name)
+            .filter(|rules| rules.name != field_name && rules.exact)
+            .map(|rules| rules.name.as_str())
+    }
+
+    /// Returns the rules for `field_name`: an exact entry first, then the
+    /// first matching pattern in selector order.
+    fn rules(&self, field_name: &str) -> Option<&FieldRules> {
+        self.fields.get(field_name).or_else(|| {
+            self.patterns
+                .iter()
+                .find(|(pattern, _)| pattern.matches(field_name))
+                .map(|(_, rules)| rules)
+        })
+    }
+
+    fn handle_unknown_field(&self, field_name: &str) -> Result<SelectorAction, MulterError> {
+        match &self.unknown_field_policy {
             UnknownFieldPolicy::Reject => Err(MulterError::UnexpectedField {
                 field: field_name.to_owned(),
             }),
             UnknownFieldPolicy::Ignore => Ok(SelectorAction::Ignore),
+            UnknownFieldPolicy::Collect { .. } => Ok(SelectorAction::Collect),
+            UnknownFieldPolicy::Warn(callback) => {
+                callback(field_name);
+                Ok(SelectorAction::Ignore)
+            }
         }
     }
 
     fn record_with_limit(
         &mut self,
+        key: &str,
         field_name: &str,
         max_count: Option<usize>,
     ) -> Result<(), MulterError> {
-        let next = self.counts.get(field_name).copied().unwrap_or(0) + 1;
+        let next = self.counts.get(key).copied().unwrap_or(0) + 1;
         if let Some(max_count) = max_count {
             if next > max_count {
                 return Err(MulterError::FieldCountLimitExceeded {
@@ -105,20 +209,47 @@ impl SelectorEngine {
                 });
             }
         }
-        self.counts.insert(field_name.to_owned(), next);
+        self.counts.insert(key.to_owned(), next);
         Ok(())
     }
 
     /// Returns MIME patterns configured for a selected field, if present.
     pub fn field_allowed_mime_types(&self, field_name: &str) -> Option<&[String]> {
-        self.fields
-            .get(field_name)
+        self.rules(field_name)
             .map(|rules| rules.allowed_mime_types.as_slice())
     }
 
+    /// Returns file extensions configured for a selected field, if present.
+    pub fn field_allowed_extensions(&self, field_name: &str) -> Option<&[String]> {
+        self.rules(field_name)
+            .map(|rules| rules.allowed_extensions.as_slice())
+    }
+
+    /// Returns the configured per-file size limit for a selected field, if present.
+    pub fn field_file_max_size(&self, field_name: &str) -> Option<u64> {
+        self.rules(field_name).and_then(|rules| {
+            if rules.kind == SelectedFieldKind::File {
+                rules.max_size
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns the configured minimum file size for a selected field, if present.
+    pub fn field_file_min_size(&self, field_name: &str) -> Option<u64> {
+        self.rules(field_name).and_then(|rules| {
+            if rules.kind == SelectedFieldKind::File {
+                rules.min_size
+            } else {
+                None
+            }
+        })
+    }
+
     /// Returns the configured text size limit for a selected field, if present.
     pub fn field_text_max_size(&self, field_name: &str) -> Option<u64> {
-        self.fields.get(field_name).and_then(|rules| {
+        self.rules(field_name).and_then(|rules| {
             if rules.kind == SelectedFieldKind::Text {
                 rules.max_size
             } else {
@@ -130,36 +261,123 @@ impl SelectorEngine {
 
 #[derive(Debug, Clone)]
 struct FieldRules {
+    name: String,
+    exact: bool,
     kind: SelectedFieldKind,
     max_count: Option<usize>,
+    min_count: Option<usize>,
     max_size: Option<u64>,
+    min_size: Option<u64>,
+    required: bool,
     allowed_mime_types: Vec<String>,
+    allowed_extensions: Vec<String>,
+}
+
+/// Compiled field name pattern of a non-exact [`SelectedField`].
+#[derive(Debug, Clone)]
+enum NamePattern {
+    Glob(String),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl NamePattern {
+    fn new(name: &str, name_match: FieldNameMatch) -> Option<Self> {
+        match name_match {
+            FieldNameMatch::Exact => None,
+            FieldNameMatch::Glob => Some(Self::Glob(name.to_owned())),
+            // Config validation rejects patterns that fail to compile.
+            #[cfg(feature = "regex")]
+            FieldNameMatch::Regex => regex::Regex::new(&format!("^(?:{name})$"))
+                .ok()
+                .map(Self::Regex),
+        }
+    }
+
+    fn matches(&self, field_name: &str) -> bool {
+        match self {
+            Self::Glob(pattern) => glob_matches(pattern, field_name),
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => regex.is_match(field_name),
+        }
+    }
+}
+
+type FieldRuleSet = (HashMap<String, FieldRules>, Vec<(NamePattern, FieldRules)>);
+
+fn build_field_rules(selector: &Selector) -> FieldRuleSet {
+    let Selector::Fields(fields) = selector else {
+        return (HashMap::new(), Vec::new());
+    };
+
+    let mut map = HashMap::with_capacity(fields.len());
+    let mut patterns = Vec::new();
+    for SelectedField {
+        name,
+        name_match,
+        aliases,
+        kind,
+        max_count,
+        min_count,
+        max_size,
+        min_size,
+        required,
+        allowed_mime_types,
+        allowed_extensions,
+    } in fields
+    {
+        let rules = FieldRules {
+            name: name.clone(),
+            exact: *name_match == FieldNameMatch::Exact,
+            kind: *kind,
+            max_count: *max_count,
+            min_count: *min_count,
+            max_size: *max_size,
+            min_size: *min_size,
+            required: *required,
+            allowed_mime_types: allowed_mime_types.clone(),
+            allowed_extensions: allowed_extensions.clone(),
+        };
+        for alias in aliases {
+            map.insert(alias.clone(), rules.clone());
+        }
+        if *name_match == FieldNameMatch::Exact {
+            map.insert(name.clone(), rules);
+        } else if let Some(pattern) = NamePattern::new(name, *name_match) {
+            patterns.push((pattern, rules));
+        }
+    }
+    (map, patterns)
 }
 
-fn build_fields_map(selector: &Selector) -> HashMap<String, FieldRules> {
-    match selector {
-        Selector::Fields(fields) => {
-            let mut map = HashMap::with_capacity(fields.len());
-            for SelectedField {
-                name,
-                kind,
-                max_count,
-                max_size,
-                allowed_mime_types,
-            } in fields
-            {
-                map.insert(
-                    name.clone(),
-                    FieldRules {
-                        kind: *kind,
-                        max_count: *max_count,
-                        max_size: *max_size,
-                        allowed_mime_types: allowed_mime_types.clone(),
-                    },
-                );
+/// Matches `name` against a glob where `*` matches any run of characters and
+/// `?` matches exactly one.
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
             }
-            map
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
         }
-        _ => HashMap::new(),
     }
+
+    pattern[p..].iter().all(|&c| c == '*')
 }
@@ -0,0 +1,60 @@
+//! Magic-byte content type sniffing for stored files.
+
+use crate::{config::MimeSniffPolicy, limits::Limits, storage::FileMeta, MulterError};
+
+/// Leading body bytes inspected when sniffing a file's content type.
+pub(crate) const SNIFF_LEN: usize = 8 * 1024;
+
+/// Records the sniffed content type on `meta` and applies `policy` to it.
+pub(crate) fn apply_mime_sniffing(
+    policy: MimeSniffPolicy,
+    limits: &Limits,
+    meta: &mut FileMeta,
+    head: &[u8],
+) -> Result<(), MulterError> {
+    let Some(sniffed) = infer::get(head).map(|kind| kind.mime_type()) else {
+        return Ok(());
+    };
+    meta.sniffed_content_type = Some(sniffed.to_owned());
+
+    let declared = meta
+        .content_type
+        .parse::<mime::Mime>()
+        .unwrap_or(mime::APPLICATION_OCTET_STREAM);
+    if declared == mime::APPLICATION_OCTET_STREAM
+        || declared.essence_str().eq_ignore_ascii_case(sniffed)
+    {
+        return Ok(());
+    }
+
+    match policy {
+        MimeSniffPolicy::TrustDeclared => Ok(()),
+        MimeSniffPolicy::PreferSniffed => {
+            let Ok(sniffed_mime) = sniffed.parse::<mime::Mime>() else {
+                return Ok(());
+            };
+            if limits.is_mime_denied(&sniffed_mime) || !limits.is_mime_allowed(&sniffed_mime) {
+                return Err(MulterError::MimeTypeNotAllowed {
+                    field: meta.field_name.clone(),
+                    mime: sniffed.to_owned(),
+                });
+            }
+            meta.content_type = sniffed.to_owned();
+            Ok(())
+        }
+        MimeSniffPolicy::RejectMismatch => {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                field_name = meta.field_name.as_str(),
+                declared = meta.content_type.as_str(),
+                sniffed,
+                "multer: sniffed content type differs from declared"
+            );
+            Err(MulterError::MimeTypeMismatch {
+                field: meta.field_name.clone(),
+                declared: declared.essence_str().to_owned(),
+                sniffed: sniffed.to_owned(),
+            })
+        }
+    }
+}
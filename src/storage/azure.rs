@@ -0,0 +1,664 @@
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use base64::Engine as _;
+use bytes::{Bytes, BytesMut};
+use reqwest::{header::HeaderValue, Client, Method, RequestBuilder, Url};
+use tokio::sync::Mutex;
+
+use super::{
+    fill_part, BoxStream, FileMeta, FileMetadata, FilenamePolicy, FilenameStrategy, StorageEngine,
+    StorageEngineExt, StoreContext, StoredFile,
+};
+use crate::{MulterError, StorageError};
+
+/// Largest block Azure accepts in a staged block blob upload.
+pub const MAX_BLOCK_SIZE: usize = 4000 * 1024 * 1024;
+
+/// Default size of the blocks a large file is staged in.
+pub const DEFAULT_BLOCK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Blob service REST API version sent with every request.
+const API_VERSION: &str = "2021-08-06";
+
+/// Azure Instance Metadata Service endpoint issuing managed identity tokens.
+const IMDS_TOKEN_ENDPOINT: &str = "http://169.254.169.254/metadata/identity/oauth2/token";
+
+/// Resource managed identity tokens are requested for.
+const STORAGE_RESOURCE: &str = "https://storage.azure.com/";
+
+/// How long before expiry a cached access token is refreshed.
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(300);
+
+/// Credential authorizing requests to the blob service.
+#[derive(Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AzureCredential {
+    /// A shared access signature appended to every request URL.
+    ///
+    /// The token needs create and write permissions on the container, plus
+    /// read and delete permissions for [`StorageEngineExt`].
+    SasToken(String),
+    /// A managed identity, authenticated with bearer tokens from the Azure
+    /// Instance Metadata Service.
+    ManagedIdentity {
+        /// Client id of a user-assigned identity, or `None` for the
+        /// system-assigned identity.
+        client_id: Option<String>,
+    },
+}
+
+impl AzureCredential {
+    /// Creates a credential from a shared access signature, with or without
+    /// its leading `?`.
+    pub fn sas_token(token: impl Into<String>) -> Self {
+        let token = token.into();
+        Self::SasToken(token.strip_prefix('?').unwrap_or(&token).to_owned())
+    }
+
+    /// Creates a credential for the system-assigned managed identity.
+    pub fn managed_identity() -> Self {
+        Self::ManagedIdentity { client_id: None }
+    }
+
+    /// Creates a credential for the user-assigned managed identity with
+    /// `client_id`.
+    pub fn user_assigned_identity(client_id: impl Into<String>) -> Self {
+        Self::ManagedIdentity {
+            client_id: Some(client_id.into()),
+        }
+    }
+}
+
+impl fmt::Debug for AzureCredential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SasToken(_) => f.debug_tuple("SasToken").field(&"<redacted>").finish(),
+            Self::ManagedIdentity { client_id } => f
+                .debug_struct("ManagedIdentity")
+                .field("client_id", client_id)
+                .finish(),
+        }
+    }
+}
+
+/// Builder for [`AzureBlobStorage`].
+#[derive(Debug, Clone)]
+pub struct AzureBlobStorageBuilder {
+    account_url: String,
+    container: String,
+    credential: AzureCredential,
+    client: Option<Client>,
+    identity_endpoint: String,
+    prefix: String,
+    block_size: usize,
+    strategy: FilenameStrategy,
+    policy: FilenamePolicy,
+}
+
+impl AzureBlobStorageBuilder {
+    /// Sets the prefix prepended to every blob name, such as `uploads/`.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Sets the size of the blocks a large file is staged in.
+    ///
+    /// Files up to this size are sent with a single `Put Blob`; larger files
+    /// are staged block by block and committed with `Put Block List`,
+    /// buffering one block at a time. Must be between one byte and
+    /// [`MAX_BLOCK_SIZE`].
+    pub fn block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size;
+        self
+    }
+
+    /// Sets how blob names are derived from uploaded filenames.
+    ///
+    /// Defaults to [`FilenameStrategy::Random`]. Blobs stored under a name
+    /// kept from the client replace existing blobs with the same name.
+    pub fn filename(mut self, strategy: FilenameStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets the policy sanitizing blob names.
+    pub fn filename_policy(mut self, policy: FilenamePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets the HTTP client requests are sent with.
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Overrides the endpoint managed identity tokens are requested from.
+    ///
+    /// Defaults to the Azure Instance Metadata Service.
+    pub fn identity_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.identity_endpoint = endpoint.into();
+        self
+    }
+
+    /// Builds a validated Azure Blob Storage backend.
+    pub fn build(self) -> Result<AzureBlobStorage, StorageError> {
+        if self.container.trim().is_empty() {
+            return Err(StorageError::new(
+                "azure blob storage container cannot be empty",
+            ));
+        }
+        if self.block_size == 0 || self.block_size > MAX_BLOCK_SIZE {
+            return Err(StorageError::new(format!(
+                "azure blob storage block size must be between 1 and {MAX_BLOCK_SIZE} bytes"
+            )));
+        }
+        if let AzureCredential::SasToken(token) = &self.credential {
+            if token.trim().is_empty() {
+                return Err(StorageError::new(
+                    "azure blob storage sas token cannot be empty",
+                ));
+            }
+        }
+
+        let mut container_url = Url::parse(&self.account_url)
+            .map_err(|err| StorageError::backend("invalid azure blob storage account url", err))?;
+        if container_url.cannot_be_a_base() {
+            return Err(StorageError::new(
+                "invalid azure blob storage account url: not a base url",
+            ));
+        }
+        container_url.set_query(None);
+        container_url
+            .path_segments_mut()
+            .map_err(|()| StorageError::new("invalid azure blob storage account url"))?
+            .pop_if_empty()
+            .push(&self.container);
+        let identity_endpoint = Url::parse(&self.identity_endpoint)
+            .map_err(|err| StorageError::backend("invalid azure identity endpoint", err))?;
+
+        Ok(AzureBlobStorage {
+            client: self.client.unwrap_or_default(),
+            container_url,
+            container: self.container,
+            credential: self.credential,
+            identity_endpoint,
+            token: Arc::default(),
+            prefix: self.prefix,
+            block_size: self.block_size,
+            strategy: self.strategy,
+            policy: self.policy,
+        })
+    }
+}
+
+/// Storage engine streaming files to block blobs in an Azure Storage
+/// container.
+///
+/// [`StoredFile::storage_key`] is the blob name.
+///
+/// ```rust
+/// use multigear::{AzureBlobStorage, AzureCredential, Multer};
+///
+/// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let storage = AzureBlobStorage::builder(
+///     "https://myaccount.blob.core.windows.net",
+///     "uploads",
+///     AzureCredential::managed_identity(),
+/// )
+/// .prefix("avatars/")
+/// .build()?;
+/// let multer = Multer::builder().storage(storage).build()?;
+/// # drop(multer);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AzureBlobStorage {
+    client: Client,
+    container_url: Url,
+    container: String,
+    credential: AzureCredential,
+    identity_endpoint: Url,
+    token: Arc<Mutex<Option<AccessToken>>>,
+    prefix: String,
+    block_size: usize,
+    strategy: FilenameStrategy,
+    policy: FilenamePolicy,
+}
+
+impl AzureBlobStorage {
+    /// Creates a builder storing blobs in `container` of the storage account
+    /// at `account_url`, such as `https://myaccount.blob.core.windows.net`.
+    pub fn builder(
+        account_url: impl Into<String>,
+        container: impl Into<String>,
+        credential: AzureCredential,
+    ) -> AzureBlobStorageBuilder {
+        AzureBlobStorageBuilder {
+            account_url: account_url.into(),
+            container: container.into(),
+            credential,
+            client: None,
+            identity_endpoint: IMDS_TOKEN_ENDPOINT.to_owned(),
+            prefix: String::new(),
+            block_size: DEFAULT_BLOCK_SIZE,
+            strategy: FilenameStrategy::Random,
+            policy: FilenamePolicy::default(),
+        }
+    }
+
+    /// Returns the container blobs are stored in.
+    pub fn container(&self) -> &str {
+        &self.container
+    }
+
+    fn blob_name(&self, file_name: Option<&str>) -> String {
+        let name = self.strategy.output_name(file_name, &self.policy);
+        format!("{}{name}", self.prefix)
+    }
+
+    /// Returns whether `blob_name` could have been stored by this backend.
+    ///
+    /// Keys may come from clients, so blobs outside the prefix are treated
+    /// as missing.
+    fn owns_blob(&self, blob_name: &str) -> bool {
+        blob_name.len() > self.prefix.len() && blob_name.starts_with(&self.prefix)
+    }
+
+    /// Sends an authorized request for `blob_name`, returning `None` when the
+    /// blob does not exist.
+    async fn blob_request(
+        &self,
+        method: Method,
+        blob_name: &str,
+        context: &str,
+    ) -> Result<Option<reqwest::Response>, StorageError> {
+        if !self.owns_blob(blob_name) {
+            return Ok(None);
+        }
+        let url = self.blob_url(blob_name, &[]);
+        let response = self
+            .request(method, url)
+            .await?
+            .send()
+            .await
+            .map_err(|err| http_error(context, err))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        check_status(context, response).await.map(Some)
+    }
+
+    fn blob_url(&self, blob_name: &str, query: &[(&str, &str)]) -> Url {
+        let mut url = self.container_url.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.extend(blob_name.split('/'));
+        }
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        if let AzureCredential::SasToken(token) = &self.credential {
+            let query = match url.query() {
+                Some(query) => format!("{query}&{token}"),
+                None => token.clone(),
+            };
+            url.set_query(Some(&query));
+        }
+        url
+    }
+
+    /// Starts an authorized blob service request.
+    async fn request(&self, method: Method, url: Url) -> Result<RequestBuilder, StorageError> {
+        let request = self
+            .client
+            .request(method, url)
+            .header("x-ms-version", API_VERSION);
+        match &self.credential {
+            AzureCredential::SasToken(_) => Ok(request),
+            AzureCredential::ManagedIdentity { client_id } => {
+                let token = self.access_token(client_id.as_deref()).await?;
+                Ok(request.bearer_auth(token))
+            }
+        }
+    }
+
+    /// Returns a cached managed identity token, fetching a new one when the
+    /// cached token is missing or about to expire.
+    async fn access_token(&self, client_id: Option<&str>) -> Result<String, StorageError> {
+        let mut cached = self.token.lock().await;
+        if let Some(token) = cached.as_ref() {
+            if token.is_fresh() {
+                return Ok(token.value.clone());
+            }
+        }
+
+        let mut url = self.identity_endpoint.clone();
+        url.query_pairs_mut()
+            .append_pair("api-version", "2018-02-01")
+            .append_pair("resource", STORAGE_RESOURCE);
+        if let Some(client_id) = client_id {
+            url.query_pairs_mut().append_pair("client_id", client_id);
+        }
+        let response = self
+            .client
+            .get(url)
+            .header("Metadata", "true")
+            .send()
+            .await
+            .map_err(|err| http_error("failed to request managed identity token", err))?;
+        let response = check_status("failed to request managed identity token", response).await?;
+        let body = response
+            .bytes()
+            .await
+            .map_err(|err| http_error("failed to read managed identity token", err))?;
+        let token = AccessToken::parse(&body)?;
+        let value = token.value.clone();
+        *cached = Some(token);
+        Ok(value)
+    }
+
+    async fn put_blob(
+        &self,
+        blob_name: &str,
+        meta: &FileMeta,
+        body: Bytes,
+    ) -> Result<(), StorageError> {
+        let url = self.blob_url(blob_name, &[]);
+        let response = self
+            .request(Method::PUT, url)
+            .await?
+            .header("x-ms-blob-type", "BlockBlob")
+            .header("x-ms-blob-content-type", content_type(meta))
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| http_error("failed to put blob", err))?;
+        check_status("failed to put blob", response).await?;
+        Ok(())
+    }
+
+    /// Stages `first` and the rest of `stream` as blocks, then commits them.
+    ///
+    /// Staged blocks that are never committed are discarded by the service,
+    /// so a failed upload needs no cleanup.
+    async fn upload_blocks(
+        &self,
+        blob_name: &str,
+        meta: &FileMeta,
+        first: Bytes,
+        buffer: &mut BytesMut,
+        stream: &mut BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<u64, StorageError> {
+        // Block ids must be unique per blob and all the same length.
+        let upload = uuid::Uuid::new_v4().simple().to_string();
+        let mut block_ids = Vec::new();
+        let mut written = 0u64;
+        let mut block = first;
+
+        loop {
+            written = written.saturating_add(block.len() as u64);
+            block_ids.push(
+                self.put_block(blob_name, &upload, block_ids.len(), block)
+                    .await?,
+            );
+            match fill_part(stream, buffer, self.block_size).await? {
+                Some(next) => block = next,
+                None => break,
+            }
+        }
+        // The held-back remainder is never empty and forms the last block.
+        let last = buffer.split().freeze();
+        written = written.saturating_add(last.len() as u64);
+        block_ids.push(
+            self.put_block(blob_name, &upload, block_ids.len(), last)
+                .await?,
+        );
+
+        let mut block_list = String::from(r#"<?xml version="1.0" encoding="utf-8"?><BlockList>"#);
+        for id in &block_ids {
+            block_list.push_str("<Latest>");
+            block_list.push_str(id);
+            block_list.push_str("</Latest>");
+        }
+        block_list.push_str("</BlockList>");
+
+        let url = self.blob_url(blob_name, &[("comp", "blocklist")]);
+        let response = self
+            .request(Method::PUT, url)
+            .await?
+            .header("x-ms-blob-content-type", content_type(meta))
+            .header(reqwest::header::CONTENT_TYPE, "application/xml")
+            .body(block_list)
+            .send()
+            .await
+            .map_err(|err| http_error("failed to commit block list", err))?;
+        check_status("failed to commit block list", response).await?;
+        Ok(written)
+    }
+
+    async fn put_block(
+        &self,
+        blob_name: &str,
+        upload: &str,
+        index: usize,
+        body: Bytes,
+    ) -> Result<String, StorageError> {
+        let block_id =
+            base64::engine::general_purpose::STANDARD.encode(format!("{upload}-{index:06}"));
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            blob = blob_name,
+            block = index,
+            size = body.len(),
+            "azure blob storage: staging block"
+        );
+        let url = self.blob_url(blob_name, &[("comp", "block"), ("blockid", &block_id)]);
+        let response = self
+            .request(Method::PUT, url)
+            .await?
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| http_error("failed to stage block", err))?;
+        check_status("failed to stage block", response).await?;
+        Ok(block_id)
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngine for AzureBlobStorage {
+    type Output = StoredFile;
+    type Error = StorageError;
+
+    async fn store(
+        &self,
+        meta: FileMeta,
+        _ctx: &StoreContext,
+        mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        let blob_name = self.blob_name(meta.file_name.as_deref());
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            file_name = meta.file_name.as_deref().unwrap_or("<none>"),
+            container = self.container.as_str(),
+            blob = blob_name.as_str(),
+            "azure blob storage: begin streaming store"
+        );
+
+        // Small files need one request; larger ones are staged block by block.
+        let mut buffer = BytesMut::new();
+        let first = fill_part(&mut stream, &mut buffer, self.block_size).await?;
+        let size = match first {
+            Some(first) => {
+                self.upload_blocks(&blob_name, &meta, first, &mut buffer, &mut stream)
+                    .await?
+            }
+            None => {
+                let body = buffer.split().freeze();
+                let size = body.len() as u64;
+                self.put_blob(&blob_name, &meta, body).await?;
+                size
+            }
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            blob = blob_name.as_str(),
+            size = size,
+            "azure blob storage: completed store"
+        );
+
+        Ok(StoredFile {
+            storage_key: blob_name,
+            field_name: meta.field_name,
+            file_name: meta.file_name,
+            content_type: meta
+                .content_type
+                .parse()
+                .unwrap_or(mime::APPLICATION_OCTET_STREAM),
+            sniffed_content_type: meta
+                .sniffed_content_type
+                .as_deref()
+                .and_then(|sniffed| sniffed.parse().ok()),
+            size,
+            path: None,
+        })
+    }
+
+    async fn abort(&self, output: Self::Output) -> Result<(), Self::Error> {
+        self.delete(&output.storage_key).await
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngineExt for AzureBlobStorage {
+    async fn open(
+        &self,
+        key: &str,
+    ) -> Result<Option<BoxStream<'static, Result<Bytes, Self::Error>>>, Self::Error> {
+        let Some(response) = self
+            .blob_request(Method::GET, key, "failed to get blob")
+            .await?
+        else {
+            return Ok(None);
+        };
+        let body = futures::stream::try_unfold(response, |mut response| async move {
+            let chunk = response
+                .chunk()
+                .await
+                .map_err(|err| http_error("failed to read blob", err))?;
+            Ok(chunk.map(|chunk| (chunk, response)))
+        });
+        Ok(Some(Box::pin(body)))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Self::Error> {
+        self.blob_request(Method::DELETE, key, "failed to delete blob")
+            .await?;
+        Ok(())
+    }
+
+    async fn metadata(&self, key: &str) -> Result<Option<FileMetadata>, Self::Error> {
+        let Some(response) = self
+            .blob_request(Method::HEAD, key, "failed to get blob properties")
+            .await?
+        else {
+            return Ok(None);
+        };
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+        Ok(Some(FileMetadata {
+            size: header("content-length")
+                .and_then(|size| size.parse().ok())
+                .unwrap_or_default(),
+            content_type: header("content-type").and_then(|content_type| content_type.parse().ok()),
+            modified: header("last-modified")
+                .and_then(|modified| httpdate::parse_http_date(modified).ok()),
+        }))
+    }
+}
+
+/// A managed identity access token and the time it expires at.
+struct AccessToken {
+    value: String,
+    expires_at: SystemTime,
+}
+
+impl AccessToken {
+    fn parse(body: &[u8]) -> Result<Self, StorageError> {
+        let json: serde_json::Value = serde_json::from_slice(body)
+            .map_err(|err| StorageError::backend("invalid managed identity token response", err))?;
+        let value = json
+            .get("access_token")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| StorageError::new("managed identity token response has no token"))?;
+        // IMDS reports `expires_on` as a string of Unix seconds.
+        let expires_on = json.get("expires_on").and_then(|expires_on| {
+            expires_on
+                .as_u64()
+                .or_else(|| expires_on.as_str()?.parse().ok())
+        });
+        Ok(Self {
+            value: value.to_owned(),
+            expires_at: expires_on
+                .map_or(UNIX_EPOCH, |secs| UNIX_EPOCH + Duration::from_secs(secs)),
+        })
+    }
+
+    fn is_fresh(&self) -> bool {
+        SystemTime::now() + TOKEN_REFRESH_MARGIN < self.expires_at
+    }
+}
+
+impl fmt::Debug for AccessToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessToken")
+            .field("value", &"<redacted>")
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+fn content_type(meta: &FileMeta) -> HeaderValue {
+    HeaderValue::from_str(&meta.content_type)
+        .unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream"))
+}
+
+/// Turns a non-success response into an error naming the service error code.
+async fn check_status(
+    context: &str,
+    response: reqwest::Response,
+) -> Result<reqwest::Response, StorageError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let code = response
+        .headers()
+        .get("x-ms-error-code")
+        .and_then(|code| code.to_str().ok())
+        .map(ToOwned::to_owned);
+    Err(StorageError::new(match code {
+        Some(code) => format!("{context}: {status} ({code})"),
+        None => format!("{context}: {status}"),
+    }))
+}
+
+fn http_error(context: &str, err: reqwest::Error) -> StorageError {
+    StorageError::backend(context, err)
+}
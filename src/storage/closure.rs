@@ -0,0 +1,106 @@
+use std::{fmt, future::Future};
+
+use bytes::Bytes;
+use futures::{channel::mpsc, future::Either, stream, SinkExt, StreamExt};
+
+use super::{BoxStream, FileMeta, StorageEngine, StoreContext};
+use crate::MulterError;
+
+/// Chunks buffered between the body and the closure.
+const CHANNEL_CAPACITY: usize = 4;
+
+/// Storage engine backed by an async closure.
+///
+/// The closure receives each file's [`FileMeta`] and body stream and returns
+/// a future resolving to any output and error type, so one-off backends need
+/// no [`StorageEngine`] impl. The stream is `'static`: chunks are forwarded
+/// to it as the closure reads them, and the body stops being read once the
+/// returned future completes. The stream and the future are `Send`, also
+/// with the `unsend` feature. When the body fails, the closure must remove
+/// whatever it already wrote, as [`StorageEngine::store`] requires. The
+/// closure does not see the request's [`StoreContext`] and
+/// [`StorageEngine::abort`] does nothing; backends that need either
+/// implement [`StorageEngine`].
+///
+/// ```rust
+/// use futures::StreamExt;
+/// use multigear::{FileMeta, FnStorage, Multer, StorageError};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let storage = FnStorage::new(|meta: FileMeta, mut body| async move {
+///     let mut size = 0;
+///     while let Some(chunk) = body.next().await {
+///         let chunk = chunk.map_err(StorageError::Stream)?;
+///         size += chunk.len();
+///     }
+///     Ok::<_, StorageError>((meta.field_name, size))
+/// });
+/// let multer = Multer::builder().storage(storage).build()?;
+/// # drop(multer);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct FnStorage<F> {
+    store: F,
+}
+
+impl<F> FnStorage<F> {
+    /// Creates a storage engine calling `store` for every file.
+    pub fn new<Fut>(store: F) -> Self
+    where
+        F: Fn(FileMeta, stream::BoxStream<'static, Result<Bytes, MulterError>>) -> Fut,
+    {
+        Self { store }
+    }
+}
+
+impl<F> fmt::Debug for FnStorage<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnStorage").finish_non_exhaustive()
+    }
+}
+
+/// Forwards `stream` to `sender` until either side ends.
+async fn forward(
+    mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    mut sender: mpsc::Sender<Result<Bytes, MulterError>>,
+) {
+    while let Some(item) = stream.next().await {
+        if sender.send(item).await.is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl<F, Fut, O, E> StorageEngine for FnStorage<F>
+where
+    F: Fn(FileMeta, stream::BoxStream<'static, Result<Bytes, MulterError>>) -> Fut
+        + Send
+        + Sync
+        + 'static,
+    Fut: Future<Output = Result<O, E>> + Send,
+    O: Send,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    type Output = O;
+    type Error = E;
+
+    async fn store(
+        &self,
+        meta: FileMeta,
+        _ctx: &StoreContext,
+        stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        let (sender, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+        let store = Box::pin((self.store)(meta, Box::pin(receiver)));
+        let forward = Box::pin(forward(stream, sender));
+
+        match futures::future::select(store, forward).await {
+            Either::Left((result, _)) => result,
+            Either::Right(((), store)) => store.await,
+        }
+    }
+}
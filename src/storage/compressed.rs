@@ -0,0 +1,389 @@
+use std::{
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use bytes::Bytes;
+use flate2::write::GzEncoder;
+use futures::StreamExt;
+
+use super::{BoxStream, FileMeta, StorageEngine, StoreContext};
+use crate::{MulterError, StorageError};
+
+/// Content types compressed by default, as exact types or `type/*` wildcards.
+pub const DEFAULT_COMPRESSIBLE_TYPES: &[&str] = &[
+    "text/*",
+    "application/json",
+    "application/x-ndjson",
+    "application/xml",
+    "application/javascript",
+    "image/svg+xml",
+    "image/bmp",
+];
+
+/// Compression format applied by [`CompressedStorage`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompressionAlgorithm {
+    /// gzip (RFC 1952), levels 0 through 9.
+    Gzip,
+    /// Zstandard, levels 1 through 22.
+    #[default]
+    Zstd,
+}
+
+impl CompressionAlgorithm {
+    /// Returns the `Content-Encoding` token for this format.
+    pub fn content_encoding(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    fn default_level(self) -> i32 {
+        match self {
+            Self::Gzip => 6,
+            Self::Zstd => 3,
+        }
+    }
+
+    fn levels(self) -> std::ops::RangeInclusive<i32> {
+        match self {
+            Self::Gzip => 0..=9,
+            Self::Zstd => 1..=22,
+        }
+    }
+}
+
+/// Builder for [`CompressedStorage`].
+#[derive(Debug, Clone)]
+pub struct CompressedStorageBuilder<S> {
+    inner: S,
+    algorithm: CompressionAlgorithm,
+    level: Option<i32>,
+    content_types: Vec<String>,
+}
+
+impl<S> CompressedStorageBuilder<S> {
+    /// Sets the compression format. Defaults to [`CompressionAlgorithm::Zstd`].
+    pub fn algorithm(mut self, algorithm: CompressionAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sets the compression level.
+    ///
+    /// Defaults to 6 for gzip and 3 for zstd.
+    pub fn level(mut self, level: i32) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Replaces the content types that are compressed.
+    ///
+    /// Entries are exact types such as `application/json` or wildcards such
+    /// as `text/*`. Defaults to [`DEFAULT_COMPRESSIBLE_TYPES`].
+    pub fn content_types<I, T>(mut self, content_types: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.content_types = content_types.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Adds one content type to the compressed set.
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_types.push(content_type.into());
+        self
+    }
+
+    /// Builds a validated compressing storage wrapper.
+    pub fn build(self) -> Result<CompressedStorage<S>, StorageError> {
+        let level = self.level.unwrap_or_else(|| self.algorithm.default_level());
+        if !self.algorithm.levels().contains(&level) {
+            let levels = self.algorithm.levels();
+            return Err(StorageError::new(format!(
+                "{} compression level must be between {} and {}",
+                self.algorithm.content_encoding(),
+                levels.start(),
+                levels.end()
+            )));
+        }
+        if let Some(pattern) = self
+            .content_types
+            .iter()
+            .find(|pattern| !pattern.contains('/'))
+        {
+            return Err(StorageError::new(format!(
+                "invalid compressible content type `{pattern}`"
+            )));
+        }
+
+        Ok(CompressedStorage {
+            inner: self.inner,
+            algorithm: self.algorithm,
+            level,
+            content_types: self.content_types,
+        })
+    }
+}
+
+/// Storage wrapper compressing compressible files before they reach the
+/// inner engine.
+///
+/// Files whose content type matches the allow list are streamed through a
+/// gzip or zstd encoder chunk by chunk; others pass through unchanged. The
+/// sniffed content type is checked when available, the declared one
+/// otherwise. The inner engine sees the compressed bytes, so sizes it
+/// reports are stored sizes; [`CompressedFile`] records both.
+///
+/// ```rust
+/// use multigear::{CompressedStorage, CompressionAlgorithm, MemoryStorage, Multer};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let storage = CompressedStorage::builder(MemoryStorage::new())
+///     .algorithm(CompressionAlgorithm::Gzip)
+///     .content_type("application/pdf")
+///     .build()?;
+/// let multer = Multer::builder().storage(storage).build()?;
+/// # drop(multer);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompressedStorage<S> {
+    inner: S,
+    algorithm: CompressionAlgorithm,
+    level: i32,
+    content_types: Vec<String>,
+}
+
+impl<S> CompressedStorage<S> {
+    /// Creates a builder wrapping `inner`.
+    pub fn builder(inner: S) -> CompressedStorageBuilder<S> {
+        CompressedStorageBuilder {
+            inner,
+            algorithm: CompressionAlgorithm::default(),
+            level: None,
+            content_types: DEFAULT_COMPRESSIBLE_TYPES
+                .iter()
+                .map(|content_type| (*content_type).to_owned())
+                .collect(),
+        }
+    }
+
+    /// Returns the wrapped storage engine.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Returns the compression format.
+    pub fn algorithm(&self) -> CompressionAlgorithm {
+        self.algorithm
+    }
+
+    fn compresses(&self, meta: &FileMeta) -> bool {
+        let content_type = meta
+            .sniffed_content_type
+            .as_deref()
+            .unwrap_or(&meta.content_type);
+        content_type.parse::<mime::Mime>().is_ok_and(|mime| {
+            self.content_types
+                .iter()
+                .any(|pattern| mime_matches_pattern(&mime, pattern))
+        })
+    }
+}
+
+/// Output of [`CompressedStorage`]: the inner engine's output plus sizes.
+#[derive(Debug, Clone)]
+pub struct CompressedFile<O> {
+    /// Output returned by the inner engine.
+    pub inner: O,
+    /// Compression applied, or `None` when the file was stored as uploaded.
+    pub encoding: Option<CompressionAlgorithm>,
+    /// Size of the uploaded file body in bytes.
+    pub original_size: u64,
+    /// Size of the bytes handed to the inner engine.
+    pub stored_size: u64,
+}
+
+impl<O> CompressedFile<O> {
+    /// Returns `true` when the stored bytes are compressed.
+    pub fn is_compressed(&self) -> bool {
+        self.encoding.is_some()
+    }
+
+    /// Consumes the wrapper and returns the inner engine's output.
+    pub fn into_inner(self) -> O {
+        self.inner
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl<S: StorageEngine> StorageEngine for CompressedStorage<S> {
+    type Output = CompressedFile<S::Output>;
+    type Error = S::Error;
+
+    async fn store(
+        &self,
+        mut meta: FileMeta,
+        ctx: &StoreContext,
+        stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        let original = Arc::new(AtomicU64::new(0));
+        let stored = Arc::new(AtomicU64::new(0));
+        let encoding = self.compresses(&meta).then_some(self.algorithm);
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            encoding = encoding.map_or("identity", CompressionAlgorithm::content_encoding),
+            "compressed storage: begin streaming store"
+        );
+
+        let stream = match encoding {
+            Some(algorithm) => {
+                // The compressed size is unknown until the body has ended.
+                meta.size_hint = None;
+                let encoder = Encoder::new(algorithm, self.level).map_err(compress_error);
+                compress(stream, encoder, Arc::clone(&original), Arc::clone(&stored))
+            }
+            None => {
+                let (original, stored) = (Arc::clone(&original), Arc::clone(&stored));
+                Box::pin(stream.inspect(move |chunk| {
+                    if let Ok(chunk) = chunk {
+                        original.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+                        stored.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+                    }
+                }))
+            }
+        };
+        let inner = self.inner.store(meta, ctx, stream).await?;
+
+        let original_size = original.load(Ordering::Relaxed);
+        let stored_size = stored.load(Ordering::Relaxed);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            original_size = original_size,
+            stored_size = stored_size,
+            "compressed storage: completed store"
+        );
+
+        Ok(CompressedFile {
+            inner,
+            encoding,
+            original_size,
+            stored_size,
+        })
+    }
+
+    async fn abort(&self, output: Self::Output) -> Result<(), Self::Error> {
+        self.inner.abort(output.inner).await
+    }
+}
+
+/// Streams `stream` through `encoder`, counting bytes on both sides.
+fn compress<'a>(
+    stream: BoxStream<'a, Result<Bytes, MulterError>>,
+    encoder: Result<Encoder, MulterError>,
+    original: Arc<AtomicU64>,
+    stored: Arc<AtomicU64>,
+) -> BoxStream<'a, Result<Bytes, MulterError>> {
+    let state = encoder.map(|encoder| (stream, encoder));
+    Box::pin(futures::stream::unfold(Some(state), move |state| {
+        let (original, stored) = (Arc::clone(&original), Arc::clone(&stored));
+        async move {
+            let (mut stream, mut encoder) = match state? {
+                Ok(state) => state,
+                Err(err) => return Some((Err(err), None)),
+            };
+            loop {
+                let output = match stream.next().await {
+                    Some(Ok(chunk)) => {
+                        original.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+                        match encoder.write(&chunk) {
+                            // Encoders buffer input; wait for actual output.
+                            Ok(output) if output.is_empty() => continue,
+                            Ok(output) => output,
+                            Err(err) => return Some((Err(compress_error(err)), None)),
+                        }
+                    }
+                    Some(Err(err)) => return Some((Err(err), None)),
+                    None => {
+                        let output = encoder.finish().map_err(compress_error);
+                        if let Ok(output) = &output {
+                            stored.fetch_add(output.len() as u64, Ordering::Relaxed);
+                        }
+                        return Some((output, None));
+                    }
+                };
+                stored.fetch_add(output.len() as u64, Ordering::Relaxed);
+                return Some((Ok(output), Some(Ok((stream, encoder)))));
+            }
+        }
+    }))
+}
+
+fn compress_error(err: io::Error) -> MulterError {
+    StorageError::io("compression failed", err).into()
+}
+
+/// Incremental encoder writing compressed output into a drained buffer.
+enum Encoder {
+    Gzip(GzEncoder<Vec<u8>>),
+    Zstd(zstd::stream::write::Encoder<'static, Vec<u8>>),
+}
+
+impl Encoder {
+    fn new(algorithm: CompressionAlgorithm, level: i32) -> io::Result<Self> {
+        Ok(match algorithm {
+            CompressionAlgorithm::Gzip => Self::Gzip(GzEncoder::new(
+                Vec::new(),
+                flate2::Compression::new(level.unsigned_abs()),
+            )),
+            CompressionAlgorithm::Zstd => {
+                Self::Zstd(zstd::stream::write::Encoder::new(Vec::new(), level)?)
+            }
+        })
+    }
+
+    /// Feeds one chunk and returns whatever compressed output it produced.
+    fn write(&mut self, chunk: &[u8]) -> io::Result<Bytes> {
+        let output = match self {
+            Self::Gzip(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.get_mut()
+            }
+            Self::Zstd(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.get_mut()
+            }
+        };
+        Ok(Bytes::from(std::mem::take(output)))
+    }
+
+    /// Ends the compressed stream and returns the remaining output.
+    fn finish(self) -> io::Result<Bytes> {
+        match self {
+            Self::Gzip(encoder) => encoder.finish(),
+            Self::Zstd(encoder) => encoder.finish(),
+        }
+        .map(Bytes::from)
+    }
+}
+
+fn mime_matches_pattern(mime: &mime::Mime, pattern: &str) -> bool {
+    if let Some((kind, subtype)) = pattern.split_once('/') {
+        if subtype == "*" {
+            return mime.type_().as_str().eq_ignore_ascii_case(kind);
+        }
+    }
+
+    mime.essence_str().eq_ignore_ascii_case(pattern)
+}
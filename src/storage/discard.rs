@@ -0,0 +1,157 @@
+use bytes::Bytes;
+use futures::StreamExt;
+use uuid::Uuid;
+
+use super::{BoxStream, FileMeta, StorageEngine, StoreContext, StoredFile};
+use crate::{MulterError, StorageError};
+
+/// Digest computed by [`DiscardStorage::digest`].
+#[cfg(feature = "checksum")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    /// MD5, as sent in `Content-MD5`.
+    Md5,
+    /// SHA-256.
+    Sha256,
+}
+
+#[cfg(feature = "checksum")]
+enum Hasher {
+    Md5(md5::Md5),
+    Sha256(sha2::Sha256),
+}
+
+#[cfg(feature = "checksum")]
+impl Hasher {
+    fn new(algorithm: DigestAlgorithm) -> Self {
+        use md5::Digest;
+
+        match algorithm {
+            DigestAlgorithm::Md5 => Self::Md5(md5::Md5::new()),
+            DigestAlgorithm::Sha256 => Self::Sha256(sha2::Sha256::new()),
+        }
+    }
+
+    fn update(&mut self, chunk: &[u8]) {
+        use md5::Digest;
+
+        match self {
+            Self::Md5(hasher) => hasher.update(chunk),
+            Self::Sha256(hasher) => hasher.update(chunk),
+        }
+    }
+
+    fn finish_hex(self) -> String {
+        use md5::Digest;
+
+        let digest = match self {
+            Self::Md5(hasher) => hasher.finalize().to_vec(),
+            Self::Sha256(hasher) => hasher.finalize().to_vec(),
+        };
+        digest.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+}
+
+/// Storage engine reading every file to the end and keeping nothing.
+///
+/// Limits, validators, and filters run as for any other engine, which makes
+/// it suited to pre-flight validation endpoints and load tests. Unlike
+/// [`NoopStorage`](super::NoopStorage), stores succeed: the returned
+/// [`StoredFile`] carries the file's size, no path, and a random storage
+/// key, or, with the `checksum` feature and [`DiscardStorage::digest`] set,
+/// the lowercase hex digest of the body.
+///
+/// ```rust
+/// use bytes::Bytes;
+/// use futures::stream;
+/// use multigear::{DiscardStorage, Multer, MulterError};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let multer = Multer::builder().storage(DiscardStorage::new()).build()?;
+/// let body = "--X\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n\r\nhello\r\n--X--\r\n";
+/// let output = multer
+///     .parse_and_store("X", stream::iter([Ok::<_, MulterError>(Bytes::from(body))]))
+///     .await?;
+///
+/// assert_eq!(output.stored_files[0].size, 5);
+/// assert_eq!(output.stored_files[0].path, None);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DiscardStorage {
+    #[cfg(feature = "checksum")]
+    digest: Option<DigestAlgorithm>,
+}
+
+impl DiscardStorage {
+    /// Creates a storage backend discarding every file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hashes each body and returns the hex digest as
+    /// [`StoredFile::storage_key`].
+    #[cfg(feature = "checksum")]
+    pub fn digest(mut self, algorithm: DigestAlgorithm) -> Self {
+        self.digest = Some(algorithm);
+        self
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngine for DiscardStorage {
+    type Output = StoredFile;
+    type Error = StorageError;
+
+    async fn store(
+        &self,
+        meta: FileMeta,
+        _ctx: &StoreContext,
+        mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        #[cfg(feature = "checksum")]
+        let mut hasher = self.digest.map(Hasher::new);
+
+        let mut size = 0u64;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(StorageError::Stream)?;
+            #[cfg(feature = "checksum")]
+            if let Some(hasher) = &mut hasher {
+                hasher.update(&chunk);
+            }
+            size = size.saturating_add(chunk.len() as u64);
+        }
+
+        #[cfg(feature = "checksum")]
+        let storage_key = hasher.map_or_else(|| Uuid::new_v4().to_string(), Hasher::finish_hex);
+        #[cfg(not(feature = "checksum"))]
+        let storage_key = Uuid::new_v4().to_string();
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            storage_key = storage_key.as_str(),
+            size = size,
+            "discard storage: consumed file"
+        );
+
+        Ok(StoredFile {
+            storage_key,
+            field_name: meta.field_name,
+            file_name: meta.file_name,
+            content_type: meta
+                .content_type
+                .parse()
+                .unwrap_or(mime::APPLICATION_OCTET_STREAM),
+            sniffed_content_type: meta
+                .sniffed_content_type
+                .as_deref()
+                .and_then(|sniffed| sniffed.parse().ok()),
+            size,
+            path: None,
+        })
+    }
+}
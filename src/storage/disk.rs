@@ -7,9 +7,13 @@ use std::{
 use bytes::Bytes;
 use futures::StreamExt;
 use tokio::io::AsyncWriteExt;
+use tokio_util::io::ReaderStream;
 use uuid::Uuid;
 
-use super::{BoxStream, FileMeta, StorageEngine, StoredFile};
+use super::{
+    BoxStream, FileMeta, FileMetadata, FilenamePolicy, StorageEngine, StorageEngineExt,
+    StoreContext, StoredFile,
+};
 use crate::{MulterError, StorageError};
 
 type CustomFilenameFn = dyn Fn(String) -> String + Send + Sync;
@@ -26,6 +30,23 @@ pub enum FilenameStrategy {
     Custom(Arc<CustomFilenameFn>),
 }
 
+impl FilenameStrategy {
+    /// Derives a stored name from an uploaded filename, sanitized by `policy`.
+    pub(super) fn output_name(&self, file_name: Option<&str>, policy: &FilenamePolicy) -> String {
+        let input_name = file_name
+            .map(ToOwned::to_owned)
+            .unwrap_or_else(random_basename);
+
+        let candidate = match self {
+            Self::Keep => input_name,
+            Self::Random => random_basename(),
+            Self::Custom(transform) => transform(input_name),
+        };
+
+        policy.apply(&candidate)
+    }
+}
+
 impl fmt::Debug for FilenameStrategy {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -41,6 +62,7 @@ impl fmt::Debug for FilenameStrategy {
 pub struct DiskStorageBuilder {
     root: PathBuf,
     strategy: FilenameStrategy,
+    policy: FilenamePolicy,
     filter: Option<Arc<FileFilterFn>>,
 }
 
@@ -49,6 +71,7 @@ impl fmt::Debug for DiskStorageBuilder {
         f.debug_struct("DiskStorageBuilder")
             .field("root", &self.root)
             .field("strategy", &self.strategy)
+            .field("policy", &self.policy)
             .field("filter", &self.filter.as_ref().map(|_| "<fn>"))
             .finish()
     }
@@ -86,6 +109,14 @@ impl DiskStorageBuilder {
         self
     }
 
+    /// Sets the policy sanitizing output filenames.
+    ///
+    /// Applies to every [`FilenameStrategy`], including custom transforms.
+    pub fn filename_policy(mut self, policy: FilenamePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
     /// Sets an optional filter to accept or reject files before persistence.
     pub fn filter<F>(mut self, filter: F) -> Self
     where
@@ -104,6 +135,7 @@ impl DiskStorageBuilder {
         Ok(DiskStorage {
             root: self.root,
             strategy: self.strategy,
+            policy: self.policy,
             filter: self.filter,
         })
     }
@@ -114,6 +146,7 @@ impl Default for DiskStorageBuilder {
         Self {
             root: std::env::temp_dir().join("multigear"),
             strategy: FilenameStrategy::Random,
+            policy: FilenamePolicy::default(),
             filter: None,
         }
     }
@@ -124,6 +157,7 @@ impl Default for DiskStorageBuilder {
 pub struct DiskStorage {
     root: PathBuf,
     strategy: FilenameStrategy,
+    policy: FilenamePolicy,
     filter: Option<Arc<FileFilterFn>>,
 }
 
@@ -132,6 +166,7 @@ impl fmt::Debug for DiskStorage {
         f.debug_struct("DiskStorage")
             .field("root", &self.root)
             .field("strategy", &self.strategy)
+            .field("policy", &self.policy)
             .field("filter", &self.filter.as_ref().map(|_| "<fn>"))
             .finish()
     }
@@ -144,36 +179,40 @@ impl DiskStorage {
     }
 
     fn choose_output_name(&self, file_name: Option<&str>) -> String {
-        let input_name = file_name
-            .map(ToOwned::to_owned)
-            .unwrap_or_else(random_basename);
-
-        let candidate = match &self.strategy {
-            FilenameStrategy::Keep => input_name,
-            FilenameStrategy::Random => random_basename(),
-            FilenameStrategy::Custom(transform) => transform(input_name),
-        };
-
-        sanitize_filename(&candidate)
+        self.strategy.output_name(file_name, &self.policy)
     }
 
     fn should_store(&self, meta: &FileMeta) -> bool {
         self.filter.as_ref().map_or(true, |filter| filter(meta))
     }
+
+    /// Returns the path a storage key names, when it is a file directly
+    /// inside the destination directory.
+    ///
+    /// Keys may come from clients, so anything else is treated as missing.
+    fn key_path(&self, key: &str) -> Option<PathBuf> {
+        let path = Path::new(key);
+        (path.file_name().is_some() && path.parent() == Some(self.root.as_path()))
+            .then(|| path.to_path_buf())
+    }
 }
 
-#[async_trait::async_trait]
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
 impl StorageEngine for DiskStorage {
     type Output = StoredFile;
     type Error = StorageError;
 
     async fn store(
         &self,
-        field_name: &str,
-        file_name: Option<&str>,
-        content_type: &str,
+        meta: FileMeta,
+        _ctx: &StoreContext,
         mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
     ) -> Result<Self::Output, Self::Error> {
+        let field_name = meta.field_name.as_str();
+        let file_name = meta.file_name.as_deref();
+        let content_type = meta.content_type.as_str();
+
         #[cfg(feature = "tracing")]
         tracing::debug!(
             field_name = field_name,
@@ -183,12 +222,7 @@ impl StorageEngine for DiskStorage {
             "disk storage: begin streaming store"
         );
 
-        let accepted_meta = FileMeta {
-            field_name: field_name.to_owned(),
-            file_name: file_name.map(ToOwned::to_owned),
-            content_type: content_type.to_owned(),
-        };
-        if !self.should_store(&accepted_meta) {
+        if !self.should_store(&meta) {
             #[cfg(feature = "tracing")]
             tracing::warn!(
                 field_name = field_name,
@@ -200,16 +234,16 @@ impl StorageEngine for DiskStorage {
             )));
         }
 
-        tokio::fs::create_dir_all(&self.root).await.map_err(|err| {
-            StorageError::new(format!("failed to create storage directory: {err}"))
-        })?;
+        tokio::fs::create_dir_all(&self.root)
+            .await
+            .map_err(|err| StorageError::io("failed to create storage directory", err))?;
 
         let file_basename = self.choose_output_name(file_name);
 
         let mut output_path = self.root.join(file_basename);
         if tokio::fs::try_exists(&output_path)
             .await
-            .map_err(|err| StorageError::new(format!("failed to inspect output path: {err}")))?
+            .map_err(|err| StorageError::io("failed to inspect output path", err))?
         {
             #[cfg(feature = "tracing")]
             tracing::debug!(
@@ -219,24 +253,27 @@ impl StorageEngine for DiskStorage {
             output_path = with_collision_suffix(&output_path);
         }
 
+        // Declared before the file so the handle is closed before cleanup runs.
+        let mut partial = PartialFileGuard::default();
         let mut file = tokio::fs::File::create(&output_path)
             .await
-            .map_err(|err| StorageError::new(format!("failed to create output file: {err}")))?;
+            .map_err(|err| StorageError::io("failed to create output file", err))?;
+        partial.arm(output_path.clone());
 
         let mut written = 0u64;
 
         while let Some(chunk) = stream.next().await {
-            let bytes =
-                chunk.map_err(|err| StorageError::new(format!("stream read failed: {err}")))?;
+            let bytes = chunk.map_err(StorageError::Stream)?;
             file.write_all(&bytes)
                 .await
-                .map_err(|err| StorageError::new(format!("failed to write output file: {err}")))?;
+                .map_err(|err| StorageError::io("failed to write output file", err))?;
             written = written.saturating_add(bytes.len() as u64);
         }
 
         file.flush()
             .await
-            .map_err(|err| StorageError::new(format!("failed to flush output file: {err}")))?;
+            .map_err(|err| StorageError::io("failed to flush output file", err))?;
+        partial.disarm();
 
         let storage_key = output_path.to_string_lossy().into_owned();
         let parsed_content_type = content_type
@@ -254,10 +291,95 @@ impl StorageEngine for DiskStorage {
             field_name: field_name.to_owned(),
             file_name: file_name.map(ToOwned::to_owned),
             content_type: parsed_content_type,
+            sniffed_content_type: meta
+                .sniffed_content_type
+                .as_deref()
+                .and_then(|sniffed| sniffed.parse().ok()),
             size: written,
             path: Some(output_path),
         })
     }
+
+    async fn abort(&self, output: Self::Output) -> Result<(), Self::Error> {
+        self.delete(&output.storage_key).await
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngineExt for DiskStorage {
+    async fn open(
+        &self,
+        key: &str,
+    ) -> Result<Option<BoxStream<'static, Result<Bytes, Self::Error>>>, Self::Error> {
+        let Some(path) = self.key_path(key) else {
+            return Ok(None);
+        };
+        match tokio::fs::File::open(&path).await {
+            Ok(file) => Ok(Some(Box::pin(ReaderStream::new(file).map(|chunk| {
+                chunk.map_err(|err| StorageError::io("failed to read stored file", err))
+            })))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(StorageError::io("failed to open stored file", err)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Self::Error> {
+        let Some(path) = self.key_path(key) else {
+            return Ok(());
+        };
+        match tokio::fs::remove_file(&path).await {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                Err(StorageError::io("failed to delete stored file", err))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn metadata(&self, key: &str) -> Result<Option<FileMetadata>, Self::Error> {
+        let Some(path) = self.key_path(key) else {
+            return Ok(None);
+        };
+        match tokio::fs::metadata(&path).await {
+            Ok(metadata) if metadata.is_file() => Ok(Some(FileMetadata {
+                size: metadata.len(),
+                content_type: mime_guess::from_path(&path).first(),
+                modified: metadata.modified().ok(),
+            })),
+            Ok(_) => Ok(None),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(StorageError::io("failed to inspect stored file", err)),
+        }
+    }
+}
+
+/// Removes a partially written file unless the store completed.
+///
+/// Covers both early error returns and the store future being dropped, for
+/// example when a request is cancelled mid-upload.
+#[derive(Debug, Default)]
+pub(crate) struct PartialFileGuard {
+    path: Option<PathBuf>,
+}
+
+impl PartialFileGuard {
+    pub(crate) fn arm(&mut self, path: PathBuf) {
+        self.path = Some(path);
+    }
+
+    pub(crate) fn disarm(&mut self) {
+        self.path = None;
+    }
+}
+
+impl Drop for PartialFileGuard {
+    fn drop(&mut self) {
+        if let Some(path) = self.path.take() {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(path = %path.display(), "disk storage: removing partial file");
+            let _ = std::fs::remove_file(path);
+        }
+    }
 }
 
 fn random_basename() -> String {
@@ -279,29 +401,8 @@ fn with_collision_suffix(path: &Path) -> PathBuf {
 }
 
 /// Sanitizes filenames to prevent traversal and unsafe path characters.
+///
+/// Equivalent to applying the default [`FilenamePolicy`].
 pub fn sanitize_filename(input: &str) -> String {
-    // Handle both separator styles so traversal-like names are normalized the
-    // same way on every OS (Windows and Unix path parsing differ here).
-    let base = input
-        .rsplit(['/', '\\'])
-        .find(|segment| !segment.is_empty())
-        .unwrap_or("file");
-
-    let mut sanitized: String = base
-        .chars()
-        .map(|ch| {
-            if ch.is_ascii_alphanumeric() || matches!(ch, '.' | '_' | '-') {
-                ch
-            } else {
-                '_'
-            }
-        })
-        .collect();
-
-    sanitized = sanitized.trim_matches(['.', ' ']).to_owned();
-    if sanitized.is_empty() || sanitized == "." || sanitized == ".." {
-        return "file".to_owned();
-    }
-
-    sanitized
+    FilenamePolicy::default().apply(input)
 }
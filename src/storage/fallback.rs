@@ -0,0 +1,203 @@
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+
+use super::{BoxStream, FileMeta, StorageEngine, StoreContext};
+use crate::{MulterError, StorageError};
+
+/// Default number of body bytes kept for replaying a file on the secondary.
+pub const DEFAULT_REPLAY_BUFFER: usize = 8 * 1024 * 1024;
+
+/// Storage wrapper retrying a file on a secondary backend when the primary
+/// fails.
+///
+/// Chunks read by the primary are kept in memory so the secondary can be fed
+/// the whole file: the buffered prefix is replayed, then the rest of the body
+/// streams straight through. Buffering stops after
+/// [`replay_buffer`](Self::replay_buffer) bytes; a primary failing past that
+/// point fails the store, as does a failure of the body stream itself.
+///
+/// ```rust
+/// use multigear::{DiskStorage, FallbackStorage, MemoryStorage, Multer};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let disk = DiskStorage::builder().destination("/var/spool/uploads").build()?;
+/// let storage = FallbackStorage::new(MemoryStorage::new(), disk).replay_buffer(16 * 1024 * 1024);
+/// let multer = Multer::builder().storage(storage).build()?;
+/// # drop(multer);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FallbackStorage<P, S> {
+    primary: P,
+    secondary: S,
+    replay_buffer: usize,
+}
+
+impl<P, S> FallbackStorage<P, S> {
+    /// Creates a wrapper storing files in `primary`, or in `secondary` when
+    /// `primary` fails.
+    pub fn new(primary: P, secondary: S) -> Self {
+        Self {
+            primary,
+            secondary,
+            replay_buffer: DEFAULT_REPLAY_BUFFER,
+        }
+    }
+
+    /// Sets how many body bytes are kept for replaying on the secondary.
+    ///
+    /// Defaults to [`DEFAULT_REPLAY_BUFFER`]. Files up to this size can always
+    /// fall back; larger ones only when the primary fails before reading past
+    /// it.
+    pub fn replay_buffer(mut self, bytes: usize) -> Self {
+        self.replay_buffer = bytes;
+        self
+    }
+
+    /// Returns the primary backend.
+    pub fn primary(&self) -> &P {
+        &self.primary
+    }
+
+    /// Returns the secondary backend.
+    pub fn secondary(&self) -> &S {
+        &self.secondary
+    }
+}
+
+/// Output of a [`FallbackStorage`], tagged with the backend that stored it.
+#[derive(Debug, Clone)]
+pub enum FallbackFile<P, S> {
+    /// Stored by the primary backend.
+    Primary(P),
+    /// Stored by the secondary backend after the primary failed.
+    Secondary(S),
+}
+
+impl<P, S> FallbackFile<P, S> {
+    /// Returns `true` when the file was stored by the secondary backend.
+    pub fn is_fallback(&self) -> bool {
+        matches!(self, Self::Secondary(_))
+    }
+}
+
+impl<T> FallbackFile<T, T> {
+    /// Returns the output of whichever backend stored the file.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Primary(output) | Self::Secondary(output) => output,
+        }
+    }
+}
+
+/// Body chunks read by the primary, kept for replay.
+#[derive(Debug, Default)]
+struct Replay {
+    chunks: Vec<Bytes>,
+    buffered: usize,
+    overflowed: bool,
+    body_failed: bool,
+}
+
+impl Replay {
+    fn record(&mut self, item: &Result<Bytes, MulterError>, limit: usize) {
+        match item {
+            Ok(_) if self.overflowed => {}
+            Ok(chunk) if self.buffered + chunk.len() > limit => {
+                self.overflowed = true;
+                self.chunks = Vec::new();
+            }
+            Ok(chunk) => {
+                self.buffered += chunk.len();
+                self.chunks.push(chunk.clone());
+            }
+            Err(_) => self.body_failed = true,
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl<P: StorageEngine, S: StorageEngine> StorageEngine for FallbackStorage<P, S> {
+    type Output = FallbackFile<P::Output, S::Output>;
+    type Error = StorageError;
+
+    async fn store(
+        &self,
+        meta: FileMeta,
+        ctx: &StoreContext,
+        mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        let replay = Arc::new(Mutex::new(Replay::default()));
+        let recorder = Arc::clone(&replay);
+        let limit = self.replay_buffer;
+        let recorded = stream.by_ref().inspect(move |item| {
+            recorder
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .record(item, limit);
+        });
+
+        let primary_err = match self
+            .primary
+            .store(meta.clone(), ctx, Box::pin(recorded))
+            .await
+        {
+            Ok(output) => return Ok(FallbackFile::Primary(output)),
+            Err(err) => err,
+        };
+
+        let replay = std::mem::take(
+            &mut *replay
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        );
+        if replay.body_failed || replay.overflowed {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                field_name = meta.field_name.as_str(),
+                error = %primary_err,
+                "fallback storage: primary failed and the body cannot be replayed"
+            );
+            return Err(StorageError::backend("primary storage failed", primary_err));
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            error = %primary_err,
+            replayed = replay.buffered,
+            "fallback storage: primary failed, retrying on secondary"
+        );
+
+        let replayed = stream::iter(replay.chunks.into_iter().map(Ok)).chain(stream);
+        self.secondary
+            .store(meta, ctx, Box::pin(replayed))
+            .await
+            .map(FallbackFile::Secondary)
+            .map_err(|err| {
+                StorageError::backend(
+                    format!("primary storage failed ({primary_err}); secondary storage failed"),
+                    err,
+                )
+            })
+    }
+
+    async fn abort(&self, output: Self::Output) -> Result<(), Self::Error> {
+        match output {
+            FallbackFile::Primary(output) => self
+                .primary
+                .abort(output)
+                .await
+                .map_err(|err| StorageError::backend("primary storage abort failed", err)),
+            FallbackFile::Secondary(output) => self
+                .secondary
+                .abort(output)
+                .await
+                .map_err(|err| StorageError::backend("secondary storage abort failed", err)),
+        }
+    }
+}
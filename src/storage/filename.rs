@@ -0,0 +1,252 @@
+//! Configurable sanitization of client-supplied filenames.
+
+use std::{fmt, sync::Arc};
+
+use unicode_normalization::UnicodeNormalization;
+
+type FilenameRuleFn = dyn Fn(String) -> String + Send + Sync;
+
+/// Device names Windows reserves regardless of extension.
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM0", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7",
+    "COM8", "COM9", "COM¹", "COM²", "COM³", "LPT0", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6",
+    "LPT7", "LPT8", "LPT9", "LPT¹", "LPT²", "LPT³",
+];
+
+/// Rules turning a client-supplied filename into a safe basename.
+///
+/// Every policy keeps only the final path segment, replaces characters
+/// outside its allowed set with `_`, trims leading and trailing dots and
+/// spaces, and falls back to `file` for empty results, so output never
+/// traverses directories. On top of that it can:
+///
+/// - normalize to Unicode NFC, so visually identical names compare equal;
+/// - map homoglyphs (Cyrillic and Greek lookalikes, fullwidth forms) to ASCII
+///   and drop invisible and bidirectional control characters;
+/// - collapse double extensions, turning `invoice.pdf.exe` into `invoice_pdf.exe`;
+/// - escape Windows reserved device names such as `CON` or `nul.txt` with a
+///   leading `_`;
+/// - run custom rules, before the character filter so they cannot reintroduce
+///   unsafe characters.
+///
+/// The default policy allows ASCII letters, digits, `.`, `_` and `-`, and
+/// escapes reserved names; [`FilenamePolicy::strict`] enables every rule.
+///
+/// ```rust
+/// use multigear::FilenamePolicy;
+///
+/// let policy = FilenamePolicy::strict().rule(|name| name.to_lowercase());
+/// assert_eq!(policy.apply("../Invoice.PDF.exe"), "invoice_pdf.exe");
+/// assert_eq!(policy.apply("C:\\temp\\CON.txt"), "_con.txt");
+///
+/// let unicode = FilenamePolicy::new().allow_unicode(true).normalize_nfc(true);
+/// assert_eq!(unicode.apply("Cafe\u{301} menu.txt"), "Café_menu.txt");
+/// ```
+#[derive(Clone)]
+pub struct FilenamePolicy {
+    allow_unicode: bool,
+    normalize_nfc: bool,
+    strip_homoglyphs: bool,
+    collapse_double_extensions: bool,
+    escape_reserved_names: bool,
+    rules: Vec<Arc<FilenameRuleFn>>,
+}
+
+impl Default for FilenamePolicy {
+    fn default() -> Self {
+        Self {
+            allow_unicode: false,
+            normalize_nfc: false,
+            strip_homoglyphs: false,
+            collapse_double_extensions: false,
+            escape_reserved_names: true,
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Debug for FilenamePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FilenamePolicy")
+            .field("allow_unicode", &self.allow_unicode)
+            .field("normalize_nfc", &self.normalize_nfc)
+            .field("strip_homoglyphs", &self.strip_homoglyphs)
+            .field(
+                "collapse_double_extensions",
+                &self.collapse_double_extensions,
+            )
+            .field("escape_reserved_names", &self.escape_reserved_names)
+            .field("rules", &self.rules.len())
+            .finish()
+    }
+}
+
+impl FilenamePolicy {
+    /// Creates the default policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an ASCII-only policy with every built-in rule enabled.
+    pub fn strict() -> Self {
+        Self {
+            allow_unicode: false,
+            normalize_nfc: true,
+            strip_homoglyphs: true,
+            collapse_double_extensions: true,
+            escape_reserved_names: true,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Keeps non-ASCII letters and digits instead of replacing them with `_`.
+    pub fn allow_unicode(mut self, allow: bool) -> Self {
+        self.allow_unicode = allow;
+        self
+    }
+
+    /// Normalizes names to Unicode Normalization Form C.
+    pub fn normalize_nfc(mut self, normalize: bool) -> Self {
+        self.normalize_nfc = normalize;
+        self
+    }
+
+    /// Maps ASCII lookalike characters to ASCII and drops invisible characters.
+    pub fn strip_homoglyphs(mut self, strip: bool) -> Self {
+        self.strip_homoglyphs = strip;
+        self
+    }
+
+    /// Keeps only the final extension, replacing earlier dots with `_`.
+    pub fn collapse_double_extensions(mut self, collapse: bool) -> Self {
+        self.collapse_double_extensions = collapse;
+        self
+    }
+
+    /// Prefixes Windows reserved device names with `_`.
+    pub fn escape_reserved_names(mut self, escape: bool) -> Self {
+        self.escape_reserved_names = escape;
+        self
+    }
+
+    /// Appends a custom rule, run in registration order on the normalized
+    /// name before the character filter.
+    pub fn rule<F>(mut self, rule: F) -> Self
+    where
+        F: Fn(String) -> String + Send + Sync + 'static,
+    {
+        self.rules.push(Arc::new(rule));
+        self
+    }
+
+    /// Applies the policy to a client-supplied filename.
+    pub fn apply(&self, input: &str) -> String {
+        let mut name: String = if self.normalize_nfc {
+            input.nfc().collect()
+        } else {
+            input.to_owned()
+        };
+        if self.strip_homoglyphs {
+            name = name.chars().filter_map(ascii_lookalike).collect();
+        }
+        for rule in &self.rules {
+            name = rule(name);
+        }
+
+        // Handle both separator styles so traversal-like names are normalized the
+        // same way on every OS (Windows and Unix path parsing differ here).
+        let base = name
+            .rsplit(['/', '\\'])
+            .find(|segment| !segment.is_empty())
+            .unwrap_or("file");
+
+        let sanitized: String = base
+            .chars()
+            .map(|ch| {
+                let allowed = if self.allow_unicode {
+                    ch.is_alphanumeric()
+                } else {
+                    ch.is_ascii_alphanumeric()
+                };
+                if allowed || matches!(ch, '.' | '_' | '-') {
+                    ch
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        let mut sanitized = sanitized.trim_matches(['.', ' ']).to_owned();
+        if sanitized.is_empty() {
+            return "file".to_owned();
+        }
+
+        if self.collapse_double_extensions {
+            if let Some(dot) = sanitized.rfind('.') {
+                let stem = sanitized[..dot].replace('.', "_");
+                sanitized = format!("{stem}{}", &sanitized[dot..]);
+            }
+        }
+
+        if self.escape_reserved_names && is_windows_reserved(&sanitized) {
+            sanitized.insert(0, '_');
+        }
+
+        sanitized
+    }
+}
+
+/// Returns `true` when the part before the first dot is a reserved device name.
+fn is_windows_reserved(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or(name);
+    WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|reserved| stem.eq_ignore_ascii_case(reserved))
+}
+
+/// Maps a character to its ASCII lookalike, or drops it when invisible.
+fn ascii_lookalike(ch: char) -> Option<char> {
+    let mapped = match ch {
+        // Zero-width, joiner, bidirectional control, and soft hyphen characters.
+        '\u{200B}'..='\u{200F}'
+        | '\u{202A}'..='\u{202E}'
+        | '\u{2060}'..='\u{2064}'
+        | '\u{2066}'..='\u{2069}'
+        | '\u{FEFF}'
+        | '\u{00AD}' => return None,
+        // Fullwidth ASCII forms.
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(ch as u32 - 0xFEE0).unwrap_or(ch),
+        '\u{2024}' => '.',
+        '\u{2044}' | '\u{2215}' => '/',
+        'а' => 'a',
+        'е' => 'e',
+        'о' | 'ο' => 'o',
+        'р' => 'p',
+        'с' => 'c',
+        'у' => 'y',
+        'х' => 'x',
+        'і' | 'ι' => 'i',
+        'ј' => 'j',
+        'ѕ' => 's',
+        'ԁ' => 'd',
+        'ν' => 'v',
+        'А' | 'Α' => 'A',
+        'В' | 'Β' => 'B',
+        'Е' | 'Ε' => 'E',
+        'Ζ' => 'Z',
+        'Н' | 'Η' => 'H',
+        'І' | 'Ι' => 'I',
+        'Ј' => 'J',
+        'К' | 'Κ' => 'K',
+        'М' | 'Μ' => 'M',
+        'Ν' => 'N',
+        'О' | 'Ο' => 'O',
+        'Р' | 'Ρ' => 'P',
+        'Ѕ' => 'S',
+        'Т' | 'Τ' => 'T',
+        'Υ' => 'Y',
+        'Х' | 'Χ' => 'X',
+        _ => ch,
+    };
+    Some(mapped)
+}
@@ -0,0 +1,301 @@
+use bytes::Bytes;
+use futures::{AsyncReadExt, AsyncWriteExt, StreamExt};
+use mongodb::{
+    bson::{doc, oid::ObjectId, Bson, Document},
+    error::{ErrorKind, GridFsErrorKind},
+    gridfs::{GridFsBucket, GridFsUploadStream},
+};
+
+use super::{
+    BoxStream, FileMeta, FileMetadata, FilenamePolicy, FilenameStrategy, StorageEngine,
+    StorageEngineExt, StoreContext, StoredFile,
+};
+
+/// Bytes read from a download stream per chunk of an opened file.
+const READ_CHUNK_SIZE: usize = 256 * 1024;
+use crate::{MulterError, StorageError};
+
+/// Builder for [`GridFsStorage`].
+#[derive(Debug, Clone)]
+pub struct GridFsStorageBuilder {
+    bucket: GridFsBucket,
+    chunk_size: Option<u32>,
+    metadata: Document,
+    strategy: FilenameStrategy,
+    policy: FilenamePolicy,
+}
+
+impl GridFsStorageBuilder {
+    /// Sets the GridFS chunk size for stored files, overriding the bucket's.
+    pub fn chunk_size(mut self, chunk_size: u32) -> Self {
+        self.chunk_size = Some(chunk_size);
+        self
+    }
+
+    /// Adds fields merged into the `metadata` document of every stored file.
+    ///
+    /// The multipart fields `fieldName`, `originalName`, and `contentType`
+    /// take precedence over fields with the same name.
+    pub fn metadata(mut self, metadata: Document) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Sets how GridFS filenames are derived from uploaded filenames.
+    ///
+    /// Defaults to [`FilenameStrategy::Random`]. GridFS filenames need not be
+    /// unique; files are addressed by their id.
+    pub fn filename(mut self, strategy: FilenameStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets the policy sanitizing GridFS filenames.
+    pub fn filename_policy(mut self, policy: FilenamePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Builds a validated GridFS storage backend.
+    pub fn build(self) -> Result<GridFsStorage, StorageError> {
+        if self.chunk_size == Some(0) {
+            return Err(StorageError::new(
+                "gridfs storage chunk size must be greater than zero",
+            ));
+        }
+
+        Ok(GridFsStorage {
+            bucket: self.bucket,
+            chunk_size: self.chunk_size,
+            metadata: self.metadata,
+            strategy: self.strategy,
+            policy: self.policy,
+        })
+    }
+}
+
+/// Storage engine streaming files into a MongoDB GridFS bucket.
+///
+/// Each file document carries the part's metadata as `metadata.fieldName`,
+/// `metadata.originalName`, and `metadata.contentType`, the layout
+/// `multer-gridfs-storage` users query by. [`StoredFile::storage_key`] is the
+/// file id, as a hex `ObjectId`. Chunks of a failed or cancelled upload are
+/// deleted.
+///
+/// ```rust
+/// use multigear::{GridFsStorage, Multer};
+///
+/// # fn run(db: mongodb::Database) -> Result<(), Box<dyn std::error::Error>> {
+/// let storage = GridFsStorage::builder(db.gridfs_bucket(None))
+///     .metadata(mongodb::bson::doc! { "app": "avatars" })
+///     .build()?;
+/// let multer = Multer::builder().storage(storage).build()?;
+/// # drop(multer);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GridFsStorage {
+    bucket: GridFsBucket,
+    chunk_size: Option<u32>,
+    metadata: Document,
+    strategy: FilenameStrategy,
+    policy: FilenamePolicy,
+}
+
+impl GridFsStorage {
+    /// Creates a builder storing files in `bucket`.
+    pub fn builder(bucket: GridFsBucket) -> GridFsStorageBuilder {
+        GridFsStorageBuilder {
+            bucket,
+            chunk_size: None,
+            metadata: Document::new(),
+            strategy: FilenameStrategy::Random,
+            policy: FilenamePolicy::default(),
+        }
+    }
+
+    /// Returns the bucket files are stored in.
+    pub fn bucket(&self) -> &GridFsBucket {
+        &self.bucket
+    }
+
+    fn file_metadata(&self, meta: &FileMeta) -> Document {
+        let mut metadata = self.metadata.clone();
+        metadata.insert("fieldName", &meta.field_name);
+        metadata.insert(
+            "originalName",
+            meta.file_name
+                .as_deref()
+                .map_or(Bson::Null, |file_name| Bson::String(file_name.to_owned())),
+        );
+        metadata.insert("contentType", &meta.content_type);
+        metadata
+    }
+
+    async fn write_body(
+        upload: &mut GridFsUploadStream,
+        stream: &mut BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<u64, StorageError> {
+        let mut size = 0u64;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(StorageError::Stream)?;
+            upload
+                .write_all(&chunk)
+                .await
+                .map_err(|err| StorageError::io("failed to write chunk", err))?;
+            size = size.saturating_add(chunk.len() as u64);
+        }
+        upload
+            .close()
+            .await
+            .map_err(|err| StorageError::io("failed to close upload", err))?;
+        Ok(size)
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngine for GridFsStorage {
+    type Output = StoredFile;
+    type Error = StorageError;
+
+    async fn store(
+        &self,
+        meta: FileMeta,
+        _ctx: &StoreContext,
+        mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        let filename = self
+            .strategy
+            .output_name(meta.file_name.as_deref(), &self.policy);
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            file_name = meta.file_name.as_deref().unwrap_or("<none>"),
+            gridfs_filename = filename.as_str(),
+            "gridfs storage: begin streaming store"
+        );
+
+        let mut open = self
+            .bucket
+            .open_upload_stream(&filename)
+            .metadata(self.file_metadata(&meta));
+        if let Some(chunk_size) = self.chunk_size {
+            open = open.chunk_size_bytes(chunk_size);
+        }
+        let mut upload = open
+            .await
+            .map_err(|err| StorageError::backend("failed to open upload", err))?;
+        let id = match upload.id() {
+            Bson::ObjectId(id) => id.to_hex(),
+            other => other.to_string(),
+        };
+
+        let size = match Self::write_body(&mut upload, &mut stream).await {
+            Ok(size) => size,
+            Err(err) => {
+                // Dropping the stream would also delete the chunks, but in
+                // a detached task; abort so cleanup finishes before returning.
+                let _ = upload.abort().await;
+                return Err(err);
+            }
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            id = id.as_str(),
+            size = size,
+            "gridfs storage: completed store"
+        );
+
+        Ok(StoredFile {
+            storage_key: id,
+            field_name: meta.field_name,
+            file_name: meta.file_name,
+            content_type: meta
+                .content_type
+                .parse()
+                .unwrap_or(mime::APPLICATION_OCTET_STREAM),
+            sniffed_content_type: meta
+                .sniffed_content_type
+                .as_deref()
+                .and_then(|sniffed| sniffed.parse().ok()),
+            size,
+            path: None,
+        })
+    }
+
+    async fn abort(&self, output: Self::Output) -> Result<(), Self::Error> {
+        self.delete(&output.storage_key).await
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngineExt for GridFsStorage {
+    async fn open(
+        &self,
+        key: &str,
+    ) -> Result<Option<BoxStream<'static, Result<Bytes, Self::Error>>>, Self::Error> {
+        let Ok(id) = ObjectId::parse_str(key) else {
+            return Ok(None);
+        };
+        let download = match self.bucket.open_download_stream(Bson::ObjectId(id)).await {
+            Ok(download) => download,
+            Err(err) if is_file_not_found(&err) => return Ok(None),
+            Err(err) => return Err(StorageError::backend("failed to open download", err)),
+        };
+        let body = futures::stream::try_unfold(download, |mut download| async move {
+            let mut chunk = vec![0; READ_CHUNK_SIZE];
+            let read = download
+                .read(&mut chunk)
+                .await
+                .map_err(|err| StorageError::io("failed to read chunk", err))?;
+            chunk.truncate(read);
+            Ok((read > 0).then(|| (Bytes::from(chunk), download)))
+        });
+        Ok(Some(Box::pin(body)))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Self::Error> {
+        let Ok(id) = ObjectId::parse_str(key) else {
+            return Ok(());
+        };
+        match self.bucket.delete(Bson::ObjectId(id)).await {
+            Err(err) if !is_file_not_found(&err) => {
+                Err(StorageError::backend("failed to delete file", err))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn metadata(&self, key: &str) -> Result<Option<FileMetadata>, Self::Error> {
+        let Ok(id) = ObjectId::parse_str(key) else {
+            return Ok(None);
+        };
+        let file = self
+            .bucket
+            .find_one(doc! { "_id": id })
+            .await
+            .map_err(|err| StorageError::backend("failed to find file", err))?;
+        Ok(file.map(|file| FileMetadata {
+            size: file.length,
+            content_type: file
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get_str("contentType").ok())
+                .and_then(|content_type| content_type.parse().ok()),
+            modified: Some(file.upload_date.to_system_time()),
+        }))
+    }
+}
+
+fn is_file_not_found(err: &mongodb::error::Error) -> bool {
+    matches!(
+        *err.kind,
+        ErrorKind::GridFs(GridFsErrorKind::FileNotFound { .. })
+    )
+}
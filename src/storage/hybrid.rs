@@ -0,0 +1,317 @@
+use std::{
+    fmt,
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+
+use bytes::{Bytes, BytesMut};
+use futures::StreamExt;
+use tokio::io::{AsyncRead, AsyncWriteExt};
+use uuid::Uuid;
+
+use super::{BoxStream, FileMeta, StorageEngine, StoreContext, StoredFile};
+use crate::{MulterError, StorageError};
+
+/// Default largest file kept in memory by [`HybridStorage`].
+pub const DEFAULT_MEMORY_THRESHOLD: usize = 1024 * 1024;
+
+/// Storage engine keeping small files in memory and spilling larger ones to
+/// a temporary directory.
+///
+/// Files up to the threshold never touch the disk; a file crossing it is
+/// moved to a spill file and streamed there from then on, so memory use per
+/// file stays bounded. Either way the [`HybridFile`] output is read the same
+/// way, and spill files are deleted when it drops unless persisted.
+///
+/// ```rust
+/// use bytes::Bytes;
+/// use futures::stream;
+/// use multigear::{HybridStorage, Multer, MulterError};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let multer = Multer::builder().storage(HybridStorage::new(4)).build()?;
+/// let body = concat!(
+///     "--X\r\nContent-Disposition: form-data; name=\"a\"; filename=\"a.txt\"\r\n\r\nhi\r\n",
+///     "--X\r\nContent-Disposition: form-data; name=\"b\"; filename=\"b.txt\"\r\n\r\nhello\r\n",
+///     "--X--\r\n",
+/// );
+/// let output = multer
+///     .parse_and_store("X", stream::iter([Ok::<_, MulterError>(Bytes::from(body))]))
+///     .await?;
+///
+/// assert!(output.stored_files[0].is_in_memory());
+/// assert!(!output.stored_files[1].is_in_memory());
+/// assert_eq!(output.stored_files[1].bytes().await?, "hello");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HybridStorage {
+    threshold: usize,
+    dir: Option<PathBuf>,
+}
+
+impl HybridStorage {
+    /// Creates a backend keeping files of up to `threshold` bytes in memory.
+    pub fn new(threshold: usize) -> Self {
+        Self {
+            threshold,
+            dir: None,
+        }
+    }
+
+    /// Sets the directory larger files spill to.
+    ///
+    /// Defaults to the system temporary directory; the directory is created
+    /// when missing.
+    pub fn spill_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = Some(dir.into());
+        self
+    }
+
+    /// Returns the largest file size kept in memory.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    async fn spill(&self, buffer: &[u8]) -> Result<(tokio::fs::File, SpillFile), StorageError> {
+        let dir = self.dir.clone().unwrap_or_else(std::env::temp_dir);
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(|err| StorageError::io("failed to create spill directory", err))?;
+        let path = dir.join(format!("multigear-spill-{}", Uuid::new_v4().simple()));
+
+        // Declared before the file so the handle is closed before cleanup runs.
+        let spill = SpillFile { path: Some(path) };
+        let mut file = tokio::fs::File::create(spill.path())
+            .await
+            .map_err(|err| StorageError::io("failed to create spill file", err))?;
+        file.write_all(buffer)
+            .await
+            .map_err(|err| StorageError::io("failed to write spill file", err))?;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            path = %spill.path().display(),
+            threshold = self.threshold,
+            "hybrid storage: spilling file to disk"
+        );
+        Ok((file, spill))
+    }
+}
+
+impl Default for HybridStorage {
+    fn default() -> Self {
+        Self::new(DEFAULT_MEMORY_THRESHOLD)
+    }
+}
+
+/// A file stored by [`HybridStorage`], held in memory or in a spill file.
+pub struct HybridFile {
+    stored: StoredFile,
+    body: HybridBody,
+}
+
+enum HybridBody {
+    Memory(Bytes),
+    Disk(SpillFile),
+}
+
+impl HybridFile {
+    /// Returns the stored file's metadata.
+    ///
+    /// [`StoredFile::path`] names the spill file for files on disk.
+    pub fn stored(&self) -> &StoredFile {
+        &self.stored
+    }
+
+    /// Returns `true` when the file is held in memory.
+    pub fn is_in_memory(&self) -> bool {
+        matches!(self.body, HybridBody::Memory(_))
+    }
+
+    /// Returns the file body when it is held in memory.
+    pub fn as_bytes(&self) -> Option<&Bytes> {
+        match &self.body {
+            HybridBody::Memory(bytes) => Some(bytes),
+            HybridBody::Disk(_) => None,
+        }
+    }
+
+    /// Returns the spill file path when the file is on disk.
+    pub fn path(&self) -> Option<&Path> {
+        match &self.body {
+            HybridBody::Memory(_) => None,
+            HybridBody::Disk(spill) => Some(spill.path()),
+        }
+    }
+
+    /// Returns the whole file body, reading it from disk when spilled.
+    pub async fn bytes(&self) -> Result<Bytes, StorageError> {
+        match &self.body {
+            HybridBody::Memory(bytes) => Ok(bytes.clone()),
+            HybridBody::Disk(spill) => tokio::fs::read(spill.path())
+                .await
+                .map(Bytes::from)
+                .map_err(|err| StorageError::io("failed to read spill file", err)),
+        }
+    }
+
+    /// Opens a reader over the file body, wherever it is held.
+    pub async fn reader(&self) -> Result<Box<dyn AsyncRead + Send + Unpin>, StorageError> {
+        match &self.body {
+            HybridBody::Memory(bytes) => Ok(Box::new(Cursor::new(bytes.clone()))),
+            HybridBody::Disk(spill) => tokio::fs::File::open(spill.path())
+                .await
+                .map(|file| Box::new(file) as Box<dyn AsyncRead + Send + Unpin>)
+                .map_err(|err| StorageError::io("failed to open spill file", err)),
+        }
+    }
+
+    /// Writes the file to `path`, returning its metadata with the new
+    /// location.
+    ///
+    /// Spill files are renamed when possible and copied otherwise. An
+    /// existing file at `path` is replaced.
+    pub async fn persist(self, path: impl AsRef<Path>) -> Result<StoredFile, StorageError> {
+        let target = path.as_ref().to_path_buf();
+        let Self { mut stored, body } = self;
+
+        match body {
+            HybridBody::Memory(bytes) => tokio::fs::write(&target, &bytes)
+                .await
+                .map_err(|err| StorageError::io("failed to persist file", err))?,
+            HybridBody::Disk(mut spill) => {
+                if tokio::fs::rename(spill.path(), &target).await.is_ok() {
+                    spill.path = None;
+                } else {
+                    // Dropping `spill` afterwards removes the spill file.
+                    tokio::fs::copy(spill.path(), &target)
+                        .await
+                        .map_err(|err| StorageError::io("failed to persist file", err))?;
+                }
+            }
+        }
+
+        stored.storage_key = target.to_string_lossy().into_owned();
+        stored.path = Some(target);
+        Ok(stored)
+    }
+}
+
+impl fmt::Debug for HybridFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HybridFile")
+            .field("stored", &self.stored)
+            .field("in_memory", &self.is_in_memory())
+            .finish()
+    }
+}
+
+/// Deletes a spill file when dropped, unless it has been moved away.
+struct SpillFile {
+    path: Option<PathBuf>,
+}
+
+impl SpillFile {
+    fn path(&self) -> &Path {
+        self.path.as_deref().unwrap_or_else(|| Path::new(""))
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        if let Some(path) = self.path.take() {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(path = %path.display(), "hybrid storage: removing spill file");
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngine for HybridStorage {
+    type Output = HybridFile;
+    type Error = StorageError;
+
+    async fn store(
+        &self,
+        meta: FileMeta,
+        _ctx: &StoreContext,
+        mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            file_name = meta.file_name.as_deref().unwrap_or("<none>"),
+            threshold = self.threshold,
+            "hybrid storage: begin streaming store"
+        );
+
+        let mut buffer = BytesMut::new();
+        let mut spilled: Option<(tokio::fs::File, SpillFile)> = None;
+        let mut written = 0u64;
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(StorageError::Stream)?;
+            written = written.saturating_add(chunk.len() as u64);
+            match &mut spilled {
+                Some((file, _)) => {
+                    file.write_all(&chunk)
+                        .await
+                        .map_err(|err| StorageError::io("failed to write spill file", err))?;
+                }
+                None => {
+                    buffer.extend_from_slice(&chunk);
+                    if buffer.len() > self.threshold {
+                        spilled = Some(self.spill(&buffer).await?);
+                        buffer = BytesMut::new();
+                    }
+                }
+            }
+        }
+
+        let storage_key = Uuid::new_v4().to_string();
+        let (body, path) = match spilled {
+            Some((mut file, spill)) => {
+                file.flush()
+                    .await
+                    .map_err(|err| StorageError::io("failed to flush spill file", err))?;
+                drop(file);
+                let path = spill.path().to_path_buf();
+                (HybridBody::Disk(spill), Some(path))
+            }
+            None => (HybridBody::Memory(buffer.freeze()), None),
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            storage_key = storage_key.as_str(),
+            size = written,
+            in_memory = path.is_none(),
+            "hybrid storage: completed store"
+        );
+
+        Ok(HybridFile {
+            stored: StoredFile {
+                storage_key,
+                field_name: meta.field_name,
+                file_name: meta.file_name,
+                content_type: meta
+                    .content_type
+                    .parse()
+                    .unwrap_or(mime::APPLICATION_OCTET_STREAM),
+                sniffed_content_type: meta
+                    .sniffed_content_type
+                    .as_deref()
+                    .and_then(|sniffed| sniffed.parse().ok()),
+                size: written,
+                path,
+            },
+            body,
+        })
+    }
+}
@@ -0,0 +1,267 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use futures::StreamExt;
+
+use super::{BoxStream, FileMeta, StorageEngine, StoreContext};
+use crate::MulterError;
+
+/// How an instrumented store ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StoreOutcome {
+    /// The inner engine stored the file.
+    Stored,
+    /// The inner engine returned an error.
+    Failed,
+    /// The store future was dropped before it finished.
+    Cancelled,
+}
+
+/// Summary of one finished store, passed to [`MetricsSink::store_finished`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreEvent {
+    /// Multipart field name of the file.
+    pub field_name: String,
+    /// Bytes handed to the inner engine.
+    pub bytes: u64,
+    /// Time from the start of the store until it ended.
+    pub duration: Duration,
+    /// How the store ended.
+    pub outcome: StoreOutcome,
+}
+
+/// Receiver of the measurements taken by [`InstrumentedStorage`].
+///
+/// Methods are called inline on the upload path, so implementations should
+/// only update counters or hand values to a metrics library. Every
+/// [`store_started`](Self::store_started) is followed by exactly one
+/// [`store_finished`](Self::store_finished), including for cancelled stores.
+pub trait MetricsSink: Send + Sync + 'static {
+    /// Called before the inner engine starts storing a file.
+    fn store_started(&self, _meta: &FileMeta) {}
+
+    /// Called for each chunk handed to the inner engine.
+    fn bytes_written(&self, _bytes: u64) {}
+
+    /// Called once the store has ended.
+    fn store_finished(&self, event: &StoreEvent);
+}
+
+impl<T: MetricsSink + ?Sized> MetricsSink for Arc<T> {
+    fn store_started(&self, meta: &FileMeta) {
+        (**self).store_started(meta);
+    }
+
+    fn bytes_written(&self, bytes: u64) {
+        (**self).bytes_written(bytes);
+    }
+
+    fn store_finished(&self, event: &StoreEvent) {
+        (**self).store_finished(event);
+    }
+}
+
+/// Point-in-time copy of the counters kept by [`StorageMetrics`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// Stores that have started.
+    pub started: u64,
+    /// Stores that finished successfully.
+    pub stored: u64,
+    /// Stores that failed or were cancelled.
+    pub failed: u64,
+    /// Stores currently in progress.
+    pub active: u64,
+    /// Bytes handed to the inner engine.
+    pub bytes_written: u64,
+    /// Summed duration of all finished stores.
+    pub total_duration: Duration,
+}
+
+/// In-process [`MetricsSink`] keeping running totals.
+///
+/// Clones share the same counters.
+#[derive(Debug, Clone, Default)]
+pub struct StorageMetrics {
+    counters: Arc<Counters>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    started: AtomicU64,
+    stored: AtomicU64,
+    failed: AtomicU64,
+    bytes_written: AtomicU64,
+    total_micros: AtomicU64,
+}
+
+impl StorageMetrics {
+    /// Creates a sink with all counters at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current counter values.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let counters = &self.counters;
+        let started = counters.started.load(Ordering::Relaxed);
+        let stored = counters.stored.load(Ordering::Relaxed);
+        let failed = counters.failed.load(Ordering::Relaxed);
+        MetricsSnapshot {
+            started,
+            stored,
+            failed,
+            active: started.saturating_sub(stored + failed),
+            bytes_written: counters.bytes_written.load(Ordering::Relaxed),
+            total_duration: Duration::from_micros(counters.total_micros.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl MetricsSink for StorageMetrics {
+    fn store_started(&self, _meta: &FileMeta) {
+        self.counters.started.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn bytes_written(&self, bytes: u64) {
+        self.counters
+            .bytes_written
+            .fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn store_finished(&self, event: &StoreEvent) {
+        let counter = match event.outcome {
+            StoreOutcome::Stored => &self.counters.stored,
+            StoreOutcome::Failed | StoreOutcome::Cancelled => &self.counters.failed,
+        };
+        self.counters.total_micros.fetch_add(
+            u64::try_from(event.duration.as_micros()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Storage wrapper reporting bytes written, latency, failures, and
+/// concurrency of the inner engine to a [`MetricsSink`].
+///
+/// ```rust
+/// use multigear::{InstrumentedStorage, MemoryStorage, Multer, StorageMetrics};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let metrics = StorageMetrics::new();
+/// let storage = InstrumentedStorage::new(MemoryStorage::new(), metrics.clone());
+/// let multer = Multer::builder().storage(storage).build()?;
+///
+/// assert_eq!(metrics.snapshot().active, 0);
+/// # drop(multer);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct InstrumentedStorage<S> {
+    inner: S,
+    sink: Arc<dyn MetricsSink>,
+}
+
+impl<S> InstrumentedStorage<S> {
+    /// Creates a wrapper reporting stores through `inner` to `sink`.
+    pub fn new(inner: S, sink: impl MetricsSink) -> Self {
+        Self {
+            inner,
+            sink: Arc::new(sink),
+        }
+    }
+
+    /// Returns the wrapped storage engine.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for InstrumentedStorage<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InstrumentedStorage")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reports the end of a store exactly once, as cancelled if it is dropped
+/// before [`finish`](Self::finish).
+struct StoreGuard {
+    sink: Arc<dyn MetricsSink>,
+    field_name: String,
+    bytes: Arc<AtomicU64>,
+    started: Instant,
+    outcome: StoreOutcome,
+}
+
+impl StoreGuard {
+    fn finish(mut self, outcome: StoreOutcome) {
+        self.outcome = outcome;
+    }
+}
+
+impl Drop for StoreGuard {
+    fn drop(&mut self) {
+        self.sink.store_finished(&StoreEvent {
+            field_name: std::mem::take(&mut self.field_name),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            duration: self.started.elapsed(),
+            outcome: self.outcome,
+        });
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl<S: StorageEngine> StorageEngine for InstrumentedStorage<S> {
+    type Output = S::Output;
+    type Error = S::Error;
+
+    async fn store(
+        &self,
+        meta: FileMeta,
+        ctx: &StoreContext,
+        stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        self.sink.store_started(&meta);
+        let bytes = Arc::new(AtomicU64::new(0));
+        let guard = StoreGuard {
+            sink: Arc::clone(&self.sink),
+            field_name: meta.field_name.clone(),
+            bytes: Arc::clone(&bytes),
+            started: Instant::now(),
+            outcome: StoreOutcome::Cancelled,
+        };
+
+        let sink = Arc::clone(&self.sink);
+        let counted = stream.inspect(move |item| {
+            if let Ok(chunk) = item {
+                bytes.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+                sink.bytes_written(chunk.len() as u64);
+            }
+        });
+
+        let result = self.inner.store(meta, ctx, Box::pin(counted)).await;
+        guard.finish(if result.is_ok() {
+            StoreOutcome::Stored
+        } else {
+            StoreOutcome::Failed
+        });
+        result
+    }
+
+    async fn abort(&self, output: Self::Output) -> Result<(), Self::Error> {
+        self.inner.abort(output).await
+    }
+}
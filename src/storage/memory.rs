@@ -1,17 +1,26 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::SystemTime};
 
 use bytes::Bytes;
 use futures::StreamExt;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use super::{BoxStream, StorageEngine, StoredFile};
+use super::{
+    BoxStream, FileMeta, FileMetadata, StorageEngine, StorageEngineExt, StoreContext, StoredFile,
+};
 use crate::{MulterError, StorageError};
 
 /// In-memory storage engine keyed by generated UUIDs.
 #[derive(Debug, Clone, Default)]
 pub struct MemoryStorage {
-    files: Arc<RwLock<HashMap<String, Bytes>>>,
+    files: Arc<RwLock<HashMap<String, MemoryFile>>>,
+}
+
+#[derive(Debug)]
+struct MemoryFile {
+    body: Bytes,
+    content_type: mime::Mime,
+    modified: SystemTime,
 }
 
 impl MemoryStorage {
@@ -22,7 +31,11 @@ impl MemoryStorage {
 
     /// Returns stored bytes for a previously stored key.
     pub async fn get(&self, key: &str) -> Option<Bytes> {
-        self.files.read().await.get(key).cloned()
+        self.files
+            .read()
+            .await
+            .get(key)
+            .map(|file| file.body.clone())
     }
 
     /// Returns the current number of stored objects.
@@ -36,18 +49,22 @@ impl MemoryStorage {
     }
 }
 
-#[async_trait::async_trait]
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
 impl StorageEngine for MemoryStorage {
     type Output = StoredFile;
     type Error = StorageError;
 
     async fn store(
         &self,
-        field_name: &str,
-        file_name: Option<&str>,
-        content_type: &str,
+        meta: FileMeta,
+        _ctx: &StoreContext,
         mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
     ) -> Result<Self::Output, Self::Error> {
+        let field_name = meta.field_name.as_str();
+        let file_name = meta.file_name.as_deref();
+        let content_type = meta.content_type.as_str();
+
         #[cfg(feature = "tracing")]
         tracing::debug!(
             field_name = field_name,
@@ -58,7 +75,7 @@ impl StorageEngine for MemoryStorage {
 
         let mut body = Vec::new();
         while let Some(chunk) = stream.next().await {
-            let chunk = chunk.map_err(|err| StorageError::new(err.to_string()))?;
+            let chunk = chunk.map_err(StorageError::Stream)?;
             body.extend_from_slice(&chunk);
         }
         let body = Bytes::from(body);
@@ -69,7 +86,14 @@ impl StorageEngine for MemoryStorage {
             .parse::<mime::Mime>()
             .unwrap_or(mime::APPLICATION_OCTET_STREAM);
 
-        self.files.write().await.insert(storage_key.clone(), body);
+        self.files.write().await.insert(
+            storage_key.clone(),
+            MemoryFile {
+                body,
+                content_type: parsed_content_type.clone(),
+                modified: SystemTime::now(),
+            },
+        );
 
         #[cfg(feature = "tracing")]
         tracing::debug!(
@@ -84,8 +108,49 @@ impl StorageEngine for MemoryStorage {
             field_name: field_name.to_owned(),
             file_name: file_name.map(ToOwned::to_owned),
             content_type: parsed_content_type,
+            sniffed_content_type: meta
+                .sniffed_content_type
+                .as_deref()
+                .and_then(|sniffed| sniffed.parse().ok()),
             size,
             path: None,
         })
     }
+
+    async fn abort(&self, output: Self::Output) -> Result<(), Self::Error> {
+        self.delete(&output.storage_key).await
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngineExt for MemoryStorage {
+    async fn open(
+        &self,
+        key: &str,
+    ) -> Result<Option<BoxStream<'static, Result<Bytes, Self::Error>>>, Self::Error> {
+        Ok(self
+            .get(key)
+            .await
+            .map(|body| -> BoxStream<'static, Result<Bytes, Self::Error>> {
+                Box::pin(futures::stream::once(async move { Ok(body) }))
+            }))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Self::Error> {
+        self.files.write().await.remove(key);
+        Ok(())
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, Self::Error> {
+        Ok(self.files.read().await.contains_key(key))
+    }
+
+    async fn metadata(&self, key: &str) -> Result<Option<FileMetadata>, Self::Error> {
+        Ok(self.files.read().await.get(key).map(|file| FileMetadata {
+            size: file.body.len() as u64,
+            content_type: Some(file.content_type.clone()),
+            modified: Some(file.modified),
+        }))
+    }
 }
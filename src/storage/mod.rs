@@ -7,18 +7,95 @@ use futures::Stream;
 
 use crate::{MulterError, StorageError};
 
+/// Azure Blob Storage backend implementation.
+#[cfg(feature = "azure")]
+pub mod azure;
+/// Closure-backed storage adapter implementation.
+pub mod closure;
+/// Compressing storage wrapper implementation.
+#[cfg(feature = "compression")]
+pub mod compressed;
+/// Validation-only storage backend implementation.
+pub mod discard;
 /// Disk-backed storage backend implementation.
 pub mod disk;
+/// Failover storage wrapper implementation.
+pub mod fallback;
+/// Filename sanitization policies.
+pub mod filename;
+/// MongoDB GridFS storage backend implementation.
+#[cfg(feature = "gridfs")]
+pub mod gridfs;
+/// Hybrid memory/disk storage backend implementation.
+pub mod hybrid;
+/// Metrics-reporting storage wrapper implementation.
+pub mod instrumented;
 /// In-memory storage backend implementation.
 pub mod memory;
+/// Postgres storage backend implementation.
+#[cfg(feature = "sqlx-postgres")]
+pub mod postgres;
+/// Amazon S3 storage backend implementation.
+#[cfg(feature = "s3")]
+pub mod s3;
+/// SQLite storage backend implementation.
+#[cfg(feature = "sqlx-sqlite")]
+pub mod sqlite;
+/// Tee storage wrapper implementation.
+pub mod tee;
+/// Self-deleting temporary file storage backend implementation.
+#[cfg(feature = "tempfile")]
+pub mod temp;
+/// Throughput-limiting storage wrapper implementation.
+pub mod throttled;
+#[cfg(feature = "azure")]
+pub use azure::{AzureBlobStorage, AzureBlobStorageBuilder, AzureCredential};
+pub use closure::FnStorage;
+#[cfg(feature = "compression")]
+pub use compressed::{
+    CompressedFile, CompressedStorage, CompressedStorageBuilder, CompressionAlgorithm,
+};
+#[cfg(feature = "checksum")]
+pub use discard::DigestAlgorithm;
+pub use discard::DiscardStorage;
 pub use disk::{DiskStorage, DiskStorageBuilder, FilenameStrategy};
+pub use fallback::{FallbackFile, FallbackStorage};
+pub use filename::FilenamePolicy;
+#[cfg(feature = "gridfs")]
+pub use gridfs::{GridFsStorage, GridFsStorageBuilder};
+pub use hybrid::{HybridFile, HybridStorage};
+pub use instrumented::{
+    InstrumentedStorage, MetricsSink, MetricsSnapshot, StorageMetrics, StoreEvent, StoreOutcome,
+};
 pub use memory::MemoryStorage;
+#[cfg(feature = "sqlx-postgres")]
+pub use postgres::{PostgresStorage, PostgresStorageBuilder};
+#[cfg(feature = "s3")]
+pub use s3::{S3Storage, S3StorageBuilder};
+#[cfg(feature = "sqlx-sqlite")]
+pub use sqlite::{SqliteStorage, SqliteStorageBuilder};
+pub use tee::{TeeCleanup, TeeFile, TeeHalf, TeeStorage};
+#[cfg(feature = "tempfile")]
+pub use temp::{TempFile, TempfileStorage};
+pub use throttled::{ThrottledStorage, ThrottledStorageBuilder};
 
 /// Boxed stream type used by storage backends.
+///
+/// The stream is `Send` unless the `unsend` feature is enabled.
+#[cfg(not(feature = "unsend"))]
 pub type BoxStream<'a, T> = Pin<Box<dyn Stream<Item = T> + Send + 'a>>;
+/// Boxed stream type used by storage backends.
+///
+/// The stream is `Send` unless the `unsend` feature is enabled.
+#[cfg(feature = "unsend")]
+pub type BoxStream<'a, T> = Pin<Box<dyn Stream<Item = T> + 'a>>;
 
 /// Metadata describing a file part before persistence.
+///
+/// New fields may be added, so build one with [`FileMeta::new`] and set the
+/// optional fields afterwards.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct FileMeta {
     /// Multipart field name.
     pub field_name: String,
@@ -26,10 +103,95 @@ pub struct FileMeta {
     pub file_name: Option<String>,
     /// Content type observed on the uploaded file part.
     pub content_type: String,
+    /// Body size declared by the part's `Content-Length` header, when present.
+    ///
+    /// This is an unverified client hint; the stream may still end early.
+    pub size_hint: Option<u64>,
+    /// Content type sniffed from the file's magic bytes, when recognized.
+    ///
+    /// Only set with the `sniff` feature.
+    pub sniffed_content_type: Option<String>,
+    /// Raw headers of the multipart part.
+    pub headers: http::HeaderMap,
+}
+
+impl FileMeta {
+    /// Creates metadata for a file part with no size hint, sniffed type or
+    /// headers.
+    ///
+    /// ```rust
+    /// use multigear::FileMeta;
+    ///
+    /// let mut meta = FileMeta::new("avatar", Some("me.png"), "image/png");
+    /// meta.size_hint = Some(1024);
+    /// assert_eq!(meta.file_name.as_deref(), Some("me.png"));
+    /// ```
+    pub fn new(
+        field_name: impl Into<String>,
+        file_name: Option<impl Into<String>>,
+        content_type: impl Into<String>,
+    ) -> Self {
+        Self {
+            field_name: field_name.into(),
+            file_name: file_name.map(Into::into),
+            content_type: content_type.into(),
+            size_hint: None,
+            sniffed_content_type: None,
+            headers: http::HeaderMap::new(),
+        }
+    }
+}
+
+/// Per-request values handed to every store of a request.
+///
+/// Applications attach values such as the authenticated user, tenant, or
+/// request id with [`Multer::with_context`](crate::Multer::with_context);
+/// storage engines read them back by type to pick a destination. Values are
+/// keyed by their type, so wrap plain strings in a newtype.
+///
+/// ```rust
+/// use multigear::StoreContext;
+///
+/// #[derive(Clone)]
+/// struct TenantId(String);
+///
+/// let ctx = StoreContext::new().with(TenantId("acme".to_owned()));
+/// assert_eq!(ctx.get::<TenantId>().map(|tenant| tenant.0.as_str()), Some("acme"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StoreContext {
+    extensions: http::Extensions,
+}
+
+impl StoreContext {
+    /// Creates an empty context.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `value`, replacing any earlier value of the same type.
+    pub fn with<T: Clone + Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.insert(value);
+        self
+    }
+
+    /// Inserts `value`, returning the earlier value of the same type.
+    pub fn insert<T: Clone + Send + Sync + 'static>(&mut self, value: T) -> Option<T> {
+        self.extensions.insert(value)
+    }
+
+    /// Returns the value of type `T`, when present.
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.extensions.get()
+    }
 }
 
 /// Metadata describing a stored file.
+///
+/// New fields may be added, so build one with [`StoredFile::new`] and set the
+/// optional fields afterwards.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct StoredFile {
     /// Backend-specific opaque key or location identifier.
     pub storage_key: String,
@@ -39,44 +201,166 @@ pub struct StoredFile {
     pub file_name: Option<String>,
     /// Content type observed on the uploaded file part.
     pub content_type: mime::Mime,
+    /// Content type sniffed from the file's magic bytes, when recognized.
+    ///
+    /// Only set with the `sniff` feature.
+    pub sniffed_content_type: Option<mime::Mime>,
     /// Persisted file size in bytes.
     pub size: u64,
     /// Final filesystem path when stored on disk.
     pub path: Option<std::path::PathBuf>,
 }
 
+impl StoredFile {
+    /// Creates a stored file record with no sniffed content type or path.
+    ///
+    /// ```rust
+    /// use multigear::StoredFile;
+    ///
+    /// let mut stored = StoredFile::new("uploads/a1", "avatar", Some("me.png"), mime::IMAGE_PNG, 1024);
+    /// stored.path = Some("/var/uploads/a1".into());
+    /// assert_eq!(stored.size, 1024);
+    /// ```
+    pub fn new(
+        storage_key: impl Into<String>,
+        field_name: impl Into<String>,
+        file_name: Option<impl Into<String>>,
+        content_type: mime::Mime,
+        size: u64,
+    ) -> Self {
+        Self {
+            storage_key: storage_key.into(),
+            field_name: field_name.into(),
+            file_name: file_name.map(Into::into),
+            content_type,
+            sniffed_content_type: None,
+            size,
+            path: None,
+        }
+    }
+}
+
 /// Async trait abstraction for file storage backends.
-#[async_trait::async_trait]
+///
+/// Store futures and body streams are `Send`, so the futures returned by
+/// [`Multer::parse_and_store`](crate::Multer::parse_and_store) and
+/// [`Multer::store`](crate::Multer::store) can be passed to `tokio::spawn` and
+/// awaited in multi-threaded handlers. The `unsend` feature drops these `Send`
+/// bounds for engines built on `!Send` clients.
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
 pub trait StorageEngine: Send + Sync + 'static {
     /// Backend-specific output type returned after a successful store.
     type Output: Send;
     /// Backend-specific error type surfaced on store failure.
     type Error: std::error::Error + Send + Sync + 'static;
 
-    /// Stores a file stream and returns backend output metadata.
+    /// Stores a file stream described by `meta` and returns backend output
+    /// metadata.
+    ///
+    /// `ctx` carries the per-request values of the upload; it is empty unless
+    /// the request went through [`Multer::with_context`](crate::Multer::with_context).
+    ///
+    /// Cleaning up a part that fails mid-stream is the engine's job. When
+    /// `stream` yields an error or the store fails for any other reason, the
+    /// engine must remove whatever it already wrote before returning the
+    /// error: no storage key or output exists until `store` returns, so
+    /// [`Multer`](crate::Multer) never calls [`StorageEngine::abort`] for that
+    /// part and nothing else can clean up after it. Every bundled backend does
+    /// so.
+    ///
+    /// Engines should return a stream error as [`StorageError::Stream`], or
+    /// keep it in their error's source chain as
+    /// [`StorageError::Backend`]; [`Multer`](crate::Multer) then surfaces the
+    /// original [`MulterError`], such as a size limit or checksum mismatch,
+    /// instead of [`MulterError::Storage`].
     async fn store(
         &self,
-        field_name: &str,
-        file_name: Option<&str>,
-        content_type: &str,
+        meta: FileMeta,
+        ctx: &StoreContext,
         stream: BoxStream<'_, Result<Bytes, MulterError>>,
     ) -> Result<Self::Output, Self::Error>;
+
+    /// Discards a file stored for a request that then failed.
+    ///
+    /// [`Multer`](crate::Multer) calls it for the files already stored when a
+    /// later part fails, and for a file the scanner rejected after it was
+    /// stored. It only receives outputs of completed stores: a part whose
+    /// body fails mid-store is never passed to `abort`, and the engine's
+    /// `store` must clean it up itself. The default implementation does
+    /// nothing, which suits outputs that remove themselves when dropped.
+    async fn abort(&self, _output: Self::Output) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Metadata of a stored file, returned by [`StorageEngineExt::metadata`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMetadata {
+    /// Stored size in bytes.
+    pub size: u64,
+    /// Content type recorded with the file, when the backend keeps one.
+    pub content_type: Option<mime::Mime>,
+    /// Time the file was last written, when the backend records it.
+    pub modified: Option<std::time::SystemTime>,
+}
+
+/// Retrieval and deletion of stored files, for backends that can address a
+/// file by its [`StoredFile::storage_key`].
+///
+/// Applications use it to serve uploads and garbage-collect them through the
+/// engine that stored them. Missing keys are not errors: `open` and
+/// `metadata` return `None`, `exists` returns `false`, and `delete` succeeds.
+///
+/// ```rust
+/// use futures::TryStreamExt;
+/// use multigear::{MemoryStorage, StorageEngineExt, StorageError};
+///
+/// async fn read_upload(storage: &MemoryStorage, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+///     let Some(body) = storage.open(key).await? else {
+///         return Ok(None);
+///     };
+///     let chunks: Vec<_> = body.try_collect().await?;
+///     Ok(Some(chunks.concat()))
+/// }
+/// ```
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+pub trait StorageEngineExt: StorageEngine {
+    /// Opens the body of the file stored under `key`.
+    async fn open(
+        &self,
+        key: &str,
+    ) -> Result<Option<BoxStream<'static, Result<Bytes, Self::Error>>>, Self::Error>;
+
+    /// Deletes the file stored under `key`.
+    async fn delete(&self, key: &str) -> Result<(), Self::Error>;
+
+    /// Returns whether a file is stored under `key`.
+    ///
+    /// The default implementation asks [`StorageEngineExt::metadata`].
+    async fn exists(&self, key: &str) -> Result<bool, Self::Error> {
+        Ok(self.metadata(key).await?.is_some())
+    }
+
+    /// Returns the metadata of the file stored under `key`.
+    async fn metadata(&self, key: &str) -> Result<Option<FileMetadata>, Self::Error>;
 }
 
 /// Placeholder storage implementation used as the default backend.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct NoopStorage;
 
-#[async_trait::async_trait]
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
 impl StorageEngine for NoopStorage {
     type Output = StoredFile;
     type Error = StorageError;
 
     async fn store(
         &self,
-        _field_name: &str,
-        _file_name: Option<&str>,
-        _content_type: &str,
+        _meta: FileMeta,
+        _ctx: &StoreContext,
         _stream: BoxStream<'_, Result<Bytes, MulterError>>,
     ) -> Result<Self::Output, Self::Error> {
         Err(StorageError::new(
@@ -84,3 +368,43 @@ impl StorageEngine for NoopStorage {
         ))
     }
 }
+
+/// Reads from `stream` until `buffer` holds more than `part_size` bytes.
+///
+/// Returns a full part split off the buffer, or `None` once the stream has
+/// ended with the remainder left in `buffer`. Holding back one byte past the
+/// part size keeps the final part non-empty.
+#[cfg(any(feature = "s3", feature = "azure"))]
+pub(crate) async fn fill_part(
+    stream: &mut BoxStream<'_, Result<Bytes, MulterError>>,
+    buffer: &mut bytes::BytesMut,
+    part_size: usize,
+) -> Result<Option<Bytes>, StorageError> {
+    while buffer.len() <= part_size {
+        match futures::StreamExt::next(stream).await {
+            Some(chunk) => {
+                let chunk = chunk.map_err(StorageError::Stream)?;
+                buffer.extend_from_slice(&chunk);
+            }
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(buffer.split_to(part_size).freeze()))
+}
+
+/// Accepts `table` or `schema.table` made of unquoted identifiers, which are
+/// safe to splice into SQL statements.
+#[cfg(any(feature = "sqlx-postgres", feature = "sqlx-sqlite"))]
+pub(crate) fn is_table_name(table: &str) -> bool {
+    let mut parts = 0;
+    let valid = table.split('.').all(|part| {
+        parts += 1;
+        let mut chars = part.chars();
+        chars
+            .next()
+            .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && part.len() <= 63
+    });
+    valid && parts <= 2
+}
@@ -0,0 +1,429 @@
+use bytes::{Bytes, BytesMut};
+use futures::StreamExt;
+use sqlx::{postgres::types::Oid, PgPool, Postgres, Transaction};
+
+use super::{
+    is_table_name, BoxStream, FileMeta, FileMetadata, StorageEngine, StorageEngineExt,
+    StoreContext, StoredFile,
+};
+use crate::{MulterError, StorageError};
+
+/// Default table file rows are inserted into.
+pub const DEFAULT_TABLE: &str = "multigear_files";
+
+/// Bytes of a large object read per query when it is opened.
+const LARGE_OBJECT_READ_SIZE: i32 = 256 * 1024;
+
+/// How [`PostgresStorage`] stores file bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum PostgresStorageMode {
+    /// Store the body in the row's `bytea` `data` column.
+    ///
+    /// The whole file is buffered in memory before it is inserted, so this
+    /// suits small files; pair it with `Limits::max_file_size`.
+    #[default]
+    Bytea,
+    /// Stream the body into a large object and store its `oid` in the
+    /// row's `data` column.
+    ///
+    /// Chunks are written as they arrive, so memory use does not grow with
+    /// the file size. Large objects are not deleted with their row;
+    /// [`StorageEngineExt::delete`] unlinks them, otherwise remove them with
+    /// `lo_unlink` or the `lo` extension's `lo_manage` trigger.
+    LargeObject,
+}
+
+/// Builder for [`PostgresStorage`].
+#[derive(Debug, Clone)]
+pub struct PostgresStorageBuilder {
+    pool: PgPool,
+    table: String,
+    mode: PostgresStorageMode,
+}
+
+impl PostgresStorageBuilder {
+    /// Sets the table file rows are inserted into, optionally schema
+    /// qualified as `schema.table`.
+    ///
+    /// Defaults to [`DEFAULT_TABLE`].
+    pub fn table(mut self, table: impl Into<String>) -> Self {
+        self.table = table.into();
+        self
+    }
+
+    /// Sets how file bodies are stored.
+    pub fn mode(mut self, mode: PostgresStorageMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Builds a validated Postgres storage backend.
+    pub fn build(self) -> Result<PostgresStorage, StorageError> {
+        if !is_table_name(&self.table) {
+            return Err(StorageError::new(format!(
+                "postgres storage table `{}` must be an unquoted identifier, optionally schema qualified",
+                self.table
+            )));
+        }
+
+        Ok(PostgresStorage {
+            pool: self.pool,
+            table: self.table,
+            mode: self.mode,
+        })
+    }
+}
+
+/// Storage engine inserting each file as a row of a Postgres table.
+///
+/// Every part is written in its own transaction, so a failed or cancelled
+/// upload leaves no row or large object behind. [`StoredFile::storage_key`]
+/// is the inserted row's `id`, as text.
+///
+/// The table needs these columns; [`PostgresStorage::create_table`] creates
+/// it when missing:
+///
+/// ```sql
+/// CREATE TABLE multigear_files (
+///     id bigint GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+///     field_name text NOT NULL,
+///     file_name text,
+///     content_type text NOT NULL,
+///     size bigint NOT NULL,
+///     data bytea NOT NULL -- `oid` in `PostgresStorageMode::LargeObject`
+/// );
+/// ```
+///
+/// ```rust
+/// use multigear::{storage::postgres::PostgresStorageMode, Multer, PostgresStorage};
+///
+/// # async fn run(pool: sqlx::PgPool) -> Result<(), Box<dyn std::error::Error>> {
+/// let storage = PostgresStorage::builder(pool)
+///     .table("uploads.files")
+///     .mode(PostgresStorageMode::LargeObject)
+///     .build()?;
+/// storage.create_table().await?;
+/// let multer = Multer::builder().storage(storage).build()?;
+/// # drop(multer);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PostgresStorage {
+    pool: PgPool,
+    table: String,
+    mode: PostgresStorageMode,
+}
+
+impl PostgresStorage {
+    /// Creates a builder storing files through `pool`.
+    pub fn builder(pool: PgPool) -> PostgresStorageBuilder {
+        PostgresStorageBuilder {
+            pool,
+            table: DEFAULT_TABLE.to_owned(),
+            mode: PostgresStorageMode::default(),
+        }
+    }
+
+    /// Returns the table file rows are inserted into.
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Creates the storage table when it does not exist yet.
+    pub async fn create_table(&self) -> Result<(), StorageError> {
+        let data_type = match self.mode {
+            PostgresStorageMode::Bytea => "bytea",
+            PostgresStorageMode::LargeObject => "oid",
+        };
+        let statement = format!(
+            "CREATE TABLE IF NOT EXISTS {} (\
+             id bigint GENERATED ALWAYS AS IDENTITY PRIMARY KEY, \
+             field_name text NOT NULL, \
+             file_name text, \
+             content_type text NOT NULL, \
+             size bigint NOT NULL, \
+             data {data_type} NOT NULL)",
+            self.table
+        );
+        sqlx::query(&statement)
+            .execute(&self.pool)
+            .await
+            .map_err(|err| sql_error("failed to create table", err))?;
+        Ok(())
+    }
+
+    async fn insert_row<D>(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        meta: &FileMeta,
+        size: u64,
+        data: D,
+    ) -> Result<String, StorageError>
+    where
+        D: for<'e> sqlx::Encode<'e, Postgres> + sqlx::Type<Postgres> + Send,
+    {
+        let size =
+            i64::try_from(size).map_err(|_| StorageError::new("file too large for postgres"))?;
+        let statement = format!(
+            "INSERT INTO {} (field_name, file_name, content_type, size, data) \
+             VALUES ($1, $2, $3, $4, $5) RETURNING id::text",
+            self.table
+        );
+        sqlx::query_scalar(&statement)
+            .bind(&meta.field_name)
+            .bind(meta.file_name.as_deref())
+            .bind(&meta.content_type)
+            .bind(size)
+            .bind(data)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(|err| sql_error("failed to insert file row", err))
+    }
+
+    async fn store_bytea(
+        &self,
+        meta: &FileMeta,
+        mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<(String, u64), StorageError> {
+        let mut body = BytesMut::new();
+        while let Some(chunk) = stream.next().await {
+            body.extend_from_slice(&read_chunk(chunk)?);
+        }
+        let size = body.len() as u64;
+
+        let mut tx = self.begin().await?;
+        let id = self.insert_row(&mut tx, meta, size, body.as_ref()).await?;
+        commit(tx).await?;
+        Ok((id, size))
+    }
+
+    async fn store_large_object(
+        &self,
+        meta: &FileMeta,
+        mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<(String, u64), StorageError> {
+        let mut tx = self.begin().await?;
+        let oid: Oid = sqlx::query_scalar("SELECT lo_create(0)")
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|err| sql_error("failed to create large object", err))?;
+
+        let mut size = 0u64;
+        while let Some(chunk) = stream.next().await {
+            let chunk = read_chunk(chunk)?;
+            if chunk.is_empty() {
+                continue;
+            }
+            let offset = i64::try_from(size)
+                .map_err(|_| StorageError::new("file too large for postgres"))?;
+            sqlx::query("SELECT lo_put($1, $2, $3)")
+                .bind(oid)
+                .bind(offset)
+                .bind(chunk.as_ref())
+                .execute(&mut *tx)
+                .await
+                .map_err(|err| sql_error("failed to write large object", err))?;
+            size = size.saturating_add(chunk.len() as u64);
+        }
+
+        let id = self.insert_row(&mut tx, meta, size, oid).await?;
+        commit(tx).await?;
+        Ok((id, size))
+    }
+
+    async fn begin(&self) -> Result<Transaction<'static, Postgres>, StorageError> {
+        self.pool
+            .begin()
+            .await
+            .map_err(|err| sql_error("failed to begin transaction", err))
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngine for PostgresStorage {
+    type Output = StoredFile;
+    type Error = StorageError;
+
+    async fn store(
+        &self,
+        meta: FileMeta,
+        _ctx: &StoreContext,
+        stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            file_name = meta.file_name.as_deref().unwrap_or("<none>"),
+            table = self.table.as_str(),
+            "postgres storage: begin streaming store"
+        );
+
+        let (id, size) = match self.mode {
+            PostgresStorageMode::Bytea => self.store_bytea(&meta, stream).await?,
+            PostgresStorageMode::LargeObject => self.store_large_object(&meta, stream).await?,
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            id = id.as_str(),
+            size = size,
+            "postgres storage: completed store"
+        );
+
+        Ok(StoredFile {
+            storage_key: id,
+            field_name: meta.field_name,
+            file_name: meta.file_name,
+            content_type: meta
+                .content_type
+                .parse()
+                .unwrap_or(mime::APPLICATION_OCTET_STREAM),
+            sniffed_content_type: meta
+                .sniffed_content_type
+                .as_deref()
+                .and_then(|sniffed| sniffed.parse().ok()),
+            size,
+            path: None,
+        })
+    }
+
+    async fn abort(&self, output: Self::Output) -> Result<(), Self::Error> {
+        self.delete(&output.storage_key).await
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngineExt for PostgresStorage {
+    async fn open(
+        &self,
+        key: &str,
+    ) -> Result<Option<BoxStream<'static, Result<Bytes, Self::Error>>>, Self::Error> {
+        let Ok(id) = key.parse::<i64>() else {
+            return Ok(None);
+        };
+        match self.mode {
+            PostgresStorageMode::Bytea => {
+                let statement = format!("SELECT data FROM {} WHERE id = $1", self.table);
+                let data: Option<Vec<u8>> = sqlx::query_scalar(&statement)
+                    .bind(id)
+                    .fetch_optional(&self.pool)
+                    .await
+                    .map_err(|err| sql_error("failed to read file row", err))?;
+                Ok(
+                    data.map(|data| -> BoxStream<'static, Result<Bytes, StorageError>> {
+                        Box::pin(futures::stream::once(async move { Ok(Bytes::from(data)) }))
+                    }),
+                )
+            }
+            PostgresStorageMode::LargeObject => {
+                let statement = format!("SELECT data FROM {} WHERE id = $1", self.table);
+                let oid: Option<Oid> = sqlx::query_scalar(&statement)
+                    .bind(id)
+                    .fetch_optional(&self.pool)
+                    .await
+                    .map_err(|err| sql_error("failed to read file row", err))?;
+                Ok(oid.map(|oid| read_large_object(self.pool.clone(), oid)))
+            }
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Self::Error> {
+        let Ok(id) = key.parse::<i64>() else {
+            return Ok(());
+        };
+        let statement = format!("DELETE FROM {} WHERE id = $1", self.table);
+        match self.mode {
+            PostgresStorageMode::Bytea => {
+                sqlx::query(&statement)
+                    .bind(id)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|err| sql_error("failed to delete file row", err))?;
+            }
+            PostgresStorageMode::LargeObject => {
+                let mut tx = self.begin().await?;
+                let oid: Option<Oid> = sqlx::query_scalar(&format!("{statement} RETURNING data"))
+                    .bind(id)
+                    .fetch_optional(&mut *tx)
+                    .await
+                    .map_err(|err| sql_error("failed to delete file row", err))?;
+                if let Some(oid) = oid {
+                    // A `lo_manage` trigger may already have unlinked it.
+                    sqlx::query(
+                        "SELECT lo_unlink(oid) FROM pg_largeobject_metadata WHERE oid = $1",
+                    )
+                    .bind(oid)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|err| sql_error("failed to unlink large object", err))?;
+                }
+                commit(tx).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn metadata(&self, key: &str) -> Result<Option<FileMetadata>, Self::Error> {
+        let Ok(id) = key.parse::<i64>() else {
+            return Ok(None);
+        };
+        let statement = format!(
+            "SELECT size, content_type FROM {} WHERE id = $1",
+            self.table
+        );
+        let row: Option<(i64, String)> = sqlx::query_as(&statement)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|err| sql_error("failed to read file row", err))?;
+        Ok(row.map(|(size, content_type)| FileMetadata {
+            size: size.try_into().unwrap_or_default(),
+            content_type: content_type.parse().ok(),
+            modified: None,
+        }))
+    }
+}
+
+/// Streams a large object in [`LARGE_OBJECT_READ_SIZE`] slices.
+fn read_large_object(pool: PgPool, oid: Oid) -> BoxStream<'static, Result<Bytes, StorageError>> {
+    Box::pin(futures::stream::try_unfold(Some(0i64), move |offset| {
+        let pool = pool.clone();
+        async move {
+            let Some(offset) = offset else {
+                return Ok(None);
+            };
+            let chunk: Vec<u8> = sqlx::query_scalar("SELECT lo_get($1, $2, $3)")
+                .bind(oid)
+                .bind(offset)
+                .bind(LARGE_OBJECT_READ_SIZE)
+                .fetch_one(&pool)
+                .await
+                .map_err(|err| sql_error("failed to read large object", err))?;
+            if chunk.is_empty() {
+                return Ok(None);
+            }
+            let next = (chunk.len() == LARGE_OBJECT_READ_SIZE as usize)
+                .then(|| offset + chunk.len() as i64);
+            Ok(Some((Bytes::from(chunk), next)))
+        }
+    }))
+}
+
+fn read_chunk(chunk: Result<Bytes, MulterError>) -> Result<Bytes, StorageError> {
+    chunk.map_err(StorageError::Stream)
+}
+
+async fn commit(tx: Transaction<'static, Postgres>) -> Result<(), StorageError> {
+    tx.commit()
+        .await
+        .map_err(|err| sql_error("failed to commit transaction", err))
+}
+
+fn sql_error(context: &str, err: sqlx::Error) -> StorageError {
+    StorageError::backend(context, err)
+}
@@ -0,0 +1,551 @@
+use std::fmt::{self, Write as _};
+
+use aws_sdk_s3::{
+    primitives::ByteStream,
+    types::{CompletedMultipartUpload, CompletedPart, ObjectCannedAcl},
+    Client,
+};
+use bytes::{Bytes, BytesMut};
+
+use super::{
+    fill_part, BoxStream, FileMeta, FileMetadata, FilenamePolicy, FilenameStrategy, StorageEngine,
+    StorageEngineExt, StoreContext, StoredFile,
+};
+use crate::{MulterError, StorageError};
+
+/// Smallest part size S3 accepts for every part of a multipart upload but the last.
+pub const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+
+/// Default size of the parts a large file is uploaded in.
+pub const DEFAULT_PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// `Content-Disposition` stored with each uploaded object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum ContentDisposition {
+    /// Store no `Content-Disposition`.
+    #[default]
+    None,
+    /// `inline`, with the original filename when present.
+    Inline,
+    /// `attachment`, with the original filename when present, so browsers
+    /// download the object instead of displaying it.
+    Attachment,
+}
+
+impl ContentDisposition {
+    fn header(self, file_name: Option<&str>) -> Option<String> {
+        let kind = match self {
+            Self::None => return None,
+            Self::Inline => "inline",
+            Self::Attachment => "attachment",
+        };
+        let Some(file_name) = file_name else {
+            return Some(kind.to_owned());
+        };
+
+        // RFC 6266: an ASCII fallback plus the exact name in RFC 5987 encoding.
+        let fallback: String = file_name
+            .chars()
+            .map(|c| {
+                if c == ' ' || (c.is_ascii_graphic() && c != '"' && c != '\\') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let mut encoded = String::new();
+        for byte in file_name.bytes() {
+            if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+                encoded.push(char::from(byte));
+            } else {
+                let _ = write!(encoded, "%{byte:02X}");
+            }
+        }
+        Some(format!(
+            "{kind}; filename=\"{fallback}\"; filename*=UTF-8''{encoded}"
+        ))
+    }
+}
+
+/// Builder for [`S3Storage`].
+#[derive(Debug, Clone)]
+pub struct S3StorageBuilder {
+    client: Client,
+    bucket: String,
+    prefix: String,
+    acl: Option<ObjectCannedAcl>,
+    content_disposition: ContentDisposition,
+    part_size: usize,
+    strategy: FilenameStrategy,
+    policy: FilenamePolicy,
+}
+
+impl S3StorageBuilder {
+    /// Sets the prefix prepended to every object key, such as `uploads/`.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Sets the canned ACL applied to uploaded objects.
+    pub fn acl(mut self, acl: ObjectCannedAcl) -> Self {
+        self.acl = Some(acl);
+        self
+    }
+
+    /// Sets the `Content-Disposition` stored with uploaded objects.
+    pub fn content_disposition(mut self, content_disposition: ContentDisposition) -> Self {
+        self.content_disposition = content_disposition;
+        self
+    }
+
+    /// Sets the size of the parts a large file is uploaded in.
+    ///
+    /// Files up to this size are sent with a single `PutObject`; larger
+    /// files use a multipart upload, buffering one part at a time. Must be
+    /// at least [`MIN_PART_SIZE`].
+    pub fn part_size(mut self, part_size: usize) -> Self {
+        self.part_size = part_size;
+        self
+    }
+
+    /// Sets how object names are derived from uploaded filenames.
+    ///
+    /// Defaults to [`FilenameStrategy::Random`]. Objects stored under a name
+    /// kept from the client replace existing objects with the same key.
+    pub fn filename(mut self, strategy: FilenameStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets the policy sanitizing object names.
+    pub fn filename_policy(mut self, policy: FilenamePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Builds a validated S3 storage backend.
+    pub fn build(self) -> Result<S3Storage, StorageError> {
+        if self.bucket.trim().is_empty() {
+            return Err(StorageError::new("s3 storage bucket cannot be empty"));
+        }
+        if self.part_size < MIN_PART_SIZE {
+            return Err(StorageError::new(format!(
+                "s3 storage part size must be at least {MIN_PART_SIZE} bytes"
+            )));
+        }
+
+        Ok(S3Storage {
+            client: self.client,
+            bucket: self.bucket,
+            prefix: self.prefix,
+            acl: self.acl,
+            content_disposition: self.content_disposition,
+            part_size: self.part_size,
+            strategy: self.strategy,
+            policy: self.policy,
+        })
+    }
+}
+
+/// Storage engine streaming files to an Amazon S3 compatible bucket.
+///
+/// [`StoredFile::storage_key`] is the object key.
+///
+/// ```rust
+/// use multigear::{
+///     storage::s3::{ContentDisposition, S3Storage},
+///     Multer,
+/// };
+///
+/// # fn run(config: aws_sdk_s3::Config) -> Result<(), Box<dyn std::error::Error>> {
+/// let storage = S3Storage::builder(aws_sdk_s3::Client::from_conf(config), "uploads")
+///     .prefix("avatars/")
+///     .content_disposition(ContentDisposition::Attachment)
+///     .build()?;
+/// let multer = Multer::builder().storage(storage).build()?;
+/// # drop(multer);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct S3Storage {
+    client: Client,
+    bucket: String,
+    prefix: String,
+    acl: Option<ObjectCannedAcl>,
+    content_disposition: ContentDisposition,
+    part_size: usize,
+    strategy: FilenameStrategy,
+    policy: FilenamePolicy,
+}
+
+impl S3Storage {
+    /// Creates a builder storing objects in `bucket` through `client`.
+    pub fn builder(client: Client, bucket: impl Into<String>) -> S3StorageBuilder {
+        S3StorageBuilder {
+            client,
+            bucket: bucket.into(),
+            prefix: String::new(),
+            acl: None,
+            content_disposition: ContentDisposition::None,
+            part_size: DEFAULT_PART_SIZE,
+            strategy: FilenameStrategy::Random,
+            policy: FilenamePolicy::default(),
+        }
+    }
+
+    /// Returns the bucket objects are stored in.
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    fn object_key(&self, file_name: Option<&str>) -> String {
+        let name = self.strategy.output_name(file_name, &self.policy);
+        format!("{}{name}", self.prefix)
+    }
+
+    /// Returns whether `key` could have been stored by this backend.
+    ///
+    /// Keys may come from clients, so objects outside the prefix are treated
+    /// as missing.
+    fn owns_key(&self, key: &str) -> bool {
+        key.len() > self.prefix.len() && key.starts_with(&self.prefix)
+    }
+
+    async fn put_object(
+        &self,
+        key: &str,
+        meta: &FileMeta,
+        body: Bytes,
+    ) -> Result<(), StorageError> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .content_type(&meta.content_type)
+            .set_acl(self.acl.clone())
+            .set_content_disposition(self.content_disposition.header(meta.file_name.as_deref()))
+            .body(ByteStream::from(body))
+            .send()
+            .await
+            .map_err(|err| sdk_error("failed to put object", err))?;
+        Ok(())
+    }
+
+    /// Uploads `first` and the rest of `stream` as a multipart upload.
+    async fn upload_parts(
+        &self,
+        key: &str,
+        meta: &FileMeta,
+        first: Bytes,
+        buffer: &mut BytesMut,
+        stream: &mut BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<u64, StorageError> {
+        let created = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(key)
+            .content_type(&meta.content_type)
+            .set_acl(self.acl.clone())
+            .set_content_disposition(self.content_disposition.header(meta.file_name.as_deref()))
+            .send()
+            .await
+            .map_err(|err| sdk_error("failed to create multipart upload", err))?;
+        let upload_id = created
+            .upload_id()
+            .ok_or_else(|| StorageError::new("s3 returned no multipart upload id"))?
+            .to_owned();
+
+        let mut upload = MultipartUploadGuard {
+            client: Some(self.client.clone()),
+            bucket: self.bucket.clone(),
+            key: key.to_owned(),
+            upload_id: upload_id.clone(),
+        };
+        let mut parts = Vec::new();
+        let mut written = 0u64;
+        let mut part = first;
+
+        loop {
+            written = written.saturating_add(part.len() as u64);
+            parts.push(
+                self.upload_part(key, &upload_id, parts.len() + 1, part)
+                    .await?,
+            );
+            match fill_part(stream, buffer, self.part_size).await? {
+                Some(next) => part = next,
+                None => break,
+            }
+        }
+        // The held-back remainder is never empty and forms the last part.
+        let last = buffer.split().freeze();
+        written = written.saturating_add(last.len() as u64);
+        parts.push(
+            self.upload_part(key, &upload_id, parts.len() + 1, last)
+                .await?,
+        );
+
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket)
+            .key(key)
+            .upload_id(&upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(|err| sdk_error("failed to complete multipart upload", err))?;
+        upload.disarm();
+        Ok(written)
+    }
+
+    async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: usize,
+        body: Bytes,
+    ) -> Result<CompletedPart, StorageError> {
+        let part_number = i32::try_from(part_number)
+            .map_err(|_| StorageError::new("s3 multipart upload has too many parts"))?;
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            key = key,
+            part_number = part_number,
+            size = body.len(),
+            "s3 storage: uploading part"
+        );
+        let uploaded = self
+            .client
+            .upload_part()
+            .bucket(&self.bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .part_number(part_number)
+            .body(ByteStream::from(body))
+            .send()
+            .await
+            .map_err(|err| sdk_error("failed to upload part", err))?;
+        Ok(CompletedPart::builder()
+            .set_e_tag(uploaded.e_tag().map(ToOwned::to_owned))
+            .part_number(part_number)
+            .build())
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngine for S3Storage {
+    type Output = StoredFile;
+    type Error = StorageError;
+
+    async fn store(
+        &self,
+        meta: FileMeta,
+        _ctx: &StoreContext,
+        mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        let key = self.object_key(meta.file_name.as_deref());
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            file_name = meta.file_name.as_deref().unwrap_or("<none>"),
+            bucket = self.bucket.as_str(),
+            key = key.as_str(),
+            "s3 storage: begin streaming store"
+        );
+
+        // Small files need one request; larger ones stream part by part.
+        let mut buffer = BytesMut::new();
+        let first = fill_part(&mut stream, &mut buffer, self.part_size).await?;
+        let size = match first {
+            Some(first) => {
+                self.upload_parts(&key, &meta, first, &mut buffer, &mut stream)
+                    .await?
+            }
+            None => {
+                let body = buffer.split().freeze();
+                let size = body.len() as u64;
+                self.put_object(&key, &meta, body).await?;
+                size
+            }
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            key = key.as_str(),
+            size = size,
+            "s3 storage: completed store"
+        );
+
+        Ok(StoredFile {
+            storage_key: key,
+            field_name: meta.field_name,
+            file_name: meta.file_name,
+            content_type: meta
+                .content_type
+                .parse()
+                .unwrap_or(mime::APPLICATION_OCTET_STREAM),
+            sniffed_content_type: meta
+                .sniffed_content_type
+                .as_deref()
+                .and_then(|sniffed| sniffed.parse().ok()),
+            size,
+            path: None,
+        })
+    }
+
+    async fn abort(&self, output: Self::Output) -> Result<(), Self::Error> {
+        self.delete(&output.storage_key).await
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngineExt for S3Storage {
+    async fn open(
+        &self,
+        key: &str,
+    ) -> Result<Option<BoxStream<'static, Result<Bytes, Self::Error>>>, Self::Error> {
+        if !self.owns_key(key) {
+            return Ok(None);
+        }
+        let output = match self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+        {
+            Ok(output) => output,
+            Err(err)
+                if err
+                    .as_service_error()
+                    .is_some_and(|err| err.is_no_such_key()) =>
+            {
+                return Ok(None)
+            }
+            Err(err) => return Err(sdk_error("failed to get object", err)),
+        };
+        let body = futures::stream::try_unfold(output.body, |mut body| async move {
+            let chunk = body
+                .try_next()
+                .await
+                .map_err(|err| sdk_error("failed to read object", err))?;
+            Ok(chunk.map(|chunk| (chunk, body)))
+        });
+        Ok(Some(Box::pin(body)))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Self::Error> {
+        if !self.owns_key(key) {
+            return Ok(());
+        }
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|err| sdk_error("failed to delete object", err))?;
+        Ok(())
+    }
+
+    async fn metadata(&self, key: &str) -> Result<Option<FileMetadata>, Self::Error> {
+        if !self.owns_key(key) {
+            return Ok(None);
+        }
+        let output = match self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+        {
+            Ok(output) => output,
+            Err(err) if err.as_service_error().is_some_and(|err| err.is_not_found()) => {
+                return Ok(None)
+            }
+            Err(err) => return Err(sdk_error("failed to head object", err)),
+        };
+        Ok(Some(FileMetadata {
+            size: output
+                .content_length()
+                .and_then(|size| u64::try_from(size).ok())
+                .unwrap_or_default(),
+            content_type: output
+                .content_type()
+                .and_then(|content_type| content_type.parse().ok()),
+            modified: output
+                .last_modified()
+                .and_then(|modified| std::time::SystemTime::try_from(*modified).ok()),
+        }))
+    }
+}
+
+/// Aborts an unfinished multipart upload so S3 discards its stored parts.
+///
+/// Covers both early error returns and the store future being dropped,
+/// for example when a request is cancelled mid-upload.
+struct MultipartUploadGuard {
+    client: Option<Client>,
+    bucket: String,
+    key: String,
+    upload_id: String,
+}
+
+impl MultipartUploadGuard {
+    fn disarm(&mut self) {
+        self.client = None;
+    }
+}
+
+impl Drop for MultipartUploadGuard {
+    fn drop(&mut self) {
+        let Some(client) = self.client.take() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            key = self.key.as_str(),
+            "s3 storage: aborting unfinished multipart upload"
+        );
+        let request = client
+            .abort_multipart_upload()
+            .bucket(std::mem::take(&mut self.bucket))
+            .key(std::mem::take(&mut self.key))
+            .upload_id(std::mem::take(&mut self.upload_id));
+        runtime.spawn(async move {
+            let _ = request.send().await;
+        });
+    }
+}
+
+impl fmt::Debug for MultipartUploadGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultipartUploadGuard")
+            .field("bucket", &self.bucket)
+            .field("key", &self.key)
+            .field("upload_id", &self.upload_id)
+            .finish()
+    }
+}
+
+fn sdk_error(context: &str, err: impl std::error::Error + Send + Sync + 'static) -> StorageError {
+    StorageError::backend(context, err)
+}
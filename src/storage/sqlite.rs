@@ -0,0 +1,310 @@
+use std::path::{Path, PathBuf};
+
+use bytes::{Bytes, BytesMut};
+use futures::StreamExt;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
+    SqlitePool,
+};
+
+use super::{
+    is_table_name, BoxStream, FileMeta, FileMetadata, StorageEngine, StorageEngineExt,
+    StoreContext, StoredFile,
+};
+use crate::{MulterError, StorageError};
+
+/// Default table file rows are inserted into.
+pub const DEFAULT_TABLE: &str = "multigear_files";
+
+/// Default cap on the size of a stored file.
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Largest blob SQLite stores with its default `SQLITE_MAX_LENGTH`.
+pub const MAX_BLOB_SIZE: u64 = 1_000_000_000;
+
+/// Builder for [`SqliteStorage`].
+#[derive(Debug, Clone)]
+pub struct SqliteStorageBuilder {
+    path: PathBuf,
+    table: String,
+    max_file_size: u64,
+    max_connections: u32,
+}
+
+impl SqliteStorageBuilder {
+    /// Sets the table file rows are inserted into.
+    ///
+    /// Defaults to [`DEFAULT_TABLE`].
+    pub fn table(mut self, table: impl Into<String>) -> Self {
+        self.table = table.into();
+        self
+    }
+
+    /// Sets the largest file stored, in bytes.
+    ///
+    /// Files are buffered in memory before they are inserted; larger files
+    /// fail as soon as they cross the cap. Defaults to
+    /// [`DEFAULT_MAX_FILE_SIZE`] and may not exceed [`MAX_BLOB_SIZE`].
+    pub fn max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Sets the size of the connection pool.
+    pub fn max_connections(mut self, max_connections: u32) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+
+    /// Opens the database in WAL mode, creating the file and the table when
+    /// missing, and builds the storage backend.
+    pub async fn build(self) -> Result<SqliteStorage, StorageError> {
+        if !is_table_name(&self.table) {
+            return Err(StorageError::new(format!(
+                "sqlite storage table `{}` must be an unquoted identifier, optionally schema qualified",
+                self.table
+            )));
+        }
+        if self.max_file_size == 0 || self.max_file_size > MAX_BLOB_SIZE {
+            return Err(StorageError::new(format!(
+                "sqlite storage max file size must be between 1 and {MAX_BLOB_SIZE} bytes"
+            )));
+        }
+        if self.max_connections == 0 {
+            return Err(StorageError::new(
+                "sqlite storage max connections must be greater than zero",
+            ));
+        }
+
+        let options = SqliteConnectOptions::new()
+            .filename(&self.path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(self.max_connections)
+            .connect_with(options)
+            .await
+            .map_err(|err| sql_error("failed to open database", err))?;
+
+        let statement = format!(
+            "CREATE TABLE IF NOT EXISTS {} (\
+             id INTEGER PRIMARY KEY AUTOINCREMENT, \
+             field_name TEXT NOT NULL, \
+             file_name TEXT, \
+             content_type TEXT NOT NULL, \
+             size INTEGER NOT NULL, \
+             data BLOB NOT NULL)",
+            self.table
+        );
+        sqlx::query(&statement)
+            .execute(&pool)
+            .await
+            .map_err(|err| sql_error("failed to create table", err))?;
+
+        Ok(SqliteStorage {
+            pool,
+            table: self.table,
+            max_file_size: self.max_file_size,
+        })
+    }
+}
+
+/// Storage engine inserting each file as a BLOB row of a SQLite database,
+/// for single-binary deployments without a separate file store.
+///
+/// The database runs in WAL mode so reads are not blocked by uploads.
+/// [`StoredFile::storage_key`] is the inserted row's `id`, as text.
+///
+/// ```rust
+/// use multigear::{Multer, SqliteStorage};
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let storage = SqliteStorage::builder("uploads.db")
+///     .max_file_size(4 * 1024 * 1024)
+///     .build()
+///     .await?;
+/// let multer = Multer::builder().storage(storage).build()?;
+/// # drop(multer);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SqliteStorage {
+    pool: SqlitePool,
+    table: String,
+    max_file_size: u64,
+}
+
+impl SqliteStorage {
+    /// Creates a builder storing files in the database at `path`.
+    pub fn builder(path: impl AsRef<Path>) -> SqliteStorageBuilder {
+        SqliteStorageBuilder {
+            path: path.as_ref().to_path_buf(),
+            table: DEFAULT_TABLE.to_owned(),
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            max_connections: 4,
+        }
+    }
+
+    /// Returns the connection pool, for reading stored files back.
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    /// Returns the table file rows are inserted into.
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    fn too_large(&self) -> StorageError {
+        StorageError::new(format!(
+            "file exceeds the sqlite storage cap of {} bytes",
+            self.max_file_size
+        ))
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngine for SqliteStorage {
+    type Output = StoredFile;
+    type Error = StorageError;
+
+    async fn store(
+        &self,
+        meta: FileMeta,
+        _ctx: &StoreContext,
+        mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            file_name = meta.file_name.as_deref().unwrap_or("<none>"),
+            table = self.table.as_str(),
+            "sqlite storage: begin streaming store"
+        );
+
+        // Refuse oversized files up front when the client declared a length.
+        if meta
+            .size_hint
+            .is_some_and(|size_hint| size_hint > self.max_file_size)
+        {
+            return Err(self.too_large());
+        }
+
+        let mut body = BytesMut::new();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(StorageError::Stream)?;
+            if (body.len() + chunk.len()) as u64 > self.max_file_size {
+                return Err(self.too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let size = body.len() as u64;
+
+        let statement = format!(
+            "INSERT INTO {} (field_name, file_name, content_type, size, data) \
+             VALUES (?1, ?2, ?3, ?4, ?5) RETURNING CAST(id AS TEXT)",
+            self.table
+        );
+        let id: String = sqlx::query_scalar(&statement)
+            .bind(&meta.field_name)
+            .bind(meta.file_name.as_deref())
+            .bind(&meta.content_type)
+            .bind(size as i64)
+            .bind(body.as_ref())
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|err| sql_error("failed to insert file row", err))?;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            id = id.as_str(),
+            size = size,
+            "sqlite storage: completed store"
+        );
+
+        Ok(StoredFile {
+            storage_key: id,
+            field_name: meta.field_name,
+            file_name: meta.file_name,
+            content_type: meta
+                .content_type
+                .parse()
+                .unwrap_or(mime::APPLICATION_OCTET_STREAM),
+            sniffed_content_type: meta
+                .sniffed_content_type
+                .as_deref()
+                .and_then(|sniffed| sniffed.parse().ok()),
+            size,
+            path: None,
+        })
+    }
+
+    async fn abort(&self, output: Self::Output) -> Result<(), Self::Error> {
+        self.delete(&output.storage_key).await
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngineExt for SqliteStorage {
+    async fn open(
+        &self,
+        key: &str,
+    ) -> Result<Option<BoxStream<'static, Result<Bytes, Self::Error>>>, Self::Error> {
+        let Ok(id) = key.parse::<i64>() else {
+            return Ok(None);
+        };
+        let statement = format!("SELECT data FROM {} WHERE id = ?1", self.table);
+        let data: Option<Vec<u8>> = sqlx::query_scalar(&statement)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|err| sql_error("failed to read file row", err))?;
+        Ok(
+            data.map(|data| -> BoxStream<'static, Result<Bytes, StorageError>> {
+                Box::pin(futures::stream::once(async move { Ok(Bytes::from(data)) }))
+            }),
+        )
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Self::Error> {
+        let Ok(id) = key.parse::<i64>() else {
+            return Ok(());
+        };
+        let statement = format!("DELETE FROM {} WHERE id = ?1", self.table);
+        sqlx::query(&statement)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|err| sql_error("failed to delete file row", err))?;
+        Ok(())
+    }
+
+    async fn metadata(&self, key: &str) -> Result<Option<FileMetadata>, Self::Error> {
+        let Ok(id) = key.parse::<i64>() else {
+            return Ok(None);
+        };
+        let statement = format!(
+            "SELECT size, content_type FROM {} WHERE id = ?1",
+            self.table
+        );
+        let row: Option<(i64, String)> = sqlx::query_as(&statement)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|err| sql_error("failed to read file row", err))?;
+        Ok(row.map(|(size, content_type)| FileMetadata {
+            size: size.try_into().unwrap_or_default(),
+            content_type: content_type.parse().ok(),
+            modified: None,
+        }))
+    }
+}
+
+fn sql_error(context: &str, err: sqlx::Error) -> StorageError {
+    StorageError::backend(context, err)
+}
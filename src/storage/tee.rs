@@ -0,0 +1,271 @@
+use std::{fmt, future::Future, sync::Arc};
+
+use bytes::Bytes;
+use futures::{channel::mpsc, SinkExt, StreamExt};
+
+use super::{BoxStream, FileMeta, StorageEngine, StoreContext};
+use crate::{MulterError, StorageError};
+
+/// Chunks buffered per backend before the faster one waits for the slower.
+const CHANNEL_CAPACITY: usize = 8;
+
+/// The output of the backend that succeeded when the other one failed.
+#[derive(Debug)]
+pub enum TeeHalf<A, B> {
+    /// The first backend stored the file.
+    First(A),
+    /// The second backend stored the file.
+    Second(B),
+}
+
+/// Async hook removing the stored half of a file whose other half failed.
+///
+/// Without a hook [`TeeStorage`] keeps the surviving copy. Closures taking a
+/// [`TeeHalf`] and returning a future implement this trait:
+///
+/// ```rust
+/// use multigear::{DiskStorage, MemoryStorage, StorageError, StoredFile, TeeHalf, TeeStorage};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let disk = DiskStorage::builder().destination("/tmp/uploads").build()?;
+/// let storage = TeeStorage::new(disk, MemoryStorage::new()).on_partial_failure(
+///     |half: TeeHalf<StoredFile, StoredFile>| async move {
+///         if let TeeHalf::First(file) = half {
+///             if let Some(path) = file.path {
+///                 tokio::fs::remove_file(path)
+///                     .await
+///                     .map_err(|err| StorageError::io("failed to remove file", err))?;
+///             }
+///         }
+///         Ok(())
+///     },
+/// );
+/// # drop(storage);
+/// # Ok(())
+/// # }
+/// ```
+#[async_trait::async_trait]
+pub trait TeeCleanup<A, B>: Send + Sync + 'static {
+    /// Removes the copy stored by the backend that succeeded.
+    async fn cleanup(&self, half: TeeHalf<A, B>) -> Result<(), StorageError>;
+}
+
+#[async_trait::async_trait]
+impl<A, B, F, Fut> TeeCleanup<A, B> for F
+where
+    A: Send + 'static,
+    B: Send + 'static,
+    F: Fn(TeeHalf<A, B>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), StorageError>> + Send,
+{
+    async fn cleanup(&self, half: TeeHalf<A, B>) -> Result<(), StorageError> {
+        self(half).await
+    }
+}
+
+/// Storage wrapper writing every file to two backends concurrently.
+///
+/// The body is streamed once and fanned out to both backends, so the faster
+/// one runs at most a few chunks ahead of the slower. A store succeeds only
+/// when both backends succeed. When just one fails, the other still receives
+/// the whole file and its output is handed to the
+/// [`on_partial_failure`](Self::on_partial_failure) hook, or kept when none is
+/// set; the store fails either way.
+///
+/// ```rust
+/// use multigear::{MemoryStorage, Multer, TeeStorage};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let storage = TeeStorage::new(MemoryStorage::new(), MemoryStorage::new());
+/// let multer = Multer::builder().storage(storage).build()?;
+/// # drop(multer);
+/// # Ok(())
+/// # }
+/// ```
+pub struct TeeStorage<A: StorageEngine, B: StorageEngine> {
+    first: A,
+    second: B,
+    cleanup: Option<Arc<dyn TeeCleanup<A::Output, B::Output>>>,
+}
+
+impl<A: StorageEngine, B: StorageEngine> TeeStorage<A, B> {
+    /// Creates a wrapper storing every file in both `first` and `second`.
+    pub fn new(first: A, second: B) -> Self {
+        Self {
+            first,
+            second,
+            cleanup: None,
+        }
+    }
+
+    /// Sets the hook removing the surviving copy when one backend fails.
+    pub fn on_partial_failure(mut self, cleanup: impl TeeCleanup<A::Output, B::Output>) -> Self {
+        self.cleanup = Some(Arc::new(cleanup));
+        self
+    }
+
+    /// Returns the first backend.
+    pub fn first(&self) -> &A {
+        &self.first
+    }
+
+    /// Returns the second backend.
+    pub fn second(&self) -> &B {
+        &self.second
+    }
+
+    async fn partial_failure(
+        &self,
+        half: TeeHalf<A::Output, B::Output>,
+        failed: &str,
+        err: impl std::error::Error + Send + Sync + 'static,
+    ) -> StorageError {
+        let mut context = format!("{failed} storage failed");
+        if let Some(cleanup) = &self.cleanup {
+            if let Err(cleanup_err) = cleanup.cleanup(half).await {
+                context.push_str(&format!(" (cleanup failed: {cleanup_err})"));
+            }
+        }
+        StorageError::backend(context, err)
+    }
+}
+
+impl<A, B> Clone for TeeStorage<A, B>
+where
+    A: StorageEngine + Clone,
+    B: StorageEngine + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            first: self.first.clone(),
+            second: self.second.clone(),
+            cleanup: self.cleanup.clone(),
+        }
+    }
+}
+
+impl<A, B> fmt::Debug for TeeStorage<A, B>
+where
+    A: StorageEngine + fmt::Debug,
+    B: StorageEngine + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TeeStorage")
+            .field("first", &self.first)
+            .field("second", &self.second)
+            .field("cleanup", &self.cleanup.is_some())
+            .finish()
+    }
+}
+
+/// Outputs of both backends of a [`TeeStorage`].
+#[derive(Debug, Clone)]
+pub struct TeeFile<A, B> {
+    /// Output of the first backend.
+    pub first: A,
+    /// Output of the second backend.
+    pub second: B,
+}
+
+/// Forwards `stream` to both senders, dropping a sender once its backend has
+/// stopped reading.
+async fn fan_out(
+    mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    mut first: Option<mpsc::Sender<Result<Bytes, MulterError>>>,
+    mut second: Option<mpsc::Sender<Result<Bytes, MulterError>>>,
+) {
+    while let Some(item) = stream.next().await {
+        if first.is_none() && second.is_none() {
+            return;
+        }
+        let (for_first, for_second) = match item {
+            Ok(chunk) => (Ok(chunk.clone()), Ok(chunk)),
+            Err(err) => {
+                // `MulterError` is not `Clone`; the first backend gets the original.
+                let copy = StorageError::new(format!("stream read failed: {err}")).into();
+                (Err(err), Err(copy))
+            }
+        };
+        if let Some(sender) = &mut first {
+            if sender.send(for_first).await.is_err() {
+                first = None;
+            }
+        }
+        if let Some(sender) = &mut second {
+            if sender.send(for_second).await.is_err() {
+                second = None;
+            }
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl<A: StorageEngine, B: StorageEngine> StorageEngine for TeeStorage<A, B> {
+    type Output = TeeFile<A::Output, B::Output>;
+    type Error = StorageError;
+
+    async fn store(
+        &self,
+        meta: FileMeta,
+        ctx: &StoreContext,
+        stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            file_name = meta.file_name.as_deref().unwrap_or("<none>"),
+            "tee storage: begin streaming store"
+        );
+
+        let (first_tx, first_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let (second_tx, second_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let (_, first, second) = futures::join!(
+            fan_out(stream, Some(first_tx), Some(second_tx)),
+            self.first.store(meta.clone(), ctx, Box::pin(first_rx)),
+            self.second.store(meta, ctx, Box::pin(second_rx)),
+        );
+
+        match (first, second) {
+            (Ok(first), Ok(second)) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("tee storage: completed store on both backends");
+                Ok(TeeFile { first, second })
+            }
+            (Ok(first), Err(err)) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(error = %err, "tee storage: second backend failed");
+                Err(self
+                    .partial_failure(TeeHalf::First(first), "second", err)
+                    .await)
+            }
+            (Err(err), Ok(second)) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(error = %err, "tee storage: first backend failed");
+                Err(self
+                    .partial_failure(TeeHalf::Second(second), "first", err)
+                    .await)
+            }
+            (Err(first), Err(second)) => Err(StorageError::backend(
+                format!("both storages failed (second: {second}); first"),
+                first,
+            )),
+        }
+    }
+
+    async fn abort(&self, output: Self::Output) -> Result<(), Self::Error> {
+        let (first, second) = futures::join!(
+            self.first.abort(output.first),
+            self.second.abort(output.second),
+        );
+        match (first, second) {
+            (Ok(()), Ok(())) => Ok(()),
+            (Err(err), Ok(())) => Err(StorageError::backend("first abort failed", err)),
+            (Ok(()), Err(err)) => Err(StorageError::backend("second abort failed", err)),
+            (Err(first), Err(second)) => Err(StorageError::backend(
+                format!("both aborts failed (second: {second}); first"),
+                first,
+            )),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use bytes::Bytes;
+use futures::StreamExt;
+use tempfile::TempPath;
+use tokio::io::AsyncWriteExt;
+
+use super::{BoxStream, FileMeta, StorageEngine, StoreContext, StoredFile};
+use crate::{MulterError, StorageError};
+
+/// Storage engine writing each file to a temporary file that is deleted
+/// when its [`TempFile`] guard drops.
+///
+/// Use it as scratch space for validate-then-move workflows: inspect the
+/// stored file, then [`TempFile::persist`] it to its final location or drop
+/// it to discard it. Files of a failed request are removed as well.
+///
+/// ```rust
+/// use bytes::Bytes;
+/// use futures::stream;
+/// use multigear::{Multer, MulterError, TempfileStorage};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let multer = Multer::builder().storage(TempfileStorage::new()).build()?;
+/// let body = "--X\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n\r\nhello\r\n--X--\r\n";
+/// let mut output = multer
+///     .parse_and_store("X", stream::iter([Ok::<_, MulterError>(Bytes::from(body))]))
+///     .await?;
+///
+/// let file = output.stored_files.remove(0);
+/// let scratch = file.path().to_path_buf();
+/// assert_eq!(std::fs::read(&scratch)?, b"hello");
+/// drop(file);
+/// assert!(!scratch.exists());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TempfileStorage {
+    dir: Option<PathBuf>,
+}
+
+impl TempfileStorage {
+    /// Creates a storage backend writing to the system temporary directory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a storage backend writing temporary files into `dir`.
+    ///
+    /// Keep `dir` on the filesystem files are persisted to, so
+    /// [`TempFile::persist`] can rename instead of copying.
+    pub fn in_dir(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(dir.into()),
+        }
+    }
+
+    /// Returns the directory temporary files are created in.
+    pub fn dir(&self) -> PathBuf {
+        self.dir.clone().unwrap_or_else(std::env::temp_dir)
+    }
+}
+
+/// A stored file that is deleted when dropped unless it is persisted.
+pub struct TempFile {
+    stored: StoredFile,
+    path: TempPath,
+}
+
+impl TempFile {
+    /// Returns the stored file's metadata.
+    ///
+    /// [`StoredFile::path`] and [`StoredFile::storage_key`] name the
+    /// temporary file.
+    pub fn stored(&self) -> &StoredFile {
+        &self.stored
+    }
+
+    /// Returns the path of the temporary file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Moves the file to `path` so it outlives the guard, returning its
+    /// metadata with the new location.
+    ///
+    /// Renames when possible and falls back to copying when `path` is on
+    /// another filesystem. An existing file at `path` is replaced.
+    pub async fn persist(self, path: impl AsRef<Path>) -> Result<StoredFile, StorageError> {
+        let target = path.as_ref().to_path_buf();
+        let Self { mut stored, path } = self;
+
+        if tokio::fs::rename(&path, &target).await.is_ok() {
+            // Nothing is left at the temporary location to delete.
+            let _ = path.keep();
+        } else {
+            // Dropping `path` afterwards removes the temporary copy.
+            tokio::fs::copy(&path, &target)
+                .await
+                .map_err(|err| StorageError::io("failed to persist file", err))?;
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            path = %target.display(),
+            "tempfile storage: persisted file"
+        );
+        stored.storage_key = target.to_string_lossy().into_owned();
+        stored.path = Some(target);
+        Ok(stored)
+    }
+}
+
+impl fmt::Debug for TempFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TempFile")
+            .field("stored", &self.stored)
+            .finish()
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngine for TempfileStorage {
+    type Output = TempFile;
+    type Error = StorageError;
+
+    async fn store(
+        &self,
+        meta: FileMeta,
+        _ctx: &StoreContext,
+        mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        let dir = self.dir();
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            file_name = meta.file_name.as_deref().unwrap_or("<none>"),
+            dir = %dir.display(),
+            "tempfile storage: begin streaming store"
+        );
+
+        let (file, path) = tokio::task::spawn_blocking(move || {
+            tempfile::Builder::new()
+                .prefix("multigear-")
+                .tempfile_in(dir)
+        })
+        .await
+        .map_err(|err| StorageError::backend("failed to create temporary file", err))?
+        .map_err(|err| StorageError::io("failed to create temporary file", err))?
+        .into_parts();
+        // `path` deletes the file if anything below fails.
+        let mut file = tokio::fs::File::from_std(file);
+
+        let mut written = 0u64;
+        while let Some(chunk) = stream.next().await {
+            let bytes = chunk.map_err(StorageError::Stream)?;
+            file.write_all(&bytes)
+                .await
+                .map_err(|err| StorageError::io("failed to write temporary file", err))?;
+            written = written.saturating_add(bytes.len() as u64);
+        }
+        file.flush()
+            .await
+            .map_err(|err| StorageError::io("failed to flush temporary file", err))?;
+        drop(file);
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            size = written,
+            path = %path.display(),
+            "tempfile storage: completed store"
+        );
+
+        Ok(TempFile {
+            stored: StoredFile {
+                storage_key: path.to_string_lossy().into_owned(),
+                field_name: meta.field_name,
+                file_name: meta.file_name,
+                content_type: meta
+                    .content_type
+                    .parse()
+                    .unwrap_or(mime::APPLICATION_OCTET_STREAM),
+                sniffed_content_type: meta
+                    .sniffed_content_type
+                    .as_deref()
+                    .and_then(|sniffed| sniffed.parse().ok()),
+                size: written,
+                path: Some(path.to_path_buf()),
+            },
+            path,
+        })
+    }
+}
@@ -0,0 +1,205 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use futures::StreamExt;
+
+use super::{BoxStream, FileMeta, StorageEngine, StoreContext};
+use crate::{MulterError, StorageError};
+
+/// Builder for [`ThrottledStorage`].
+#[derive(Debug, Clone)]
+pub struct ThrottledStorageBuilder<S> {
+    inner: S,
+    per_part: Option<u64>,
+    global: Option<u64>,
+    burst: Option<u64>,
+}
+
+impl<S> ThrottledStorageBuilder<S> {
+    /// Caps each file at `bytes_per_sec`.
+    pub fn per_part(mut self, bytes_per_sec: u64) -> Self {
+        self.per_part = Some(bytes_per_sec);
+        self
+    }
+
+    /// Caps all files stored through this wrapper and its clones together at
+    /// `bytes_per_sec`.
+    pub fn global(mut self, bytes_per_sec: u64) -> Self {
+        self.global = Some(bytes_per_sec);
+        self
+    }
+
+    /// Sets how many bytes may pass at once after an idle period.
+    ///
+    /// Defaults to one second's worth of each rate.
+    pub fn burst(mut self, bytes: u64) -> Self {
+        self.burst = Some(bytes);
+        self
+    }
+
+    /// Builds a validated throttling storage wrapper.
+    pub fn build(self) -> Result<ThrottledStorage<S>, StorageError> {
+        if self.per_part == Some(0) || self.global == Some(0) {
+            return Err(StorageError::new(
+                "throttled storage rate must be greater than zero",
+            ));
+        }
+        if self.burst == Some(0) {
+            return Err(StorageError::new(
+                "throttled storage burst must be greater than zero",
+            ));
+        }
+
+        Ok(ThrottledStorage {
+            inner: self.inner,
+            per_part: self.per_part,
+            global: self
+                .global
+                .map(|rate| Arc::new(TokenBucket::new(rate, self.burst))),
+            burst: self.burst,
+        })
+    }
+}
+
+/// Storage wrapper limiting how fast files are written to the inner engine.
+///
+/// Chunks are released to the inner engine by token buckets: one per file
+/// when [`per_part`](ThrottledStorageBuilder::per_part) is set, and one
+/// shared by every file when [`global`](ThrottledStorageBuilder::global) is
+/// set. A chunk larger than the bucket passes once the debt it leaves has been
+/// paid back, so chunk sizes need not match the burst.
+///
+/// ```rust
+/// use multigear::{DiskStorage, Multer, ThrottledStorage};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let disk = DiskStorage::builder().destination("/var/uploads").build()?;
+/// let storage = ThrottledStorage::builder(disk)
+///     .per_part(4 * 1024 * 1024)
+///     .global(32 * 1024 * 1024)
+///     .build()?;
+/// let multer = Multer::builder().storage(storage).build()?;
+/// # drop(multer);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ThrottledStorage<S> {
+    inner: S,
+    per_part: Option<u64>,
+    global: Option<Arc<TokenBucket>>,
+    burst: Option<u64>,
+}
+
+impl<S> ThrottledStorage<S> {
+    /// Creates a builder wrapping `inner`; no limit applies until one is set.
+    pub fn builder(inner: S) -> ThrottledStorageBuilder<S> {
+        ThrottledStorageBuilder {
+            inner,
+            per_part: None,
+            global: None,
+            burst: None,
+        }
+    }
+
+    /// Returns the wrapped storage engine.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+}
+
+/// Token bucket that lets callers go into debt and sleep it off.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    fn new(rate: u64, burst: Option<u64>) -> Self {
+        let capacity = burst.unwrap_or(rate) as f64;
+        Self {
+            rate: rate as f64,
+            capacity,
+            state: Mutex::new((capacity, Instant::now())),
+        }
+    }
+
+    /// Takes `bytes` tokens and returns how long to wait before using them.
+    fn take(&self, bytes: usize) -> Duration {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let (tokens, last) = &mut *state;
+        let now = Instant::now();
+        *tokens =
+            (*tokens + now.duration_since(*last).as_secs_f64() * self.rate).min(self.capacity);
+        *last = now;
+        *tokens -= bytes as f64;
+
+        if *tokens < 0.0 {
+            Duration::from_secs_f64(-*tokens / self.rate)
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    async fn acquire(&self, bytes: usize) {
+        let wait = self.take(bytes);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl<S: StorageEngine> StorageEngine for ThrottledStorage<S> {
+    type Output = S::Output;
+    type Error = S::Error;
+
+    async fn store(
+        &self,
+        meta: FileMeta,
+        ctx: &StoreContext,
+        stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            field_name = meta.field_name.as_str(),
+            per_part = self.per_part,
+            global = self.global.as_ref().map(|bucket| bucket.rate),
+            "throttled storage: begin streaming store"
+        );
+
+        let per_part = self
+            .per_part
+            .map(|rate| Arc::new(TokenBucket::new(rate, self.burst)));
+        let global = self.global.clone();
+        let throttled = stream.then(move |item| {
+            let (per_part, global) = (per_part.clone(), global.clone());
+            async move {
+                if let Ok(chunk) = &item {
+                    if let Some(bucket) = per_part {
+                        bucket.acquire(chunk.len()).await;
+                    }
+                    if let Some(bucket) = global {
+                        bucket.acquire(chunk.len()).await;
+                    }
+                }
+                item
+            }
+        });
+
+        self.inner.store(meta, ctx, Box::pin(throttled)).await
+    }
+
+    async fn abort(&self, output: Self::Output) -> Result<(), Self::Error> {
+        self.inner.abort(output).await
+    }
+}
@@ -0,0 +1,122 @@
+//! qs-style bracket notation for text field names.
+
+use serde_json::{Map, Value};
+
+/// Builds a nested JSON value from `(name, value)` text field pairs.
+///
+/// `user[address][street]` nests objects, `tags[]` appends to an array, and
+/// objects whose keys are all numeric (`items[0]`, `items[1]`) become arrays
+/// ordered by index. Repeated plain names collect into an array. When the
+/// same name is used with conflicting shapes, the first shape wins.
+pub(crate) fn structure_fields<'a>(fields: impl IntoIterator<Item = (&'a str, &'a str)>) -> Value {
+    let mut root = Map::new();
+    for (name, value) in fields {
+        let (base, segments) = split_name(name);
+        let entry = root.entry(base.to_owned()).or_insert(Value::Null);
+        insert(entry, &segments, value);
+    }
+    Value::Object(
+        root.into_iter()
+            .map(|(key, value)| (key, arrays_from_indexed_objects(value)))
+            .collect(),
+    )
+}
+
+/// Splits `a[b][]` into `("a", ["b", ""])`; malformed names are kept whole.
+fn split_name(name: &str) -> (&str, Vec<&str>) {
+    let Some(open) = name.find('[').filter(|&open| open > 0) else {
+        return (name, Vec::new());
+    };
+
+    let mut segments = Vec::new();
+    let mut rest = &name[open..];
+    while let Some(inner) = rest.strip_prefix('[') {
+        let Some(close) = inner.find(']') else {
+            return (name, Vec::new());
+        };
+        segments.push(&inner[..close]);
+        rest = &inner[close + 1..];
+    }
+    if !rest.is_empty() {
+        return (name, Vec::new());
+    }
+
+    (&name[..open], segments)
+}
+
+fn insert(target: &mut Value, segments: &[&str], value: &str) {
+    let Some((segment, rest)) = segments.split_first() else {
+        match target {
+            Value::Null => *target = Value::String(value.to_owned()),
+            Value::String(existing) => {
+                let first = Value::String(std::mem::take(existing));
+                *target = Value::Array(vec![first, Value::String(value.to_owned())]);
+            }
+            Value::Array(items) => items.push(Value::String(value.to_owned())),
+            _ => {}
+        }
+        return;
+    };
+
+    if segment.is_empty() {
+        match target {
+            Value::Null => *target = Value::Array(Vec::new()),
+            Value::String(existing) => {
+                *target = Value::Array(vec![Value::String(std::mem::take(existing))]);
+            }
+            _ => {}
+        }
+        if let Value::Array(items) = target {
+            let mut item = Value::Null;
+            insert(&mut item, rest, value);
+            items.push(item);
+        }
+        return;
+    }
+
+    if target.is_null() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(map) = target {
+        let entry = map.entry((*segment).to_owned()).or_insert(Value::Null);
+        insert(entry, rest, value);
+    }
+}
+
+fn arrays_from_indexed_objects(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut indexed = Vec::with_capacity(map.len());
+            for key in map.keys() {
+                match key.parse::<usize>() {
+                    Ok(index) => indexed.push(index),
+                    Err(_) => break,
+                }
+            }
+
+            if !map.is_empty() && indexed.len() == map.len() {
+                let mut items: Vec<(usize, Value)> = indexed
+                    .into_iter()
+                    .zip(map.into_iter().map(|(_, value)| value))
+                    .collect();
+                items.sort_by_key(|(index, _)| *index);
+                return Value::Array(
+                    items
+                        .into_iter()
+                        .map(|(_, value)| arrays_from_indexed_objects(value))
+                        .collect(),
+                );
+            }
+
+            Value::Object(
+                map.into_iter()
+                    .map(|(key, value)| (key, arrays_from_indexed_objects(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => {
+            Value::Array(items.into_iter().map(arrays_from_indexed_objects).collect())
+        }
+        other => other,
+    }
+}
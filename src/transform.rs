@@ -0,0 +1,96 @@
+//! Streaming transformation of file bodies between parser and storage.
+
+use std::{fmt, sync::Arc};
+
+use bytes::Bytes;
+
+use crate::{
+    storage::{BoxStream, FileMeta},
+    MulterError,
+};
+
+/// Rewrites a file body as it streams from the parser into storage, such as
+/// stripping EXIF metadata or re-encoding images.
+///
+/// Transformers registered with [`MulterBuilder::transformer`](crate::MulterBuilder::transformer)
+/// wrap the body stream in registration order, after validators and checksum
+/// verification have seen the client's bytes and before any
+/// [`AvScanner`](crate::AvScanner) and storage see the result. A transformer
+/// may update `meta`, for example the content type of a re-encoded image;
+/// storage receives the updated metadata. Parts a transformer does not
+/// handle should be returned unchanged.
+///
+/// Transformers work chunk by chunk, so whole files are only buffered when
+/// a transformer chooses to.
+///
+/// ```rust
+/// use bytes::Bytes;
+/// use futures::StreamExt;
+/// use multigear::{
+///     transform::PartTransformer, BoxStream, FileMeta, MemoryStorage, Multer, MulterError,
+/// };
+///
+/// /// Normalizes CRLF line endings in plain-text uploads.
+/// struct StripCarriageReturns;
+///
+/// impl PartTransformer for StripCarriageReturns {
+///     fn transform<'a>(
+///         &self,
+///         meta: &mut FileMeta,
+///         body: BoxStream<'a, Result<Bytes, MulterError>>,
+///     ) -> BoxStream<'a, Result<Bytes, MulterError>> {
+///         if meta.content_type != "text/plain" {
+///             return body;
+///         }
+///         Box::pin(body.map(|chunk| {
+///             chunk.map(|chunk| chunk.iter().copied().filter(|byte| *byte != b'\r').collect())
+///         }))
+///     }
+/// }
+///
+/// let multer = Multer::builder()
+///     .storage(MemoryStorage::new())
+///     .transformer(StripCarriageReturns)
+///     .build()
+///     .expect("valid config");
+/// # drop(multer);
+/// ```
+pub trait PartTransformer: Send + Sync + 'static {
+    /// Wraps a file body stream, returning the stream handed on to storage.
+    fn transform<'a>(
+        &self,
+        meta: &mut FileMeta,
+        body: BoxStream<'a, Result<Bytes, MulterError>>,
+    ) -> BoxStream<'a, Result<Bytes, MulterError>>;
+}
+
+/// Ordered transformers shared by a builder and the `Multer` it builds.
+#[derive(Clone, Default)]
+pub(crate) struct TransformerChain {
+    transformers: Vec<Arc<dyn PartTransformer>>,
+}
+
+impl TransformerChain {
+    pub(crate) fn push(&mut self, transformer: impl PartTransformer) {
+        self.transformers.push(Arc::new(transformer));
+    }
+
+    /// Wraps `body` in every transformer, first registered innermost.
+    pub(crate) fn apply<'a>(
+        &self,
+        meta: &mut FileMeta,
+        body: BoxStream<'a, Result<Bytes, MulterError>>,
+    ) -> BoxStream<'a, Result<Bytes, MulterError>> {
+        self.transformers
+            .iter()
+            .fold(body, |body, transformer| transformer.transform(meta, body))
+    }
+}
+
+impl fmt::Debug for TransformerChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransformerChain")
+            .field("transformers", &self.transformers.len())
+            .finish()
+    }
+}
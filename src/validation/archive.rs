@@ -0,0 +1,253 @@
+//! Zip and gzip expansion checks that read declared sizes without extracting.
+
+use crate::{error::ValidationError, storage::FileMeta, validation::PartValidator};
+
+/// Default largest archive [`ArchiveValidator`] buffers to read its declared sizes.
+pub const DEFAULT_ARCHIVE_SCAN_LIMIT: usize = 32 * 1024 * 1024;
+
+/// Default maximum ratio of declared uncompressed size to archive size.
+pub const DEFAULT_MAX_EXPANSION_RATIO: u64 = 100;
+
+const ZIP_LOCAL_HEADER: u32 = 0x0403_4b50;
+const ZIP_CENTRAL_HEADER: u32 = 0x0201_4b50;
+const ZIP_END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+const ZIP64_END_OF_CENTRAL_DIRECTORY: u32 = 0x0606_4b50;
+const ZIP64_LOCATOR: u32 = 0x0706_4b50;
+const ZIP64_EXTRA_FIELD: u16 = 0x0001;
+
+/// [`PartValidator`] that rejects zip and gzip archives whose declared
+/// contents expand beyond configured limits.
+///
+/// Archives are recognized by their magic bytes, whatever their declared
+/// content type. Zip archives are checked against the sizes in their central
+/// directory (including Zip64 records) and gzip files against the
+/// uncompressed size in their trailer, which gzip stores modulo 2^32. Nothing
+/// is decompressed, but the whole archive is buffered, so archives larger than
+/// [`ArchiveValidator::scan_limit`] are rejected.
+///
+/// ```rust
+/// use multigear::{validation::ArchiveValidator, MemoryStorage, Multer};
+///
+/// let multer = Multer::builder()
+///     .storage(MemoryStorage::new())
+///     .validator(
+///         ArchiveValidator::new()
+///             .max_expansion_ratio(50)
+///             .max_entries(1_000)
+///             .max_uncompressed_size(512 * 1024 * 1024),
+///     )
+///     .build()
+///     .expect("valid config");
+/// # drop(multer);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArchiveValidator {
+    max_expansion_ratio: Option<u64>,
+    max_uncompressed_size: Option<u64>,
+    max_entries: Option<u64>,
+    scan_limit: usize,
+}
+
+impl Default for ArchiveValidator {
+    fn default() -> Self {
+        Self {
+            max_expansion_ratio: Some(DEFAULT_MAX_EXPANSION_RATIO),
+            max_uncompressed_size: None,
+            max_entries: None,
+            scan_limit: DEFAULT_ARCHIVE_SCAN_LIMIT,
+        }
+    }
+}
+
+impl ArchiveValidator {
+    /// Creates a validator enforcing [`DEFAULT_MAX_EXPANSION_RATIO`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum ratio of declared uncompressed size to archive size.
+    pub fn max_expansion_ratio(mut self, max_expansion_ratio: u64) -> Self {
+        self.max_expansion_ratio = Some(max_expansion_ratio);
+        self
+    }
+
+    /// Sets the maximum declared uncompressed size in bytes of all entries.
+    pub fn max_uncompressed_size(mut self, max_uncompressed_size: u64) -> Self {
+        self.max_uncompressed_size = Some(max_uncompressed_size);
+        self
+    }
+
+    /// Sets the maximum number of entries in a zip archive.
+    pub fn max_entries(mut self, max_entries: u64) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Sets the largest archive in bytes buffered for inspection.
+    pub fn scan_limit(mut self, scan_limit: usize) -> Self {
+        self.scan_limit = scan_limit;
+        self
+    }
+
+    fn ensure_scanned(&self, head: &[u8]) -> Result<(), ValidationError> {
+        if head.len() > self.scan_limit {
+            return Err(ValidationError::new(format!(
+                "archive is larger than {} bytes and cannot be inspected",
+                self.scan_limit
+            )));
+        }
+        Ok(())
+    }
+
+    fn check(&self, archive_len: usize, summary: ArchiveSummary) -> Result<(), ValidationError> {
+        if let Some(max_entries) = self.max_entries.filter(|max| summary.entries > *max) {
+            return Err(ValidationError::new(format!(
+                "archive has {} entries, more than {max_entries}",
+                summary.entries
+            )));
+        }
+        if let Some(max_size) = self
+            .max_uncompressed_size
+            .filter(|max| summary.uncompressed_size > *max)
+        {
+            return Err(ValidationError::new(format!(
+                "archive expands to {} bytes, more than {max_size}",
+                summary.uncompressed_size
+            )));
+        }
+        if let Some(max_ratio) = self.max_expansion_ratio {
+            let allowed = (archive_len as u64).saturating_mul(max_ratio);
+            if summary.uncompressed_size > allowed {
+                return Err(ValidationError::new(format!(
+                    "archive expands to {} bytes from {archive_len}, beyond a ratio of {max_ratio}",
+                    summary.uncompressed_size
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl PartValidator for ArchiveValidator {
+    async fn validate(&self, _meta: &FileMeta, head: &[u8]) -> Result<(), ValidationError> {
+        let summary = if head.starts_with(&ZIP_LOCAL_HEADER.to_le_bytes())
+            || head.starts_with(&ZIP_END_OF_CENTRAL_DIRECTORY.to_le_bytes())
+        {
+            self.ensure_scanned(head)?;
+            zip_summary(head).ok_or_else(|| ValidationError::new("malformed zip archive"))?
+        } else if head.starts_with(&[0x1f, 0x8b]) {
+            self.ensure_scanned(head)?;
+            gzip_summary(head).ok_or_else(|| ValidationError::new("malformed gzip file"))?
+        } else {
+            return Ok(());
+        };
+
+        self.check(head.len(), summary)
+    }
+
+    fn head_len(&self) -> usize {
+        // One byte past the limit reveals archives too large to inspect.
+        self.scan_limit.saturating_add(1)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ArchiveSummary {
+    entries: u64,
+    uncompressed_size: u64,
+}
+
+fn gzip_summary(file: &[u8]) -> Option<ArchiveSummary> {
+    // 10-byte header plus the 8-byte CRC32 and ISIZE trailer.
+    if file.len() < 18 {
+        return None;
+    }
+    let uncompressed_size = read_u32(file, file.len() - 4)?;
+    Some(ArchiveSummary {
+        entries: 1,
+        uncompressed_size: u64::from(uncompressed_size),
+    })
+}
+
+fn zip_summary(file: &[u8]) -> Option<ArchiveSummary> {
+    let eocd = find_end_of_central_directory(file)?;
+    let mut entries = u64::from(read_u16(file, eocd + 10)?);
+    let mut directory_offset = u64::from(read_u32(file, eocd + 16)?);
+
+    if entries == u64::from(u16::MAX) || directory_offset == u64::from(u32::MAX) {
+        let locator = eocd.checked_sub(20)?;
+        if read_u32(file, locator)? != ZIP64_LOCATOR {
+            return None;
+        }
+        let zip64_eocd = usize::try_from(read_u64(file, locator + 8)?).ok()?;
+        if read_u32(file, zip64_eocd)? != ZIP64_END_OF_CENTRAL_DIRECTORY {
+            return None;
+        }
+        entries = read_u64(file, zip64_eocd + 32)?;
+        directory_offset = read_u64(file, zip64_eocd + 48)?;
+    }
+
+    let mut offset = usize::try_from(directory_offset).ok()?;
+    let mut uncompressed_size = 0u64;
+    for _ in 0..entries {
+        if read_u32(file, offset)? != ZIP_CENTRAL_HEADER {
+            return None;
+        }
+        let mut size = u64::from(read_u32(file, offset + 24)?);
+        let name_len = usize::from(read_u16(file, offset + 28)?);
+        let extra_len = usize::from(read_u16(file, offset + 30)?);
+        let comment_len = usize::from(read_u16(file, offset + 32)?);
+        let extra_start = offset + 46 + name_len;
+        if size == u64::from(u32::MAX) {
+            size = zip64_uncompressed_size(file.get(extra_start..extra_start + extra_len)?)?;
+        }
+        uncompressed_size = uncompressed_size.saturating_add(size);
+        offset = extra_start + extra_len + comment_len;
+    }
+
+    Some(ArchiveSummary {
+        entries,
+        uncompressed_size,
+    })
+}
+
+/// Scans back over the optional trailing comment for the end-of-central-directory record.
+fn find_end_of_central_directory(file: &[u8]) -> Option<usize> {
+    let last = file.len().checked_sub(22)?;
+    let first = last.saturating_sub(usize::from(u16::MAX));
+    (first..=last)
+        .rev()
+        .find(|&offset| read_u32(file, offset) == Some(ZIP_END_OF_CENTRAL_DIRECTORY))
+}
+
+/// Reads the uncompressed size, the first value of a Zip64 extended information field.
+fn zip64_uncompressed_size(mut extra: &[u8]) -> Option<u64> {
+    while extra.len() >= 4 {
+        let id = read_u16(extra, 0)?;
+        let len = usize::from(read_u16(extra, 2)?);
+        if id == ZIP64_EXTRA_FIELD {
+            return read_u64(extra, 4);
+        }
+        extra = extra.get(4 + len..)?;
+    }
+    None
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        bytes.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        bytes.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
@@ -0,0 +1,103 @@
+//! Pre-storage validation of file parts.
+
+use std::{fmt, sync::Arc};
+
+use crate::{error::ValidationError, storage::FileMeta};
+
+/// Archive bomb detection.
+pub mod archive;
+pub use archive::ArchiveValidator;
+
+/// Number of leading body bytes handed to validators unless they ask for more.
+pub const DEFAULT_HEAD_LEN: usize = 512;
+
+/// Async check run on every file part before [`Multer::store`](crate::Multer::store)
+/// hands it to storage.
+///
+/// Validators receive the part metadata and up to [`PartValidator::head_len`]
+/// leading body bytes, read with [`Part::peek`](crate::Part::peek) so storage
+/// still sees the full body. Validators registered with
+/// [`MulterBuilder::validator`](crate::MulterBuilder::validator) run in order
+/// and the first failure rejects the part.
+///
+/// Plain closures taking `(&FileMeta, &[u8])` implement this trait:
+///
+/// ```rust
+/// use multigear::{FileMeta, MemoryStorage, Multer, ValidationError};
+///
+/// let multer = Multer::builder()
+///     .storage(MemoryStorage::new())
+///     .validator(|meta: &FileMeta, head: &[u8]| {
+///         if meta.content_type == "application/pdf" && !head.starts_with(b"%PDF-") {
+///             return Err(ValidationError::new("not a PDF document"));
+///         }
+///         Ok(())
+///     })
+///     .build()
+///     .expect("valid config");
+/// # drop(multer);
+/// ```
+#[async_trait::async_trait]
+pub trait PartValidator: Send + Sync + 'static {
+    /// Checks a file part before it is stored.
+    ///
+    /// `head` is shorter than [`PartValidator::head_len`] only when the body is.
+    async fn validate(&self, meta: &FileMeta, head: &[u8]) -> Result<(), ValidationError>;
+
+    /// Returns how many leading body bytes this validator needs.
+    fn head_len(&self) -> usize {
+        DEFAULT_HEAD_LEN
+    }
+}
+
+#[async_trait::async_trait]
+impl<F> PartValidator for F
+where
+    F: Fn(&FileMeta, &[u8]) -> Result<(), ValidationError> + Send + Sync + 'static,
+{
+    async fn validate(&self, meta: &FileMeta, head: &[u8]) -> Result<(), ValidationError> {
+        self(meta, head)
+    }
+}
+
+/// Ordered validators shared by a builder and the `Multer` it builds.
+#[derive(Clone, Default)]
+pub(crate) struct ValidatorChain {
+    validators: Vec<Arc<dyn PartValidator>>,
+}
+
+impl ValidatorChain {
+    pub(crate) fn push(&mut self, validator: impl PartValidator) {
+        self.validators.push(Arc::new(validator));
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.validators.is_empty()
+    }
+
+    /// Returns the largest head length requested by any validator.
+    pub(crate) fn head_len(&self) -> usize {
+        self.validators
+            .iter()
+            .map(|validator| validator.head_len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Runs every validator in order, stopping at the first failure.
+    pub(crate) async fn run(&self, meta: &FileMeta, head: &[u8]) -> Result<(), ValidationError> {
+        for validator in &self.validators {
+            let len = validator.head_len().min(head.len());
+            validator.validate(meta, &head[..len]).await?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ValidatorChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidatorChain")
+            .field("validators", &self.validators.len())
+            .finish()
+    }
+}
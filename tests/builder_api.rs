@@ -1,5 +1,7 @@
 #![allow(missing_docs)]
 
+use std::time::Duration;
+
 use multigear::{
     ConfigError, Field, Limits, Multer, MulterBuilder, MulterConfig, SelectedFieldKind, Selector,
     UnknownFieldPolicy,
@@ -23,12 +25,10 @@ fn builder_default_is_permissive() {
 
 #[test]
 fn fluent_chaining_sets_expected_configuration() {
-    let limits = Limits {
-        max_file_size: Some(1024),
-        max_files: Some(4),
-        allowed_mime_types: vec!["image/*".to_owned()],
-        ..Limits::default()
-    };
+    let limits = Limits::new()
+        .max_file_size(1024)
+        .max_files(4)
+        .allowed_mime_types(["image/*"]);
 
     let multer = Multer::builder()
         .single("avatar")
@@ -39,11 +39,10 @@ fn fluent_chaining_sets_expected_configuration() {
 
     assert_eq!(
         multer.config(),
-        &MulterConfig {
-            selector: Selector::single("avatar"),
-            unknown_field_policy: UnknownFieldPolicy::Reject,
-            limits,
-        }
+        &MulterConfig::new()
+            .selector(Selector::single("avatar"))
+            .unknown_field_policy(UnknownFieldPolicy::Reject)
+            .limits(limits)
     );
 }
 
@@ -74,7 +73,12 @@ fn fluent_limit_shortcuts_set_expected_values() {
         .max_files(2)
         .max_field_size(20)
         .max_fields(3)
+        .max_parts(4)
         .max_body_size(100)
+        .max_header_size(512)
+        .max_headers_per_part(8)
+        .max_parse_duration(Duration::from_secs(30))
+        .idle_chunk_timeout(Duration::from_secs(5))
         .allowed_mime_types(["image/*", "application/pdf"])
         .build()
         .expect("builder config should validate");
@@ -83,7 +87,18 @@ fn fluent_limit_shortcuts_set_expected_values() {
     assert_eq!(multer.config().limits.max_files, Some(2));
     assert_eq!(multer.config().limits.max_field_size, Some(20));
     assert_eq!(multer.config().limits.max_fields, Some(3));
+    assert_eq!(multer.config().limits.max_parts, Some(4));
     assert_eq!(multer.config().limits.max_body_size, Some(100));
+    assert_eq!(multer.config().limits.max_header_size, Some(512));
+    assert_eq!(multer.config().limits.max_headers_per_part, Some(8));
+    assert_eq!(
+        multer.config().limits.max_parse_duration,
+        Some(Duration::from_secs(30))
+    );
+    assert_eq!(
+        multer.config().limits.idle_chunk_timeout,
+        Some(Duration::from_secs(5))
+    );
     assert_eq!(
         multer.config().limits.allowed_mime_types,
         vec!["image/*".to_owned(), "application/pdf".to_owned()]
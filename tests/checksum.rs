@@ -0,0 +1,115 @@
+#![allow(missing_docs)]
+#![cfg(feature = "checksum")]
+
+use bytes::Bytes;
+use futures::stream;
+use http::HeaderMap;
+use multigear::{checksum, MemoryStorage, Multer, MulterError};
+
+const HELLO_MD5_BASE64: &str = "XrY7u+Ae7tCTyyK7j1rNww==";
+const HELLO_SHA256_HEX: &str = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+
+fn body(checksum_header: &str, payload: &str) -> Vec<Result<Bytes, MulterError>> {
+    let body = format!(
+        "--BOUND\r\nContent-Disposition: form-data; name=\"upload\"; filename=\"hello.txt\"\r\n{checksum_header}\r\n\r\n{payload}\r\n--BOUND--\r\n"
+    );
+    body.into_bytes()
+        .chunks(5)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect()
+}
+
+#[tokio::test]
+async fn matching_part_checksums_are_stored() {
+    let multer = Multer::new(MemoryStorage::new());
+
+    for header in [
+        format!("Content-MD5: {HELLO_MD5_BASE64}"),
+        format!("X-Checksum-SHA256: {HELLO_SHA256_HEX}"),
+    ] {
+        let output = multer
+            .parse_and_store("BOUND", stream::iter(body(&header, "hello world")))
+            .await
+            .expect("matching checksum should be stored");
+        assert_eq!(output.stored_files.len(), 1);
+    }
+}
+
+#[tokio::test]
+async fn mismatched_part_checksums_fail_without_storing() {
+    let storage = MemoryStorage::new();
+    let multer = Multer::new(storage.clone());
+
+    let err = multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter(body(
+                &format!("X-Checksum-SHA256: {HELLO_SHA256_HEX}"),
+                "hello wurld",
+            )),
+        )
+        .await
+        .expect_err("corrupted body should be rejected");
+
+    assert!(matches!(
+        err,
+        MulterError::ChecksumMismatch { field: Some(field), algorithm: "SHA-256", expected, .. }
+            if field == "upload" && expected == HELLO_SHA256_HEX
+    ));
+    assert!(storage.is_empty().await);
+}
+
+#[tokio::test]
+async fn malformed_checksum_headers_are_parse_errors() {
+    let err = Multer::new(MemoryStorage::new())
+        .parse_and_store(
+            "BOUND",
+            stream::iter(body("Content-MD5: not-a-digest", "hello world")),
+        )
+        .await
+        .expect_err("malformed header should be rejected");
+
+    assert!(matches!(err, MulterError::Parse(_)));
+}
+
+#[tokio::test]
+async fn request_body_checksums_cover_the_whole_body() {
+    let raw =
+        "--BOUND\r\nContent-Disposition: form-data; name=\"note\"\r\n\r\nhello\r\n--BOUND--\r\n";
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        checksum::X_CHECKSUM_SHA256,
+        "ffc9cff0f18f0d27d5a2e2d1fa7d0e22fe6ad0ac06f3e27c7c3c2a4a5df1ad6b"
+            .parse()
+            .unwrap(),
+    );
+    let body = stream::iter([Ok::<_, MulterError>(Bytes::from(raw))]);
+    let err = Multer::new(MemoryStorage::new())
+        .parse_and_store(
+            "BOUND",
+            checksum::verify_body(&headers, body).expect("header should parse"),
+        )
+        .await
+        .expect_err("digest of a different body should fail");
+    assert!(matches!(
+        err,
+        MulterError::ChecksumMismatch {
+            field: None,
+            algorithm: "SHA-256",
+            ..
+        }
+    ));
+
+    let unchecked = stream::iter([Ok::<_, MulterError>(Bytes::from(raw))]);
+    let output = Multer::new(MemoryStorage::new())
+        .parse_and_store(
+            "BOUND",
+            checksum::verify_body(&HeaderMap::new(), unchecked).expect("no headers"),
+        )
+        .await
+        .expect("bodies without checksum headers pass");
+    assert_eq!(
+        output.text_fields,
+        vec![("note".to_owned(), "hello".to_owned())]
+    );
+}
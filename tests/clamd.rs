@@ -0,0 +1,90 @@
+#![allow(missing_docs)]
+#![cfg(feature = "clamav")]
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::stream;
+use multigear::{scan::ClamdScanner, MemoryStorage, Multer, MulterError};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+/// Serves one `INSTREAM` request, flagging bodies that contain `EICAR`.
+async fn fake_clamd() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let address = listener.local_addr().expect("address").to_string();
+
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.expect("accept");
+        let mut command = [0u8; 10];
+        socket.read_exact(&mut command).await.expect("command");
+        assert_eq!(&command, b"zINSTREAM\0");
+
+        let mut body = Vec::new();
+        loop {
+            let len = socket.read_u32().await.expect("chunk length") as usize;
+            if len == 0 {
+                break;
+            }
+            let start = body.len();
+            body.resize(start + len, 0);
+            socket.read_exact(&mut body[start..]).await.expect("chunk");
+        }
+
+        let reply: &[u8] = if body.windows(5).any(|window| window == b"EICAR") {
+            b"stream: Eicar-Test-Signature FOUND\0"
+        } else {
+            b"stream: OK\0"
+        };
+        socket.write_all(reply).await.expect("reply");
+    });
+
+    address
+}
+
+fn body(payload: &str) -> Vec<Result<Bytes, MulterError>> {
+    vec![Ok(Bytes::from(format!(
+        "--BOUND\r\nContent-Disposition: form-data; name=\"upload\"; filename=\"a.bin\"\r\n\r\n{payload}\r\n--BOUND--\r\n"
+    )))]
+}
+
+fn multer(scanner: ClamdScanner) -> Multer<MemoryStorage> {
+    Multer::builder()
+        .storage(MemoryStorage::new())
+        .scanner(scanner.timeout(Duration::from_secs(5)))
+        .build()
+        .expect("config should validate")
+}
+
+#[tokio::test]
+async fn clamd_scanner_reports_clean_and_infected_streams() {
+    let clean = multer(ClamdScanner::tcp(fake_clamd().await))
+        .parse_and_store("BOUND", stream::iter(body("quarterly report")))
+        .await
+        .expect("clean file should be stored");
+    assert_eq!(clean.stored_files.len(), 1);
+
+    let err = multer(ClamdScanner::tcp(fake_clamd().await))
+        .parse_and_store("BOUND", stream::iter(body("EICAR test payload")))
+        .await
+        .expect_err("infected file should be rejected");
+    assert!(matches!(
+        err,
+        MulterError::ScanRejected { signature, .. } if signature == "Eicar-Test-Signature"
+    ));
+}
+
+#[tokio::test]
+async fn unreachable_clamd_fails_the_scan() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let address = listener.local_addr().expect("address").to_string();
+    drop(listener);
+
+    let err = multer(ClamdScanner::tcp(address))
+        .parse_and_store("BOUND", stream::iter(body("data")))
+        .await
+        .expect_err("unreachable clamd should fail");
+    assert!(matches!(err, MulterError::Scan { .. }));
+}
@@ -0,0 +1,151 @@
+#![allow(missing_docs)]
+
+#[cfg(feature = "codec")]
+use bytes::{Bytes, BytesMut};
+#[cfg(feature = "codec")]
+use futures::StreamExt;
+#[cfg(feature = "codec")]
+use multigear::{
+    codec::{MultipartDecoder, PartEvent},
+    parser::stream::StreamLimits,
+    MulterError,
+};
+#[cfg(feature = "codec")]
+use tokio::io::AsyncWriteExt;
+#[cfg(feature = "codec")]
+use tokio_util::codec::{Decoder, FramedRead};
+
+#[cfg(feature = "codec")]
+const BODY: &str = concat!(
+    "--XyZ\r\n",
+    "Content-Disposition: form-data; name=\"title\"\r\n",
+    "\r\n",
+    "hello\r\n",
+    "--XyZ\r\n",
+    "Content-Disposition: form-data; name=\"upload\"; filename=\"a.txt\"\r\n",
+    "Content-Type: text/plain\r\n",
+    "\r\n",
+    "file body\r\n",
+    "--XyZ--\r\n",
+    "epilogue"
+);
+
+#[cfg(feature = "codec")]
+async fn collect_events(
+    events: impl futures::Stream<Item = Result<PartEvent, MulterError>>,
+) -> Vec<(String, Option<String>, Bytes)> {
+    futures::pin_mut!(events);
+    let mut parts = Vec::new();
+    let mut body = BytesMut::new();
+    let mut current = None;
+
+    while let Some(event) = events.next().await {
+        match event.expect("event should decode") {
+            PartEvent::Headers(headers) => {
+                assert!(current.is_none(), "headers before previous part ended");
+                let headers = *headers;
+                current = Some((headers.field_name, headers.file_name));
+            }
+            PartEvent::Data(chunk) => body.extend_from_slice(&chunk),
+            PartEvent::PartEnd => {
+                let (name, file_name) = current.take().expect("part end without headers");
+                parts.push((name, file_name, body.split().freeze()));
+            }
+            _ => unreachable!("unexpected event"),
+        }
+    }
+
+    assert!(current.is_none(), "stream ended inside a part");
+    parts
+}
+
+#[cfg(feature = "codec")]
+#[tokio::test]
+async fn framed_read_emits_part_events_over_duplex_io() {
+    for chunk_size in [BODY.len(), 5, 1] {
+        let (mut writer, reader) = tokio::io::duplex(16);
+        let write = tokio::spawn(async move {
+            for chunk in BODY.as_bytes().chunks(chunk_size) {
+                writer.write_all(chunk).await.expect("write");
+            }
+        });
+
+        let decoder = MultipartDecoder::new("XyZ").expect("decoder should initialize");
+        let parts = collect_events(FramedRead::new(reader, decoder)).await;
+        write.await.expect("writer task");
+
+        assert_eq!(
+            parts,
+            vec![
+                ("title".to_owned(), None, Bytes::from_static(b"hello")),
+                (
+                    "upload".to_owned(),
+                    Some("a.txt".to_owned()),
+                    Bytes::from_static(b"file body")
+                ),
+            ]
+        );
+    }
+}
+
+#[cfg(feature = "codec")]
+#[test]
+fn decoder_waits_for_more_input_and_finishes_at_closing_boundary() {
+    let mut decoder = MultipartDecoder::new("XyZ").expect("decoder should initialize");
+    let mut src = BytesMut::from(&BODY.as_bytes()[..20]);
+
+    assert!(decoder.decode(&mut src).expect("partial input").is_none());
+    assert!(src.is_empty(), "decoder takes ownership of buffered input");
+
+    src.extend_from_slice(&BODY.as_bytes()[20..]);
+    let mut events = Vec::new();
+    while let Some(event) = decoder.decode(&mut src).expect("event should decode") {
+        events.push(event);
+    }
+
+    assert_eq!(events.len(), 6);
+    assert!(matches!(events.last(), Some(PartEvent::PartEnd)));
+    assert!(decoder.decode_eof(&mut src).expect("eof").is_none());
+    assert!(decoder.is_finished());
+}
+
+#[cfg(feature = "codec")]
+#[test]
+fn decoder_reports_truncated_input_at_eof() {
+    let mut decoder = MultipartDecoder::new("XyZ").expect("decoder should initialize");
+    let mut src = BytesMut::from(&BODY.as_bytes()[..60]);
+
+    let mut result = Ok(None);
+    for _ in 0..8 {
+        result = decoder.decode_eof(&mut src);
+        if !matches!(result, Ok(Some(_))) {
+            break;
+        }
+    }
+
+    assert!(matches!(result, Err(MulterError::IncompleteStream)));
+}
+
+#[cfg(feature = "codec")]
+#[test]
+fn decoder_enforces_stream_limits() {
+    let limits = StreamLimits::default().with_max_file_size(4);
+    let mut decoder =
+        MultipartDecoder::with_limits("XyZ", limits).expect("decoder should initialize");
+    let mut src = BytesMut::from(BODY.as_bytes());
+
+    let err = loop {
+        match decoder.decode_eof(&mut src) {
+            Ok(Some(_)) => {}
+            Ok(None) => panic!("limit should be enforced"),
+            Err(err) => break err,
+        }
+    };
+    assert!(matches!(
+        err,
+        MulterError::FileSizeLimitExceeded {
+            max_file_size: 4,
+            ..
+        }
+    ));
+}
@@ -1,15 +1,15 @@
 #![allow(missing_docs)]
 
+use std::time::Duration;
+
 use multigear::{
-    ConfigError, Limits, MulterBuilder, MulterConfig, SelectedField, Selector, UnknownFieldPolicy,
+    ByteSize, ConfigError, FieldConstraint, FilenameCharset, Limits, MulterBuilder, MulterConfig,
+    SelectedField, Selector, UnknownFieldPolicy,
 };
 
 #[test]
 fn rejects_empty_single_selector_name() {
-    let config = MulterConfig {
-        selector: Selector::single("   "),
-        ..MulterConfig::default()
-    };
+    let config = MulterConfig::new().selector(Selector::single("   "));
 
     let result = config.validate();
     assert!(matches!(result, Err(ConfigError::EmptyFieldName)));
@@ -17,13 +17,10 @@ fn rejects_empty_single_selector_name() {
 
 #[test]
 fn rejects_array_with_zero_max_count() {
-    let config = MulterConfig {
-        selector: Selector::Array {
-            name: "photos".to_owned(),
-            max_count: Some(0),
-        },
-        ..MulterConfig::default()
-    };
+    let config = MulterConfig::new().selector(Selector::Array {
+        name: "photos".to_owned(),
+        max_count: Some(0),
+    });
 
     let result = config.validate();
     assert!(matches!(
@@ -34,10 +31,7 @@ fn rejects_array_with_zero_max_count() {
 
 #[test]
 fn rejects_empty_fields_selector() {
-    let config = MulterConfig {
-        selector: Selector::fields(Vec::<SelectedField>::new()),
-        ..MulterConfig::default()
-    };
+    let config = MulterConfig::new().selector(Selector::fields(Vec::<SelectedField>::new()));
 
     let result = config.validate();
     assert!(matches!(result, Err(ConfigError::EmptyFieldsSelector)));
@@ -45,10 +39,10 @@ fn rejects_empty_fields_selector() {
 
 #[test]
 fn rejects_duplicate_field_names_in_fields_selector() {
-    let config = MulterConfig {
-        selector: Selector::fields([SelectedField::new("avatar"), SelectedField::new("avatar")]),
-        ..MulterConfig::default()
-    };
+    let config = MulterConfig::new().selector(Selector::fields([
+        SelectedField::new("avatar"),
+        SelectedField::new("avatar"),
+    ]));
 
     let result = config.validate();
     assert!(matches!(
@@ -57,17 +51,37 @@ fn rejects_duplicate_field_names_in_fields_selector() {
     ));
 }
 
+#[test]
+fn rejects_alias_that_duplicates_another_field_name() {
+    let config = MulterConfig::new().selector(Selector::fields([
+        SelectedField::new("avatar").aliases(["photo"]),
+        SelectedField::new("photo"),
+    ]));
+
+    let result = config.validate();
+    assert!(matches!(
+        result,
+        Err(ConfigError::DuplicateFieldName { name }) if name == "photo"
+    ));
+}
+
+#[test]
+fn rejects_field_constraint_without_fields() {
+    let config =
+        MulterConfig::new().constraint(FieldConstraint::at_least_one_of(Vec::<String>::new()));
+
+    let result = config.validate();
+    assert!(matches!(
+        result,
+        Err(ConfigError::InvalidFieldConstraint { .. })
+    ));
+}
+
 #[test]
 fn rejects_invalid_numeric_limit_values() {
-    let limits = Limits {
-        max_files: Some(0),
-        ..Limits::default()
-    };
+    let limits = Limits::new().max_files(0);
 
-    let config = MulterConfig {
-        limits,
-        ..MulterConfig::default()
-    };
+    let config = MulterConfig::new().limits(limits);
 
     let result = config.validate();
     assert!(matches!(result, Err(ConfigError::InvalidLimitValue { .. })));
@@ -75,16 +89,9 @@ fn rejects_invalid_numeric_limit_values() {
 
 #[test]
 fn rejects_part_limit_greater_than_max_body_size() {
-    let limits = Limits {
-        max_body_size: Some(8),
-        max_file_size: Some(16),
-        ..Limits::default()
-    };
+    let limits = Limits::new().max_body_size(8).max_file_size(16);
 
-    let config = MulterConfig {
-        limits,
-        ..MulterConfig::default()
-    };
+    let config = MulterConfig::new().limits(limits);
 
     let result = config.validate();
     assert!(matches!(
@@ -93,17 +100,24 @@ fn rejects_part_limit_greater_than_max_body_size() {
     ));
 }
 
+#[test]
+fn rejects_min_file_size_greater_than_max_file_size() {
+    let config = MulterConfig::new().limits(Limits::new().min_file_size(32).max_file_size(16));
+
+    assert!(matches!(
+        config.validate(),
+        Err(ConfigError::MinFileSizeExceedsMax {
+            min_file_size: 32,
+            max_file_size: 16
+        })
+    ));
+}
+
 #[test]
 fn rejects_invalid_mime_pattern() {
-    let limits = Limits {
-        allowed_mime_types: vec!["image".to_owned()],
-        ..Limits::default()
-    };
+    let limits = Limits::new().allowed_mime_types(["image"]);
 
-    let config = MulterConfig {
-        limits,
-        ..MulterConfig::default()
-    };
+    let config = MulterConfig::new().limits(limits);
 
     let result = config.validate();
     assert!(matches!(
@@ -114,10 +128,9 @@ fn rejects_invalid_mime_pattern() {
 
 #[test]
 fn rejects_invalid_selected_field_mime_pattern() {
-    let config = MulterConfig {
-        selector: Selector::fields([SelectedField::new("avatar").allowed_mime_types(["image"])]),
-        ..MulterConfig::default()
-    };
+    let config = MulterConfig::new().selector(Selector::fields([
+        SelectedField::new("avatar").allowed_mime_types(["image"])
+    ]));
 
     let result = config.validate();
     assert!(matches!(
@@ -126,12 +139,36 @@ fn rejects_invalid_selected_field_mime_pattern() {
     ));
 }
 
+#[test]
+fn rejects_invalid_selected_field_extension() {
+    let config = MulterConfig::new().selector(Selector::fields([
+        SelectedField::new("docs").allowed_extensions(["pdf", "."])
+    ]));
+
+    let result = config.validate();
+    assert!(matches!(
+        result,
+        Err(ConfigError::InvalidFileExtension { extension }) if extension == "."
+    ));
+}
+
+#[test]
+fn rejects_min_count_above_max_count() {
+    let config = MulterConfig::new().selector(Selector::fields([SelectedField::new("gallery")
+        .min_count(3)
+        .max_count(2)]));
+
+    let result = config.validate();
+    assert!(matches!(
+        result,
+        Err(ConfigError::InvalidFieldMinCount { name }) if name == "gallery"
+    ));
+}
+
 #[test]
 fn rejects_invalid_selected_field_max_size() {
-    let config = MulterConfig {
-        selector: Selector::fields([SelectedField::text("meta").max_size(0)]),
-        ..MulterConfig::default()
-    };
+    let config =
+        MulterConfig::new().selector(Selector::fields([SelectedField::text("meta").max_size(0)]));
 
     let result = config.validate();
     assert!(matches!(
@@ -140,14 +177,170 @@ fn rejects_invalid_selected_field_max_size() {
     ));
 }
 
+#[cfg(feature = "regex")]
+#[test]
+fn rejects_invalid_selected_field_regex_pattern() {
+    let config = MulterConfig::new().selector(Selector::fields([
+        SelectedField::new("files[").name_match(multigear::FieldNameMatch::Regex)
+    ]));
+
+    let result = config.validate();
+    assert!(matches!(
+        result,
+        Err(ConfigError::InvalidFieldPattern { pattern, .. }) if pattern == "files["
+    ));
+}
+
 #[test]
 fn builder_validation_surfaces_config_errors() {
-    let config = MulterConfig {
-        selector: Selector::single(""),
-        unknown_field_policy: UnknownFieldPolicy::Ignore,
-        ..MulterConfig::default()
-    };
+    let config = MulterConfig::new()
+        .selector(Selector::single(""))
+        .unknown_field_policy(UnknownFieldPolicy::Ignore);
 
     let result = MulterBuilder::new().with_config(config).build_config();
     assert!(matches!(result, Err(ConfigError::EmptyFieldName)));
 }
+
+#[test]
+fn rejects_zero_timeouts() {
+    for limits in [
+        Limits::new().max_parse_duration(Duration::ZERO),
+        Limits::new().idle_chunk_timeout(Duration::ZERO),
+    ] {
+        let config = MulterConfig::new().limits(limits);
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidLimitValue { .. })
+        ));
+    }
+}
+
+#[test]
+fn parses_human_readable_byte_sizes() {
+    for (input, expected) in [
+        ("512", 512),
+        ("10B", 10),
+        ("64MB", 64_000_000),
+        ("64mb", 64_000_000),
+        ("2 KiB", 2_048),
+        ("1GiB", 1 << 30),
+        ("1.5KB", 1_500),
+        ("0.5MiB", 512 * 1024),
+    ] {
+        let size: ByteSize = input.parse().expect(input);
+        assert_eq!(size.as_u64(), expected, "{input}");
+    }
+
+    for input in ["", "MB", "1.2.3MB", "12XB", "-1MB", "99999999999TB"] {
+        assert!(
+            matches!(
+                input.parse::<ByteSize>(),
+                Err(ConfigError::InvalidByteSize { value }) if value == input
+            ),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn builder_accepts_size_strings() {
+    let builder = MulterBuilder::new()
+        .max_file_size_str("1MiB")
+        .and_then(|builder| builder.max_body_size_str("8 MB"))
+        .expect("size strings should parse");
+    assert_eq!(builder.config().limits.max_file_size, Some(1_048_576));
+    assert_eq!(builder.config().limits.max_body_size, Some(8_000_000));
+
+    assert!(matches!(
+        MulterBuilder::new().max_field_size_str("lots"),
+        Err(ConfigError::InvalidByteSize { .. })
+    ));
+}
+
+#[cfg(all(feature = "serde", feature = "json"))]
+#[test]
+fn byte_sizes_deserialize_from_numbers_and_strings() {
+    let from_string: ByteSize = serde_json::from_str("\"64MB\"").expect("size string");
+    let from_number: ByteSize = serde_json::from_str("1024").expect("byte count");
+    assert_eq!(from_string, ByteSize(64_000_000));
+    assert_eq!(from_number, ByteSize(1024));
+    assert!(serde_json::from_str::<ByteSize>("\"64 parsecs\"").is_err());
+}
+
+#[cfg(all(feature = "serde", feature = "json"))]
+#[test]
+fn config_round_trips_through_serde() {
+    let config = MulterBuilder::new()
+        .fields([
+            SelectedField::new("avatar")
+                .max_count(1)
+                .allowed_mime_types(["image/*"]),
+            SelectedField::text("title").required(),
+        ])
+        .unknown_field_policy(UnknownFieldPolicy::Collect { max_size: 1024 })
+        .max_file_size(4096)
+        .constraint(FieldConstraint::requires("avatar", "title"))
+        .build_config()
+        .expect("config should be valid");
+
+    let json = serde_json::to_string(&config).expect("config should serialize");
+    let decoded: MulterConfig = serde_json::from_str(&json).expect("config should deserialize");
+    assert_eq!(decoded, config);
+
+    let partial: MulterConfig = serde_json::from_str(
+        r#"{ "selector": { "Fields": [{ "name": "doc" }] }, "limits": { "max_files": 2 } }"#,
+    )
+    .expect("omitted fields should default");
+    assert_eq!(
+        partial.selector,
+        Selector::fields([SelectedField::new("doc")])
+    );
+    assert_eq!(partial.limits, Limits::new().max_files(2));
+    assert_eq!(partial.unknown_field_policy, UnknownFieldPolicy::Ignore);
+
+    let warn = MulterConfig::new().unknown_field_policy(UnknownFieldPolicy::warn(|_| {}));
+    assert!(serde_json::to_string(&warn).is_err());
+}
+
+#[test]
+fn limits_load_from_environment() {
+    for (name, value) in [
+        ("MULTIGEAR_ENV_TEST_MAX_FILE_SIZE", "64MB"),
+        ("MULTIGEAR_ENV_TEST_MAX_FILES", "3"),
+        ("MULTIGEAR_ENV_TEST_IDLE_CHUNK_TIMEOUT", "1.5"),
+        (
+            "MULTIGEAR_ENV_TEST_ALLOWED_MIME_TYPES",
+            "image/*, application/pdf",
+        ),
+        ("MULTIGEAR_ENV_TEST_FILENAME_CHARSET", "portable"),
+        ("MULTIGEAR_ENV_TEST_REJECT_EMPTY_FILES", "true"),
+        ("MULTIGEAR_ENV_TEST_REJECT_DOUBLE_EXTENSIONS", "1"),
+        (
+            "MULTIGEAR_ENV_TEST_ALLOWED_DOUBLE_EXTENSIONS",
+            "tar.gz,tar.xz",
+        ),
+    ] {
+        std::env::set_var(name, value);
+    }
+
+    let limits = Limits::from_env("MULTIGEAR_ENV_TEST").expect("environment should parse");
+    assert_eq!(
+        limits,
+        Limits::new()
+            .max_file_size(64_000_000)
+            .max_files(3)
+            .idle_chunk_timeout(Duration::from_millis(1500))
+            .allowed_mime_types(["image/*", "application/pdf"])
+            .filename_charset(FilenameCharset::Portable)
+            .reject_empty_files(true)
+            .reject_double_extensions(true)
+            .allowed_double_extensions(["tar.gz", "tar.xz"])
+    );
+
+    std::env::set_var("MULTIGEAR_ENV_BAD_MAX_PARTS", "many");
+    assert!(matches!(
+        Limits::from_env("MULTIGEAR_ENV_BAD"),
+        Err(ConfigError::InvalidEnvValue { name, value })
+            if name == "MULTIGEAR_ENV_BAD_MAX_PARTS" && value == "many"
+    ));
+}
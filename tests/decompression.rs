@@ -0,0 +1,140 @@
+#![allow(missing_docs)]
+
+#[cfg(feature = "decompression")]
+use std::io::Write;
+
+#[cfg(feature = "decompression")]
+use bytes::Bytes;
+#[cfg(feature = "decompression")]
+use flate2::{
+    write::{GzEncoder, ZlibEncoder},
+    Compression,
+};
+#[cfg(feature = "decompression")]
+use futures::stream;
+use multigear::parser::headers::{parse_content_encoding, ContentEncoding};
+#[cfg(feature = "decompression")]
+use multigear::{Limits, MulterConfig, MulterError, Multipart};
+
+#[cfg(feature = "decompression")]
+const PAYLOAD: &[u8] = b"hello from brotli, hello from brotli!";
+
+/// `PAYLOAD` compressed with Brotli (quality 5, window 22).
+#[cfg(feature = "decompression")]
+const BROTLI_PAYLOAD: &[u8] = &[
+    27, 36, 0, 0, 4, 156, 99, 169, 206, 110, 77, 54, 225, 112, 56, 79, 69, 6, 209, 39, 169, 203,
+    235, 105, 68, 159, 220, 254, 212, 48, 1,
+];
+
+#[test]
+fn parses_content_encoding_tokens() {
+    assert_eq!(parse_content_encoding(None), ContentEncoding::Identity);
+    assert_eq!(parse_content_encoding(Some("GZIP")), ContentEncoding::Gzip);
+    assert_eq!(
+        parse_content_encoding(Some("deflate")),
+        ContentEncoding::Deflate
+    );
+    assert_eq!(
+        parse_content_encoding(Some(" br ")),
+        ContentEncoding::Brotli
+    );
+    assert_eq!(
+        parse_content_encoding(Some("zstd")),
+        ContentEncoding::Other("zstd".to_owned())
+    );
+}
+
+#[cfg(feature = "decompression")]
+#[tokio::test]
+async fn decompresses_gzip_deflate_and_brotli_parts() {
+    let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+    gzip.write_all(PAYLOAD).expect("gzip write");
+    let gzip = gzip.finish().expect("gzip finish");
+
+    let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+    zlib.write_all(PAYLOAD).expect("zlib write");
+    let zlib = zlib.finish().expect("zlib finish");
+
+    for (encoding, compressed) in [
+        ("gzip", gzip),
+        ("deflate", zlib),
+        ("br", BROTLI_PAYLOAD.to_vec()),
+    ] {
+        let body = compressed_body(encoding, &compressed);
+        for chunk_size in [body.len(), 3] {
+            let chunks = body
+                .chunks(chunk_size)
+                .map(|chunk| Ok::<Bytes, MulterError>(Bytes::copy_from_slice(chunk)))
+                .collect::<Vec<_>>();
+            let mut multipart =
+                Multipart::new("BOUND", stream::iter(chunks)).expect("multipart should initialize");
+            let mut part = multipart
+                .next_part()
+                .await
+                .expect("headers should parse")
+                .expect("part expected");
+
+            assert_eq!(
+                part.bytes().await.expect("body should decompress"),
+                Bytes::from_static(PAYLOAD),
+                "encoding {encoding}"
+            );
+        }
+    }
+}
+
+#[cfg(feature = "decompression")]
+#[tokio::test]
+async fn rejects_decompression_bombs() {
+    let mut gzip = GzEncoder::new(Vec::new(), Compression::best());
+    gzip.write_all(&vec![0_u8; 1024 * 1024])
+        .expect("gzip write");
+    let gzip = gzip.finish().expect("gzip finish");
+    let body = compressed_body("gzip", &gzip);
+
+    let config = MulterConfig::new().limits(Limits::new().max_decompressed_size(64 * 1024));
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from(body))]);
+    let mut multipart =
+        Multipart::with_config("BOUND", input, config).expect("multipart should initialize");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("part expected");
+
+    let err = part.bytes().await.expect_err("bomb should be rejected");
+    assert!(matches!(
+        err,
+        MulterError::DecompressedSizeLimitExceeded {
+            field,
+            max_decompressed_size: 65536
+        } if field == "doc"
+    ));
+}
+
+#[cfg(feature = "decompression")]
+#[tokio::test]
+async fn rejects_corrupt_compressed_parts() {
+    let body = compressed_body("gzip", b"definitely not gzip");
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from(body))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("multipart should initialize");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("part expected");
+
+    let err = part.bytes().await.expect_err("corrupt body should fail");
+    assert!(matches!(err, MulterError::Parse(_)));
+}
+
+#[cfg(feature = "decompression")]
+fn compressed_body(encoding: &str, compressed: &[u8]) -> Vec<u8> {
+    let mut body = format!(
+        "--BOUND\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\nContent-Encoding: {encoding}\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(compressed);
+    body.extend_from_slice(b"\r\n--BOUND--\r\n");
+    body
+}
@@ -0,0 +1,97 @@
+#![allow(missing_docs)]
+
+use bytes::Bytes;
+use futures::stream;
+use multigear::{DuplicateFile, DuplicateFilePolicy, MemoryStorage, Multer, MulterError};
+
+fn body() -> Vec<Result<Bytes, MulterError>> {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"photos\"; filename=\"a.jpg\"\r\n",
+        "\r\n",
+        "same bytes\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"caption\"\r\n",
+        "\r\n",
+        "same bytes\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"photos\"; filename=\"b.jpg\"\r\n",
+        "\r\n",
+        "other bytes\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"retry\"; filename=\"a (1).jpg\"\r\n",
+        "\r\n",
+        "same bytes\r\n",
+        "--BOUND--\r\n",
+    );
+    body.as_bytes()
+        .chunks(9)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect()
+}
+
+fn multer(storage: MemoryStorage, policy: DuplicateFilePolicy) -> Multer<MemoryStorage> {
+    Multer::builder()
+        .storage(storage)
+        .duplicate_file_policy(policy)
+        .build()
+        .expect("config should validate")
+}
+
+#[tokio::test]
+async fn duplicates_are_stored_by_default() {
+    let storage = MemoryStorage::new();
+
+    let output = multer(storage.clone(), DuplicateFilePolicy::default())
+        .parse_and_store("BOUND", stream::iter(body()))
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(output.stored_files.len(), 3);
+    assert!(output.duplicates.is_empty());
+    assert_eq!(storage.len().await, 3);
+}
+
+#[tokio::test]
+async fn skip_policy_discards_and_reports_duplicates() {
+    let storage = MemoryStorage::new();
+
+    let output = multer(storage.clone(), DuplicateFilePolicy::Skip)
+        .parse_and_store("BOUND", stream::iter(body()))
+        .await
+        .expect("duplicates should be skipped");
+
+    let names: Vec<_> = output
+        .stored_files
+        .iter()
+        .map(|file| file.file_name.as_deref().unwrap_or_default())
+        .collect();
+    assert_eq!(names, ["a.jpg", "b.jpg"]);
+    assert_eq!(
+        output.duplicates,
+        vec![DuplicateFile {
+            field_name: "retry".to_owned(),
+            file_name: Some("a (1).jpg".to_owned()),
+            original_field_name: "photos".to_owned(),
+            original_file_name: Some("a.jpg".to_owned()),
+        }]
+    );
+    assert_eq!(output.text_fields.len(), 1);
+    assert_eq!(storage.len().await, 2);
+}
+
+#[tokio::test]
+async fn reject_policy_fails_on_the_first_duplicate() {
+    let err = multer(MemoryStorage::new(), DuplicateFilePolicy::Reject)
+        .parse_and_store("BOUND", stream::iter(body()))
+        .await
+        .expect_err("duplicate should be rejected");
+
+    assert!(matches!(
+        err,
+        MulterError::DuplicateFile { field, file_name, original_field, .. }
+            if field == "retry"
+                && file_name.as_deref() == Some("a (1).jpg")
+                && original_field == "photos"
+    ));
+}
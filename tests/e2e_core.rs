@@ -3,24 +3,22 @@
 use bytes::Bytes;
 use futures::stream;
 use multigear::{
-    Limits, MemoryStorage, Multer, MulterConfig, MulterError, Selector, StorageError,
-    UnknownFieldPolicy,
+    Limits, MemoryStorage, Multer, MulterConfig, MulterError, Selector, UnknownFieldPolicy,
 };
 use tokio::io::AsyncWriteExt;
 
 #[tokio::test]
 async fn parse_and_store_wires_parser_selector_limits_and_storage() {
     let storage = MemoryStorage::new();
-    let config = MulterConfig {
-        selector: Selector::single("avatar"),
-        unknown_field_policy: UnknownFieldPolicy::Reject,
-        limits: Limits {
-            max_files: Some(1),
-            max_fields: Some(1),
-            allowed_mime_types: vec!["image/*".to_owned()],
-            ..Limits::default()
-        },
-    };
+    let config = MulterConfig::new()
+        .selector(Selector::single("avatar"))
+        .unknown_field_policy(UnknownFieldPolicy::Reject)
+        .limits(
+            Limits::new()
+                .max_files(1)
+                .max_fields(1)
+                .allowed_mime_types(["image/*"]),
+        );
     let multer = Multer::with_config(storage.clone(), config).expect("config should validate");
 
     let body = concat!(
@@ -137,23 +135,17 @@ async fn parse_and_store_reports_malformed_stream_regression() {
             ))]),
         )
         .await;
-    let is_expected = match result {
-        Err(MulterError::IncompleteStream) => true,
-        Err(MulterError::Storage(StorageError::Message { message })) => {
-            message.contains("ended unexpectedly")
-        }
-        _ => false,
-    };
-    assert!(is_expected, "unexpected malformed-stream result");
+    assert!(
+        matches!(result, Err(MulterError::IncompleteStream)),
+        "unexpected malformed-stream result"
+    );
 }
 
 #[tokio::test]
 async fn parse_and_store_respects_unknown_field_policy_regression() {
-    let config = MulterConfig {
-        selector: Selector::single("avatar"),
-        unknown_field_policy: UnknownFieldPolicy::Reject,
-        ..MulterConfig::default()
-    };
+    let config = MulterConfig::new()
+        .selector(Selector::single("avatar"))
+        .unknown_field_policy(UnknownFieldPolicy::Reject);
     let multer = Multer::with_config(MemoryStorage::new(), config).expect("config should validate");
     let body = concat!(
         "--BOUND\r\n",
@@ -176,3 +168,252 @@ async fn parse_and_store_respects_unknown_field_policy_regression() {
         Err(MulterError::UnexpectedField { field }) if field == "other"
     ));
 }
+
+#[cfg(feature = "json")]
+#[tokio::test]
+async fn structured_fields_reads_bracket_notation_into_nested_values() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Signup {
+        user: User,
+        tags: Vec<String>,
+        items: Vec<Item>,
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct User {
+        name: String,
+        address: Address,
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Address {
+        street: String,
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Item {
+        sku: String,
+    }
+
+    let mut body = String::new();
+    for (name, value) in [
+        ("user[name]", "Ada"),
+        ("user[address][street]", "Main St"),
+        ("tags[]", "a"),
+        ("tags[]", "b"),
+        ("items[1][sku]", "second"),
+        ("items[0][sku]", "first"),
+    ] {
+        body.push_str(&format!(
+            "--BOUND\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+        ));
+    }
+    body.push_str("--BOUND--\r\n");
+
+    let processed = Multer::new(MemoryStorage::new())
+        .parse_and_store(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from(body))]),
+        )
+        .await
+        .expect("form should parse");
+
+    let signup: Signup =
+        serde_json::from_value(processed.structured_fields()).expect("fields should deserialize");
+    assert_eq!(
+        signup,
+        Signup {
+            user: User {
+                name: "Ada".to_owned(),
+                address: Address {
+                    street: "Main St".to_owned(),
+                },
+            },
+            tags: vec!["a".to_owned(), "b".to_owned()],
+            items: vec![
+                Item {
+                    sku: "first".to_owned(),
+                },
+                Item {
+                    sku: "second".to_owned(),
+                },
+            ],
+        }
+    );
+}
+
+#[tokio::test]
+async fn parse_and_store_collects_unknown_parts_up_to_the_size_cap() {
+    let config = MulterConfig::new()
+        .selector(Selector::single("avatar"))
+        .unknown_field_policy(UnknownFieldPolicy::Collect { max_size: 8 });
+    let multer = Multer::with_config(MemoryStorage::new(), config).expect("config should validate");
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"avatar\"; filename=\"a.png\"\r\n",
+        "\r\n",
+        "png\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"debug\"; filename=\"d.log\"\r\n",
+        "Content-Type: text/plain\r\n",
+        "\r\n",
+        "hello\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"too_big\"; filename=\"x.bin\"\r\n",
+        "\r\n",
+        "0123456789\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"note\"; filename=\"n.txt\"\r\n",
+        "\r\n",
+        "abc\r\n",
+        "--BOUND--\r\n"
+    );
+
+    let processed = multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+                body.as_bytes(),
+            ))]),
+        )
+        .await
+        .expect("unknown parts should not fail the upload");
+
+    assert_eq!(processed.stored_files.len(), 1);
+    let collected: Vec<_> = processed
+        .unknown_parts
+        .iter()
+        .map(|part| (part.field_name.as_str(), part.data.clone()))
+        .collect();
+    assert_eq!(
+        collected,
+        [
+            ("debug", Bytes::from_static(b"hello")),
+            ("note", Bytes::from_static(b"abc")),
+        ]
+    );
+    assert_eq!(processed.unknown_parts[0].content_type, mime::TEXT_PLAIN);
+}
+
+#[tokio::test]
+async fn collect_form_buffers_files_and_fields_without_storage() {
+    let multer = Multer::builder()
+        .any()
+        .max_file_size(16)
+        .build()
+        .expect("config should validate");
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"tag\"\r\n",
+        "\r\n",
+        "red\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n",
+        "Content-Type: text/plain\r\n",
+        "\r\n",
+        "hello\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"tag\"\r\n",
+        "\r\n",
+        "blue\r\n",
+        "--BOUND--\r\n"
+    );
+    let multipart = multer
+        .multipart_from_boundary(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+                body.as_bytes(),
+            ))]),
+        )
+        .expect("multipart should initialize");
+
+    let form = multipart.collect_form().await.expect("form should collect");
+    assert_eq!(form.field("tag"), Some("red"));
+    assert_eq!(
+        form.fields.get_vec("tag").map(Vec::as_slice),
+        Some(&["red".to_owned(), "blue".to_owned()][..])
+    );
+    let doc = form.file("doc").expect("file expected");
+    assert_eq!(doc.file_name.as_deref(), Some("a.txt"));
+    assert_eq!(doc.content_type, mime::TEXT_PLAIN);
+    assert_eq!(doc.data, Bytes::from_static(b"hello"));
+    assert_eq!(form.files.len(), 1);
+}
+
+#[tokio::test]
+async fn collect_form_enforces_limits() {
+    let multer = Multer::builder()
+        .any()
+        .max_file_size(2)
+        .build()
+        .expect("config should validate");
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n",
+        "\r\n",
+        "hello\r\n",
+        "--BOUND--\r\n"
+    );
+    let multipart = multer
+        .multipart_from_boundary(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+                body.as_bytes(),
+            ))]),
+        )
+        .expect("multipart should initialize");
+
+    let err = multipart
+        .collect_form()
+        .await
+        .expect_err("file exceeds limit");
+    assert!(matches!(err, MulterError::FileSizeLimitExceeded { .. }));
+}
+
+#[tokio::test]
+async fn per_request_limit_overrides_leave_shared_config_untouched() {
+    let multer = Multer::builder()
+        .any()
+        .max_file_size(100)
+        .build()
+        .expect("config should validate");
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n",
+        "\r\n",
+        "hello\r\n",
+        "--BOUND--\r\n"
+    );
+    let input = || {
+        stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+            body.as_bytes(),
+        ))])
+    };
+
+    let mut strict = multer
+        .multipart_from_boundary_with("BOUND", input(), |limits| limits.max_file_size(3))
+        .expect("override should validate");
+    let mut part = strict.next_part().await.expect("parse").expect("part");
+    let err = part.bytes().await.expect_err("override applies");
+    assert!(matches!(
+        err,
+        MulterError::FileSizeLimitExceeded {
+            max_file_size: 3,
+            ..
+        }
+    ));
+
+    assert_eq!(multer.config().limits.max_file_size, Some(100));
+    let form = multer
+        .multipart_from_boundary("BOUND", input())
+        .expect("multipart should initialize")
+        .collect_form()
+        .await
+        .expect("shared limits still allow the file");
+    assert_eq!(form.files.len(), 1);
+
+    let err = multer
+        .multipart_from_boundary_with("BOUND", input(), |limits| limits.max_file_size(0))
+        .expect_err("invalid override should fail validation");
+    assert!(matches!(err, MulterError::Config(_)));
+}
@@ -0,0 +1,96 @@
+#![allow(missing_docs)]
+
+use bytes::Bytes;
+use futures::stream;
+use multigear::{FileMeta, FilterDecision, Limits, MemoryStorage, Multer, MulterError, Selector};
+
+fn body() -> Vec<Result<Bytes, MulterError>> {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"docs\"; filename=\".DS_Store\"\r\n",
+        "\r\n",
+        "junk\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"title\"\r\n",
+        "\r\n",
+        "Report\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"docs\"; filename=\"report.exe\"\r\n",
+        "Content-Type: application/x-msdownload\r\n",
+        "\r\n",
+        "MZ\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"docs\"; filename=\"report.txt\"\r\n",
+        "\r\n",
+        "hello\r\n",
+        "--BOUND--\r\n",
+    );
+    vec![Ok(Bytes::from(body))]
+}
+
+async fn decide(meta: &FileMeta, reject_executables: bool) -> FilterDecision {
+    let name = meta.file_name.as_deref().unwrap_or_default();
+    if name.starts_with('.') {
+        FilterDecision::Skip
+    } else if name.ends_with(".exe") && reject_executables {
+        FilterDecision::reject("executables are not accepted")
+    } else if name.ends_with(".exe") {
+        FilterDecision::Skip
+    } else {
+        FilterDecision::Accept
+    }
+}
+
+#[tokio::test]
+async fn skipped_files_are_drained_before_selector_and_limits() {
+    let storage = MemoryStorage::new();
+    let multer = Multer::builder()
+        .storage(storage.clone())
+        .selector(Selector::array("docs", 1))
+        .limits(Limits::new().max_files(1))
+        .file_filter(|meta: &FileMeta| {
+            let meta = meta.clone();
+            async move { decide(&meta, false).await }
+        })
+        .build()
+        .expect("config should validate");
+
+    let output = multer
+        .parse_and_store("BOUND", stream::iter(body()))
+        .await
+        .expect("skipped parts should not count toward limits");
+
+    assert_eq!(output.stored_files.len(), 1);
+    assert_eq!(
+        output.stored_files[0].file_name.as_deref(),
+        Some("report.txt")
+    );
+    assert_eq!(
+        output.text_fields,
+        vec![("title".to_owned(), "Report".to_owned())]
+    );
+    assert_eq!(storage.len().await, 1);
+}
+
+#[tokio::test]
+async fn rejected_files_fail_with_the_filter_message() {
+    let multer = Multer::builder()
+        .storage(MemoryStorage::new())
+        .file_filter(|meta: &FileMeta| {
+            let meta = meta.clone();
+            async move { decide(&meta, true).await }
+        })
+        .build()
+        .expect("config should validate");
+
+    let err = multer
+        .parse_and_store("BOUND", stream::iter(body()))
+        .await
+        .expect_err("executables should be rejected");
+
+    assert!(matches!(
+        err,
+        MulterError::FileRejected { field, message }
+            if field == "docs" && message == "executables are not accepted"
+    ));
+}
@@ -10,7 +10,11 @@ use http_body_util::{BodyExt, Full};
 #[cfg(feature = "hyper")]
 use hyper::{header, service::Service, Request, Response};
 #[cfg(feature = "hyper")]
-use multigear::{extract_boundary, hyper::MulterService, MemoryStorage, Multer, StoredFile};
+use multigear::{
+    extract_boundary,
+    hyper::{multipart_from_request, MulterService},
+    MemoryStorage, Multer, StoredFile,
+};
 
 #[cfg(feature = "hyper")]
 #[tokio::test]
@@ -103,3 +107,82 @@ async fn multer_service_rejects_requests_without_content_type() {
         .expect_err("service should fail");
     assert!(err.to_string().contains("missing Content-Type"));
 }
+
+#[cfg(feature = "hyper")]
+#[tokio::test]
+async fn multipart_from_request_skips_trailers_and_empty_frames() {
+    use futures::stream;
+    use http_body_util::StreamBody;
+    use hyper::body::Frame;
+
+    let mut trailers = hyper::HeaderMap::new();
+    trailers.insert("x-checksum", "abc".parse().expect("header value"));
+    let frames = vec![
+        Ok::<_, std::io::Error>(Frame::data(Bytes::from_static(
+            b"--BOUND\r\nContent-Disposition: form-data; name=\"field\"\r\n\r\nval",
+        ))),
+        Ok(Frame::data(Bytes::new())),
+        Ok(Frame::data(Bytes::from_static(b"ue\r\n--BOUND--\r\n"))),
+        Ok(Frame::trailers(trailers)),
+    ];
+    let request = Request::builder()
+        .header(header::CONTENT_TYPE, "multipart/form-data; boundary=BOUND")
+        .body(StreamBody::new(stream::iter(frames)))
+        .expect("request should build");
+
+    let mut multipart = multipart_from_request(request).expect("multipart should initialize");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("part parse should succeed")
+        .expect("part should exist");
+    assert_eq!(part.field_name(), "field");
+    assert_eq!(part.text().await.expect("text should decode"), "value");
+    assert!(multipart.next_part().await.expect("end").is_none());
+}
+
+#[cfg(feature = "hyper")]
+#[tokio::test]
+async fn multipart_from_request_maps_body_errors() {
+    use futures::stream;
+    use http_body_util::StreamBody;
+    use hyper::body::Frame;
+
+    let frames = vec![
+        Ok(Frame::data(Bytes::from_static(
+            b"--BOUND\r\nContent-Disposition: form-data; name=\"field\"\r\n\r\nva",
+        ))),
+        Err(std::io::Error::new(
+            std::io::ErrorKind::ConnectionReset,
+            "peer reset",
+        )),
+    ];
+    let request = Request::builder()
+        .header(header::CONTENT_TYPE, "multipart/form-data; boundary=BOUND")
+        .body(StreamBody::new(stream::iter(frames)))
+        .expect("request should build");
+
+    let mut multipart = multipart_from_request(request).expect("multipart should initialize");
+    let err = match multipart.next_part().await {
+        Ok(Some(mut part)) => part.text().await.expect_err("body error should surface"),
+        Ok(None) => panic!("part should exist"),
+        Err(err) => err,
+    };
+    assert!(err
+        .to_string()
+        .contains("hyper body stream error: peer reset"));
+}
+
+#[cfg(feature = "hyper")]
+#[tokio::test]
+async fn multipart_from_request_requires_multipart_content_type() {
+    let request = Request::builder()
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from_static(b"{}")))
+        .expect("request should build");
+
+    let Err(err) = multipart_from_request(request) else {
+        panic!("non-multipart should fail");
+    };
+    assert!(matches!(err, multigear::MulterError::Parse(_)));
+}
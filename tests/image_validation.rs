@@ -0,0 +1,95 @@
+#![allow(missing_docs)]
+#![cfg(feature = "image-validation")]
+
+use std::io::Cursor;
+
+use image::{ImageFormat, RgbImage};
+use multigear::{image_validation::ImageValidator, FileMeta, PartValidator};
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    RgbImage::new(width, height)
+        .write_to(&mut out, ImageFormat::Png)
+        .expect("png should encode");
+    out.into_inner()
+}
+
+#[tokio::test]
+async fn enforces_width_height_and_pixel_limits() {
+    let image = png(40, 20);
+    let validator = ImageValidator::new().max_width(40).max_height(20);
+    validator
+        .validate(
+            &FileMeta::new("photo", Some("photo.png"), "image/png"),
+            &image,
+        )
+        .await
+        .expect("image within limits");
+
+    for validator in [
+        ImageValidator::new().max_width(39),
+        ImageValidator::new().max_height(19),
+        ImageValidator::new().max_pixels(799),
+    ] {
+        assert!(validator
+            .validate(
+                &FileMeta::new("photo", Some("photo.png"), "image/png"),
+                &image
+            )
+            .await
+            .is_err());
+    }
+}
+
+#[tokio::test]
+async fn verify_decode_rejects_truncated_and_oversized_images() {
+    let image = png(64, 64);
+    let validator = ImageValidator::new().verify_decode(1024 * 1024);
+    validator
+        .validate(
+            &FileMeta::new("photo", Some("photo.png"), "image/png"),
+            &image,
+        )
+        .await
+        .expect("complete image decodes");
+
+    let truncated = &image[..image.len() - 16];
+    assert!(validator
+        .validate(
+            &FileMeta::new("photo", Some("photo.png"), "image/png"),
+            truncated
+        )
+        .await
+        .is_err());
+
+    let small = ImageValidator::new().verify_decode(image.len() - 1);
+    assert_eq!(small.head_len(), image.len());
+    assert!(small
+        .validate(
+            &FileMeta::new("photo", Some("photo.png"), "image/png"),
+            &image
+        )
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn only_checks_image_parts() {
+    let validator = ImageValidator::new().max_width(1);
+    validator
+        .validate(
+            &FileMeta::new("photo", Some("photo.png"), "text/plain"),
+            b"not an image",
+        )
+        .await
+        .expect("non-image parts pass");
+
+    let err = validator
+        .validate(
+            &FileMeta::new("photo", Some("photo.png"), "image/png"),
+            b"not an image",
+        )
+        .await
+        .expect_err("garbage declared as an image fails");
+    assert_eq!(err.to_string(), "unrecognized image format");
+}
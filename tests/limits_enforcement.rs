@@ -1,17 +1,17 @@
 #![allow(missing_docs)]
 
+use std::time::Duration;
+
 use bytes::Bytes;
 use futures::{channel::mpsc, stream};
 use multigear::{
-    Limits, MulterConfig, MulterError, Multipart, SelectedField, Selector, UnknownFieldPolicy,
+    FilenameCharset, Limits, MulterConfig, MulterError, Multipart, SelectedField, Selector,
+    UnknownFieldPolicy,
 };
 
 #[tokio::test]
 async fn enforces_max_file_size() {
-    let config = config_with_limits(Limits {
-        max_file_size: Some(3),
-        ..Limits::default()
-    });
+    let config = config_with_limits(Limits::new().max_file_size(3));
     let body = multipart_body(&[part(
         "upload",
         Some("a.bin"),
@@ -37,11 +37,216 @@ async fn enforces_max_file_size() {
 }
 
 #[tokio::test]
-async fn enforces_max_field_size() {
-    let config = config_with_limits(Limits {
-        max_field_size: Some(4),
-        ..Limits::default()
+async fn declared_content_length_over_max_file_size_fails_before_body() {
+    let config = config_with_limits(Limits::new().max_file_size(3));
+    let headers = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"upload\"; filename=\"a.bin\"\r\n",
+        "Content-Length: 4096\r\n",
+        "\r\n"
+    );
+
+    // The body never arrives; the declared length alone must trip the limit.
+    let (tx, rx) = mpsc::unbounded::<Result<Bytes, MulterError>>();
+    tx.unbounded_send(Ok(Bytes::from_static(headers.as_bytes())))
+        .expect("send chunk");
+
+    let mut multipart =
+        Multipart::with_config("BOUND", rx, config).expect("multipart should initialize");
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("declared length should fail fast");
+    assert!(matches!(
+        err,
+        MulterError::FileSizeLimitExceeded {
+            field,
+            max_file_size: 3
+        } if field == "upload"
+    ));
+    drop(tx);
+}
+
+#[tokio::test]
+async fn declared_content_length_is_ignored_for_encoded_parts() {
+    let config = config_with_limits(Limits::new().max_file_size(3));
+    // 4 base64 characters decode to 3 bytes, within the limit.
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"upload\"; filename=\"a.bin\"\r\n",
+        "Content-Transfer-Encoding: base64\r\n",
+        "Content-Length: 4\r\n",
+        "\r\n",
+        "YWJj\r\n",
+        "--BOUND--\r\n"
+    );
+    let mut multipart =
+        Multipart::with_config("BOUND", bytes_stream(body.as_bytes().to_vec()), config)
+            .expect("multipart should initialize");
+
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("item expected");
+    assert_eq!(part.size_hint(), Some(4));
+    assert_eq!(
+        part.bytes().await.expect("decoded body fits"),
+        Bytes::from_static(b"abc")
+    );
+}
+
+#[tokio::test]
+async fn idle_chunk_timeout_fails_stalled_bodies() {
+    let config = config_with_limits(Limits::new().idle_chunk_timeout(Duration::from_millis(50)));
+    let headers = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"upload\"; filename=\"a.bin\"\r\n",
+        "\r\n",
+        "partial"
+    );
+
+    // The sender stays open but never delivers the rest of the body.
+    let (tx, rx) = mpsc::unbounded::<Result<Bytes, MulterError>>();
+    tx.unbounded_send(Ok(Bytes::from_static(headers.as_bytes())))
+        .expect("send chunk");
+
+    let mut multipart =
+        Multipart::with_config("BOUND", rx, config).expect("multipart should initialize");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("item expected");
+    let err = part
+        .bytes()
+        .await
+        .expect_err("stalled body should time out");
+    assert!(matches!(
+        err,
+        MulterError::Timeout {
+            limit: "idle_chunk_timeout",
+            ..
+        }
+    ));
+    drop(tx);
+}
+
+#[tokio::test]
+async fn max_bytes_per_second_paces_body_reads() {
+    let config = config_with_limits(Limits::new().max_bytes_per_second(2_000));
+    let payload = "x".repeat(400);
+    let body = multipart_body(&[part("upload", Some("a.bin"), None, &payload)]);
+    let chunks = body
+        .chunks(100)
+        .map(|chunk| Ok::<Bytes, MulterError>(Bytes::copy_from_slice(chunk)))
+        .collect::<Vec<_>>();
+    let before_last_chunk = Duration::from_millis((body.len() as u64 - 100) * 1_000 / 2_000);
+
+    let started = std::time::Instant::now();
+    let mut multipart = Multipart::with_config("BOUND", stream::iter(chunks), config)
+        .expect("multipart should initialize");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("item expected");
+    assert_eq!(part.bytes().await.expect("throttled body").len(), 400);
+    assert!(multipart
+        .next_part()
+        .await
+        .expect("end of stream")
+        .is_none());
+    assert!(started.elapsed() >= before_last_chunk);
+}
+
+#[tokio::test]
+async fn max_parse_duration_fails_slow_drip_clients() {
+    let config = config_with_limits(
+        Limits::new()
+            .max_parse_duration(Duration::from_millis(100))
+            .idle_chunk_timeout(Duration::from_secs(5)),
+    );
+    let headers = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"upload\"; filename=\"a.bin\"\r\n",
+        "\r\n"
+    );
+
+    let (tx, rx) = mpsc::unbounded::<Result<Bytes, MulterError>>();
+    tx.unbounded_send(Ok(Bytes::from_static(headers.as_bytes())))
+        .expect("send chunk");
+    let drip = tokio::spawn(async move {
+        // Each byte arrives well within the idle timeout.
+        while tx.unbounded_send(Ok(Bytes::from_static(b"x"))).is_ok() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    });
+
+    let mut multipart =
+        Multipart::with_config("BOUND", rx, config).expect("multipart should initialize");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("item expected");
+    let err = part.bytes().await.expect_err("slow drip should time out");
+    assert!(matches!(
+        err,
+        MulterError::Timeout {
+            limit: "max_parse_duration",
+            duration,
+        } if duration == Duration::from_millis(100)
+    ));
+
+    drop(multipart);
+    drip.await.expect("drip task");
+}
+
+#[tokio::test]
+async fn min_throughput_fails_trickling_uploads_after_grace() {
+    let config = config_with_limits(
+        Limits::new()
+            .min_throughput_bytes_per_sec(10_000)
+            .min_throughput_grace(Duration::from_millis(50)),
+    );
+    let headers = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"upload\"; filename=\"a.bin\"\r\n",
+        "\r\n"
+    );
+
+    let (tx, rx) = mpsc::unbounded::<Result<Bytes, MulterError>>();
+    tx.unbounded_send(Ok(Bytes::from_static(headers.as_bytes())))
+        .expect("send chunk");
+    let drip = tokio::spawn(async move {
+        while tx.unbounded_send(Ok(Bytes::from_static(b"x"))).is_ok() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
     });
+
+    let mut multipart =
+        Multipart::with_config("BOUND", rx, config).expect("multipart should initialize");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("item expected");
+    let err = part.bytes().await.expect_err("trickle should fail");
+    assert!(matches!(
+        err,
+        MulterError::ThroughputTooLow {
+            min_throughput_bytes_per_sec: 10_000
+        }
+    ));
+
+    drop(multipart);
+    drip.await.expect("drip task");
+}
+
+#[tokio::test]
+async fn enforces_max_field_size() {
+    let config = config_with_limits(Limits::new().max_field_size(4));
     let body = multipart_body(&[part("note", None, None, "hello")]);
     let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
         .expect("multipart should initialize");
@@ -63,10 +268,7 @@ async fn enforces_max_field_size() {
 
 #[tokio::test]
 async fn enforces_max_files() {
-    let config = config_with_limits(Limits {
-        max_files: Some(1),
-        ..Limits::default()
-    });
+    let config = config_with_limits(Limits::new().max_files(1));
     let body = multipart_body(&[
         part("a", Some("a.bin"), Some("application/octet-stream"), "one"),
         part("b", Some("b.bin"), Some("application/octet-stream"), "two"),
@@ -93,10 +295,7 @@ async fn enforces_max_files() {
 
 #[tokio::test]
 async fn enforces_max_fields() {
-    let config = config_with_limits(Limits {
-        max_fields: Some(1),
-        ..Limits::default()
-    });
+    let config = config_with_limits(Limits::new().max_fields(1));
     let body = multipart_body(&[
         part("first", None, None, "one"),
         part("second", None, None, "two"),
@@ -121,12 +320,66 @@ async fn enforces_max_fields() {
     ));
 }
 
+#[tokio::test]
+async fn enforces_max_parts_across_files_and_fields() {
+    let config = config_with_limits(Limits::new().max_parts(2));
+    let body = multipart_body(&[
+        part("first", None, None, "one"),
+        part("upload", Some("a.txt"), Some("text/plain"), "two"),
+        part("third", None, None, "three"),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    for _ in 0..2 {
+        let mut part = multipart
+            .next_part()
+            .await
+            .expect("part within limit")
+            .expect("part expected");
+        part.bytes().await.expect("body");
+    }
+
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("third part exceeds max_parts");
+    assert!(matches!(
+        err,
+        MulterError::PartsLimitExceeded { max_parts: 2 }
+    ));
+}
+
+#[tokio::test]
+async fn max_parts_counts_ignored_parts() {
+    let config = MulterConfig::new()
+        .selector(Selector::single("upload"))
+        .unknown_field_policy(UnknownFieldPolicy::Ignore)
+        .limits(Limits::new().max_parts(3));
+    let noise = (0..10)
+        .map(|index| format!("noise{index}"))
+        .collect::<Vec<_>>();
+    let parts = noise
+        .iter()
+        .map(|name| part(name, Some("noise.txt"), None, "x"))
+        .collect::<Vec<_>>();
+    let body = multipart_body(&parts);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("ignored parts still count toward max_parts");
+    assert!(matches!(
+        err,
+        MulterError::PartsLimitExceeded { max_parts: 3 }
+    ));
+}
+
 #[tokio::test]
 async fn enforces_max_body_size() {
-    let config = config_with_limits(Limits {
-        max_body_size: Some(32),
-        ..Limits::default()
-    });
+    let config = config_with_limits(Limits::new().max_body_size(32));
     let body = multipart_body(&[part(
         "upload",
         Some("a.bin"),
@@ -143,12 +396,91 @@ async fn enforces_max_body_size() {
     ));
 }
 
+#[tokio::test]
+async fn enforces_max_header_size() {
+    let config = config_with_limits(Limits::new().max_header_size(64));
+    let body = multipart_body(&[
+        part("small", None, None, "ok"),
+        part(
+            "upload",
+            Some("a-rather-long-file-name-that-pushes-the-header-block.bin"),
+            Some("application/octet-stream"),
+            "data",
+        ),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let mut first = multipart
+        .next_part()
+        .await
+        .expect("small headers should parse")
+        .expect("item expected");
+    assert_eq!(first.text().await.expect("text should read"), "ok");
+
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("oversized headers should fail");
+    assert!(matches!(
+        err,
+        MulterError::HeaderSizeLimitExceeded {
+            max_header_size: 64
+        }
+    ));
+}
+
+#[tokio::test]
+async fn enforces_max_header_size_before_header_terminator_arrives() {
+    let config = config_with_limits(Limits::new().max_header_size(128));
+    let mut chunks = vec![Ok::<Bytes, MulterError>(Bytes::from_static(
+        b"--BOUND\r\nContent-Disposition: form-data; name=\"a\"\r\nX-Pad: ",
+    ))];
+    chunks.extend(std::iter::repeat_with(|| Ok(Bytes::from_static(b"aaaaaaaaaaaaaaaa"))).take(64));
+    let mut multipart = Multipart::with_config("BOUND", stream::iter(chunks), config)
+        .expect("multipart should initialize");
+
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("unterminated headers should fail");
+    assert!(matches!(
+        err,
+        MulterError::HeaderSizeLimitExceeded {
+            max_header_size: 128
+        }
+    ));
+}
+
+#[tokio::test]
+async fn enforces_max_headers_per_part() {
+    let config = config_with_limits(Limits::new().max_headers_per_part(2));
+    let body = b"--BOUND\r\n\
+Content-Disposition: form-data; name=\"a\"\r\n\
+X-One: 1\r\n\
+X-Two: 2\r\n\
+\r\n\
+value\r\n\
+--BOUND--\r\n"
+        .to_vec();
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("too many headers should fail");
+    assert!(matches!(
+        err,
+        MulterError::HeaderCountLimitExceeded {
+            max_headers_per_part: 2
+        }
+    ));
+}
+
 #[tokio::test]
 async fn enforces_allowed_mime_types_with_wildcard() {
-    let config = config_with_limits(Limits {
-        allowed_mime_types: vec!["image/*".to_owned()],
-        ..Limits::default()
-    });
+    let config = config_with_limits(Limits::new().allowed_mime_types(["image/*"]));
     let body = multipart_body(&[
         part("avatar", Some("a.png"), Some("image/png"), "one"),
         part("notes", Some("a.txt"), Some("text/plain"), "two"),
@@ -176,10 +508,7 @@ async fn enforces_allowed_mime_types_with_wildcard() {
 
 #[tokio::test]
 async fn fails_early_before_terminal_boundary_for_large_file_chunks() {
-    let config = config_with_limits(Limits {
-        max_file_size: Some(4),
-        ..Limits::default()
-    });
+    let config = config_with_limits(Limits::new().max_file_size(4));
     let first_chunk = concat!(
         "--BOUND\r\n",
         "Content-Disposition: form-data; name=\"upload\"; filename=\"a.bin\"\r\n",
@@ -212,16 +541,12 @@ async fn fails_early_before_terminal_boundary_for_large_file_chunks() {
 
 #[tokio::test]
 async fn per_field_mime_rules_override_broader_global_allowlist() {
-    let config = MulterConfig {
-        selector: Selector::fields([SelectedField::new("docs")
+    let config = MulterConfig::new()
+        .selector(Selector::fields([SelectedField::new("docs")
             .max_count(1)
-            .allowed_mime_types(["application/pdf"])]),
-        unknown_field_policy: UnknownFieldPolicy::Reject,
-        limits: Limits {
-            allowed_mime_types: vec!["application/*".to_owned()],
-            ..Limits::default()
-        },
-    };
+            .allowed_mime_types(["application/pdf"])]))
+        .unknown_field_policy(UnknownFieldPolicy::Reject)
+        .limits(Limits::new().allowed_mime_types(["application/*"]));
 
     let body = multipart_body(&[part("docs", Some("a.json"), Some("application/json"), "{}")]);
     let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
@@ -237,16 +562,12 @@ async fn per_field_mime_rules_override_broader_global_allowlist() {
 
 #[tokio::test]
 async fn global_mime_rules_still_apply_when_field_rule_allows() {
-    let config = MulterConfig {
-        selector: Selector::fields([SelectedField::new("docs")
+    let config = MulterConfig::new()
+        .selector(Selector::fields([SelectedField::new("docs")
             .max_count(1)
-            .allowed_mime_types(["application/pdf"])]),
-        unknown_field_policy: UnknownFieldPolicy::Reject,
-        limits: Limits {
-            allowed_mime_types: vec!["image/*".to_owned()],
-            ..Limits::default()
-        },
-    };
+            .allowed_mime_types(["application/pdf"])]))
+        .unknown_field_policy(UnknownFieldPolicy::Reject)
+        .limits(Limits::new().allowed_mime_types(["image/*"]));
 
     let body = multipart_body(&[part("docs", Some("a.pdf"), Some("application/pdf"), "pdf")]);
     let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
@@ -260,13 +581,104 @@ async fn global_mime_rules_still_apply_when_field_rule_allows() {
     ));
 }
 
+#[tokio::test]
+async fn denied_mime_types_are_checked_before_allowlists() {
+    let config = config_with_limits(
+        Limits::new()
+            .allowed_mime_types(["text/*"])
+            .denied_mime_types(["text/html", "application/x-msdownload"]),
+    );
+
+    let body = multipart_body(&[
+        part("doc", Some("a.txt"), Some("text/plain"), "plain"),
+        part(
+            "doc",
+            Some("b.html"),
+            Some("text/html; charset=utf-8"),
+            "<p>",
+        ),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let plain = multipart
+        .next_part()
+        .await
+        .expect("allowed type should parse")
+        .expect("part expected");
+    drop(plain);
+
+    let err = multipart.next_part().await.expect_err("item expected");
+    assert!(matches!(
+        err,
+        MulterError::MimeTypeNotAllowed { field, mime }
+        if field == "doc" && mime == "text/html"
+    ));
+}
+
+#[tokio::test]
+async fn per_field_extension_allowlist_rejects_spoofed_mime_types() {
+    let config = MulterConfig::new()
+        .selector(Selector::fields([SelectedField::new("docs")
+            .allowed_extensions(["pdf", ".docx"])
+            .allowed_mime_types(["application/pdf"])]))
+        .unknown_field_policy(UnknownFieldPolicy::Reject);
+
+    let body = multipart_body(&[
+        part("docs", Some("Report.PDF"), Some("application/pdf"), "pdf"),
+        part("docs", Some("../run.exe"), Some("application/pdf"), "MZ"),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let report = multipart
+        .next_part()
+        .await
+        .expect("allowed extension should parse")
+        .expect("report expected");
+    assert_eq!(report.file_name(), Some("Report.PDF"));
+    drop(report);
+
+    let err = multipart.next_part().await.expect_err("item expected");
+    assert!(matches!(
+        err,
+        MulterError::ExtensionNotAllowed { field, extension }
+        if field == "docs" && extension == "exe"
+    ));
+}
+
+#[tokio::test]
+async fn global_extension_allowlist_checks_sanitized_file_names() {
+    let config = config_with_limits(Limits::new().allowed_extensions(["png", ".JPG"]));
+
+    let body = multipart_body(&[
+        part("photo", Some("dir/holiday.jpg"), Some("image/jpeg"), "jpg"),
+        part("photo", Some("shot.png.svg"), Some("image/png"), "svg"),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let photo = multipart
+        .next_part()
+        .await
+        .expect("allowed extension should parse")
+        .expect("part expected");
+    drop(photo);
+
+    let err = multipart.next_part().await.expect_err("item expected");
+    assert!(matches!(
+        err,
+        MulterError::ExtensionNotAllowed { field, extension }
+        if field == "photo" && extension == "svg"
+    ));
+}
+
 #[tokio::test]
 async fn enforces_per_field_text_size_limit() {
-    let config = MulterConfig {
-        selector: Selector::fields([SelectedField::text("meta").max_size(4)]),
-        unknown_field_policy: UnknownFieldPolicy::Reject,
-        limits: Limits::default(),
-    };
+    let config = MulterConfig::new()
+        .selector(Selector::fields([SelectedField::text("meta").max_size(4)]))
+        .unknown_field_policy(UnknownFieldPolicy::Reject)
+        .limits(Limits::default());
     let body = multipart_body(&[part("meta", None, None, "hello")]);
     let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
         .expect("multipart should initialize");
@@ -291,11 +703,10 @@ async fn enforces_per_field_text_size_limit() {
 
 #[tokio::test]
 async fn fields_selector_rejects_unknown_text_fields() {
-    let config = MulterConfig {
-        selector: Selector::fields([SelectedField::text("meta")]),
-        unknown_field_policy: UnknownFieldPolicy::Reject,
-        limits: Limits::default(),
-    };
+    let config = MulterConfig::new()
+        .selector(Selector::fields([SelectedField::text("meta")]))
+        .unknown_field_policy(UnknownFieldPolicy::Reject)
+        .limits(Limits::default());
     let body = multipart_body(&[part("other", None, None, "value")]);
     let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
         .expect("multipart should initialize");
@@ -310,12 +721,315 @@ async fn fields_selector_rejects_unknown_text_fields() {
     ));
 }
 
-fn config_with_limits(limits: Limits) -> MulterConfig {
-    MulterConfig {
-        selector: Selector::any(),
-        unknown_field_policy: UnknownFieldPolicy::Reject,
-        limits,
+#[tokio::test]
+async fn skip_empty_files_drops_blank_file_inputs() {
+    let config = config_with_limits(Limits::new().skip_empty_files(true).max_files(1));
+    let body = multipart_body(&[
+        part("blank", Some(""), Some("application/octet-stream"), ""),
+        part("named", Some(""), Some("text/plain"), "data"),
+        part("note", None, None, "hi"),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let mut named = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("non-empty file expected");
+    assert_eq!(named.field_name(), "named");
+    assert_eq!(
+        named.bytes().await.expect("body"),
+        Bytes::from_static(b"data")
+    );
+    drop(named);
+
+    let note = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("text field expected");
+    assert_eq!(note.field_name(), "note");
+}
+
+#[tokio::test]
+async fn reject_empty_files_fails_blank_file_inputs() {
+    let config = config_with_limits(
+        Limits::new()
+            .reject_empty_files(true)
+            .skip_empty_files(true),
+    );
+    let body = multipart_body(&[part(
+        "avatar",
+        Some(""),
+        Some("application/octet-stream"),
+        "",
+    )]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("blank file input should fail");
+    assert!(matches!(
+        err,
+        MulterError::EmptyFile { field } if field == "avatar"
+    ));
+}
+
+#[tokio::test]
+async fn empty_files_are_yielded_by_default() {
+    let config = config_with_limits(Limits::default());
+    let body = multipart_body(&[part("avatar", Some(""), None, "")]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("empty file expected");
+    assert_eq!(part.file_name(), Some(""));
+    assert!(part.bytes().await.expect("body").is_empty());
+}
+
+#[tokio::test]
+async fn enforces_min_file_size_when_the_body_ends() {
+    let config = config_with_limits(Limits::new().min_file_size(4));
+    let body = multipart_body(&[
+        part("note", None, None, "x"),
+        part("exact", Some("a.bin"), None, "four"),
+        part("upload", Some("b.bin"), None, "abc"),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let mut note = multipart.next_part().await.expect("parse").expect("note");
+    assert_eq!(note.text().await.expect("text fields have no minimum"), "x");
+    drop(note);
+
+    let mut exact = multipart.next_part().await.expect("parse").expect("exact");
+    assert_eq!(
+        exact.bytes().await.expect("at minimum"),
+        Bytes::from_static(b"four")
+    );
+    drop(exact);
+
+    let mut upload = multipart.next_part().await.expect("parse").expect("upload");
+    let err = upload.bytes().await.expect_err("below minimum");
+    assert!(matches!(
+        err,
+        MulterError::FileSizeBelowMinimum {
+            field,
+            min_file_size: 4
+        } if field == "upload"
+    ));
+}
+
+#[tokio::test]
+async fn per_field_min_size_tightens_global_minimum() {
+    let config = MulterConfig::new()
+        .selector(Selector::fields([
+            SelectedField::new("avatar").min_size(8),
+            SelectedField::new("doc"),
+        ]))
+        .limits(Limits::new().min_file_size(2));
+    let body = multipart_body(&[
+        part("doc", Some("d.txt"), None, "ok"),
+        part("avatar", Some("a.png"), None, "tiny"),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let mut doc = multipart.next_part().await.expect("parse").expect("doc");
+    assert_eq!(doc.bytes().await.expect("meets global minimum"), "ok");
+    drop(doc);
+
+    let mut avatar = multipart.next_part().await.expect("parse").expect("avatar");
+    let err = avatar.bytes().await.expect_err("below field minimum");
+    assert!(matches!(
+        err,
+        MulterError::FileSizeBelowMinimum {
+            field,
+            min_file_size: 8
+        } if field == "avatar"
+    ));
+}
+
+#[tokio::test]
+async fn min_file_size_applies_to_bodies_read_ahead_for_empty_file_checks() {
+    let config = config_with_limits(Limits::new().skip_empty_files(true).min_file_size(4));
+    let body = multipart_body(&[part("upload", Some(""), None, "abc")]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("undersized body was already read");
+    assert!(matches!(
+        err,
+        MulterError::FileSizeBelowMinimum {
+            field,
+            min_file_size: 4
+        } if field == "upload"
+    ));
+}
+
+#[tokio::test]
+async fn per_field_max_size_tightens_global_file_limit() {
+    let config = MulterConfig::new()
+        .selector(Selector::fields([
+            SelectedField::new("avatar").max_size(4),
+            SelectedField::new("doc"),
+        ]))
+        .limits(Limits::new().max_file_size(16));
+    let body = multipart_body(&[
+        part("doc", Some("d.txt"), None, "longer than four"),
+        part("avatar", Some("a.png"), None, "too large"),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let mut doc = multipart.next_part().await.expect("parse").expect("doc");
+    assert_eq!(
+        doc.bytes().await.expect("within global limit"),
+        "longer than four"
+    );
+    drop(doc);
+
+    let mut avatar = multipart.next_part().await.expect("parse").expect("avatar");
+    let err = avatar.bytes().await.expect_err("over field limit");
+    assert!(matches!(
+        err,
+        MulterError::FileSizeLimitExceeded {
+            field,
+            max_file_size: 4
+        } if field == "avatar"
+    ));
+}
+
+#[tokio::test]
+async fn rejects_long_filenames_and_disallowed_characters() {
+    let config = config_with_limits(Limits::new().max_filename_length(8));
+    let body = multipart_body(&[part("doc", Some("much-too-long.txt"), None, "x")]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+    let err = multipart.next_part().await.expect_err("long filename");
+    assert!(matches!(
+        err,
+        MulterError::FilenameTooLong {
+            field,
+            max_filename_length: 8
+        } if field == "doc"
+    ));
+
+    let config = config_with_limits(Limits::new().filename_charset(FilenameCharset::NoControl));
+    let body = multipart_body(&[
+        part("doc", Some("lunch menu.txt"), None, "ok"),
+        part("doc", Some("evil\tname.txt"), None, "x"),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+    let menu = multipart
+        .next_part()
+        .await
+        .expect("printable filename should parse")
+        .expect("part expected");
+    drop(menu);
+    let err = multipart.next_part().await.expect_err("control character");
+    assert!(matches!(
+        err,
+        MulterError::InvalidFilename { field, file_name }
+        if field == "doc" && file_name == "evil\tname.txt"
+    ));
+
+    assert!(FilenameCharset::Portable.allows("report 2024_v-1.pdf"));
+    assert!(!FilenameCharset::Portable.allows("caf\u{e9}.txt"));
+}
+
+#[tokio::test]
+async fn rejects_double_extensions_outside_the_allow_list() {
+    let config = config_with_limits(
+        Limits::new()
+            .reject_double_extensions(true)
+            .allowed_double_extensions([".TAR.gz"]),
+    );
+    let body = multipart_body(&[
+        part("doc", Some("report.final.pdf"), None, "ok"),
+        part("doc", Some("v1.2.zip"), None, "ok"),
+        part("doc", Some("backup.tar.gz"), None, "ok"),
+        part("doc", Some("invoice.PDF.exe"), None, "x"),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+    for _ in 0..3 {
+        let part = multipart
+            .next_part()
+            .await
+            .expect("benign filename should parse")
+            .expect("part expected");
+        drop(part);
     }
+    let err = multipart.next_part().await.expect_err("double extension");
+    assert!(matches!(
+        err,
+        MulterError::DoubleExtension { field, extension }
+        if field == "doc" && extension == "pdf.exe"
+    ));
+
+    let config = config_with_limits(Limits::new());
+    let body = multipart_body(&[part("doc", Some("photo.jpg.php"), None, "x")]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+    multipart
+        .next_part()
+        .await
+        .expect("double extensions are allowed by default")
+        .expect("part expected");
+}
+
+#[tokio::test]
+async fn enforces_max_total_file_size_across_files_only() {
+    let config = config_with_limits(Limits::new().max_total_file_size(10));
+    let body = multipart_body(&[
+        part("a", Some("a.txt"), None, "12345"),
+        part("note", None, None, "text fields are not counted"),
+        part("b", Some("b.txt"), None, "12345"),
+        part("c", Some("c.txt"), None, "1"),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    for _ in 0..3 {
+        let mut part = multipart
+            .next_part()
+            .await
+            .expect("part within budget")
+            .expect("part expected");
+        part.bytes().await.expect("body within budget");
+    }
+
+    let mut last = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("part expected");
+    let err = last.bytes().await.expect_err("budget exhausted");
+    assert!(matches!(
+        err,
+        MulterError::TotalFileSizeLimitExceeded {
+            max_total_file_size: 10
+        }
+    ));
+}
+
+fn config_with_limits(limits: Limits) -> MulterConfig {
+    MulterConfig::new()
+        .selector(Selector::any())
+        .unknown_field_policy(UnknownFieldPolicy::Reject)
+        .limits(limits)
 }
 
 fn part<'a>(
@@ -1,6 +1,6 @@
 #![allow(missing_docs)]
 
-use multigear::parser::boundary::extract_multipart_boundary;
+use multigear::parser::boundary::{extract_any_multipart_boundary, extract_multipart_boundary};
 
 #[test]
 fn extracts_boundary_from_content_type() {
@@ -57,6 +57,32 @@ fn rejects_malformed_percent_encoding_in_boundary() {
     assert_err_contains(&err.to_string(), "percent-encoding");
 }
 
+#[test]
+fn extracts_boundary_from_other_multipart_subtypes() {
+    for content_type in [
+        "multipart/related; type=\"application/xop+xml\"; boundary=mtom-1",
+        "multipart/mixed; boundary=mtom-1",
+        "Multipart/Alternative; boundary=\"mtom-1\"",
+    ] {
+        let boundary = extract_any_multipart_boundary(content_type).expect("boundary should parse");
+        assert_eq!(boundary, "mtom-1");
+    }
+
+    let err = extract_multipart_boundary("multipart/related; boundary=mtom-1")
+        .expect_err("form-data extractor stays strict");
+    assert_err_contains(&err.to_string(), "multipart/form-data");
+}
+
+#[test]
+fn rejects_non_multipart_types_for_any_subtype_extraction() {
+    let err =
+        extract_any_multipart_boundary("application/json; boundary=abc").expect_err("must fail");
+    assert_err_contains(&err.to_string(), "multipart type");
+
+    let err = extract_any_multipart_boundary("multipart/related").expect_err("must fail");
+    assert_err_contains(&err.to_string(), "boundary");
+}
+
 fn assert_err_contains(actual: &str, expected_fragment: &str) {
     assert!(
         actual.contains(expected_fragment),
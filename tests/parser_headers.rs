@@ -1,8 +1,12 @@
 #![allow(missing_docs)]
 
 use http::{header, HeaderMap, HeaderValue};
-use multigear::parser::headers::{
-    parse_content_disposition, parse_part_content_type, parse_part_headers,
+use multigear::{
+    parser::headers::{
+        decode_encoded_words, parse_content_disposition, parse_part_content_type,
+        parse_part_headers, parse_part_headers_with_policy,
+    },
+    DuplicateHeaderPolicy,
 };
 
 #[test]
@@ -43,6 +47,81 @@ fn filename_star_takes_precedence_over_filename() {
     assert_eq!(parsed.filename.as_deref(), Some("real name.txt"));
 }
 
+#[test]
+fn decodes_utf8_filename_star() {
+    let parsed = parse_content_disposition(
+        "form-data; name=\"file\"; filename*=UTF-8''%E2%82%AC%20rates.txt",
+    )
+    .expect("header should parse");
+    assert_eq!(parsed.filename.as_deref(), Some("\u{20ac} rates.txt"));
+}
+
+#[test]
+fn decodes_iso_8859_1_filename_star_with_language_tag() {
+    let parsed =
+        parse_content_disposition("form-data; name=\"file\"; filename*=iso-8859-1'de'M%FCller.txt")
+            .expect("header should parse");
+    assert_eq!(parsed.filename.as_deref(), Some("M\u{fc}ller.txt"));
+}
+
+#[test]
+fn joins_rfc2231_filename_continuations() {
+    let parsed = parse_content_disposition(
+        "form-data; name=\"file\"; filename*1=\" report.txt\"; filename*0*=UTF-8''%E2%82%AC",
+    )
+    .expect("header should parse");
+    assert_eq!(parsed.filename.as_deref(), Some("\u{20ac} report.txt"));
+}
+
+#[test]
+fn rejects_incomplete_rfc2231_filename_continuations() {
+    let err = parse_content_disposition(
+        "form-data; name=\"file\"; filename*0*=UTF-8''a; filename*2=\"c\"",
+    )
+    .expect_err("must fail");
+    assert_err_contains(&err.to_string(), "incomplete filename* continuation");
+}
+
+#[test]
+fn unsupported_filename_star_charset_falls_back_to_filename() {
+    let parsed = parse_content_disposition(
+        "form-data; name=\"file\"; filename=\"plain.txt\"; filename*=koi8-r''%C1.txt",
+    )
+    .expect("header should parse");
+    assert_eq!(parsed.filename.as_deref(), Some("plain.txt"));
+
+    let err = parse_content_disposition("form-data; name=\"file\"; filename*=koi8-r''%C1.txt")
+        .expect_err("must fail without fallback");
+    assert_err_contains(&err.to_string(), "filename* charsets");
+}
+
+#[test]
+fn decodes_rfc2047_base64_and_q_encoded_words() {
+    assert_eq!(
+        decode_encoded_words("=?UTF-8?B?4oKsLnR4dA==?="),
+        "\u{20ac}.txt"
+    );
+    assert_eq!(
+        decode_encoded_words("=?iso-8859-1?Q?M=FCller_report.txt?="),
+        "M\u{fc}ller report.txt"
+    );
+    assert_eq!(
+        decode_encoded_words("=?UTF-8?Q?a?= =?UTF-8?Q?b?=.txt"),
+        "ab.txt"
+    );
+}
+
+#[test]
+fn leaves_malformed_or_unsupported_encoded_words_untouched() {
+    assert_eq!(decode_encoded_words("plain.txt"), "plain.txt");
+    assert_eq!(decode_encoded_words("=?UTF-8?X?abc?="), "=?UTF-8?X?abc?=");
+    assert_eq!(decode_encoded_words("=?koi8-r?Q?abc?="), "=?koi8-r?Q?abc?=");
+    assert_eq!(
+        decode_encoded_words("report =?UTF-8?B?!!?="),
+        "report =?UTF-8?B?!!?="
+    );
+}
+
 #[test]
 fn defaults_part_content_type_to_octet_stream() {
     let mime = parse_part_content_type(None).expect("default MIME should parse");
@@ -110,3 +189,59 @@ fn assert_err_contains(actual: &str, expected_fragment: &str) {
         "expected `{actual}` to contain `{expected_fragment}`"
     );
 }
+
+#[test]
+fn duplicate_header_policy_selects_or_rejects_repeated_headers() {
+    let mut headers = HeaderMap::new();
+    headers.append(
+        header::CONTENT_DISPOSITION,
+        HeaderValue::from_static("form-data; name=\"first\"; filename=\"a.txt\""),
+    );
+    headers.append(
+        header::CONTENT_DISPOSITION,
+        HeaderValue::from_static("form-data; name=\"second\"; filename=\"b.exe\""),
+    );
+    headers.append(header::CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+
+    let first = parse_part_headers_with_policy(&headers, DuplicateHeaderPolicy::UseFirst)
+        .expect("first occurrence should be used");
+    assert_eq!(first.field_name, "first");
+    assert_eq!(first.file_name.as_deref(), Some("a.txt"));
+    assert_eq!(
+        parse_part_headers(&headers)
+            .expect("default policy")
+            .field_name,
+        "first"
+    );
+
+    let last = parse_part_headers_with_policy(&headers, DuplicateHeaderPolicy::UseLast)
+        .expect("last occurrence should be used");
+    assert_eq!(last.field_name, "second");
+    assert_eq!(last.file_name.as_deref(), Some("b.exe"));
+    assert_eq!(last.content_type.essence_str(), "text/plain");
+
+    let err = parse_part_headers_with_policy(&headers, DuplicateHeaderPolicy::Reject)
+        .expect_err("duplicates should be rejected");
+    assert!(err
+        .to_string()
+        .contains("duplicate Content-Disposition header"));
+}
+
+#[test]
+fn duplicate_header_policy_applies_to_content_type() {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        HeaderValue::from_static("form-data; name=\"file\"; filename=\"a.bin\""),
+    );
+    headers.append(header::CONTENT_TYPE, HeaderValue::from_static("image/png"));
+    headers.append(header::CONTENT_TYPE, HeaderValue::from_static("text/html"));
+
+    let err = parse_part_headers_with_policy(&headers, DuplicateHeaderPolicy::Reject)
+        .expect_err("duplicate content type should be rejected");
+    assert!(err.to_string().contains("duplicate Content-Type header"));
+
+    let last = parse_part_headers_with_policy(&headers, DuplicateHeaderPolicy::UseLast)
+        .expect("last content type should be used");
+    assert_eq!(last.content_type.essence_str(), "text/html");
+}
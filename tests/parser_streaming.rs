@@ -1,8 +1,13 @@
 #![allow(missing_docs)]
 
 use bytes::Bytes;
-use futures::{channel::mpsc, stream, StreamExt};
-use multigear::{MulterError, Multipart, ParseError};
+use futures::{channel::mpsc, future::poll_fn, stream, StreamExt};
+use multigear::{
+    parser::stream::{MultipartStream, StreamLimits},
+    DuplicateHeaderPolicy, EpiloguePolicy, LineEndingPolicy, MulterConfig, MulterError, Multipart,
+    MultipartEvent, ParseError, ParserCompliance,
+};
+use tokio_util::sync::CancellationToken;
 
 #[tokio::test]
 async fn parses_chunked_stream_and_yields_parts() {
@@ -105,6 +110,54 @@ async fn yields_first_part_before_input_completes() {
         .is_none());
 }
 
+#[tokio::test]
+async fn parses_body_delivered_one_byte_at_a_time() {
+    let body = concat!(
+        "--XBOUND\r\n",
+        "Content-Disposition: form-data; name=\"alpha\"\r\n",
+        "\r\n",
+        "one\r\n-- not a boundary\r\n",
+        "--XBOUND\r\n",
+        "Content-Disposition: form-data; name=\"beta\"; filename=\"b.txt\"\r\n",
+        "\r\n",
+        "two\r\n",
+        "--XBOUND--\r\n"
+    );
+
+    let chunks = body
+        .as_bytes()
+        .iter()
+        .map(|byte| Ok::<Bytes, MulterError>(Bytes::copy_from_slice(&[*byte])));
+    let mut multipart = Multipart::new("XBOUND", stream::iter(chunks)).expect("valid boundary");
+
+    let mut first = multipart
+        .next_part()
+        .await
+        .expect("first part should parse")
+        .expect("first item should exist");
+    assert_eq!(
+        first.bytes().await.expect("body bytes"),
+        Bytes::from_static(b"one\r\n-- not a boundary")
+    );
+
+    let mut second = multipart
+        .next_part()
+        .await
+        .expect("second part should parse")
+        .expect("second item should exist");
+    assert_eq!(second.headers.file_name.as_deref(), Some("b.txt"));
+    assert_eq!(
+        second.bytes().await.expect("body bytes"),
+        Bytes::from_static(b"two")
+    );
+
+    assert!(multipart
+        .next_part()
+        .await
+        .expect("stream should finish")
+        .is_none());
+}
+
 #[tokio::test]
 async fn reports_malformed_boundary_as_parse_error() {
     let body = concat!(
@@ -131,6 +184,31 @@ async fn reports_malformed_boundary_as_parse_error() {
     ));
 }
 
+#[tokio::test]
+async fn reports_malformed_boundary_split_across_chunks() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"field\"\r\n",
+        "\r\n",
+        "hello\r\n",
+        "--WRONG--\r\n"
+    );
+    let chunks = split_bytes(body.as_bytes(), &[64, 3, 2, 1, 1, 1]);
+    let input = stream::iter(chunks.into_iter().map(Ok::<Bytes, MulterError>));
+    let mut multipart = Multipart::new("BOUND", input).expect("boundary should be valid");
+
+    let mut item = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("item expected");
+    let err = item.bytes().await.expect_err("body should fail");
+    assert!(matches!(
+        err,
+        MulterError::Parse(ParseError::Message { .. })
+    ));
+}
+
 #[tokio::test]
 async fn reports_incomplete_terminal_boundary() {
     let body = concat!(
@@ -233,3 +311,1442 @@ async fn streams_large_body_before_terminal_boundary_arrives() {
 
     assert_eq!(total, 256 * 1024);
 }
+
+#[tokio::test]
+async fn slices_part_body_from_source_chunk_without_copying() {
+    let source = Bytes::from(
+        b"--BOUND\r\nContent-Disposition: form-data; name=\"upload\"; filename=\"a.bin\"\r\n\r\npayload\r\n--BOUND--\r\n"
+            .to_vec(),
+    );
+    let source_range = source.as_ptr_range();
+    let input = stream::iter([Ok::<Bytes, MulterError>(source.clone())]);
+    drop(source);
+    let mut multipart = Multipart::new("BOUND", input).expect("boundary should be valid");
+
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("part should exist");
+    let body = part.bytes().await.expect("body bytes");
+
+    assert_eq!(body, Bytes::from_static(b"payload"));
+    assert!(source_range.contains(&body.as_ptr()));
+}
+
+#[tokio::test]
+async fn parses_many_small_parts_from_one_chunk() {
+    let mut body = Vec::new();
+    for index in 0..5_000 {
+        body.extend_from_slice(
+            format!(
+                "--BOUND\r\nContent-Disposition: form-data; name=\"f{index}\"\r\n\r\n{index}\r\n"
+            )
+            .as_bytes(),
+        );
+    }
+    body.extend_from_slice(b"--BOUND--\r\n");
+
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from(body))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("boundary should be valid");
+
+    let mut seen = 0usize;
+    while let Some(mut part) = multipart.next_part().await.expect("part should parse") {
+        assert_eq!(part.field_name(), format!("f{seen}"));
+        assert_eq!(part.text().await.expect("text"), seen.to_string());
+        seen += 1;
+    }
+    assert_eq!(seen, 5_000);
+}
+
+#[tokio::test]
+async fn fails_when_header_block_never_terminates_within_buffer_cap() {
+    let mut body = b"--BOUND\r\nContent-Disposition: form-data; name=\"field\"\r\n".to_vec();
+    body.extend(std::iter::repeat(b'x').take(4096));
+    let chunks = body
+        .chunks(16)
+        .map(|chunk| Ok::<Bytes, MulterError>(Bytes::copy_from_slice(chunk)))
+        .collect::<Vec<_>>();
+
+    let limits = StreamLimits::default().with_max_buffered_bytes(Some(256));
+    let mut parser = MultipartStream::with_limits("BOUND", stream::iter(chunks), limits)
+        .expect("boundary should be valid");
+
+    let err = poll_fn(|cx| parser.poll_next_part_headers(cx))
+        .await
+        .expect_err("unterminated headers should hit the buffer cap");
+    assert!(matches!(
+        err,
+        MulterError::BufferLimitExceeded {
+            max_buffered_bytes: 256
+        }
+    ));
+}
+
+#[test]
+fn stream_limits_cap_buffering_by_default() {
+    assert_eq!(
+        StreamLimits::default().max_buffered_bytes,
+        Some(multigear::parser::stream::DEFAULT_MAX_BUFFERED_BYTES)
+    );
+}
+
+#[tokio::test]
+async fn skips_preamble_before_opening_boundary() {
+    let body = concat!(
+        "This is a multi-part message in MIME format.\r\n",
+        "--BOUND-ish line that is not a delimiter\r\n",
+        "\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"field\"\r\n",
+        "\r\n",
+        "value\r\n",
+        "--BOUND--\r\n"
+    );
+    let chunks = split_bytes(body.as_bytes(), &[5, 11, 3, 40, 2, 17]);
+    let input = stream::iter(chunks.into_iter().map(Ok::<Bytes, MulterError>));
+    let mut multipart = Multipart::new("BOUND", input).expect("boundary should be valid");
+
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("preamble should be skipped")
+        .expect("part should exist");
+    assert_eq!(part.field_name(), "field");
+    assert_eq!(part.text().await.expect("text"), "value");
+    assert!(multipart
+        .next_part()
+        .await
+        .expect("stream should finish")
+        .is_none());
+}
+
+#[tokio::test]
+async fn reports_missing_opening_boundary_after_preamble_only() {
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        b"just some preamble\r\nand nothing else\r\n",
+    ))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("boundary should be valid");
+
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("missing boundary should fail");
+    assert!(err.to_string().contains("missing opening boundary"));
+}
+
+#[tokio::test]
+async fn discards_epilogue_after_terminal_boundary() {
+    for epilogue in [
+        "\r\n\r\n\r\n",
+        "\r\ntrailing garbage",
+        "garbage-without-crlf",
+    ] {
+        let body = format!(
+            "--BOUND\r\nContent-Disposition: form-data; name=\"field\"\r\n\r\nvalue\r\n--BOUND--{epilogue}"
+        );
+        let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from(body))]);
+        let mut multipart = Multipart::new("BOUND", input).expect("boundary should be valid");
+
+        let mut part = multipart
+            .next_part()
+            .await
+            .expect("part should parse")
+            .expect("part should exist");
+        assert_eq!(part.text().await.expect("text"), "value");
+        assert!(multipart
+            .next_part()
+            .await
+            .expect("epilogue should be ignored")
+            .is_none());
+    }
+}
+
+#[tokio::test]
+async fn strict_epilogue_policy_rejects_trailing_bytes() {
+    let config = MulterConfig::new().epilogue_policy(EpiloguePolicy::Reject);
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        b"--BOUND\r\nContent-Disposition: form-data; name=\"field\"\r\n\r\nvalue\r\n--BOUND--\r\nextra",
+    ))]);
+    let mut multipart =
+        Multipart::with_config("BOUND", input, config).expect("multipart should initialize");
+
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("part should parse")
+        .expect("part should exist");
+    assert_eq!(part.text().await.expect("text"), "value");
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("epilogue should be rejected");
+    assert!(err.to_string().contains("after terminal boundary"));
+}
+
+#[tokio::test]
+async fn strict_epilogue_policy_accepts_close_line_split_across_chunks() {
+    let config = MulterConfig::new().epilogue_policy(EpiloguePolicy::Reject);
+    let input = stream::iter([
+        Ok::<Bytes, MulterError>(Bytes::from_static(
+            b"--BOUND\r\nContent-Disposition: form-data; name=\"field\"\r\n\r\nvalue\r\n--BOUND--",
+        )),
+        Ok(Bytes::from_static(b"\r\n")),
+    ]);
+    let mut multipart =
+        Multipart::with_config("BOUND", input, config).expect("multipart should initialize");
+
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("part should parse")
+        .expect("part should exist");
+    assert_eq!(part.text().await.expect("text"), "value");
+    assert!(multipart
+        .next_part()
+        .await
+        .expect("close line should not count as epilogue")
+        .is_none());
+}
+
+#[tokio::test]
+async fn lenient_line_endings_accept_bare_lf() {
+    let body = concat!(
+        "--BOUND\n",
+        "Content-Disposition: form-data; name=\"alpha\"\n",
+        "\n",
+        "one\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"beta\"; filename=\"b.txt\"\r\n",
+        "Content-Type: text/plain\n",
+        "\r\n",
+        "two\r\n",
+        "--BOUND--\n"
+    );
+    let config = MulterConfig::new().line_ending_policy(LineEndingPolicy::Lenient);
+
+    for sizes in [&[4096][..], &[1][..], &[3, 7, 2, 5, 11, 1, 13]] {
+        let chunks = if sizes == [1] {
+            body.as_bytes()
+                .iter()
+                .map(|byte| Bytes::copy_from_slice(&[*byte]))
+                .collect()
+        } else {
+            split_bytes(body.as_bytes(), sizes)
+        };
+        let input = stream::iter(chunks.into_iter().map(Ok::<Bytes, MulterError>));
+        let mut multipart = Multipart::with_config("BOUND", input, config.clone())
+            .expect("multipart should initialize");
+
+        let mut first = multipart
+            .next_part()
+            .await
+            .expect("first part should parse")
+            .expect("first item should exist");
+        assert_eq!(first.field_name(), "alpha");
+        assert_eq!(first.text().await.expect("text"), "one");
+
+        let mut second = multipart
+            .next_part()
+            .await
+            .expect("second part should parse")
+            .expect("second item should exist");
+        assert_eq!(second.file_name(), Some("b.txt"));
+        assert_eq!(second.content_type(), "text/plain");
+        assert_eq!(second.text().await.expect("text"), "two");
+
+        assert!(multipart
+            .next_part()
+            .await
+            .expect("stream should finish")
+            .is_none());
+    }
+}
+
+#[tokio::test]
+async fn strict_line_endings_reject_bare_lf() {
+    let body = "--BOUND\nContent-Disposition: form-data; name=\"alpha\"\n\none\n--BOUND--\n";
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("boundary should be valid");
+
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("bare LF framing should fail in strict mode");
+    assert!(err.to_string().contains("missing opening boundary"));
+}
+
+#[tokio::test]
+async fn accepts_transport_padding_after_boundaries() {
+    let body = concat!(
+        "--BOUND \t\r\n",
+        "Content-Disposition: form-data; name=\"alpha\"\r\n",
+        "\r\n",
+        "one\r\n",
+        "--BOUND  \r\n",
+        "Content-Disposition: form-data; name=\"beta\"\r\n",
+        "\r\n",
+        "two\r\n",
+        "--BOUND--\t \r\n"
+    );
+
+    for chunks in [
+        vec![Bytes::from_static(body.as_bytes())],
+        split_bytes(body.as_bytes(), &[1; 256]),
+    ] {
+        let input = stream::iter(chunks.into_iter().map(Ok::<Bytes, MulterError>));
+        let config = MulterConfig::new().epilogue_policy(EpiloguePolicy::Reject);
+        let mut multipart =
+            Multipart::with_config("BOUND", input, config).expect("multipart should initialize");
+
+        let mut first = multipart
+            .next_part()
+            .await
+            .expect("padded opening boundary should parse")
+            .expect("first item should exist");
+        assert_eq!(first.text().await.expect("text"), "one");
+
+        let mut second = multipart
+            .next_part()
+            .await
+            .expect("padded delimiter should parse")
+            .expect("second item should exist");
+        assert_eq!(second.text().await.expect("text"), "two");
+
+        assert!(multipart
+            .next_part()
+            .await
+            .expect("padded close delimiter should not count as epilogue")
+            .is_none());
+    }
+}
+
+#[tokio::test]
+async fn nested_multipart_mixed_parts_surface_as_files_of_outer_field() {
+    let body = concat!(
+        "--AaB03x\r\n",
+        "Content-Disposition: form-data; name=\"title\"\r\n",
+        "\r\n",
+        "holiday\r\n",
+        "--AaB03x\r\n",
+        "Content-Disposition: form-data; name=\"files\"\r\n",
+        "Content-Type: multipart/mixed; boundary=BbC04y\r\n",
+        "\r\n",
+        "--BbC04y\r\n",
+        "Content-Disposition: file; filename=\"file1.txt\"\r\n",
+        "Content-Type: text/plain\r\n",
+        "\r\n",
+        "contents of file1\r\n",
+        "--BbC04y\r\n",
+        "Content-Disposition: file; filename=\"file2.gif\"\r\n",
+        "Content-Type: image/gif\r\n",
+        "\r\n",
+        "GIF89a\r\n",
+        "--BbC04y--\r\n",
+        "\r\n--AaB03x\r\n",
+        "Content-Disposition: form-data; name=\"after\"\r\n",
+        "\r\n",
+        "tail\r\n",
+        "--AaB03x--\r\n"
+    );
+
+    for chunks in [
+        vec![Bytes::from_static(body.as_bytes())],
+        split_bytes(body.as_bytes(), &[1; 1024]),
+    ] {
+        let input = stream::iter(chunks.into_iter().map(Ok::<Bytes, MulterError>));
+        let mut multipart = Multipart::new("AaB03x", input).expect("multipart should initialize");
+
+        let mut title = multipart
+            .next_part()
+            .await
+            .expect("title should parse")
+            .expect("title expected");
+        assert_eq!(title.text().await.expect("text"), "holiday");
+
+        let mut first = multipart
+            .next_part()
+            .await
+            .expect("nested part should parse")
+            .expect("first nested file expected");
+        assert_eq!(first.field_name(), "files");
+        assert_eq!(first.file_name(), Some("file1.txt"));
+        assert_eq!(first.content_type(), "text/plain");
+        assert_eq!(first.text().await.expect("text"), "contents of file1");
+
+        // The second nested file is left unread and must be drained.
+        let second = multipart
+            .next_part()
+            .await
+            .expect("nested part should parse")
+            .expect("second nested file expected");
+        assert_eq!(second.field_name(), "files");
+        assert_eq!(second.file_name(), Some("file2.gif"));
+        drop(second);
+
+        let mut after = multipart
+            .next_part()
+            .await
+            .expect("outer part after nested body should parse")
+            .expect("after expected");
+        assert_eq!(after.field_name(), "after");
+        assert_eq!(after.text().await.expect("text"), "tail");
+
+        assert!(multipart.next_part().await.expect("end").is_none());
+    }
+}
+
+#[tokio::test]
+async fn nested_multipart_parts_count_toward_file_limits() {
+    let body = concat!(
+        "--AaB03x\r\n",
+        "Content-Disposition: form-data; name=\"files\"\r\n",
+        "Content-Type: multipart/mixed; boundary=BbC04y\r\n",
+        "\r\n",
+        "--BbC04y\r\n",
+        "Content-Disposition: file; filename=\"a.txt\"\r\n",
+        "\r\n",
+        "a\r\n",
+        "--BbC04y\r\n",
+        "Content-Disposition: file; filename=\"b.txt\"\r\n",
+        "\r\n",
+        "b\r\n",
+        "--BbC04y--\r\n",
+        "--AaB03x--\r\n"
+    );
+    let config = MulterConfig::new().limits(multigear::Limits::new().max_files(1));
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart =
+        Multipart::with_config("AaB03x", input, config).expect("multipart should initialize");
+
+    let first = multipart
+        .next_part()
+        .await
+        .expect("first nested file should parse")
+        .expect("first nested file expected");
+    drop(first);
+
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("second nested file exceeds max_files");
+    assert!(matches!(
+        err,
+        MulterError::FilesLimitExceeded { max_files: 1 }
+    ));
+}
+
+async fn first_part_under(
+    compliance: ParserCompliance,
+    headers: &str,
+) -> Result<(String, Option<String>), MulterError> {
+    let body = format!("--BOUND\r\n{headers}\r\n\r\nvalue\r\n--BOUND--\r\n");
+    let config = MulterConfig::new().parser_compliance(compliance);
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from(body))]);
+    let mut multipart =
+        Multipart::with_config("BOUND", input, config).expect("multipart should initialize");
+
+    let part = multipart.next_part().await?.expect("part expected");
+    Ok((
+        part.field_name().to_owned(),
+        part.file_name().map(ToOwned::to_owned),
+    ))
+}
+
+#[tokio::test]
+async fn lenient_compliance_tolerates_common_header_deviations() {
+    let cases = [
+        ("Content-Disposition: form-data", ""),
+        ("Content-Disposition: form-data; name=\"\"", ""),
+        ("Content-Disposition : form-data; name=\"spaced\"", "spaced"),
+        ("content-disposition:form-data; name=\"tight\"", "tight"),
+        (
+            "Content-Disposition: form-data;\r\n name=\"folded\"",
+            "folded",
+        ),
+        ("Content-Disposition: attachment; name=\"other\"", "other"),
+        (
+            "Content-Disposition: form-data; name=\"first\"\r\nContent-Disposition: form-data; name=\"second\"",
+            "first",
+        ),
+    ];
+
+    for (headers, expected) in cases {
+        let (name, _) = first_part_under(ParserCompliance::Lenient, headers)
+            .await
+            .unwrap_or_else(|err| panic!("`{headers}` should parse leniently: {err}"));
+        assert_eq!(name, expected, "headers: {headers}");
+    }
+}
+
+#[tokio::test]
+async fn strict_compliance_rejects_header_deviations() {
+    let cases = [
+        ("Content-Disposition: form-data", "non-empty `name`"),
+        ("Content-Disposition: form-data; name=\"\"", "non-empty `name`"),
+        (
+            "Content-Disposition : form-data; name=\"spaced\"",
+            "invalid part header name",
+        ),
+        (
+            "Content-Disposition: form-data;\r\n name=\"folded\"",
+            "folded part header",
+        ),
+        (
+            "Content-Disposition: attachment; name=\"other\"",
+            "must be form-data",
+        ),
+        (
+            "Content-Disposition: form-data; name=\"first\"\r\nContent-Disposition: form-data; name=\"second\"",
+            "duplicate Content-Disposition",
+        ),
+    ];
+
+    for (headers, fragment) in cases {
+        let err = first_part_under(ParserCompliance::Strict, headers)
+            .await
+            .expect_err("strict compliance should reject");
+        assert!(
+            err.to_string().contains(fragment),
+            "`{headers}` failed with `{err}`, expected `{fragment}`"
+        );
+    }
+
+    let (name, file_name) = first_part_under(
+        ParserCompliance::Strict,
+        "content-disposition: form-data; name=\"ok\"; filename=\"a.txt\"",
+    )
+    .await
+    .expect("well-formed headers pass strict compliance");
+    assert_eq!(name, "ok");
+    assert_eq!(file_name.as_deref(), Some("a.txt"));
+}
+
+#[tokio::test]
+async fn missing_terminal_boundary_fails_unless_allowed() {
+    let complete = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"alpha\"\r\n",
+        "\r\n",
+        "one\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"beta\"\r\n",
+        "\r\n",
+        "two\r\n",
+        "--BOUND--\r\n"
+    );
+    let truncations = [
+        "--BOUND--\r\n".len(),
+        "BOUND--\r\n".len(),
+        "--\r\n".len(),
+        "-\r\n".len(),
+    ];
+
+    for cut in truncations {
+        let body = &complete[..complete.len() - cut];
+        for allow in [false, true] {
+            let config = MulterConfig::new().allow_missing_terminal_boundary(allow);
+            let chunks = split_bytes(body.as_bytes(), &[1; 256]);
+            let input = stream::iter(chunks.into_iter().map(Ok::<Bytes, MulterError>));
+            let mut multipart = Multipart::with_config("BOUND", input, config)
+                .expect("multipart should initialize");
+
+            let mut first = multipart
+                .next_part()
+                .await
+                .expect("first part should parse")
+                .expect("first part expected");
+            assert_eq!(first.text().await.expect("text"), "one");
+
+            let mut second = multipart
+                .next_part()
+                .await
+                .expect("second part should parse")
+                .expect("second part expected");
+            let text = second.text().await;
+
+            if allow {
+                assert_eq!(text.expect("text"), "two", "cut {cut}");
+                assert!(multipart.next_part().await.expect("clean end").is_none());
+            } else {
+                let err = match text {
+                    Err(err) => err,
+                    Ok(_) => multipart
+                        .next_part()
+                        .await
+                        .expect_err("missing terminal boundary should fail"),
+                };
+                assert!(
+                    matches!(err, MulterError::IncompleteStream)
+                        || err.to_string().contains("malformed"),
+                    "cut {cut}: {err}"
+                );
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn missing_terminal_boundary_allows_stream_ending_after_delimiter_line() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"alpha\"\r\n",
+        "\r\n",
+        "one\r\n",
+        "--BOUND\r\n"
+    );
+    let config = MulterConfig::new().allow_missing_terminal_boundary(true);
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart =
+        Multipart::with_config("BOUND", input, config).expect("multipart should initialize");
+
+    let mut first = multipart
+        .next_part()
+        .await
+        .expect("first part should parse")
+        .expect("first part expected");
+    assert_eq!(first.text().await.expect("text"), "one");
+    assert!(multipart.next_part().await.expect("clean end").is_none());
+}
+
+#[tokio::test]
+async fn missing_terminal_boundary_still_rejects_truncated_part_bodies() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"alpha\"\r\n",
+        "\r\n",
+        "one and a half"
+    );
+    let config = MulterConfig::new().allow_missing_terminal_boundary(true);
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart =
+        Multipart::with_config("BOUND", input, config).expect("multipart should initialize");
+
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("part expected");
+    let err = part.text().await.expect_err("truncated body should fail");
+    assert!(matches!(err, MulterError::IncompleteStream));
+}
+
+#[tokio::test]
+async fn cancellation_stops_parsing_mid_body() {
+    let head = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"alpha\"\r\n",
+        "\r\n",
+        "partial"
+    );
+    let (tx, rx) = mpsc::unbounded::<Result<Bytes, MulterError>>();
+    tx.unbounded_send(Ok(Bytes::from_static(head.as_bytes())))
+        .expect("send chunk");
+
+    let token = CancellationToken::new();
+    let mut multipart = Multipart::new("BOUND", rx)
+        .expect("multipart should initialize")
+        .with_cancellation(token.clone());
+
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("part expected");
+    let mut stream = part.stream();
+    assert_eq!(
+        stream.next().await.expect("chunk").expect("chunk ok"),
+        Bytes::from_static(b"partial")
+    );
+
+    token.cancel();
+    let err = stream
+        .next()
+        .await
+        .expect("cancellation is reported")
+        .expect_err("body read should be cancelled");
+    assert!(matches!(err, MulterError::Cancelled));
+    drop(stream);
+    drop(part);
+
+    assert!(matches!(
+        multipart.next_part().await,
+        Err(MulterError::Cancelled)
+    ));
+    drop(tx);
+}
+
+#[derive(Debug)]
+struct UpstreamError(&'static str);
+
+impl From<UpstreamError> for MulterError {
+    fn from(err: UpstreamError) -> Self {
+        ParseError::new(format!("upstream failed: {}", err.0)).into()
+    }
+}
+
+#[tokio::test]
+async fn accepts_streams_with_foreign_error_types() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"alpha\"\r\n",
+        "\r\n",
+        "one\r\n",
+        "--BOUND--\r\n"
+    );
+
+    let input = stream::iter([Ok::<Bytes, std::convert::Infallible>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("multipart should initialize");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("part should parse")
+        .expect("part expected");
+    assert_eq!(part.text().await.expect("text"), "one");
+
+    let input = stream::iter([Ok::<Bytes, std::io::Error>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("multipart should initialize");
+    assert!(multipart.next_part().await.expect("part").is_some());
+
+    let input = stream::iter([
+        Ok(Bytes::from_static(&body.as_bytes()[..20])),
+        Err(UpstreamError("connection reset")),
+    ]);
+    let mut multipart = Multipart::new("BOUND", input).expect("multipart should initialize");
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("upstream error should surface");
+    assert!(err
+        .to_string()
+        .contains("upstream failed: connection reset"));
+}
+
+#[tokio::test]
+async fn input_errors_keep_their_source() {
+    let input = stream::iter([
+        Ok(Bytes::from_static(b"--BOUND\r\n")),
+        Err(std::io::Error::new(
+            std::io::ErrorKind::ConnectionReset,
+            "peer reset",
+        )),
+    ]);
+    let mut multipart = Multipart::new("BOUND", input).expect("multipart should initialize");
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("upstream error should surface");
+
+    assert!(matches!(err, MulterError::Parse(ParseError::Input { .. })));
+    assert_eq!(err.to_string(), "body stream error: peer reset");
+    let source = std::error::Error::source(&err)
+        .and_then(|source| source.downcast_ref::<std::io::Error>())
+        .expect("io error source");
+    assert_eq!(source.kind(), std::io::ErrorKind::ConnectionReset);
+}
+
+#[tokio::test]
+async fn reads_from_async_read_sources() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"alpha\"\r\n",
+        "\r\n",
+        "one\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"beta\"; filename=\"b.txt\"\r\n",
+        "\r\n",
+        "two\r\n",
+        "--BOUND--\r\n"
+    );
+
+    for chunk_size in [1, 7, multigear::multipart::DEFAULT_READ_CHUNK_SIZE] {
+        let (mut writer, reader) = tokio::io::duplex(64);
+        let write = tokio::spawn(async move {
+            use tokio::io::AsyncWriteExt;
+            writer.write_all(body.as_bytes()).await.expect("write");
+        });
+
+        let mut multipart = Multipart::from_async_read_with_chunk_size("BOUND", reader, chunk_size)
+            .expect("multipart should initialize");
+        let mut first = multipart
+            .next_part()
+            .await
+            .expect("first part should parse")
+            .expect("first part expected");
+        assert_eq!(first.text().await.expect("text"), "one");
+
+        let mut second = multipart
+            .next_part()
+            .await
+            .expect("second part should parse")
+            .expect("second part expected");
+        assert_eq!(second.file_name(), Some("b.txt"));
+        assert_eq!(second.text().await.expect("text"), "two");
+        assert!(multipart.next_part().await.expect("end").is_none());
+        write.await.expect("writer task");
+    }
+
+    let err = Multipart::from_async_read_with_chunk_size("BOUND", &b""[..], 0)
+        .expect_err("zero chunk size is rejected");
+    assert!(err.to_string().contains("chunk size"));
+}
+
+#[tokio::test]
+async fn events_interleave_part_boundaries_and_chunks() {
+    let body = concat!(
+        "--AaB03x\r\n",
+        "Content-Disposition: form-data; name=\"_charset_\"\r\n",
+        "\r\n",
+        "utf-8\r\n",
+        "--AaB03x\r\n",
+        "Content-Disposition: form-data; name=\"skipped\"; filename=\"s.bin\"\r\n",
+        "\r\n",
+        "ignored\r\n",
+        "--AaB03x\r\n",
+        "Content-Disposition: form-data; name=\"files\"\r\n",
+        "Content-Type: multipart/mixed; boundary=BbC04y\r\n",
+        "\r\n",
+        "--BbC04y\r\n",
+        "Content-Disposition: file; filename=\"file1.txt\"\r\n",
+        "\r\n",
+        "contents of file1\r\n",
+        "--BbC04y--\r\n",
+        "\r\n--AaB03x\r\n",
+        "Content-Disposition: form-data; name=\"title\"\r\n",
+        "\r\n",
+        "holiday\r\n",
+        "--AaB03x--\r\n"
+    );
+    let config = MulterConfig::new().selector(multigear::Selector::array("files", 4));
+
+    for chunks in [
+        vec![Bytes::from_static(body.as_bytes())],
+        split_bytes(body.as_bytes(), &[1; 1024]),
+    ] {
+        let input = stream::iter(chunks.into_iter().map(Ok::<Bytes, MulterError>));
+        let mut multipart = Multipart::with_config("AaB03x", input, config.clone())
+            .expect("multipart should initialize");
+
+        let mut summary = Vec::new();
+        let mut body = Vec::new();
+        let mut events = Box::pin(multipart.events());
+        while let Some(event) = events.next().await {
+            match event.expect("event should parse") {
+                MultipartEvent::PartStart(headers) => {
+                    summary.push(format!(
+                        "start {} {:?}",
+                        headers.field_name, headers.file_name
+                    ));
+                }
+                MultipartEvent::Chunk(chunk) => body.extend_from_slice(&chunk),
+                MultipartEvent::PartEnd => {
+                    summary.push(String::from_utf8(std::mem::take(&mut body)).expect("utf-8"));
+                }
+                MultipartEvent::Finished => summary.push("finished".to_owned()),
+                other => panic!("unexpected event: {other:?}"),
+            }
+        }
+
+        assert_eq!(
+            summary,
+            [
+                "start _charset_ None",
+                "utf-8",
+                "start files Some(\"file1.txt\")",
+                "contents of file1",
+                "start title None",
+                "holiday",
+                "finished",
+            ]
+        );
+    }
+}
+
+#[tokio::test]
+async fn events_stop_after_first_error() {
+    let input = stream::iter([
+        Ok::<Bytes, MulterError>(Bytes::from_static(
+            b"--BOUND\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\npartial",
+        )),
+        Err(ParseError::new("connection reset").into()),
+    ]);
+    let mut multipart = Multipart::new("BOUND", input).expect("multipart should initialize");
+    let events: Vec<_> = multipart.events().collect().await;
+
+    assert!(matches!(events[0], Ok(MultipartEvent::PartStart(_))));
+    assert!(matches!(events.last(), Some(Err(_))));
+    assert!(events
+        .iter()
+        .all(|event| !matches!(event, Ok(MultipartEvent::Finished))));
+}
+
+#[tokio::test]
+async fn coalesces_tiny_ready_chunks_before_scanning() {
+    let mut body =
+        b"--BOUND\r\nContent-Disposition: form-data; name=\"upload\"; filename=\"a.bin\"\r\n\r\n"
+            .to_vec();
+    body.extend(std::iter::repeat(b'x').take(64 * 1024));
+    body.extend_from_slice(b"\r\n--BOUND--\r\n");
+
+    let chunks = split_bytes(&body, &[1; 128 * 1024]);
+    let input = stream::iter(chunks.into_iter().map(Ok::<Bytes, MulterError>));
+    let mut multipart = Multipart::new("BOUND", input).expect("boundary should be valid");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("part should exist");
+
+    let mut stream = part.stream();
+    let mut total = 0;
+    let mut emitted = 0;
+    while let Some(chunk) = stream.next().await {
+        total += chunk.expect("chunk should parse").len();
+        emitted += 1;
+    }
+
+    assert_eq!(total, 64 * 1024);
+    assert!(emitted <= 10, "expected coalesced chunks, got {emitted}");
+}
+
+#[tokio::test]
+async fn coalescing_yields_buffered_data_before_upstream_error() {
+    let input = stream::iter([
+        Ok::<Bytes, MulterError>(Bytes::from_static(
+            b"--BOUND\r\nContent-Disposition: form-data; name=\"upload\"; filename=\"a.bin\"\r\n\r\n",
+        )),
+        Ok(Bytes::from_static(b"partial")),
+        Err(ParseError::new("connection reset").into()),
+    ]);
+    let mut multipart = Multipart::new("BOUND", input).expect("boundary should be valid");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("part should exist");
+
+    let mut stream = part.stream();
+    let first = stream
+        .next()
+        .await
+        .expect("chunk should exist")
+        .expect("buffered data should be yielded");
+    assert_eq!(first, Bytes::from_static(b"partial"));
+    let err = stream
+        .next()
+        .await
+        .expect("error should follow")
+        .expect_err("upstream error should surface");
+    assert!(err.to_string().contains("connection reset"));
+}
+
+#[tokio::test]
+async fn parse_errors_carry_part_and_offset_context() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"alpha\"\r\n",
+        "\r\n",
+        "one\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"beta\"\r\n",
+        "\r\n",
+        "two\r\n",
+        "--WRONG--\r\n"
+    );
+    let input = stream::iter(
+        split_bytes(body.as_bytes(), &[16; 16])
+            .into_iter()
+            .map(Ok::<Bytes, MulterError>),
+    );
+    let mut multipart = Multipart::new("BOUND", input).expect("boundary should be valid");
+
+    let mut first = multipart
+        .next_part()
+        .await
+        .expect("first part should parse")
+        .expect("first part expected");
+    assert_eq!(first.text().await.expect("text"), "one");
+
+    let mut second = multipart
+        .next_part()
+        .await
+        .expect("second part headers should parse")
+        .expect("second part expected");
+    let err = second.bytes().await.expect_err("body should fail");
+    let MulterError::Parse(err) = err else {
+        panic!("expected parse error, got {err:?}");
+    };
+    assert_eq!(err.message(), "malformed multipart boundary");
+    assert_eq!(err.part_index(), Some(1));
+    assert_eq!(err.field_name(), Some("beta"));
+    let offset = err.offset().expect("offset should be recorded");
+    assert!(
+        offset > 90 && offset <= body.len() as u64,
+        "offset {offset}"
+    );
+    assert!(err
+        .to_string()
+        .starts_with("malformed multipart boundary (part 1, field `beta`, near byte "));
+}
+
+#[tokio::test]
+async fn header_errors_report_part_index_without_field_name() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition form-data; name=\"field\"\r\n",
+        "\r\n",
+        "hello\r\n",
+        "--BOUND--\r\n"
+    );
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("boundary should be valid");
+
+    let Err(MulterError::Parse(err)) = multipart.next_part().await else {
+        panic!("invalid headers should fail");
+    };
+    assert_eq!(err.part_index(), Some(0));
+    assert_eq!(err.field_name(), None);
+    assert!(err.offset().is_some());
+}
+
+#[tokio::test]
+async fn lenient_compliance_unfolds_multi_line_header_values() {
+    let (name, file_name) = first_part_under(
+        ParserCompliance::Lenient,
+        "Content-Disposition: form-data;\r\n\tname=\"report\";\r\n   filename=\"q1.csv\"\r\nContent-Type:\r\n text/csv",
+    )
+    .await
+    .expect("folded headers should parse leniently");
+    assert_eq!(name, "report");
+    assert_eq!(file_name.as_deref(), Some("q1.csv"));
+
+    let err = first_part_under(
+        ParserCompliance::Lenient,
+        " X-Leading: fold\r\nContent-Disposition: form-data; name=\"a\"",
+    )
+    .await
+    .expect_err("continuation without a header should fail");
+    assert!(err.to_string().contains("invalid part header line"));
+}
+
+#[tokio::test]
+async fn duplicate_header_policy_is_applied_while_streaming() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n",
+        "Content-Type: text/plain\r\n",
+        "Content-Type: application/x-msdownload\r\n",
+        "\r\n",
+        "value\r\n",
+        "--BOUND--\r\n"
+    );
+
+    for (policy, expected) in [
+        (DuplicateHeaderPolicy::UseFirst, Some("text/plain")),
+        (
+            DuplicateHeaderPolicy::UseLast,
+            Some("application/x-msdownload"),
+        ),
+        (DuplicateHeaderPolicy::Reject, None),
+    ] {
+        let config = MulterConfig::new().duplicate_header_policy(policy);
+        let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+            body.as_bytes(),
+        ))]);
+        let mut multipart =
+            Multipart::with_config("BOUND", input, config).expect("multipart should initialize");
+
+        match (multipart.next_part().await, expected) {
+            (Ok(Some(part)), Some(expected)) => assert_eq!(part.content_type(), expected),
+            (Err(err), None) => {
+                assert!(err.to_string().contains("duplicate Content-Type header"))
+            }
+            (other, _) => panic!("unexpected result under {policy:?}: {other:?}"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn stats_track_parser_throughput() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"alpha\"\r\n",
+        "\r\n",
+        "one\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"beta\"; filename=\"b.txt\"\r\n",
+        "\r\n",
+        "two\r\n",
+        "--BOUND--\r\n"
+    );
+    let chunks = split_bytes(body.as_bytes(), &[40, 40, 40, 40]);
+    let chunk_count = chunks.len() as u64;
+    let input = stream::iter(chunks.into_iter().map(Ok::<Bytes, MulterError>));
+    let mut multipart = Multipart::new("BOUND", input).expect("boundary should be valid");
+    assert_eq!(multipart.stats(), multigear::ParserStats::default());
+
+    while let Some(mut part) = multipart.next_part().await.expect("part should parse") {
+        part.bytes().await.expect("body should read");
+    }
+
+    let stats = multipart.stats();
+    assert_eq!(stats.parts_emitted, 2);
+    assert_eq!(stats.chunks_received, chunk_count);
+    assert_eq!(stats.bytes_consumed, body.len() as u64);
+    assert!(stats.buffer_high_water_mark > 0);
+    assert!(stats.buffer_high_water_mark <= body.len());
+    assert!(stats.poll_count >= 4);
+}
+
+#[tokio::test]
+async fn next_file_and_next_text_skip_the_other_part_kind() {
+    let body = concat!(
+        "--XBOUND\r\n",
+        "Content-Disposition: form-data; name=\"_charset_\"\r\n",
+        "\r\n",
+        "iso-8859-1\r\n",
+        "--XBOUND\r\n",
+        "Content-Disposition: form-data; name=\"title\"\r\n",
+        "\r\n",
+        "caf\u{e9}\r\n",
+        "--XBOUND\r\n",
+        "Content-Disposition: form-data; name=\"photo\"; filename=\"p.jpg\"\r\n",
+        "\r\n",
+        "jpg\r\n",
+        "--XBOUND\r\n",
+        "Content-Disposition: form-data; name=\"note\"\r\n",
+        "\r\n",
+        "caf\u{e9}\r\n",
+        "--XBOUND--\r\n"
+    );
+
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("XBOUND", input).expect("multipart should initialize");
+    let mut photo = multipart
+        .next_file()
+        .await
+        .expect("file should parse")
+        .expect("file expected");
+    assert_eq!(photo.field_name(), "photo");
+    assert_eq!(photo.index(), 2);
+    assert_eq!(
+        photo.bytes().await.expect("body"),
+        Bytes::from_static(b"jpg")
+    );
+    drop(photo);
+
+    let mut note = multipart
+        .next_text()
+        .await
+        .expect("text should parse")
+        .expect("text expected");
+    assert_eq!(note.field_name(), "note");
+    // UTF-8 input decoded as ISO-8859-1 proves the skipped `_charset_` field applied.
+    assert_eq!(
+        note.text().await.expect("charset applies"),
+        "caf\u{c3}\u{a9}"
+    );
+    drop(note);
+    assert!(multipart.next_file().await.expect("end").is_none());
+
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("XBOUND", input).expect("multipart should initialize");
+    let mut texts = Vec::new();
+    while let Some(part) = multipart.next_text().await.expect("text should parse") {
+        texts.push(part.field_name().to_owned());
+    }
+    assert_eq!(texts, ["_charset_", "title", "note"]);
+}
+
+fn tracked_body(
+    chunks: Vec<&'static [u8]>,
+) -> (
+    impl futures::Stream<Item = Result<Bytes, MulterError>> + Unpin + Send + 'static,
+    futures::channel::oneshot::Receiver<()>,
+) {
+    let (done_tx, done_rx) = futures::channel::oneshot::channel();
+    let mut done_tx = Some(done_tx);
+    let end = stream::poll_fn(move |_| {
+        if let Some(done_tx) = done_tx.take() {
+            let _ = done_tx.send(());
+        }
+        std::task::Poll::Ready(None)
+    });
+    let body = stream::iter(
+        chunks
+            .into_iter()
+            .map(|chunk| Ok(Bytes::from_static(chunk))),
+    );
+    (body.chain(end), done_rx)
+}
+
+#[tokio::test]
+async fn drain_discards_the_rest_of_the_body_after_an_error() {
+    let (tx, rx) = mpsc::unbounded::<Result<Bytes, MulterError>>();
+    for chunk in [
+        &b"--XBOUND\r\nContent-Disposition: form-data; name=\"a\"; filename=\"a.bin\"\r\n\r\n"[..],
+        b"0123456789",
+    ] {
+        tx.unbounded_send(Ok(Bytes::from_static(chunk)))
+            .expect("send chunk");
+    }
+    let config = MulterConfig::new().limits(multigear::Limits::new().max_file_size(4));
+    let mut multipart = Multipart::with_config("XBOUND", rx, config).expect("multipart");
+
+    let mut part = multipart.next_part().await.expect("parse").expect("part");
+    let err = part.bytes().await.expect_err("file exceeds limit");
+    assert!(matches!(err, MulterError::FileSizeLimitExceeded { .. }));
+    drop(part);
+
+    let rest = b"0123456789\r\n--XBOUND--\r\n";
+    tx.unbounded_send(Ok(Bytes::from_static(rest)))
+        .expect("send chunk");
+    drop(tx);
+
+    let discarded = multipart.drain().await.expect("drain should succeed");
+    assert!(discarded >= rest.len() as u64);
+    assert!(multipart.next_part().await.expect("drained").is_none());
+}
+
+#[tokio::test]
+async fn drain_on_drop_reads_the_unread_body_in_the_background() {
+    let (body, done) = tracked_body(vec![
+        b"--XBOUND\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\none\r\n",
+        b"--XBOUND\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\ntwo\r\n",
+        b"--XBOUND--\r\n",
+    ]);
+    let mut multipart = Multipart::new("XBOUND", body)
+        .expect("multipart")
+        .drain_on_drop();
+
+    let part = multipart.next_part().await.expect("parse").expect("part");
+    assert_eq!(part.field_name(), "a");
+    drop(part);
+    drop(multipart);
+
+    tokio::time::timeout(std::time::Duration::from_secs(5), done)
+        .await
+        .expect("body should be drained")
+        .expect("stream should reach its end");
+}
+
+/// Signals once the body stream holding it is dropped.
+struct DropSignal(Option<futures::channel::oneshot::Sender<()>>);
+
+impl Drop for DropSignal {
+    fn drop(&mut self) {
+        if let Some(tx) = self.0.take() {
+            let _ = tx.send(());
+        }
+    }
+}
+
+fn endless_body(
+    tail: impl futures::Stream<Item = Result<Bytes, MulterError>> + Unpin + Send + 'static,
+) -> (
+    impl futures::Stream<Item = Result<Bytes, MulterError>> + Unpin + Send + 'static,
+    futures::channel::oneshot::Receiver<()>,
+) {
+    let (dropped_tx, dropped_rx) = futures::channel::oneshot::channel();
+    let signal = DropSignal(Some(dropped_tx));
+    let head = stream::iter([Ok(Bytes::from_static(
+        b"--XBOUND\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\none\r\n",
+    ))]);
+    let body = head.chain(tail).map(move |chunk| {
+        let _ = &signal;
+        chunk
+    });
+    (body, dropped_rx)
+}
+
+#[tokio::test]
+async fn rejects_unbounded_padding_after_a_boundary() {
+    let (body, _dropped) = endless_body(
+        stream::iter([Ok(Bytes::from_static(b"--XBOUND"))])
+            .chain(stream::iter(
+                (0..64).map(|_| Ok(Bytes::from_static(&[b' '; 64]))),
+            ))
+            .chain(stream::pending()),
+    );
+    let mut multipart = Multipart::new("XBOUND", body).expect("multipart");
+
+    let mut part = multipart.next_part().await.expect("parse").expect("part");
+    let err = tokio::time::timeout(std::time::Duration::from_secs(5), part.bytes())
+        .await
+        .expect("padding should be capped")
+        .expect_err("padding past the cap should fail");
+    assert!(matches!(
+        err,
+        MulterError::Parse(ref err) if err.message() == "malformed multipart boundary"
+    ));
+}
+
+#[tokio::test]
+async fn drain_on_drop_stops_at_the_byte_cap() {
+    let (body, dropped) = endless_body(stream::repeat_with(|| {
+        Ok(Bytes::from_static(&[b'x'; 1024]))
+    }));
+    let mut multipart = Multipart::new("XBOUND", body)
+        .expect("multipart")
+        .drain_on_drop()
+        .max_bytes(64 * 1024);
+
+    drop(multipart.next_part().await.expect("parse").expect("part"));
+    drop(multipart);
+
+    tokio::time::timeout(std::time::Duration::from_secs(5), dropped)
+        .await
+        .expect("drain should give up on an endless body")
+        .expect("body should be dropped");
+}
+
+#[tokio::test]
+async fn drain_on_drop_stops_at_the_timeout() {
+    let (body, dropped) = endless_body(stream::pending());
+    let mut multipart = Multipart::new("XBOUND", body)
+        .expect("multipart")
+        .drain_on_drop()
+        .timeout(std::time::Duration::from_millis(50));
+
+    drop(multipart.next_part().await.expect("parse").expect("part"));
+    drop(multipart);
+
+    tokio::time::timeout(std::time::Duration::from_secs(5), dropped)
+        .await
+        .expect("drain should give up on a stalled body")
+        .expect("body should be dropped");
+}
+
+#[tokio::test]
+async fn next_group_batches_consecutive_parts_by_field_name() {
+    let body = concat!(
+        "--XBOUND\r\n",
+        "Content-Disposition: form-data; name=\"files\"; filename=\"a.txt\"\r\n",
+        "\r\n",
+        "a\r\n",
+        "--XBOUND\r\n",
+        "Content-Disposition: form-data; name=\"files\"; filename=\"b.txt\"\r\n",
+        "\r\n",
+        "b\r\n",
+        "--XBOUND\r\n",
+        "Content-Disposition: form-data; name=\"title\"\r\n",
+        "\r\n",
+        "gallery\r\n",
+        "--XBOUND\r\n",
+        "Content-Disposition: form-data; name=\"files\"; filename=\"c.txt\"\r\n",
+        "\r\n",
+        "c\r\n",
+        "--XBOUND--\r\n"
+    );
+
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("XBOUND", input).expect("multipart should initialize");
+
+    let mut groups = Vec::new();
+    while let Some(mut group) = multipart.next_group().await.expect("group should parse") {
+        let field_name = group.field_name().to_owned();
+        let mut bodies = Vec::new();
+        while let Some(mut part) = group.next_part().await.expect("part should parse") {
+            bodies.push(part.text().await.expect("body"));
+        }
+        groups.push((field_name, bodies));
+    }
+
+    assert_eq!(
+        groups,
+        vec![
+            ("files".to_owned(), vec!["a".to_owned(), "b".to_owned()]),
+            ("title".to_owned(), vec!["gallery".to_owned()]),
+            ("files".to_owned(), vec!["c".to_owned()]),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn next_part_resumes_after_a_group_ends() {
+    let body = concat!(
+        "--XBOUND\r\n",
+        "Content-Disposition: form-data; name=\"files\"; filename=\"a.txt\"\r\n",
+        "\r\n",
+        "a\r\n",
+        "--XBOUND\r\n",
+        "Content-Disposition: form-data; name=\"title\"\r\n",
+        "\r\n",
+        "gallery\r\n",
+        "--XBOUND--\r\n"
+    );
+
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("XBOUND", input).expect("multipart should initialize");
+
+    let mut group = multipart
+        .next_group()
+        .await
+        .expect("group should parse")
+        .expect("group expected");
+    // Leave the file body unread; the group drains it.
+    assert!(group.next_part().await.expect("part").is_some());
+    assert!(group.next_part().await.expect("group end").is_none());
+
+    let mut title = multipart
+        .next_part()
+        .await
+        .expect("part should parse")
+        .expect("part expected");
+    assert_eq!(title.field_name(), "title");
+    assert_eq!(title.text().await.expect("body"), "gallery");
+    assert!(multipart.next_part().await.expect("end").is_none());
+}
+
+#[tokio::test]
+async fn into_inner_hands_back_buffered_input_and_source() {
+    let (tx, rx) = mpsc::unbounded::<Result<Bytes, MulterError>>();
+    tx.unbounded_send(Ok(Bytes::from_static(
+        b"--XBOUND\r\nContent-Disposition: form-data; name=\"meta\"\r\n\r\nv1\r\n--XBOUND\r\nContent-Type: app",
+    )))
+    .expect("send first chunk");
+
+    let mut multipart = Multipart::new("XBOUND", rx).expect("multipart should initialize");
+    let mut meta = multipart
+        .next_part()
+        .await
+        .expect("part should parse")
+        .expect("part expected");
+    assert_eq!(meta.text().await.expect("body"), "v1");
+
+    let (mut source, buffered) = multipart.into_inner();
+    assert_eq!(buffered, Bytes::from_static(b"Content-Type: app"));
+
+    tx.unbounded_send(Ok(Bytes::from_static(b"lication/octet-stream\r\n")))
+        .expect("send rest");
+    drop(tx);
+    let rest = source
+        .next()
+        .await
+        .expect("chunk expected")
+        .expect("chunk should be ok");
+    assert_eq!(rest, Bytes::from_static(b"lication/octet-stream\r\n"));
+    assert!(source.next().await.is_none());
+}
@@ -1,8 +1,8 @@
 #![allow(missing_docs)]
 
 use bytes::Bytes;
-use futures::{stream, TryStreamExt};
-use multigear::{MulterError, Multipart, ParseError};
+use futures::{stream, StreamExt, TryStreamExt};
+use multigear::{EncodedWordPolicy, MulterConfig, MulterError, Multipart, ParseError};
 
 #[tokio::test]
 async fn exposes_metadata_accessors() {
@@ -39,6 +39,59 @@ async fn exposes_metadata_accessors() {
     assert_eq!(part.size_hint(), Some(3));
 }
 
+#[tokio::test]
+async fn file_name_exposes_decoded_extended_parameter() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"doc\"; filename=\"EUR.txt\"; filename*=UTF-8''%E2%82%AC.txt\r\n",
+        "\r\n",
+        "abc\r\n",
+        "--BOUND--\r\n"
+    );
+
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("boundary should be valid");
+    let part = multipart
+        .next_part()
+        .await
+        .expect("part expected")
+        .expect("part should parse");
+
+    assert_eq!(part.file_name(), Some("\u{20ac}.txt"));
+}
+
+#[tokio::test]
+async fn file_name_decodes_encoded_words_when_enabled() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"doc\"; filename=\"=?UTF-8?B?4oKsLnR4dA==?=\"\r\n",
+        "\r\n",
+        "abc\r\n",
+        "--BOUND--\r\n"
+    );
+
+    for (policy, expected) in [
+        (EncodedWordPolicy::Preserve, "=?UTF-8?B?4oKsLnR4dA==?="),
+        (EncodedWordPolicy::Decode, "\u{20ac}.txt"),
+    ] {
+        let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+            body.as_bytes(),
+        ))]);
+        let config = MulterConfig::new().encoded_word_policy(policy);
+        let mut multipart =
+            Multipart::with_config("BOUND", input, config).expect("boundary should be valid");
+        let part = multipart
+            .next_part()
+            .await
+            .expect("part expected")
+            .expect("part should parse");
+
+        assert_eq!(part.file_name(), Some(expected));
+    }
+}
+
 #[tokio::test]
 async fn bytes_are_single_pass() {
     let input_body =
@@ -122,3 +175,473 @@ fn assert_already_consumed(err: MulterError) {
         "unexpected error: {err}"
     );
 }
+
+#[tokio::test]
+async fn charset_field_sets_default_text_charset() {
+    let mut body = Vec::new();
+    body.extend_from_slice(
+        concat!(
+            "--BOUND\r\n",
+            "Content-Disposition: form-data; name=\"_charset_\"\r\n",
+            "\r\n",
+            "iso-8859-1\r\n",
+            "--BOUND\r\n",
+            "Content-Disposition: form-data; name=\"city\"\r\n",
+            "\r\n",
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(b"M\xfcnchen\r\n");
+    body.extend_from_slice(
+        concat!(
+            "--BOUND\r\n",
+            "Content-Disposition: form-data; name=\"explicit\"\r\n",
+            "Content-Type: text/plain; charset=utf-8\r\n",
+            "\r\n",
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice("M\u{fc}nchen\r\n--BOUND--\r\n".as_bytes());
+
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from(body))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("boundary should be valid");
+
+    let mut charset = multipart
+        .next_part()
+        .await
+        .expect("part expected")
+        .expect("part should parse");
+    assert_eq!(charset.field_name(), "_charset_");
+    assert_eq!(charset.text().await.expect("charset text"), "iso-8859-1");
+
+    let mut city = multipart
+        .next_part()
+        .await
+        .expect("part expected")
+        .expect("part should parse");
+    assert_eq!(city.text().await.expect("latin-1 text"), "M\u{fc}nchen");
+
+    let mut explicit = multipart
+        .next_part()
+        .await
+        .expect("part expected")
+        .expect("part should parse");
+    assert_eq!(explicit.text().await.expect("utf-8 text"), "M\u{fc}nchen");
+}
+
+#[tokio::test]
+async fn strip_text_bom_removes_leading_bom_from_text_fields_only() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"note\"\r\n",
+        "\r\n",
+        "\u{feff}hello\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"doc\"; filename=\"doc.txt\"\r\n",
+        "Content-Type: text/plain\r\n",
+        "\r\n",
+        "\u{feff}file\r\n",
+        "--BOUND--\r\n"
+    );
+
+    for (strip_text_bom, note) in [(false, "\u{feff}hello"), (true, "hello")] {
+        let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+            body.as_bytes(),
+        ))]);
+        let config = MulterConfig::new().strip_text_bom(strip_text_bom);
+        let mut multipart =
+            Multipart::with_config("BOUND", input, config).expect("multipart should initialize");
+
+        let mut text = multipart
+            .next_part()
+            .await
+            .expect("part should parse")
+            .expect("part expected");
+        assert_eq!(text.text().await.expect("text"), note);
+
+        let mut file = multipart
+            .next_part()
+            .await
+            .expect("part should parse")
+            .expect("part expected");
+        assert_eq!(file.text().await.expect("text"), "\u{feff}file");
+    }
+}
+
+#[cfg(feature = "json")]
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct UploadMeta {
+    title: String,
+    tags: Vec<String>,
+}
+
+#[cfg(feature = "json")]
+async fn first_part_json(content_type: &str, json: &str) -> Result<UploadMeta, MulterError> {
+    let body = format!(
+        "--BOUND\r\nContent-Disposition: form-data; name=\"meta\"\r\nContent-Type: {content_type}\r\n\r\n{json}\r\n--BOUND--\r\n"
+    );
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from(body))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("boundary should be valid");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("part should parse")
+        .expect("part expected");
+    part.json().await
+}
+
+#[cfg(feature = "json")]
+#[tokio::test]
+async fn json_deserializes_json_parts() {
+    let expected = UploadMeta {
+        title: "holiday".to_owned(),
+        tags: vec!["beach".to_owned()],
+    };
+    for content_type in [
+        "application/json",
+        "application/json; charset=utf-8",
+        "application/vnd.api+json",
+    ] {
+        let meta = first_part_json(content_type, r#"{"title":"holiday","tags":["beach"]}"#)
+            .await
+            .unwrap_or_else(|err| panic!("{content_type} should deserialize: {err}"));
+        assert_eq!(meta, expected);
+    }
+}
+
+#[cfg(feature = "json")]
+#[tokio::test]
+async fn json_rejects_other_content_types_and_invalid_bodies() {
+    let err = first_part_json("text/plain", r#"{"title":"a","tags":[]}"#)
+        .await
+        .expect_err("text/plain should be rejected");
+    assert!(err.to_string().contains("is not JSON"));
+
+    let err = first_part_json("application/json", r#"{"title":1}"#)
+        .await
+        .expect_err("mismatched body should fail");
+    assert!(err.to_string().contains("invalid JSON part body"));
+}
+
+fn save_dir() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("multigear-save-{}", uuid::Uuid::new_v4()))
+}
+
+async fn dir_entries(path: &std::path::Path) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut dir = tokio::fs::read_dir(path).await.expect("dir should exist");
+    while let Some(entry) = dir.next_entry().await.expect("entry should read") {
+        entries.push(entry.file_name().to_string_lossy().into_owned());
+    }
+    entries
+}
+
+#[tokio::test]
+async fn save_to_creates_parents_and_replaces_existing_files() {
+    let root = save_dir();
+    let target = root.join("nested").join("upload.bin");
+    tokio::fs::create_dir_all(target.parent().expect("parent"))
+        .await
+        .expect("parent should be created");
+    tokio::fs::write(&target, b"stale contents")
+        .await
+        .expect("existing file should be written");
+
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"file\"; filename=\"a.bin\"\r\n",
+        "\r\n",
+        "payload\r\n",
+        "--BOUND--\r\n"
+    );
+    let chunks = body
+        .as_bytes()
+        .chunks(5)
+        .map(|chunk| Ok::<Bytes, MulterError>(Bytes::copy_from_slice(chunk)))
+        .collect::<Vec<_>>();
+    let mut multipart = Multipart::new("BOUND", stream::iter(chunks)).expect("multipart");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("part should parse")
+        .expect("part expected");
+
+    let written = part.save_to(&target).await.expect("save should succeed");
+    assert_eq!(written, 7);
+    assert_eq!(
+        tokio::fs::read(&target).await.expect("file should read"),
+        b"payload"
+    );
+    assert_eq!(
+        dir_entries(target.parent().expect("parent")).await,
+        ["upload.bin"]
+    );
+
+    let _ = tokio::fs::remove_dir_all(&root).await;
+}
+
+#[tokio::test]
+async fn save_to_leaves_no_file_when_the_body_fails() {
+    let root = save_dir();
+    let target = root.join("upload.bin");
+    let input = stream::iter([
+        Ok::<Bytes, MulterError>(Bytes::from_static(
+            b"--BOUND\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.bin\"\r\n\r\npartial",
+        )),
+        Err(ParseError::new("connection reset").into()),
+    ]);
+    let mut multipart = Multipart::new("BOUND", input).expect("multipart");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("part should parse")
+        .expect("part expected");
+
+    let err = part
+        .save_to(&target)
+        .await
+        .expect_err("body failure should surface");
+    assert!(err.to_string().contains("connection reset"));
+    assert!(dir_entries(&root).await.is_empty());
+
+    let _ = tokio::fs::remove_dir_all(&root).await;
+}
+
+#[tokio::test]
+async fn peek_sniffs_leading_bytes_without_consuming_the_body() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"image\"; filename=\"a.png\"\r\n",
+        "Content-Type: image/png\r\n",
+        "\r\n",
+        "\u{89}PNG-image-bytes\r\n",
+        "--BOUND--\r\n"
+    );
+    let (head, tail) = body.split_at(body.find("PNG").expect("marker"));
+    // Yield between chunks so the parser hands the body out in pieces.
+    let input = Box::pin(
+        stream::iter([head, &tail[..2], &tail[2..]]).then(|chunk| async move {
+            tokio::task::yield_now().await;
+            Ok::<Bytes, MulterError>(Bytes::copy_from_slice(chunk.as_bytes()))
+        }),
+    );
+    let mut multipart = Multipart::new("BOUND", input).expect("multipart");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("part should parse")
+        .expect("part expected");
+
+    let magic = part.peek(5).await.expect("peek should succeed");
+    assert_eq!(&magic[..], "\u{89}PNG".as_bytes());
+    assert_eq!(
+        part.peek(2).await.expect("shorter peek"),
+        Bytes::from_static(&[0xC2, 0x89])
+    );
+    assert_eq!(
+        part.bytes().await.expect("body should read"),
+        Bytes::from("\u{89}PNG-image-bytes")
+    );
+}
+
+#[tokio::test]
+async fn peeked_parts_store_their_full_body() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n",
+        "\r\n",
+        "tiny\r\n",
+        "--BOUND--\r\n"
+    );
+    let storage = multigear::MemoryStorage::new();
+    let multer = multigear::Multer::new(storage.clone());
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("multipart");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("part should parse")
+        .expect("part expected");
+
+    assert_eq!(
+        part.peek(64).await.expect("peek past the end"),
+        Bytes::from_static(b"tiny")
+    );
+    let stored = multer.store(part).await.expect("store should succeed");
+    assert_eq!(
+        storage.get(&stored.storage_key).await,
+        Some(Bytes::from_static(b"tiny"))
+    );
+}
+
+#[tokio::test]
+async fn parts_report_index_and_kind() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"title\"\r\n",
+        "\r\n",
+        "holiday\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"skipped\"; filename=\"s.bin\"\r\n",
+        "\r\n",
+        "ignored\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"photo\"; filename=\"p.jpg\"\r\n",
+        "Content-Length: 3\r\n",
+        "\r\n",
+        "jpg\r\n",
+        "--BOUND--\r\n"
+    );
+    let config = MulterConfig::new().selector(multigear::Selector::single("photo"));
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::with_config("BOUND", input, config).expect("multipart");
+
+    let title = multipart
+        .next_part()
+        .await
+        .expect("part should parse")
+        .expect("title expected");
+    assert_eq!(title.index(), 0);
+    assert!(!title.is_file());
+    assert_eq!(title.size_hint(), None);
+    drop(title);
+
+    let photo = multipart
+        .next_part()
+        .await
+        .expect("part should parse")
+        .expect("photo expected");
+    assert_eq!(photo.field_name(), "photo");
+    assert_eq!(photo.index(), 2);
+    assert!(photo.is_file());
+    assert_eq!(photo.size_hint(), Some(3));
+}
+
+#[tokio::test]
+async fn bytes_limited_enforces_explicit_cap() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"note\"\r\n",
+        "\r\n",
+        "hello\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"exact\"\r\n",
+        "\r\n",
+        "12345\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"upload\"; filename=\"a.bin\"\r\n",
+        "\r\n",
+        "0123456789\r\n",
+        "--BOUND--\r\n"
+    );
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("multipart");
+
+    let mut note = multipart.next_part().await.expect("parse").expect("note");
+    let err = note.bytes_limited(4).await.expect_err("over cap");
+    assert!(matches!(
+        err,
+        MulterError::FieldSizeLimitExceeded { ref field, max_field_size: 4 } if field == "note"
+    ));
+    drop(note);
+
+    let mut exact = multipart.next_part().await.expect("parse").expect("exact");
+    assert_eq!(
+        exact.bytes_limited(5).await.expect("at cap"),
+        Bytes::from_static(b"12345")
+    );
+
+    let mut upload = multipart.next_part().await.expect("parse").expect("upload");
+    let err = upload.bytes_limited(3).await.expect_err("over cap");
+    assert!(matches!(
+        err,
+        MulterError::FileSizeLimitExceeded { ref field, max_file_size: 3 } if field == "upload"
+    ));
+}
+
+#[tokio::test]
+async fn extension_and_guessed_mime_follow_the_sanitized_file_name() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"photo\"; filename=\"../evil.PNG\"\r\n",
+        "Content-Type: application/octet-stream\r\n",
+        "\r\n",
+        "png\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"blob\"; filename=\"README\"\r\n",
+        "\r\n",
+        "text\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"note\"\r\n",
+        "\r\n",
+        "hello\r\n",
+        "--BOUND--\r\n"
+    );
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("multipart");
+
+    let photo = multipart.next_part().await.expect("parse").expect("photo");
+    assert_eq!(photo.extension().as_deref(), Some("png"));
+    assert_eq!(photo.guessed_mime(), Some(mime::IMAGE_PNG));
+    drop(photo);
+
+    let blob = multipart.next_part().await.expect("parse").expect("blob");
+    assert_eq!(blob.extension(), None);
+    assert_eq!(blob.guessed_mime(), None);
+    drop(blob);
+
+    let note = multipart.next_part().await.expect("parse").expect("note");
+    assert_eq!(note.extension(), None);
+}
+
+async fn drain<S>(body: S) -> Result<Vec<u8>, MulterError>
+where
+    S: futures::Stream<Item = Result<Bytes, MulterError>>,
+{
+    body.try_fold(Vec::new(), |mut out, chunk| async move {
+        out.extend_from_slice(&chunk);
+        Ok(out)
+    })
+    .await
+}
+
+#[tokio::test]
+async fn into_parts_hands_out_headers_and_body_stream() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"upload\"; filename=\"a.txt\"\r\n",
+        "Content-Type: text/plain\r\n",
+        "\r\n",
+        "payload\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"note\"\r\n",
+        "\r\n",
+        "hello\r\n",
+        "--BOUND--\r\n"
+    );
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("multipart");
+
+    let mut upload = multipart.next_part().await.expect("parse").expect("upload");
+    assert_eq!(
+        upload.peek(3).await.expect("peek"),
+        Bytes::from_static(b"pay")
+    );
+    let (headers, body) = upload.into_parts();
+    assert_eq!(headers.field_name, "upload");
+    assert_eq!(headers.file_name.as_deref(), Some("a.txt"));
+    assert_eq!(drain(body).await.expect("body"), b"payload");
+
+    let note = multipart.next_part().await.expect("parse").expect("note");
+    assert_eq!(drain(note.into_stream()).await.expect("body"), b"hello");
+}
@@ -0,0 +1,114 @@
+#![allow(missing_docs)]
+
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use multigear::{
+    scan::{AvScanner, ScanVerdict},
+    BoxStream, FileMeta, MemoryStorage, Multer, MulterError, ScanError,
+};
+
+const EICAR_PREFIX: &[u8] = b"X5O!P%@AP";
+
+/// Flags bodies containing the EICAR prefix; fails on `unscannable` files.
+struct PrefixScanner;
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl AvScanner for PrefixScanner {
+    async fn scan(
+        &self,
+        meta: &FileMeta,
+        mut body: BoxStream<'_, Bytes>,
+    ) -> Result<ScanVerdict, ScanError> {
+        if meta.file_name.as_deref() == Some("unscannable.bin") {
+            return Err(ScanError::new("scanner offline"));
+        }
+        let mut seen = Vec::new();
+        while let Some(chunk) = body.next().await {
+            seen.extend_from_slice(&chunk);
+            if seen
+                .windows(EICAR_PREFIX.len())
+                .any(|window| window == EICAR_PREFIX)
+            {
+                return Ok(ScanVerdict::Infected {
+                    signature: "Eicar-Test-Signature".to_owned(),
+                });
+            }
+        }
+        Ok(ScanVerdict::Clean)
+    }
+}
+
+fn chunks(file_name: &str, payload: &str) -> Vec<Result<Bytes, MulterError>> {
+    let body = format!(
+        "--BOUND\r\nContent-Disposition: form-data; name=\"upload\"; filename=\"{file_name}\"\r\nContent-Type: application/octet-stream\r\n\r\n{payload}\r\n--BOUND--\r\n"
+    );
+    body.into_bytes()
+        .chunks(7)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect()
+}
+
+fn multer(storage: MemoryStorage) -> Multer<MemoryStorage> {
+    Multer::builder()
+        .storage(storage)
+        .scanner(PrefixScanner)
+        .build()
+        .expect("config should validate")
+}
+
+#[tokio::test]
+async fn clean_files_are_stored_in_full() {
+    let storage = MemoryStorage::new();
+    let payload = "harmless text ".repeat(64);
+
+    let output = multer(storage.clone())
+        .parse_and_store("BOUND", stream::iter(chunks("notes.txt", &payload)))
+        .await
+        .expect("clean file should be stored");
+
+    let stored = storage
+        .get(&output.stored_files[0].storage_key)
+        .await
+        .expect("stored payload should exist");
+    assert_eq!(stored, Bytes::from(payload));
+}
+
+#[tokio::test]
+async fn infected_files_are_vetoed_before_storage_completes() {
+    let storage = MemoryStorage::new();
+    let payload = format!(
+        "{}{}",
+        "padding ".repeat(32),
+        "X5O!P%@AP[4\\PZX54(P^)7CC)7}"
+    );
+
+    let err = multer(storage.clone())
+        .parse_and_store("BOUND", stream::iter(chunks("eicar.com", &payload)))
+        .await
+        .expect_err("infected file should be rejected");
+
+    assert!(matches!(
+        err,
+        MulterError::ScanRejected { field, signature }
+            if field == "upload" && signature == "Eicar-Test-Signature"
+    ));
+    assert!(storage.is_empty().await);
+}
+
+#[tokio::test]
+async fn scanner_failures_reject_the_upload() {
+    let storage = MemoryStorage::new();
+
+    let err = multer(storage.clone())
+        .parse_and_store("BOUND", stream::iter(chunks("unscannable.bin", "data")))
+        .await
+        .expect_err("scanner failure should be rejected");
+
+    assert!(matches!(
+        err,
+        MulterError::Scan { field, source }
+            if field == "upload" && source == ScanError::new("scanner offline")
+    ));
+    assert!(storage.is_empty().await);
+}
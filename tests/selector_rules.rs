@@ -1,18 +1,19 @@
 #![allow(missing_docs)]
 
+use std::sync::{Arc, Mutex};
+
 use bytes::Bytes;
 use futures::stream;
 use multigear::{
-    MulterConfig, MulterError, Multipart, SelectedField, Selector, UnknownFieldPolicy,
+    FieldConstraint, FieldNameMatch, MulterConfig, MulterError, Multipart, SelectedField, Selector,
+    UnknownFieldPolicy,
 };
 
 #[tokio::test]
 async fn single_selector_rejects_second_file_for_same_field() {
-    let config = MulterConfig {
-        selector: Selector::single("avatar"),
-        unknown_field_policy: UnknownFieldPolicy::Reject,
-        ..MulterConfig::default()
-    };
+    let config = MulterConfig::new()
+        .selector(Selector::single("avatar"))
+        .unknown_field_policy(UnknownFieldPolicy::Reject);
     let body = multipart_body(&[
         ("avatar", Some("a.png"), "one"),
         ("avatar", Some("b.png"), "two"),
@@ -43,11 +44,9 @@ async fn single_selector_rejects_second_file_for_same_field() {
 
 #[tokio::test]
 async fn array_selector_rejects_unknown_file_field() {
-    let config = MulterConfig {
-        selector: Selector::array("photos", 2),
-        unknown_field_policy: UnknownFieldPolicy::Reject,
-        ..MulterConfig::default()
-    };
+    let config = MulterConfig::new()
+        .selector(Selector::array("photos", 2))
+        .unknown_field_policy(UnknownFieldPolicy::Reject);
     let body = multipart_body(&[("avatar", Some("a.png"), "one")]);
     let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
         .expect("multipart should initialize");
@@ -61,14 +60,12 @@ async fn array_selector_rejects_unknown_file_field() {
 
 #[tokio::test]
 async fn fields_selector_enforces_per_field_max_counts() {
-    let config = MulterConfig {
-        selector: Selector::fields([
+    let config = MulterConfig::new()
+        .selector(Selector::fields([
             SelectedField::new("docs").with_max_count(1),
             SelectedField::new("images").with_max_count(2),
-        ]),
-        unknown_field_policy: UnknownFieldPolicy::Reject,
-        ..MulterConfig::default()
-    };
+        ]))
+        .unknown_field_policy(UnknownFieldPolicy::Reject);
     let body = multipart_body(&[
         ("docs", Some("a.txt"), "one"),
         ("images", Some("1.png"), "two"),
@@ -118,11 +115,9 @@ async fn fields_selector_enforces_per_field_max_counts() {
 
 #[tokio::test]
 async fn none_selector_with_ignore_policy_skips_files_but_keeps_text_fields() {
-    let config = MulterConfig {
-        selector: Selector::none(),
-        unknown_field_policy: UnknownFieldPolicy::Ignore,
-        ..MulterConfig::default()
-    };
+    let config = MulterConfig::new()
+        .selector(Selector::none())
+        .unknown_field_policy(UnknownFieldPolicy::Ignore);
     let body = multipart_body(&[
         ("avatar", Some("a.png"), "file-one"),
         ("note", None, "hello"),
@@ -145,11 +140,9 @@ async fn none_selector_with_ignore_policy_skips_files_but_keeps_text_fields() {
 
 #[tokio::test]
 async fn any_selector_accepts_all_file_fields() {
-    let config = MulterConfig {
-        selector: Selector::any(),
-        unknown_field_policy: UnknownFieldPolicy::Reject,
-        ..MulterConfig::default()
-    };
+    let config = MulterConfig::new()
+        .selector(Selector::any())
+        .unknown_field_policy(UnknownFieldPolicy::Reject);
     let body = multipart_body(&[("a", Some("a.bin"), "one"), ("b", Some("b.bin"), "two")]);
     let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
         .expect("multipart should initialize");
@@ -169,6 +162,266 @@ async fn any_selector_accepts_all_file_fields() {
     assert_eq!(names, vec!["a", "b"]);
 }
 
+#[tokio::test]
+async fn fields_selector_reports_missing_required_fields_at_end_of_stream() {
+    let config = MulterConfig::new().selector(Selector::fields([
+        SelectedField::new("avatar").required(),
+        SelectedField::text("title").required(),
+        SelectedField::text("notes"),
+    ]));
+    let body = multipart_body(&[("avatar", Some("a.png"), "png"), ("notes", None, "hi")]);
+
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config.clone())
+        .expect("multipart should initialize");
+    let mut seen = Vec::new();
+    let err = loop {
+        match multipart.next_part().await {
+            Ok(Some(part)) => seen.push(part.field_name().to_owned()),
+            Ok(None) => panic!("missing required field should fail"),
+            Err(err) => break err,
+        }
+    };
+    assert_eq!(seen, ["avatar", "notes"]);
+    assert!(matches!(
+        err,
+        MulterError::MissingRequiredField { field } if field == "title"
+    ));
+
+    let body = multipart_body(&[("title", None, "t"), ("avatar", Some("a.png"), "png")]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+    while multipart
+        .next_part()
+        .await
+        .expect("required fields are present")
+        .is_some()
+    {}
+}
+
+#[tokio::test]
+async fn fields_selector_enforces_min_count_at_end_of_stream() {
+    let config = MulterConfig::new().selector(Selector::fields([SelectedField::new("gallery")
+        .min_count(2)
+        .max_count(4)]));
+
+    let body = multipart_body(&[("gallery", Some("a.png"), "png")]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config.clone())
+        .expect("multipart should initialize");
+    assert!(multipart.next_part().await.expect("first photo").is_some());
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("one photo is below min_count");
+    assert!(matches!(
+        err,
+        MulterError::FieldCountBelowMinimum { field, min_count: 2 } if field == "gallery"
+    ));
+
+    let body = multipart_body(&[
+        ("gallery", Some("a.png"), "png"),
+        ("gallery", Some("b.png"), "png"),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+    while multipart
+        .next_part()
+        .await
+        .expect("min_count is met")
+        .is_some()
+    {}
+}
+
+#[tokio::test]
+async fn fields_selector_maps_aliases_to_the_selected_field() {
+    let config = MulterConfig::new()
+        .selector(Selector::fields([SelectedField::new("avatar")
+            .aliases(["profile_image", "photo"])
+            .max_count(2)]))
+        .unknown_field_policy(UnknownFieldPolicy::Reject);
+    let body = multipart_body(&[
+        ("profile_image", Some("a.png"), "one"),
+        ("avatar", Some("b.png"), "two"),
+        ("photo", Some("c.png"), "three"),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    for _ in 0..2 {
+        let part = multipart
+            .next_part()
+            .await
+            .expect("part should parse")
+            .expect("part expected");
+        assert_eq!(part.field_name(), "avatar");
+    }
+
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("aliases share the field's max_count");
+    assert!(matches!(
+        err,
+        MulterError::FieldCountLimitExceeded { field, max_count: 2 } if field == "avatar"
+    ));
+}
+
+#[tokio::test]
+async fn fields_selector_matches_glob_patterns_after_exact_names() {
+    let config = MulterConfig::new()
+        .selector(Selector::fields([
+            SelectedField::new("photo_*")
+                .name_match(FieldNameMatch::Glob)
+                .max_count(2),
+            SelectedField::text("photo_caption"),
+        ]))
+        .unknown_field_policy(UnknownFieldPolicy::Reject);
+    let body = multipart_body(&[
+        ("photo_caption", None, "beach"),
+        ("photo_0", Some("a.png"), "one"),
+        ("photo_1", Some("b.png"), "two"),
+        ("photo_2", Some("c.png"), "three"),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let caption = multipart
+        .next_part()
+        .await
+        .expect("caption should parse")
+        .expect("caption expected");
+    assert_eq!(caption.field_name(), "photo_caption");
+    assert!(caption.file_name().is_none());
+    drop(caption);
+
+    for name in ["photo_0", "photo_1"] {
+        let part = multipart
+            .next_part()
+            .await
+            .expect("photo should parse")
+            .expect("photo expected");
+        assert_eq!(part.field_name(), name);
+    }
+
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("pattern max_count should cover all matching fields");
+    assert!(matches!(
+        err,
+        MulterError::FieldCountLimitExceeded { field, max_count: 2 } if field == "photo_2"
+    ));
+}
+
+#[cfg(feature = "regex")]
+#[tokio::test]
+async fn fields_selector_matches_whole_name_regex_patterns() {
+    let config = MulterConfig::new()
+        .selector(Selector::fields([SelectedField::new(r"files\[\d+\]")
+            .name_match(FieldNameMatch::Regex)
+            .required()]))
+        .unknown_field_policy(UnknownFieldPolicy::Reject);
+    let body = multipart_body(&[
+        ("files[0]", Some("a.txt"), "one"),
+        ("files[1]x", Some("b.txt"), "two"),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let first = multipart
+        .next_part()
+        .await
+        .expect("first file should parse")
+        .expect("first file expected");
+    assert_eq!(first.field_name(), "files[0]");
+    drop(first);
+
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("regex must match the whole field name");
+    assert!(matches!(err, MulterError::UnexpectedField { field } if field == "files[1]x"));
+}
+
+#[tokio::test]
+async fn field_constraints_are_checked_at_end_of_stream() {
+    let config = MulterConfig::new()
+        .constraint(FieldConstraint::exactly_one_of([
+            "avatar_file",
+            "avatar_url",
+        ]))
+        .constraint(FieldConstraint::requires("cover", "gallery"));
+
+    let cases = [
+        (multipart_body(&[("avatar_url", None, "https://x")]), None),
+        (
+            multipart_body(&[
+                ("avatar_file", Some("a.png"), "png"),
+                ("avatar_url", None, "https://x"),
+            ]),
+            Some("exactly one of `avatar_file`, `avatar_url`"),
+        ),
+        (
+            multipart_body(&[
+                ("avatar_file", Some("a.png"), "png"),
+                ("cover", Some("c.png"), "png"),
+            ]),
+            Some("`cover` requires `gallery`"),
+        ),
+        (
+            multipart_body(&[("cover", Some("c.png"), "png")]),
+            Some("exactly one of `avatar_file`, `avatar_url`"),
+        ),
+    ];
+
+    for (body, expected) in cases {
+        let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config.clone())
+            .expect("multipart should initialize");
+        let result = loop {
+            match multipart.next_part().await {
+                Ok(Some(_)) => {}
+                Ok(None) => break None,
+                Err(err) => break Some(err),
+            }
+        };
+        match (result, expected) {
+            (None, None) => {}
+            (Some(MulterError::FieldConstraintViolated { constraint }), Some(expected)) => {
+                assert_eq!(constraint.to_string(), expected);
+            }
+            (result, expected) => panic!("expected {expected:?}, got {result:?}"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn warn_policy_reports_unknown_fields_and_ignores_them() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Arc::clone(&seen);
+    let config = MulterConfig::new()
+        .selector(Selector::fields([SelectedField::new("avatar")]))
+        .unknown_field_policy(UnknownFieldPolicy::warn(move |field| {
+            recorder.lock().expect("lock").push(field.to_owned());
+        }));
+    let body = multipart_body(&[
+        ("tracking", None, "x"),
+        ("avatar", Some("a.png"), "png"),
+        ("extra", Some("e.bin"), "bin"),
+    ]);
+    let mut multipart = Multipart::with_config("BOUND", bytes_stream(body), config)
+        .expect("multipart should initialize");
+
+    let mut accepted = Vec::new();
+    while let Some(part) = multipart
+        .next_part()
+        .await
+        .expect("unknown fields are not fatal")
+    {
+        accepted.push(part.field_name().to_owned());
+    }
+    assert_eq!(accepted, ["avatar"]);
+    assert_eq!(*seen.lock().expect("lock"), ["tracking", "extra"]);
+}
+
 fn multipart_body(parts: &[(&str, Option<&str>, &str)]) -> Vec<u8> {
     let mut out = Vec::new();
     for (field, file_name, body) in parts {
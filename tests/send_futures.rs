@@ -0,0 +1,61 @@
+#![allow(missing_docs)]
+#![cfg(not(feature = "unsend"))]
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::stream;
+use multigear::{MemoryStorage, Multer, MulterError, StorageEngine, StoreContext};
+use tokio_util::sync::CancellationToken;
+
+fn assert_send<T: Send>(_: &T) {}
+
+/// Compiles only while upload futures are `Send` for every storage engine.
+#[allow(dead_code)]
+fn upload_futures_are_send<S: StorageEngine>(multer: &Multer<S>) {
+    let body = || stream::iter(Vec::<Result<Bytes, MulterError>>::new());
+    assert_send(&multer.parse_and_store("BOUND", body()));
+    assert_send(&multer.parse_and_store_with_cancellation(
+        "BOUND",
+        body(),
+        CancellationToken::new(),
+    ));
+
+    let with_context = multer.with_context(StoreContext::new());
+    assert_send(&with_context.parse_and_store("BOUND", body()));
+    assert_send(&with_context.parse_and_store_with_cancellation(
+        "BOUND",
+        body(),
+        CancellationToken::new(),
+    ));
+}
+
+#[tokio::test]
+async fn uploads_can_run_in_spawned_tasks() {
+    let multer = Arc::new(
+        Multer::builder()
+            .storage(MemoryStorage::new())
+            .build()
+            .expect("config should validate"),
+    );
+
+    let task = tokio::spawn({
+        let multer = Arc::clone(&multer);
+        async move {
+            multer
+                .parse_and_store(
+                    "BOUND",
+                    stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+                        b"--BOUND\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n\r\nhello\r\n--BOUND--\r\n",
+                    ))]),
+                )
+                .await
+        }
+    });
+
+    let output = task
+        .await
+        .expect("task should not panic")
+        .expect("upload should succeed");
+    assert_eq!(output.stored_files[0].size, 5);
+}
@@ -0,0 +1,97 @@
+#![allow(missing_docs)]
+#![cfg(feature = "sniff")]
+
+use bytes::Bytes;
+use futures::stream;
+use multigear::{Limits, MemoryStorage, MimeSniffPolicy, Multer, MulterError, ProcessedMultipart};
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+fn png_body(declared: &str) -> Vec<u8> {
+    let mut body = format!(
+        concat!(
+            "--BOUND\r\n",
+            "Content-Disposition: form-data; name=\"avatar\"; filename=\"a.png\"\r\n",
+            "Content-Type: {}\r\n",
+            "\r\n"
+        ),
+        declared
+    )
+    .into_bytes();
+    body.extend_from_slice(PNG);
+    body.extend_from_slice(b"\r\n--BOUND--\r\n");
+    body
+}
+
+async fn store(
+    policy: MimeSniffPolicy,
+    limits: Limits,
+    declared: &str,
+) -> Result<ProcessedMultipart, MulterError> {
+    let multer = Multer::builder()
+        .storage(MemoryStorage::new())
+        .mime_sniff_policy(policy)
+        .limits(limits)
+        .build()
+        .expect("config should validate");
+    multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from(png_body(declared)))]),
+        )
+        .await
+}
+
+#[tokio::test]
+async fn trust_declared_records_the_sniffed_type() {
+    let output = store(MimeSniffPolicy::TrustDeclared, Limits::new(), "text/plain")
+        .await
+        .expect("declared type is trusted");
+
+    let stored = &output.stored_files[0];
+    assert_eq!(stored.content_type, mime::TEXT_PLAIN);
+    assert_eq!(stored.sniffed_content_type, Some(mime::IMAGE_PNG));
+    assert_eq!(stored.size, PNG.len() as u64);
+}
+
+#[tokio::test]
+async fn prefer_sniffed_replaces_the_declared_type_and_rechecks_limits() {
+    let output = store(MimeSniffPolicy::PreferSniffed, Limits::new(), "text/plain")
+        .await
+        .expect("sniffed type is preferred");
+    assert_eq!(output.stored_files[0].content_type, mime::IMAGE_PNG);
+
+    let err = store(
+        MimeSniffPolicy::PreferSniffed,
+        Limits::new().denied_mime_types(["image/*"]),
+        "text/plain",
+    )
+    .await
+    .expect_err("sniffed type is denied");
+    assert!(matches!(
+        err,
+        MulterError::MimeTypeNotAllowed { field, mime } if field == "avatar" && mime == "image/png"
+    ));
+}
+
+#[tokio::test]
+async fn reject_mismatch_fails_unless_types_agree() {
+    let err = store(
+        MimeSniffPolicy::RejectMismatch,
+        Limits::new(),
+        "application/pdf",
+    )
+    .await
+    .expect_err("mismatch should be rejected");
+    assert!(matches!(
+        err,
+        MulterError::MimeTypeMismatch { field, declared, sniffed }
+            if field == "avatar" && declared == "application/pdf" && sniffed == "image/png"
+    ));
+
+    for declared in ["image/png", "application/octet-stream"] {
+        store(MimeSniffPolicy::RejectMismatch, Limits::new(), declared)
+            .await
+            .expect("matching or generic types are accepted");
+    }
+}
@@ -0,0 +1,413 @@
+#![allow(missing_docs)]
+#![cfg(feature = "azure")]
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, UNIX_EPOCH},
+};
+
+use base64::Engine as _;
+use bytes::Bytes;
+use futures::{stream, TryStreamExt};
+use multigear::{
+    AzureBlobStorage, AzureCredential, FileMeta, FilenameStrategy, Multer, MulterError,
+    StorageEngine, StorageEngineExt, StorageError, StoreContext,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+#[derive(Debug, Clone)]
+struct Request {
+    method: String,
+    target: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn query(&self, name: &str) -> Option<String> {
+        let (_, query) = self.target.split_once('?')?;
+        query.split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            (key == name).then(|| {
+                value
+                    .replace("%2B", "+")
+                    .replace("%2F", "/")
+                    .replace("%3D", "=")
+            })
+        })
+    }
+}
+
+type Requests = Arc<Mutex<Vec<Request>>>;
+
+/// Serves just enough of the blob service and IMDS APIs for block blob uploads
+/// and reads.
+async fn fake_azure() -> (String, Requests) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let endpoint = format!("http://{}", listener.local_addr().expect("address"));
+    let requests = Requests::default();
+
+    let recorded = requests.clone();
+    tokio::spawn(async move {
+        loop {
+            let (socket, _) = listener.accept().await.expect("accept");
+            tokio::spawn(serve(socket, recorded.clone()));
+        }
+    });
+
+    (endpoint, requests)
+}
+
+async fn serve(socket: TcpStream, requests: Requests) {
+    let mut socket = BufReader::new(socket);
+    loop {
+        let mut line = String::new();
+        if socket.read_line(&mut line).await.unwrap_or(0) == 0 {
+            return;
+        }
+        let mut words = line.split_whitespace();
+        let method = words.next().unwrap_or_default().to_owned();
+        let target = words.next().unwrap_or_default().to_owned();
+
+        let mut headers = Vec::new();
+        loop {
+            let mut line = String::new();
+            socket.read_line(&mut line).await.expect("header line");
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let (name, value) = line.split_once(':').expect("header");
+            headers.push((name.trim().to_owned(), value.trim().to_owned()));
+        }
+        let mut request = Request {
+            method,
+            target,
+            headers,
+            body: Vec::new(),
+        };
+        let length = request
+            .header("content-length")
+            .map_or(0, |value| value.parse().expect("content length"));
+        request.body.resize(length, 0);
+        socket.read_exact(&mut request.body).await.expect("body");
+
+        let (status, extra_headers, body) = respond(&request);
+        let payload = if request.method == "HEAD" { "" } else { &body };
+        requests.lock().expect("requests").push(request);
+        let response = format!(
+            "HTTP/1.1 {status}\r\n{extra_headers}Content-Length: {}\r\n\r\n{payload}",
+            body.len()
+        );
+        socket
+            .get_mut()
+            .write_all(response.as_bytes())
+            .await
+            .expect("response");
+    }
+}
+
+fn respond(request: &Request) -> (&'static str, String, String) {
+    if request.target.starts_with("/identity") {
+        return (
+            "200 OK",
+            String::new(),
+            r#"{"access_token":"token-1","expires_on":"4102444800","token_type":"Bearer"}"#
+                .to_owned(),
+        );
+    }
+    if request.target.contains("/denied/") {
+        return (
+            "403 Forbidden",
+            "x-ms-error-code: AuthorizationFailure\r\n".to_owned(),
+            String::new(),
+        );
+    }
+    if request.target.contains("/missing") {
+        return (
+            "404 The specified blob does not exist.",
+            "x-ms-error-code: BlobNotFound\r\n".to_owned(),
+            String::new(),
+        );
+    }
+    match request.method.as_str() {
+        "PUT" => ("201 Created", String::new(), String::new()),
+        "GET" | "HEAD" => (
+            "200 OK",
+            "Content-Type: text/plain\r\nLast-Modified: Sun, 06 Nov 1994 08:49:37 GMT\r\n"
+                .to_owned(),
+            "hello".to_owned(),
+        ),
+        "DELETE" => ("202 Accepted", String::new(), String::new()),
+        _ => ("400 Bad Request", String::new(), String::new()),
+    }
+}
+
+fn chunks(len: usize) -> Vec<Result<Bytes, MulterError>> {
+    let body: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+    body.chunks(100)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect()
+}
+
+#[tokio::test]
+async fn small_files_are_put_as_one_blob() {
+    let (endpoint, requests) = fake_azure().await;
+    let storage = AzureBlobStorage::builder(
+        format!("{endpoint}/devstoreaccount1"),
+        "docs",
+        AzureCredential::sas_token("?sv=2021-08-06&sig=abc%3D"),
+    )
+    .prefix("uploads/")
+    .filename(FilenameStrategy::Keep)
+    .build()
+    .expect("storage should build");
+    let multer = Multer::builder()
+        .storage(storage)
+        .build()
+        .expect("config should validate");
+
+    let output = multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+                b"--BOUND\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"report.txt\"\r\nContent-Type: text/plain\r\n\r\nhello\r\n--BOUND--\r\n",
+            ))]),
+        )
+        .await
+        .expect("upload should succeed");
+
+    let stored = &output.stored_files[0];
+    assert_eq!(stored.storage_key, "uploads/report.txt");
+    assert_eq!(stored.size, 5);
+    assert_eq!(stored.path, None);
+
+    let requests = requests.lock().expect("requests").clone();
+    assert_eq!(requests.len(), 1);
+    let put = &requests[0];
+    assert_eq!(put.method, "PUT");
+    assert_eq!(
+        put.target,
+        "/devstoreaccount1/docs/uploads/report.txt?sv=2021-08-06&sig=abc%3D"
+    );
+    assert_eq!(put.body, b"hello");
+    assert_eq!(put.header("x-ms-blob-type"), Some("BlockBlob"));
+    assert_eq!(put.header("x-ms-blob-content-type"), Some("text/plain"));
+    assert!(put.header("x-ms-version").is_some());
+    assert_eq!(put.header("authorization"), None);
+}
+
+#[tokio::test]
+async fn large_files_are_staged_as_blocks_with_a_managed_identity() {
+    let (endpoint, requests) = fake_azure().await;
+    let storage = AzureBlobStorage::builder(
+        endpoint.clone(),
+        "docs",
+        AzureCredential::user_assigned_identity("client-1"),
+    )
+    .identity_endpoint(format!("{endpoint}/identity"))
+    .block_size(1024)
+    .build()
+    .expect("storage should build");
+
+    let stored = storage
+        .store(
+            FileMeta::new("upload", Some("big.bin"), "application/octet-stream"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(2500))),
+        )
+        .await
+        .expect("upload should succeed");
+    assert_eq!(stored.size, 2500);
+
+    let requests = requests.lock().expect("requests").clone();
+    let summary: Vec<_> = requests
+        .iter()
+        .map(|request| (request.method.as_str(), request.body.len()))
+        .collect();
+    assert_eq!(
+        summary[..4],
+        [("GET", 0), ("PUT", 1024), ("PUT", 1024), ("PUT", 452)]
+    );
+
+    let identity = &requests[0];
+    assert_eq!(identity.header("metadata"), Some("true"));
+    assert_eq!(identity.query("client_id").as_deref(), Some("client-1"));
+
+    let blocks: Vec<_> = requests[1..4]
+        .iter()
+        .map(|request| {
+            assert_eq!(request.query("comp").as_deref(), Some("block"));
+            assert_eq!(request.header("authorization"), Some("Bearer token-1"));
+            request.query("blockid").expect("block id")
+        })
+        .collect();
+    for block in &blocks {
+        base64::engine::general_purpose::STANDARD
+            .decode(block)
+            .expect("block ids are base64");
+        assert_eq!(block.len(), blocks[0].len());
+    }
+    assert_ne!(blocks[0], blocks[1]);
+
+    let commit = &requests[4];
+    assert_eq!(requests.len(), 5, "the token should be fetched once");
+    assert_eq!(commit.query("comp").as_deref(), Some("blocklist"));
+    assert_eq!(
+        commit.header("x-ms-blob-content-type"),
+        Some("application/octet-stream")
+    );
+    let block_list = String::from_utf8_lossy(&commit.body);
+    let expected: String = blocks
+        .iter()
+        .map(|block| format!("<Latest>{block}</Latest>"))
+        .collect();
+    assert!(block_list.contains(&expected), "{block_list}");
+}
+
+#[tokio::test]
+async fn failed_bodies_never_commit_the_staged_blocks() {
+    let (endpoint, requests) = fake_azure().await;
+    let storage =
+        AzureBlobStorage::builder(endpoint, "docs", AzureCredential::sas_token("sig=abc"))
+            .block_size(1024)
+            .build()
+            .expect("storage should build");
+
+    let mut body = chunks(2500);
+    body.push(Err(MulterError::IncompleteStream));
+    storage
+        .store(
+            FileMeta::new("upload", Some("big.bin"), "application/octet-stream"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(body)),
+        )
+        .await
+        .expect_err("the body error should fail the store");
+
+    let requests = requests.lock().expect("requests").clone();
+    assert!(!requests.is_empty(), "blocks should have been staged");
+    assert!(requests
+        .iter()
+        .all(|request| request.query("comp").as_deref() == Some("block")));
+}
+
+#[tokio::test]
+async fn service_errors_fail_the_store() {
+    let (endpoint, _requests) = fake_azure().await;
+    let storage =
+        AzureBlobStorage::builder(endpoint, "denied", AzureCredential::sas_token("sig=abc"))
+            .build()
+            .expect("storage should build");
+
+    let err = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "application/octet-stream"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(10))),
+        )
+        .await
+        .expect_err("forbidden upload should fail");
+    assert!(err.to_string().contains("AuthorizationFailure"), "{err}");
+}
+
+#[tokio::test]
+async fn blobs_under_the_prefix_can_be_opened_inspected_and_deleted() {
+    let (endpoint, requests) = fake_azure().await;
+    let storage =
+        AzureBlobStorage::builder(endpoint, "docs", AzureCredential::sas_token("sig=abc"))
+            .prefix("uploads/")
+            .build()
+            .expect("storage should build");
+
+    let body = storage
+        .open("uploads/a.txt")
+        .await
+        .expect("open should succeed")
+        .expect("blob should exist");
+    let chunks: Vec<Bytes> = body.try_collect().await.expect("body should stream");
+    assert_eq!(chunks.concat(), b"hello");
+
+    let metadata = storage
+        .metadata("uploads/a.txt")
+        .await
+        .expect("metadata should succeed")
+        .expect("blob should exist");
+    assert_eq!(metadata.size, 5);
+    assert_eq!(metadata.content_type, Some(mime::TEXT_PLAIN));
+    assert_eq!(
+        metadata.modified,
+        Some(UNIX_EPOCH + Duration::from_secs(784_111_777))
+    );
+
+    assert!(storage
+        .open("uploads/missing.txt")
+        .await
+        .expect("open should succeed")
+        .is_none());
+    assert!(!storage
+        .exists("uploads/missing.txt")
+        .await
+        .expect("exists should succeed"));
+    storage
+        .delete("uploads/a.txt")
+        .await
+        .expect("delete should succeed");
+    // Blobs outside the prefix are never requested.
+    assert!(!storage
+        .exists("other/a.txt")
+        .await
+        .expect("exists should succeed"));
+
+    let requests = requests.lock().expect("requests").clone();
+    let summary: Vec<_> = requests
+        .iter()
+        .map(|request| (request.method.as_str(), request.target.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("GET", "/docs/uploads/a.txt?sig=abc"),
+            ("HEAD", "/docs/uploads/a.txt?sig=abc"),
+            ("GET", "/docs/uploads/missing.txt?sig=abc"),
+            ("HEAD", "/docs/uploads/missing.txt?sig=abc"),
+            ("DELETE", "/docs/uploads/a.txt?sig=abc"),
+        ]
+    );
+}
+
+#[test]
+fn builder_rejects_invalid_settings() {
+    let credential = AzureCredential::sas_token("sig=abc");
+    assert!(matches!(
+        AzureBlobStorage::builder("https://a.blob.core.windows.net", " ", credential.clone())
+            .build(),
+        Err(StorageError::Message { .. })
+    ));
+    assert!(
+        AzureBlobStorage::builder("https://a.blob.core.windows.net", "c", credential.clone())
+            .block_size(0)
+            .build()
+            .is_err()
+    );
+    assert!(AzureBlobStorage::builder("not a url", "c", credential)
+        .build()
+        .is_err());
+    assert!(AzureBlobStorage::builder(
+        "https://a.blob.core.windows.net",
+        "c",
+        AzureCredential::sas_token("?")
+    )
+    .build()
+    .is_err());
+}
@@ -0,0 +1,131 @@
+#![allow(missing_docs)]
+#![cfg(feature = "compression")]
+
+use std::io::Read;
+
+use bytes::Bytes;
+use futures::stream;
+use multigear::{
+    CompressedStorage, CompressionAlgorithm, FileMeta, MemoryStorage, MulterError, StorageEngine,
+    StorageError, StoreContext,
+};
+
+fn meta(content_type: &str) -> FileMeta {
+    let mut meta = FileMeta::new("upload", Some("data"), content_type);
+    meta.size_hint = Some(4096);
+    meta
+}
+
+fn chunks(body: &[u8]) -> Vec<Result<Bytes, MulterError>> {
+    body.chunks(500)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect()
+}
+
+fn text() -> Vec<u8> {
+    "the quick brown fox jumps over the lazy dog\n"
+        .repeat(100)
+        .into_bytes()
+}
+
+#[tokio::test]
+async fn compressible_files_are_stored_as_zstd() {
+    let memory = MemoryStorage::new();
+    let storage = CompressedStorage::builder(memory.clone())
+        .build()
+        .expect("storage should build");
+    let body = text();
+
+    let file = storage
+        .store(
+            meta("text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(&body))),
+        )
+        .await
+        .expect("store should succeed");
+    assert_eq!(file.encoding, Some(CompressionAlgorithm::Zstd));
+    assert_eq!(file.original_size, body.len() as u64);
+    assert_eq!(file.stored_size, file.inner.size);
+    assert!(file.stored_size < file.original_size);
+
+    let stored = memory
+        .get(&file.inner.storage_key)
+        .await
+        .expect("stored bytes");
+    assert_eq!(zstd::decode_all(&stored[..]).expect("valid zstd"), body);
+}
+
+#[tokio::test]
+async fn gzip_output_round_trips() {
+    let memory = MemoryStorage::new();
+    let storage = CompressedStorage::builder(memory.clone())
+        .algorithm(CompressionAlgorithm::Gzip)
+        .level(9)
+        .build()
+        .expect("storage should build");
+    let body = text();
+
+    let file = storage
+        .store(
+            meta("application/json"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(&body))),
+        )
+        .await
+        .expect("store should succeed");
+    assert!(file.is_compressed());
+
+    let stored = memory
+        .get(&file.inner.storage_key)
+        .await
+        .expect("stored bytes");
+    let mut decoded = Vec::new();
+    flate2::read::GzDecoder::new(&stored[..])
+        .read_to_end(&mut decoded)
+        .expect("valid gzip");
+    assert_eq!(decoded, body);
+}
+
+#[tokio::test]
+async fn other_content_types_pass_through() {
+    let memory = MemoryStorage::new();
+    let storage = CompressedStorage::builder(memory.clone())
+        .content_types(["text/csv"])
+        .build()
+        .expect("storage should build");
+
+    let file = storage
+        .store(
+            meta("text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(b"hello"))),
+        )
+        .await
+        .expect("store should succeed");
+    assert_eq!(file.encoding, None);
+    assert_eq!((file.original_size, file.stored_size), (5, 5));
+    assert_eq!(
+        memory.get(&file.inner.storage_key).await.as_deref(),
+        Some(&b"hello"[..])
+    );
+}
+
+#[test]
+fn builder_validates_levels_and_content_types() {
+    assert!(matches!(
+        CompressedStorage::builder(MemoryStorage::new())
+            .algorithm(CompressionAlgorithm::Gzip)
+            .level(10)
+            .build(),
+        Err(StorageError::Message { .. })
+    ));
+    assert!(CompressedStorage::builder(MemoryStorage::new())
+        .level(0)
+        .build()
+        .is_err());
+    assert!(CompressedStorage::builder(MemoryStorage::new())
+        .content_type("text")
+        .build()
+        .is_err());
+}
@@ -4,7 +4,10 @@ use std::{collections::HashMap, sync::Arc};
 
 use bytes::Bytes;
 use futures::{stream, StreamExt};
-use multigear::{BoxStream, Multer, MulterError, Multipart, StorageEngine, StorageError};
+use multigear::{
+    BoxStream, DiskStorage, FallbackStorage, FileMeta, MemoryStorage, Multer, MulterError,
+    Multipart, StorageEngine, StorageError, StoreContext,
+};
 use tokio::sync::RwLock;
 
 #[derive(Debug, Clone, Default)]
@@ -21,22 +24,22 @@ struct MapStoredFile {
     size: u64,
 }
 
-#[async_trait::async_trait]
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
 impl StorageEngine for MapStorage {
     type Output = MapStoredFile;
     type Error = StorageError;
 
     async fn store(
         &self,
-        field_name: &str,
-        file_name: Option<&str>,
-        content_type: &str,
+        meta: FileMeta,
+        _ctx: &StoreContext,
         mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
     ) -> Result<Self::Output, Self::Error> {
-        let key = format!("{field_name}-{}", self.items.read().await.len());
+        let key = format!("{}-{}", meta.field_name, self.items.read().await.len());
         let mut bytes = Vec::new();
         while let Some(chunk) = stream.next().await {
-            let chunk = chunk.map_err(|err| StorageError::new(err.to_string()))?;
+            let chunk = chunk.map_err(StorageError::Stream)?;
             bytes.extend_from_slice(&chunk);
         }
         let bytes = Bytes::from(bytes);
@@ -45,12 +48,17 @@ impl StorageEngine for MapStorage {
         self.items.write().await.insert(key.clone(), bytes);
         Ok(MapStoredFile {
             key,
-            field_name: field_name.to_owned(),
-            file_name: file_name.map(ToOwned::to_owned),
-            content_type: content_type.to_owned(),
+            field_name: meta.field_name,
+            file_name: meta.file_name,
+            content_type: meta.content_type,
             size,
         })
     }
+
+    async fn abort(&self, output: Self::Output) -> Result<(), Self::Error> {
+        self.items.write().await.remove(&output.key);
+        Ok(())
+    }
 }
 
 #[tokio::test]
@@ -121,3 +129,211 @@ async fn custom_storage_works_with_parse_and_store_pipeline() {
         vec![("note".to_owned(), "two".to_owned())]
     );
 }
+
+#[tokio::test]
+async fn failed_requests_abort_files_already_stored() {
+    let storage = MapStorage::default();
+    let multer = Multer::builder()
+        .storage(storage.clone())
+        .max_files(1)
+        .build()
+        .expect("config should validate");
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"first\"; filename=\"a.bin\"\r\n",
+        "\r\n",
+        "one\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"second\"; filename=\"b.bin\"\r\n",
+        "\r\n",
+        "two\r\n",
+        "--BOUND--\r\n"
+    );
+
+    multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+                body.as_bytes(),
+            ))]),
+        )
+        .await
+        .expect_err("the second file should exceed max_files");
+    assert!(storage.items.read().await.is_empty());
+}
+
+#[derive(Debug, Clone)]
+struct Tenant(&'static str);
+
+#[derive(Debug, Clone, Copy)]
+struct ContextStorage;
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngine for ContextStorage {
+    type Output = (Option<&'static str>, Option<String>);
+    type Error = StorageError;
+
+    async fn store(
+        &self,
+        meta: FileMeta,
+        ctx: &StoreContext,
+        mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        while let Some(chunk) = stream.next().await {
+            chunk.map_err(StorageError::Stream)?;
+        }
+        let upload_id = meta
+            .headers
+            .get("x-upload-id")
+            .and_then(|value| value.to_str().ok())
+            .map(ToOwned::to_owned);
+        Ok((ctx.get::<Tenant>().map(|tenant| tenant.0), upload_id))
+    }
+}
+
+#[tokio::test]
+async fn store_context_and_part_headers_reach_the_engine() {
+    let multer = Multer::new(ContextStorage);
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n",
+        "X-Upload-Id: 42\r\n",
+        "\r\n",
+        "hello\r\n",
+        "--BOUND--\r\n"
+    );
+    let input = || {
+        stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+            body.as_bytes(),
+        ))])
+    };
+
+    let output = multer
+        .with_context(StoreContext::new().with(Tenant("acme")))
+        .parse_and_store("BOUND", input())
+        .await
+        .expect("pipeline should succeed");
+    assert_eq!(
+        output.stored_files,
+        vec![(Some("acme"), Some("42".to_owned()))]
+    );
+
+    let output = multer
+        .parse_and_store("BOUND", input())
+        .await
+        .expect("pipeline should succeed");
+    assert_eq!(output.stored_files, vec![(None, Some("42".to_owned()))]);
+}
+
+#[derive(Debug, thiserror::Error)]
+enum QuotaError {
+    #[error("tenant `{0}` is over quota")]
+    OverQuota(String),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct QuotaStorage;
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngine for QuotaStorage {
+    type Output = ();
+    type Error = QuotaError;
+
+    async fn store(
+        &self,
+        _meta: FileMeta,
+        _ctx: &StoreContext,
+        _stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        Err(QuotaError::OverQuota("acme".to_owned()))
+    }
+}
+
+#[tokio::test]
+async fn storage_errors_keep_the_engine_error_type() {
+    let multer = Multer::new(QuotaStorage);
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n",
+        "\r\n",
+        "hello\r\n",
+        "--BOUND--\r\n"
+    );
+
+    let err = multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+                body.as_bytes(),
+            ))]),
+        )
+        .await
+        .expect_err("the engine should reject the file");
+    assert_eq!(err.to_string(), "tenant `acme` is over quota");
+    let MulterError::Storage(source) = err else {
+        panic!("expected a storage error, got {err:?}");
+    };
+    assert!(matches!(
+        source.downcast_ref::<QuotaError>(),
+        Some(QuotaError::OverQuota(tenant)) if tenant == "acme"
+    ));
+}
+
+fn one_file_body(content: &'static str) -> Vec<Result<Bytes, MulterError>> {
+    let body = format!(
+        "--BOUND\r\n\
+         Content-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n\
+         \r\n\
+         {content}\r\n\
+         --BOUND--\r\n"
+    );
+    vec![Ok(Bytes::from(body))]
+}
+
+#[tokio::test]
+async fn io_failures_keep_the_io_error_as_source() {
+    let blocker = std::env::temp_dir().join(format!("multigear-test-{}", uuid::Uuid::new_v4()));
+    std::fs::write(&blocker, b"not a directory").expect("blocking file should be written");
+    let storage = DiskStorage::builder()
+        .destination(blocker.join("uploads"))
+        .build()
+        .expect("builder should succeed");
+
+    let err = Multer::new(storage)
+        .parse_and_store("BOUND", stream::iter(one_file_body("hello")))
+        .await
+        .expect_err("the destination cannot be created");
+    let _ = std::fs::remove_file(blocker);
+
+    let MulterError::Storage(source) = err else {
+        panic!("expected a storage error, got {err:?}");
+    };
+    let storage_err = source
+        .downcast_ref::<StorageError>()
+        .expect("disk storage reports a StorageError");
+    assert!(matches!(storage_err, StorageError::Io { .. }));
+    assert!(
+        std::error::Error::source(storage_err).is_some_and(|source| source.is::<std::io::Error>())
+    );
+}
+
+#[tokio::test]
+async fn body_errors_surface_through_wrapping_engines() {
+    let storage = FallbackStorage::new(MemoryStorage::new(), MemoryStorage::new());
+    let multer = Multer::builder()
+        .storage(storage)
+        .max_file_size(4)
+        .build()
+        .expect("config should validate");
+
+    let err = multer
+        .parse_and_store("BOUND", stream::iter(one_file_body("far too large")))
+        .await
+        .expect_err("the file should exceed max_file_size");
+    assert!(matches!(
+        err,
+        MulterError::FileSizeLimitExceeded { ref field, .. } if field == "doc"
+    ));
+}
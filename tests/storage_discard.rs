@@ -0,0 +1,85 @@
+#![allow(missing_docs)]
+
+use bytes::Bytes;
+use futures::stream;
+use multigear::{DiscardStorage, FileMeta, MulterError, StorageEngine, StoreContext};
+
+fn body(parts: &[&'static [u8]]) -> Vec<Result<Bytes, MulterError>> {
+    parts
+        .iter()
+        .map(|part| Ok(Bytes::from_static(part)))
+        .collect()
+}
+
+#[tokio::test]
+async fn files_are_consumed_and_counted() {
+    let storage = DiscardStorage::new();
+
+    let first = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(body(&[b"hel", b"lo"]))),
+        )
+        .await
+        .expect("store should succeed");
+    let second = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(body(&[b"hello"]))),
+        )
+        .await
+        .expect("store should succeed");
+
+    assert_eq!(first.size, 5);
+    assert_eq!(first.path, None);
+    assert_eq!(first.file_name.as_deref(), Some("a.txt"));
+    assert_eq!(first.content_type, mime::TEXT_PLAIN);
+    assert_ne!(first.storage_key, second.storage_key);
+}
+
+#[tokio::test]
+async fn stream_errors_fail_the_store() {
+    let mut chunks = body(&[b"partial"]);
+    chunks.push(Err(MulterError::IncompleteStream));
+    DiscardStorage::new()
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks)),
+        )
+        .await
+        .expect_err("stream error should fail the store");
+}
+
+#[cfg(feature = "checksum")]
+#[tokio::test]
+async fn digests_become_storage_keys() {
+    use multigear::DigestAlgorithm;
+
+    let sha256 = DiscardStorage::new()
+        .digest(DigestAlgorithm::Sha256)
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(body(&[b"hel", b"lo"]))),
+        )
+        .await
+        .expect("store should succeed");
+    assert_eq!(
+        sha256.storage_key,
+        "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+    );
+
+    let md5 = DiscardStorage::new()
+        .digest(DigestAlgorithm::Md5)
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(body(&[b"hello"]))),
+        )
+        .await
+        .expect("store should succeed");
+    assert_eq!(md5.storage_key, "5d41402abc4b2a76b9719d911017c592");
+}
@@ -4,12 +4,15 @@ use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
 use bytes::Bytes;
-use futures::{channel::mpsc, stream, SinkExt};
+use futures::{channel::mpsc, stream, SinkExt, TryStreamExt};
 use multigear::storage::disk::sanitize_filename;
-use multigear::{DiskStorage, FilenameStrategy, Multer, MulterError, Multipart};
+use multigear::{
+    DiskStorage, FilenamePolicy, FilenameStrategy, Multer, MulterError, Multipart, StorageEngineExt,
+};
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
-type ObservedFileMeta = Option<(String, Option<String>, String)>;
+type ObservedFileMeta = Option<(String, Option<String>, String, Option<u64>)>;
 
 #[tokio::test]
 async fn keep_strategy_sanitizes_filename_and_writes_to_disk() {
@@ -160,6 +163,7 @@ async fn disk_filter_receives_core_file_metadata() {
                 meta.field_name.clone(),
                 meta.file_name.clone(),
                 meta.content_type.clone(),
+                meta.size_hint,
             ));
             true
         })
@@ -192,6 +196,7 @@ async fn disk_filter_receives_core_file_metadata() {
             "upload".to_owned(),
             Some("hinted.txt".to_owned()),
             "text/plain".to_owned(),
+            Some(5),
         ))
     );
 
@@ -211,6 +216,73 @@ fn sanitize_filename_rejects_traversal_and_null_bytes() {
     assert!(!nul.contains('?'));
 }
 
+#[test]
+fn sanitize_filename_escapes_windows_reserved_names() {
+    assert_eq!(sanitize_filename("CON"), "_CON");
+    assert_eq!(sanitize_filename("nul.txt"), "_nul.txt");
+    assert_eq!(sanitize_filename("lpt1.tar.gz"), "_lpt1.tar.gz");
+    assert_eq!(sanitize_filename("console.txt"), "console.txt");
+}
+
+#[test]
+fn strict_filename_policy_defeats_spoofed_names() {
+    let policy = FilenamePolicy::strict();
+
+    // Right-to-left override hides the real `.exe` extension.
+    assert_eq!(policy.apply("invoice\u{202E}fdp.exe"), "invoicefdp.exe");
+    // Cyrillic lookalikes and fullwidth dots.
+    assert_eq!(policy.apply("р\u{0430}ypal\u{FF0E}pdf"), "paypal.pdf");
+    assert_eq!(policy.apply("photo.jpg.php"), "photo_jpg.php");
+    assert_eq!(policy.apply("aux.log"), "_aux.log");
+
+    let default = FilenamePolicy::default();
+    assert_eq!(default.apply("photo.jpg.php"), "photo.jpg.php");
+    assert_eq!(default.apply("р\u{0430}ypal.pdf"), "__ypal.pdf");
+}
+
+#[test]
+fn unicode_filename_policy_keeps_letters_in_nfc() {
+    let policy = FilenamePolicy::new()
+        .allow_unicode(true)
+        .normalize_nfc(true)
+        .rule(|name| name.replace(' ', "-"));
+
+    let composed = policy.apply("Cafe\u{301} menu.txt");
+    assert_eq!(composed, "Caf\u{e9}-menu.txt");
+    assert_eq!(policy.apply("Caf\u{e9} menu.txt"), composed);
+    assert_eq!(policy.apply("../отчёт?.pdf"), "отчёт_.pdf");
+}
+
+#[tokio::test]
+async fn disk_storage_applies_configured_filename_policy() {
+    let root = temp_root();
+    let storage = DiskStorage::builder()
+        .destination(&root)
+        .filename(FilenameStrategy::Keep)
+        .filename_policy(FilenamePolicy::strict())
+        .build()
+        .expect("builder should succeed");
+    let multer = Multer::new(storage);
+
+    let body = multipart_body(&[("upload", "shell.jpg.php", "image/jpeg", "<?php")]);
+    let mut multipart =
+        Multipart::new("BOUND", bytes_stream(body)).expect("multipart should initialize");
+    let part = multipart
+        .next_part()
+        .await
+        .expect("part should parse")
+        .expect("part expected");
+
+    let stored = multer.store(part).await.expect("store should succeed");
+    let path = stored.path.expect("disk storage should return a path");
+    assert_eq!(
+        path.file_name().and_then(|value| value.to_str()),
+        Some("shell_jpg.php")
+    );
+
+    cleanup(root).await;
+}
+
 fn temp_root() -> PathBuf {
     std::env::temp_dir().join(format!("multigear-test-{}", Uuid::new_v4()))
 }
@@ -340,3 +412,259 @@ async fn stress_multi_gb_disk_upload_uses_bounded_stream_memory() {
 
     cleanup(root).await;
 }
+
+#[tokio::test]
+async fn cancellation_aborts_store_and_removes_partial_file() {
+    let root = temp_root();
+    let storage = DiskStorage::builder()
+        .destination(&root)
+        .filename(FilenameStrategy::Keep)
+        .build()
+        .expect("builder should succeed");
+    let multer = Multer::new(storage);
+
+    let head = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"upload\"; filename=\"partial.bin\"\r\n",
+        "\r\n",
+        "first half of the body"
+    );
+    // The sender stays open, so the body never completes on its own.
+    let (mut tx, rx) = mpsc::channel::<Result<Bytes, MulterError>>(4);
+    tx.send(Ok(Bytes::from_static(head.as_bytes())))
+        .await
+        .expect("send chunk");
+
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        canceller.cancel();
+    });
+
+    let err = multer
+        .parse_and_store_with_cancellation("BOUND", rx, token)
+        .await
+        .expect_err("cancellation should abort the store");
+    assert!(matches!(err, MulterError::Cancelled));
+    assert!(
+        !root.join("partial.bin").exists(),
+        "partial file should be removed"
+    );
+    drop(tx);
+
+    cleanup(root).await;
+}
+
+#[tokio::test]
+async fn failed_requests_remove_files_already_written() {
+    let root = temp_root();
+    let storage = DiskStorage::builder()
+        .destination(&root)
+        .filename(FilenameStrategy::Keep)
+        .build()
+        .expect("builder should succeed");
+    let multer = Multer::builder()
+        .storage(storage)
+        .max_file_size(8)
+        .build()
+        .expect("config should validate");
+    let body = multipart_body(&[
+        ("first", "small.txt", "text/plain", "fits"),
+        (
+            "second",
+            "large.txt",
+            "text/plain",
+            "far larger than the limit",
+        ),
+    ]);
+
+    let err = multer
+        .parse_and_store("BOUND", bytes_stream(body))
+        .await
+        .expect_err("the second file should exceed max_file_size");
+    assert!(matches!(
+        err,
+        MulterError::FileSizeLimitExceeded { ref field, .. } if field == "second"
+    ));
+    assert!(
+        !root.join("small.txt").exists(),
+        "stored file should be aborted"
+    );
+    assert!(
+        !root.join("large.txt").exists(),
+        "partial file should be removed"
+    );
+
+    cleanup(root).await;
+}
+
+#[tokio::test]
+async fn abort_handle_stops_store_and_removes_partial_file() {
+    let root = temp_root();
+    let storage = DiskStorage::builder()
+        .destination(&root)
+        .filename(FilenameStrategy::Keep)
+        .build()
+        .expect("builder should succeed");
+    let multer = Multer::new(storage);
+
+    let head = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"upload\"; filename=\"aborted.bin\"\r\n",
+        "\r\n",
+        "first half of the body"
+    );
+    // The sender stays open, so the body never completes on its own.
+    let (mut tx, rx) = mpsc::channel::<Result<Bytes, MulterError>>(4);
+    tx.send(Ok(Bytes::from_static(head.as_bytes())))
+        .await
+        .expect("send chunk");
+
+    let mut multipart = multer
+        .multipart_from_boundary("BOUND", rx)
+        .expect("multipart should initialize");
+    let handle = multipart.abort_handle();
+    tokio::spawn({
+        let handle = handle.clone();
+        async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            handle.abort();
+        }
+    });
+
+    let part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("part expected");
+    let err = multer
+        .store(part)
+        .await
+        .expect_err("abort should stop the store");
+    assert!(err.to_string().contains("aborted"), "{err}");
+    assert!(handle.is_aborted());
+    assert!(
+        !root.join("aborted.bin").exists(),
+        "partial file should be removed"
+    );
+    assert!(matches!(
+        multipart.next_part().await.map(|part| part.is_some()),
+        Err(MulterError::Aborted)
+    ));
+    drop(tx);
+
+    cleanup(root).await;
+}
+
+#[tokio::test]
+async fn abort_stops_polling_upstream() {
+    let (mut tx, rx) = mpsc::channel::<Result<Bytes, MulterError>>(4);
+    tx.send(Ok(Bytes::from_static(
+        b"--BOUND\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\none\r\n",
+    )))
+    .await
+    .expect("send chunk");
+
+    let mut multipart = Multipart::new("BOUND", rx).expect("multipart should initialize");
+    let part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("part expected");
+    assert_eq!(part.field_name(), "a");
+    drop(part);
+
+    multipart.abort();
+    assert!(matches!(
+        multipart.next_part().await.map(|part| part.is_some()),
+        Err(MulterError::Aborted)
+    ));
+    assert_eq!(multipart.drain().await.expect("nothing left to drain"), 0);
+    drop(tx);
+}
+
+#[tokio::test]
+async fn stored_files_can_be_opened_inspected_and_deleted() {
+    let root = temp_root();
+    let storage = DiskStorage::builder()
+        .destination(&root)
+        .filename(FilenameStrategy::Keep)
+        .build()
+        .expect("builder should succeed");
+    let multer = Multer::new(storage.clone());
+    let output = multer
+        .parse_and_store(
+            "BOUND",
+            bytes_stream(multipart_body(&[("doc", "a.txt", "text/plain", "hello")])),
+        )
+        .await
+        .expect("store should succeed");
+    let key = output.stored_files[0].storage_key.as_str();
+
+    let metadata = storage
+        .metadata(key)
+        .await
+        .expect("metadata should load")
+        .expect("file should exist");
+    assert_eq!(metadata.size, 5);
+    assert_eq!(metadata.content_type, Some(mime::TEXT_PLAIN));
+    assert!(metadata.modified.is_some());
+    let body: Vec<Bytes> = storage
+        .open(key)
+        .await
+        .expect("open should succeed")
+        .expect("file should exist")
+        .try_collect()
+        .await
+        .expect("body should read");
+    assert_eq!(body.concat(), b"hello");
+
+    storage.delete(key).await.expect("delete should succeed");
+    assert!(!storage.exists(key).await.expect("exists should succeed"));
+    assert!(!root.join("a.txt").exists());
+    cleanup(root).await;
+}
+
+#[tokio::test]
+async fn retrieval_ignores_keys_outside_the_destination() {
+    let root = temp_root();
+    let outside = temp_root();
+    tokio::fs::create_dir_all(root.join("nested"))
+        .await
+        .expect("create nested dir");
+    tokio::fs::create_dir_all(&outside)
+        .await
+        .expect("create outside dir");
+    tokio::fs::write(outside.join("secret.txt"), b"secret")
+        .await
+        .expect("write outside file");
+    tokio::fs::write(root.join("nested").join("inner.txt"), b"inner")
+        .await
+        .expect("write nested file");
+    let storage = DiskStorage::builder()
+        .destination(&root)
+        .build()
+        .expect("builder should succeed");
+
+    let secret = outside.join("secret.txt");
+    let escaping = root
+        .join("..")
+        .join(outside.file_name().expect("name"))
+        .join("secret.txt");
+    let nested = root.join("nested").join("inner.txt");
+    for key in [&secret, &escaping, &nested] {
+        let key = key.to_str().expect("utf-8 path");
+        assert!(storage
+            .open(key)
+            .await
+            .expect("open should succeed")
+            .is_none());
+        assert!(!storage.exists(key).await.expect("exists should succeed"));
+        storage.delete(key).await.expect("delete should succeed");
+    }
+    assert!(secret.exists());
+    assert!(nested.exists());
+    cleanup(root).await;
+    cleanup(outside).await;
+}
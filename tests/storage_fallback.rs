@@ -0,0 +1,135 @@
+#![allow(missing_docs)]
+
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use multigear::{
+    BoxStream, FallbackStorage, FileMeta, MemoryStorage, MulterError, StorageEngine, StorageError,
+    StoreContext, StoredFile,
+};
+
+/// Backend that reads `chunks` chunks and then fails.
+#[derive(Debug, Clone, Copy)]
+struct FailingStorage {
+    chunks: usize,
+}
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngine for FailingStorage {
+    type Output = StoredFile;
+    type Error = StorageError;
+
+    async fn store(
+        &self,
+        _meta: FileMeta,
+        _ctx: &StoreContext,
+        mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        for _ in 0..self.chunks {
+            let _ = stream.next().await;
+        }
+        Err(StorageError::new("bucket unavailable"))
+    }
+}
+
+fn chunks(body: &[u8]) -> Vec<Result<Bytes, MulterError>> {
+    body.chunks(4)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect()
+}
+
+#[tokio::test]
+async fn healthy_primaries_store_the_file() {
+    let (primary, secondary) = (MemoryStorage::new(), MemoryStorage::new());
+    let storage = FallbackStorage::new(primary.clone(), secondary.clone());
+
+    let file = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(b"hello world"))),
+        )
+        .await
+        .expect("store should succeed");
+    assert!(!file.is_fallback());
+    let stored = file.into_inner();
+    assert_eq!(
+        primary.get(&stored.storage_key).await.as_deref(),
+        Some(&b"hello world"[..])
+    );
+    assert!(secondary.is_empty().await);
+}
+
+#[tokio::test]
+async fn failed_primaries_replay_the_body_on_the_secondary() {
+    let secondary = MemoryStorage::new();
+    let storage = FallbackStorage::new(FailingStorage { chunks: 2 }, secondary.clone());
+
+    let file = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(b"hello world"))),
+        )
+        .await
+        .expect("secondary should store the file");
+    assert!(file.is_fallback());
+    let stored = file.into_inner();
+    assert_eq!(stored.size, 11);
+    assert_eq!(
+        secondary.get(&stored.storage_key).await.as_deref(),
+        Some(&b"hello world"[..])
+    );
+}
+
+#[tokio::test]
+async fn primaries_failing_past_the_replay_buffer_fail_the_store() {
+    let secondary = MemoryStorage::new();
+    let storage =
+        FallbackStorage::new(FailingStorage { chunks: 2 }, secondary.clone()).replay_buffer(6);
+
+    let err = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(b"hello world"))),
+        )
+        .await
+        .expect_err("the body cannot be replayed");
+    assert_eq!(
+        err.to_string(),
+        "primary storage failed: bucket unavailable"
+    );
+    assert!(secondary.is_empty().await);
+
+    // A primary failing before it reads anything can always fall back.
+    let storage =
+        FallbackStorage::new(FailingStorage { chunks: 0 }, secondary.clone()).replay_buffer(0);
+    let file = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(b"hello world"))),
+        )
+        .await
+        .expect("secondary should store the file");
+    assert!(file.is_fallback());
+}
+
+#[tokio::test]
+async fn body_errors_are_not_retried() {
+    let (primary, secondary) = (MemoryStorage::new(), MemoryStorage::new());
+    let storage = FallbackStorage::new(primary, secondary.clone());
+
+    let mut body = chunks(b"partial");
+    body.push(Err(MulterError::IncompleteStream));
+    storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(body)),
+        )
+        .await
+        .expect_err("stream error should fail the store");
+    assert!(secondary.is_empty().await);
+}
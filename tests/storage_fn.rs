@@ -0,0 +1,91 @@
+#![allow(missing_docs)]
+
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use multigear::{
+    FileMeta, FnStorage, Multer, MulterError, StorageEngine, StorageError, StoreContext,
+};
+
+#[tokio::test]
+async fn closures_store_files_through_multer() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&received);
+    let storage = FnStorage::new(move |meta, mut body| {
+        let sink = Arc::clone(&sink);
+        async move {
+            let mut collected = Vec::new();
+            while let Some(chunk) = body.next().await {
+                collected.extend_from_slice(&chunk.map_err(StorageError::Stream)?);
+            }
+            sink.lock()
+                .unwrap()
+                .push((meta.file_name.clone(), collected));
+            Ok::<_, StorageError>(meta.field_name)
+        }
+    });
+    let multer = Multer::builder()
+        .storage(storage)
+        .build()
+        .expect("config should validate");
+
+    let output = multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+                b"--BOUND\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n\r\nhello\r\n--BOUND--\r\n",
+            ))]),
+        )
+        .await
+        .expect("upload should succeed");
+
+    assert_eq!(output.stored_files, ["doc"]);
+    assert_eq!(
+        *received.lock().unwrap(),
+        [(Some("a.txt".to_owned()), b"hello".to_vec())]
+    );
+}
+
+#[tokio::test]
+async fn closures_may_stop_reading_early() {
+    let storage = FnStorage::new(|_meta, mut body| async move {
+        let first = body.next().await;
+        Ok::<_, StorageError>(first.and_then(Result::ok))
+    });
+
+    let body = stream::iter([Ok(Bytes::from_static(b"first"))]).chain(stream::pending());
+    let first = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(body),
+        )
+        .await
+        .expect("store should succeed");
+    assert_eq!(first.as_deref(), Some(&b"first"[..]));
+}
+
+#[tokio::test]
+async fn body_errors_reach_the_closure() {
+    let storage = FnStorage::new(|_meta, body| async move {
+        let items: Vec<_> = body.collect().await;
+        match items.last() {
+            Some(Err(err)) => Err(StorageError::new(err.to_string())),
+            _ => Ok(items.len()),
+        }
+    });
+
+    let body = stream::iter([
+        Ok(Bytes::from_static(b"partial")),
+        Err(MulterError::IncompleteStream),
+    ]);
+    storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(body),
+        )
+        .await
+        .expect_err("the closure should see the stream error");
+}
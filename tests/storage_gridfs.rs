@@ -0,0 +1,137 @@
+#![allow(missing_docs)]
+#![cfg(feature = "gridfs")]
+
+//! Round-trip tests run against the server in `MONGODB_URI` and are skipped
+//! when it is unset.
+
+use bytes::Bytes;
+use futures::{stream, AsyncReadExt, TryStreamExt};
+use mongodb::{
+    bson::{doc, oid::ObjectId, Bson},
+    gridfs::GridFsBucket,
+    Client, Database,
+};
+use multigear::{
+    FileMeta, GridFsStorage, Multer, MulterError, StorageEngine, StorageError, StoreContext,
+};
+
+async fn bucket(name: &str) -> Option<(Database, GridFsBucket)> {
+    let uri = std::env::var("MONGODB_URI").ok()?;
+    let client = Client::with_uri_str(&uri)
+        .await
+        .expect("MONGODB_URI should be valid");
+    let db = client.database("multigear_test");
+    let bucket = db.gridfs_bucket(
+        mongodb::options::GridFsBucketOptions::builder()
+            .bucket_name(name.to_owned())
+            .build(),
+    );
+    bucket.drop().await.expect("drop bucket");
+    Some((db, bucket))
+}
+
+fn chunks(body: &[u8]) -> Vec<Result<Bytes, MulterError>> {
+    body.chunks(1000)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect()
+}
+
+#[tokio::test]
+async fn files_are_streamed_with_multipart_metadata() {
+    let Some((_, bucket)) = bucket("metadata").await else {
+        return;
+    };
+    let storage = GridFsStorage::builder(bucket.clone())
+        .chunk_size(1024)
+        .metadata(doc! { "app": "tests", "fieldName": "overridden" })
+        .build()
+        .expect("storage should build");
+    let multer = Multer::builder()
+        .storage(storage)
+        .build()
+        .expect("config should validate");
+
+    let output = multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+                b"--BOUND\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\nContent-Type: text/plain\r\n\r\nhello\r\n--BOUND--\r\n",
+            ))]),
+        )
+        .await
+        .expect("upload should succeed");
+    let stored = &output.stored_files[0];
+    assert_eq!(stored.size, 5);
+
+    let id = ObjectId::parse_str(&stored.storage_key).expect("storage key is an ObjectId");
+    let file = bucket
+        .find_one(doc! { "_id": id })
+        .await
+        .expect("find file")
+        .expect("file document should exist");
+    let metadata = file.metadata.expect("metadata");
+    assert_eq!(metadata.get_str("fieldName"), Ok("doc"));
+    assert_eq!(metadata.get_str("originalName"), Ok("a.txt"));
+    assert_eq!(metadata.get_str("contentType"), Ok("text/plain"));
+    assert_eq!(metadata.get_str("app"), Ok("tests"));
+
+    let mut body = Vec::new();
+    bucket
+        .open_download_stream(Bson::ObjectId(id))
+        .await
+        .expect("open download")
+        .read_to_end(&mut body)
+        .await
+        .expect("download");
+    assert_eq!(body, b"hello");
+}
+
+#[tokio::test]
+async fn failed_uploads_leave_no_chunks() {
+    let Some((db, bucket)) = bucket("failed").await else {
+        return;
+    };
+    let storage = GridFsStorage::builder(bucket.clone())
+        .chunk_size(1024)
+        .build()
+        .expect("storage should build");
+
+    let mut body = chunks(&[7; 3000]);
+    body.push(Err(MulterError::IncompleteStream));
+    storage
+        .store(
+            FileMeta::new("upload", Some("broken.bin"), "application/octet-stream"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(body)),
+        )
+        .await
+        .expect_err("stream error should fail the store");
+
+    let files: Vec<_> = bucket
+        .find(doc! {})
+        .await
+        .expect("find files")
+        .try_collect()
+        .await
+        .expect("collect files");
+    assert!(files.is_empty());
+    let chunks = db
+        .collection::<mongodb::bson::Document>("failed.chunks")
+        .count_documents(doc! {})
+        .await
+        .expect("count chunks");
+    assert_eq!(chunks, 0);
+}
+
+#[tokio::test]
+async fn builder_rejects_a_zero_chunk_size() {
+    let client = Client::with_uri_str("mongodb://127.0.0.1:1")
+        .await
+        .expect("client should build without connecting");
+    let bucket = client.database("multigear_test").gridfs_bucket(None);
+    assert!(matches!(
+        GridFsStorage::builder(bucket.clone()).chunk_size(0).build(),
+        Err(StorageError::Message { .. })
+    ));
+    assert!(GridFsStorage::builder(bucket).build().is_ok());
+}
@@ -0,0 +1,139 @@
+#![allow(missing_docs)]
+
+use std::path::{Path, PathBuf};
+
+use bytes::Bytes;
+use futures::stream;
+use multigear::{FileMeta, HybridStorage, MulterError, StorageEngine, StoreContext};
+use tokio::io::AsyncReadExt;
+use uuid::Uuid;
+
+fn temp_root() -> PathBuf {
+    std::env::temp_dir().join(format!("multigear-test-{}", Uuid::new_v4()))
+}
+
+fn body(parts: &[&'static [u8]]) -> Vec<Result<Bytes, MulterError>> {
+    parts
+        .iter()
+        .map(|part| Ok(Bytes::from_static(part)))
+        .collect()
+}
+
+fn entries(root: &Path) -> usize {
+    std::fs::read_dir(root).map_or(0, Iterator::count)
+}
+
+#[tokio::test]
+async fn files_up_to_the_threshold_stay_in_memory() {
+    let root = temp_root();
+    let storage = HybridStorage::new(5).spill_dir(&root);
+
+    let file = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(body(&[b"hel", b"lo"]))),
+        )
+        .await
+        .expect("store should succeed");
+    assert!(file.is_in_memory());
+    assert_eq!(file.path(), None);
+    assert_eq!(file.stored().path, None);
+    assert_eq!(file.stored().size, 5);
+    assert_eq!(file.as_bytes().map(|bytes| &bytes[..]), Some(&b"hello"[..]));
+
+    let mut read = Vec::new();
+    file.reader()
+        .await
+        .expect("open reader")
+        .read_to_end(&mut read)
+        .await
+        .expect("read");
+    assert_eq!(read, b"hello");
+    assert_eq!(entries(&root), 0);
+}
+
+#[tokio::test]
+async fn larger_files_spill_and_are_deleted_on_drop() {
+    let root = temp_root();
+    let storage = HybridStorage::new(4).spill_dir(&root);
+
+    let file = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(body(&[b"hel", b"lo", b" world"]))),
+        )
+        .await
+        .expect("store should succeed");
+    assert!(!file.is_in_memory());
+    assert!(file.as_bytes().is_none());
+    let path = file.path().expect("spill path").to_path_buf();
+    assert!(path.starts_with(&root));
+    assert_eq!(file.stored().path.as_deref(), Some(path.as_path()));
+    assert_eq!(file.stored().size, 11);
+    assert_eq!(file.bytes().await.expect("read"), "hello world");
+
+    let mut read = String::new();
+    file.reader()
+        .await
+        .expect("open reader")
+        .read_to_string(&mut read)
+        .await
+        .expect("read");
+    assert_eq!(read, "hello world");
+
+    drop(file);
+    assert!(!path.exists());
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[tokio::test]
+async fn persist_writes_either_kind_of_file() {
+    let root = temp_root();
+    let storage = HybridStorage::new(4).spill_dir(root.join("spill"));
+    std::fs::create_dir_all(&root).expect("create root");
+
+    for (name, chunks) in [
+        ("small.txt", body(&[b"hi"])),
+        ("large.txt", body(&[b"hello"])),
+    ] {
+        let file = storage
+            .store(
+                FileMeta::new("upload", Some("a.txt"), "text/plain"),
+                &StoreContext::new(),
+                Box::pin(stream::iter(chunks)),
+            )
+            .await
+            .expect("store should succeed");
+        let expected = file.bytes().await.expect("read");
+        let target = root.join(name);
+        let stored = file.persist(&target).await.expect("persist should succeed");
+
+        assert_eq!(stored.path.as_deref(), Some(target.as_path()));
+        assert_eq!(stored.storage_key, target.to_string_lossy());
+        assert_eq!(std::fs::read(&target).expect("read"), expected);
+    }
+    assert_eq!(entries(&root.join("spill")), 0);
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[tokio::test]
+async fn failed_streams_leave_no_spill_file() {
+    let root = temp_root();
+    let storage = HybridStorage::new(2).spill_dir(&root);
+
+    let mut chunks = body(&[b"partial"]);
+    chunks.push(Err(MulterError::IncompleteStream));
+    storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks)),
+        )
+        .await
+        .expect_err("stream error should fail the store");
+
+    assert_eq!(entries(&root), 0);
+    let _ = std::fs::remove_dir_all(root);
+}
@@ -0,0 +1,115 @@
+#![allow(missing_docs)]
+
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use multigear::{
+    BoxStream, FileMeta, InstrumentedStorage, MemoryStorage, MetricsSink, MulterError,
+    StorageEngine, StorageError, StorageMetrics, StoreContext, StoreEvent, StoreOutcome,
+    StoredFile,
+};
+
+/// Backend that reads one chunk and then fails.
+#[derive(Debug, Clone, Copy)]
+struct FailingStorage;
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngine for FailingStorage {
+    type Output = StoredFile;
+    type Error = StorageError;
+
+    async fn store(
+        &self,
+        _meta: FileMeta,
+        _ctx: &StoreContext,
+        mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        let _ = stream.next().await;
+        Err(StorageError::new("disk full"))
+    }
+}
+
+#[derive(Debug, Default)]
+struct RecordingSink {
+    events: Mutex<Vec<StoreEvent>>,
+}
+
+impl MetricsSink for RecordingSink {
+    fn store_finished(&self, event: &StoreEvent) {
+        self.events.lock().unwrap().push(event.clone());
+    }
+}
+
+fn chunks(parts: &[&'static [u8]]) -> Vec<Result<Bytes, MulterError>> {
+    parts
+        .iter()
+        .map(|part| Ok(Bytes::from_static(part)))
+        .collect()
+}
+
+#[tokio::test]
+async fn successful_and_failed_stores_are_counted() {
+    let metrics = StorageMetrics::new();
+    let storage = InstrumentedStorage::new(MemoryStorage::new(), metrics.clone());
+    storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(&[b"hel", b"lo"]))),
+        )
+        .await
+        .expect("store should succeed");
+
+    let failing = InstrumentedStorage::new(FailingStorage, metrics.clone());
+    failing
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(&[b"abc", b"def"]))),
+        )
+        .await
+        .expect_err("store should fail");
+
+    let snapshot = metrics.snapshot();
+    assert_eq!(snapshot.started, 2);
+    assert_eq!(snapshot.stored, 1);
+    assert_eq!(snapshot.failed, 1);
+    assert_eq!(snapshot.active, 0);
+    assert_eq!(snapshot.bytes_written, 8);
+}
+
+#[tokio::test]
+async fn cancelled_stores_are_reported_once() {
+    let sink = Arc::new(RecordingSink::default());
+    let metrics = StorageMetrics::new();
+    let storage = InstrumentedStorage::new(MemoryStorage::new(), metrics.clone());
+    let recorded = InstrumentedStorage::new(MemoryStorage::new(), Arc::clone(&sink));
+
+    let ctx = StoreContext::new();
+    let body = || stream::iter(chunks(&[b"hello"])).chain(stream::pending());
+    let mut store = storage.store(
+        FileMeta::new("upload", Some("a.txt"), "text/plain"),
+        &ctx,
+        Box::pin(body()),
+    );
+    let mut other = recorded.store(
+        FileMeta::new("upload", Some("a.txt"), "text/plain"),
+        &ctx,
+        Box::pin(body()),
+    );
+    assert!(futures::poll!(&mut store).is_pending());
+    assert!(futures::poll!(&mut other).is_pending());
+    assert_eq!(metrics.snapshot().active, 1);
+    drop(store);
+    drop(other);
+
+    let snapshot = metrics.snapshot();
+    assert_eq!((snapshot.active, snapshot.failed), (0, 1));
+    let events = sink.events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].outcome, StoreOutcome::Cancelled);
+    assert_eq!(events[0].bytes, 5);
+    assert_eq!(events[0].field_name, "upload");
+}
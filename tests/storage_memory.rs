@@ -1,8 +1,8 @@
 #![allow(missing_docs)]
 
 use bytes::Bytes;
-use futures::stream;
-use multigear::{MemoryStorage, Multer, MulterError, Multipart};
+use futures::{stream, TryStreamExt};
+use multigear::{MemoryStorage, Multer, MulterError, Multipart, StorageEngineExt};
 
 #[tokio::test]
 async fn stores_file_part_and_returns_metadata() {
@@ -74,6 +74,46 @@ async fn memory_storage_conformance_unique_keys_and_payload_integrity() {
     );
 }
 
+#[tokio::test]
+async fn stored_files_can_be_opened_inspected_and_deleted() {
+    let storage = MemoryStorage::new();
+    let multer = Multer::new(storage.clone());
+    let output = multer
+        .parse_and_store(
+            "BOUND",
+            bytes_stream(multipart_body(&[("doc", "a.txt", "text/plain", "hello")])),
+        )
+        .await
+        .expect("store should succeed");
+    let key = output.stored_files[0].storage_key.as_str();
+
+    let metadata = storage
+        .metadata(key)
+        .await
+        .expect("metadata should load")
+        .expect("file should exist");
+    assert_eq!(metadata.size, 5);
+    assert_eq!(metadata.content_type, Some(mime::TEXT_PLAIN));
+    let body: Vec<Bytes> = storage
+        .open(key)
+        .await
+        .expect("open should succeed")
+        .expect("file should exist")
+        .try_collect()
+        .await
+        .expect("body should read");
+    assert_eq!(body.concat(), b"hello");
+
+    storage.delete(key).await.expect("delete should succeed");
+    assert!(!storage.exists(key).await.expect("exists should succeed"));
+    assert!(storage
+        .open(key)
+        .await
+        .expect("open should succeed")
+        .is_none());
+    storage.delete(key).await.expect("deleting twice succeeds");
+}
+
 fn multipart_body(parts: &[(&str, &str, &str, &str)]) -> Vec<u8> {
     let mut out = Vec::new();
     for (field, file_name, content_type, body) in parts {
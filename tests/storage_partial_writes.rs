@@ -0,0 +1,125 @@
+#![allow(missing_docs)]
+
+//! Bundled storage engines clean up after a body that fails mid-part: the
+//! store has no output for `StorageEngine::abort`, so the engine itself must
+//! remove what it wrote. Backends behind a network service are covered in
+//! their own test files.
+
+use std::path::{Path, PathBuf};
+
+use bytes::Bytes;
+use futures::stream;
+use multigear::{
+    DiskStorage, FallbackStorage, FilenameStrategy, HybridStorage, InstrumentedStorage,
+    MemoryStorage, Multer, MulterError, StorageEngine, StorageMetrics, TeeStorage,
+    ThrottledStorage,
+};
+use uuid::Uuid;
+
+fn temp_root() -> PathBuf {
+    std::env::temp_dir().join(format!("multigear-test-{}", Uuid::new_v4()))
+}
+
+fn entries(root: &Path) -> usize {
+    std::fs::read_dir(root).map_or(0, Iterator::count)
+}
+
+/// Uploads one file whose body fails after 64 KiB, through `Multer`.
+async fn fail_mid_part<S: StorageEngine>(storage: S) {
+    let mut head =
+        b"--BOUND\r\nContent-Disposition: form-data; name=\"upload\"; filename=\"a.txt\"\r\n\r\n"
+            .to_vec();
+    head.extend_from_slice(&[b'x'; 64 * 1024]);
+    let body = stream::iter([Ok(Bytes::from(head)), Err(MulterError::IncompleteStream)]);
+
+    let result = Multer::new(storage).parse_and_store("BOUND", body).await;
+    assert!(result.is_err(), "the body should fail mid-part");
+}
+
+#[tokio::test]
+async fn memory_storage_keeps_nothing() {
+    let memory = MemoryStorage::new();
+    fail_mid_part(memory.clone()).await;
+    assert!(memory.is_empty().await);
+}
+
+#[tokio::test]
+async fn disk_storage_removes_the_partial_file() {
+    let root = temp_root();
+    let storage = DiskStorage::builder()
+        .destination(&root)
+        .filename(FilenameStrategy::Keep)
+        .build()
+        .expect("builder should succeed");
+    fail_mid_part(storage).await;
+    assert_eq!(entries(&root), 0);
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[tokio::test]
+async fn hybrid_storage_removes_the_spill_file() {
+    let root = temp_root();
+    fail_mid_part(HybridStorage::new(2).spill_dir(&root)).await;
+    assert_eq!(entries(&root), 0);
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[cfg(feature = "tempfile")]
+#[tokio::test]
+async fn tempfile_storage_removes_the_temporary_file() {
+    let root = temp_root();
+    fail_mid_part(multigear::TempfileStorage::in_dir(&root)).await;
+    assert_eq!(entries(&root), 0);
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[cfg(feature = "sqlx-sqlite")]
+#[tokio::test]
+async fn sqlite_storage_inserts_no_row() {
+    let path = std::env::temp_dir().join(format!("multigear-test-{}.db", Uuid::new_v4()));
+    let storage = multigear::SqliteStorage::builder(&path)
+        .build()
+        .await
+        .expect("storage should build");
+    let pool = storage.pool().clone();
+    fail_mid_part(storage).await;
+
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM multigear_files")
+        .fetch_one(&pool)
+        .await
+        .expect("count rows");
+    assert_eq!(rows, 0);
+    pool.close().await;
+    let _ = std::fs::remove_file(path);
+}
+
+#[cfg(feature = "compression")]
+#[tokio::test]
+async fn compressed_storage_keeps_nothing_in_the_inner_engine() {
+    let memory = MemoryStorage::new();
+    let storage = multigear::CompressedStorage::builder(memory.clone())
+        .build()
+        .expect("storage should build");
+    fail_mid_part(storage).await;
+    assert!(memory.is_empty().await);
+}
+
+#[tokio::test]
+async fn wrappers_keep_nothing_in_the_engines_they_wrap() {
+    let (first, second) = (MemoryStorage::new(), MemoryStorage::new());
+    fail_mid_part(TeeStorage::new(first.clone(), second.clone())).await;
+    fail_mid_part(FallbackStorage::new(first.clone(), second.clone())).await;
+    fail_mid_part(InstrumentedStorage::new(
+        first.clone(),
+        StorageMetrics::new(),
+    ))
+    .await;
+    fail_mid_part(
+        ThrottledStorage::builder(first.clone())
+            .build()
+            .expect("storage should build"),
+    )
+    .await;
+    assert!(first.is_empty().await);
+    assert!(second.is_empty().await);
+}
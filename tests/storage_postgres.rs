@@ -0,0 +1,234 @@
+#![allow(missing_docs)]
+#![cfg(feature = "sqlx-postgres")]
+
+//! Round-trip tests run against the database in `DATABASE_URL` and are
+//! skipped when it is unset.
+
+use bytes::Bytes;
+use futures::{stream, TryStreamExt};
+use multigear::{
+    storage::postgres::PostgresStorageMode, FileMeta, Multer, MulterError, PostgresStorage,
+    StorageEngine, StorageEngineExt, StorageError, StoreContext,
+};
+use sqlx::{postgres::PgPoolOptions, PgPool, Row};
+
+async fn pool() -> Option<PgPool> {
+    let url = std::env::var("DATABASE_URL").ok()?;
+    Some(
+        PgPoolOptions::new()
+            .max_connections(2)
+            .connect(&url)
+            .await
+            .expect("DATABASE_URL should be reachable"),
+    )
+}
+
+async fn storage(pool: &PgPool, table: &str, mode: PostgresStorageMode) -> PostgresStorage {
+    sqlx::query(&format!("DROP TABLE IF EXISTS {table}"))
+        .execute(pool)
+        .await
+        .expect("drop table");
+    let storage = PostgresStorage::builder(pool.clone())
+        .table(table)
+        .mode(mode)
+        .build()
+        .expect("storage should build");
+    storage.create_table().await.expect("create table");
+    storage
+}
+
+fn chunks(body: &[u8]) -> Vec<Result<Bytes, MulterError>> {
+    body.chunks(1000)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect()
+}
+
+#[tokio::test]
+async fn bytea_rows_are_inserted_per_file() {
+    let Some(pool) = pool().await else {
+        return;
+    };
+    let storage = storage(&pool, "multigear_test_bytea", PostgresStorageMode::Bytea).await;
+    let multer = Multer::builder()
+        .storage(storage)
+        .build()
+        .expect("config should validate");
+
+    let output = multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+                b"--BOUND\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\nContent-Type: text/plain\r\n\r\nhello\r\n--BOUND--\r\n",
+            ))]),
+        )
+        .await
+        .expect("upload should succeed");
+    let stored = &output.stored_files[0];
+    assert_eq!(stored.size, 5);
+
+    let row = sqlx::query(
+        "SELECT field_name, file_name, content_type, size, data FROM multigear_test_bytea WHERE id = $1::bigint",
+    )
+    .bind(&stored.storage_key)
+    .fetch_one(&pool)
+    .await
+    .expect("row should exist");
+    assert_eq!(row.get::<String, _>("field_name"), "doc");
+    assert_eq!(
+        row.get::<Option<String>, _>("file_name").as_deref(),
+        Some("a.txt")
+    );
+    assert_eq!(row.get::<String, _>("content_type"), "text/plain");
+    assert_eq!(row.get::<i64, _>("size"), 5);
+    assert_eq!(row.get::<Vec<u8>, _>("data"), b"hello");
+}
+
+#[tokio::test]
+async fn large_objects_stream_chunk_by_chunk() {
+    let Some(pool) = pool().await else {
+        return;
+    };
+    let storage = storage(&pool, "multigear_test_lo", PostgresStorageMode::LargeObject).await;
+
+    let body: Vec<u8> = (0..5000).map(|i| (i % 251) as u8).collect();
+    let stored = storage
+        .store(
+            FileMeta::new("upload", Some("big.bin"), "application/octet-stream"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(&body))),
+        )
+        .await
+        .expect("upload should succeed");
+    assert_eq!(stored.size, 5000);
+
+    let data: Vec<u8> =
+        sqlx::query_scalar("SELECT lo_get(data) FROM multigear_test_lo WHERE id = $1::bigint")
+            .bind(&stored.storage_key)
+            .fetch_one(&pool)
+            .await
+            .expect("large object should exist");
+    assert_eq!(data, body);
+}
+
+#[tokio::test]
+async fn large_objects_are_read_back_and_unlinked_on_delete() {
+    let Some(pool) = pool().await else {
+        return;
+    };
+    let storage = storage(
+        &pool,
+        "multigear_test_lo_read",
+        PostgresStorageMode::LargeObject,
+    )
+    .await;
+    let body: Vec<u8> = (0..600_000u32).map(|i| (i % 251) as u8).collect();
+    let stored = storage
+        .store(
+            FileMeta::new("upload", Some("big.bin"), "application/octet-stream"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(&body))),
+        )
+        .await
+        .expect("store should succeed");
+    let key = stored.storage_key.as_str();
+    let oid: sqlx::postgres::types::Oid =
+        sqlx::query_scalar("SELECT data FROM multigear_test_lo_read WHERE id = $1::bigint")
+            .bind(key)
+            .fetch_one(&pool)
+            .await
+            .expect("row should exist");
+
+    let metadata = storage
+        .metadata(key)
+        .await
+        .expect("metadata should load")
+        .expect("row should exist");
+    assert_eq!(metadata.size, body.len() as u64);
+    let read: Vec<Bytes> = storage
+        .open(key)
+        .await
+        .expect("open should succeed")
+        .expect("row should exist")
+        .try_collect()
+        .await
+        .expect("body should read");
+    assert!(read.len() > 1);
+    assert_eq!(read.concat(), body);
+
+    storage.delete(key).await.expect("delete should succeed");
+    assert!(!storage.exists(key).await.expect("exists should succeed"));
+    let objects: i64 =
+        sqlx::query_scalar("SELECT count(*) FROM pg_largeobject_metadata WHERE oid = $1")
+            .bind(oid)
+            .fetch_one(&pool)
+            .await
+            .expect("count large objects");
+    assert_eq!(objects, 0);
+}
+
+#[tokio::test]
+async fn failed_streams_roll_back() {
+    let Some(pool) = pool().await else {
+        return;
+    };
+    let storage = storage(
+        &pool,
+        "multigear_test_rollback",
+        PostgresStorageMode::LargeObject,
+    )
+    .await;
+    let objects = || async {
+        sqlx::query_scalar::<_, i64>("SELECT count(*) FROM pg_largeobject_metadata")
+            .fetch_one(&pool)
+            .await
+            .expect("count large objects")
+    };
+    let before = objects().await;
+
+    let mut body = chunks(&[7; 3000]);
+    body.push(Err(MulterError::IncompleteStream));
+    storage
+        .store(
+            FileMeta::new("upload", Some("broken.bin"), "application/octet-stream"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(body)),
+        )
+        .await
+        .expect_err("stream error should fail the store");
+
+    let rows: i64 = sqlx::query_scalar("SELECT count(*) FROM multigear_test_rollback")
+        .fetch_one(&pool)
+        .await
+        .expect("count rows");
+    assert_eq!(rows, 0);
+    assert_eq!(objects().await, before);
+}
+
+#[tokio::test]
+async fn builder_rejects_unsafe_table_names() {
+    let pool = PgPool::connect_lazy("postgres://localhost/multigear").expect("lazy pool");
+    for table in [
+        "files; DROP TABLE users",
+        "",
+        "a.b.c",
+        "1files",
+        "\"files\"",
+    ] {
+        assert!(
+            matches!(
+                PostgresStorage::builder(pool.clone()).table(table).build(),
+                Err(StorageError::Message { .. })
+            ),
+            "{table}"
+        );
+    }
+    for table in ["files", "uploads.files", "_Files2"] {
+        assert!(
+            PostgresStorage::builder(pool.clone())
+                .table(table)
+                .build()
+                .is_ok(),
+            "{table}"
+        );
+    }
+}
@@ -0,0 +1,384 @@
+#![allow(missing_docs)]
+#![cfg(feature = "s3")]
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use aws_sdk_s3::{
+    config::{BehaviorVersion, Credentials, Region, RequestChecksumCalculation},
+    types::ObjectCannedAcl,
+    Client,
+};
+use bytes::Bytes;
+use futures::{stream, TryStreamExt};
+use multigear::{
+    storage::s3::{ContentDisposition, MIN_PART_SIZE},
+    FileMeta, FilenameStrategy, Multer, MulterError, S3Storage, StorageEngine, StorageEngineExt,
+    StorageError, StoreContext,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+#[derive(Debug, Clone)]
+struct Request {
+    method: String,
+    target: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+type Requests = Arc<Mutex<Vec<Request>>>;
+
+/// Serves just enough of the S3 REST API for object and multipart uploads.
+async fn fake_s3() -> (String, Requests) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let endpoint = format!("http://{}", listener.local_addr().expect("address"));
+    let requests = Requests::default();
+
+    let recorded = requests.clone();
+    tokio::spawn(async move {
+        loop {
+            let (socket, _) = listener.accept().await.expect("accept");
+            tokio::spawn(serve(socket, recorded.clone()));
+        }
+    });
+
+    (endpoint, requests)
+}
+
+async fn serve(socket: TcpStream, requests: Requests) {
+    let mut socket = BufReader::new(socket);
+    loop {
+        let mut line = String::new();
+        if socket.read_line(&mut line).await.unwrap_or(0) == 0 {
+            return;
+        }
+        let mut words = line.split_whitespace();
+        let method = words.next().unwrap_or_default().to_owned();
+        let target = words.next().unwrap_or_default().to_owned();
+
+        let mut headers = Vec::new();
+        loop {
+            let mut line = String::new();
+            socket.read_line(&mut line).await.expect("header line");
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let (name, value) = line.split_once(':').expect("header");
+            headers.push((name.trim().to_owned(), value.trim().to_owned()));
+        }
+        let mut request = Request {
+            method,
+            target,
+            headers,
+            body: Vec::new(),
+        };
+
+        if request
+            .header("expect")
+            .is_some_and(|value| value.eq_ignore_ascii_case("100-continue"))
+        {
+            socket
+                .get_mut()
+                .write_all(b"HTTP/1.1 100 Continue\r\n\r\n")
+                .await
+                .expect("continue");
+        }
+        let length = request
+            .header("content-length")
+            .map_or(0, |value| value.parse().expect("content length"));
+        request.body.resize(length, 0);
+        socket.read_exact(&mut request.body).await.expect("body");
+
+        let (status, extra_headers, body) = respond(&request);
+        // HEAD responses declare the object's length but carry no body.
+        let payload = if request.method == "HEAD" { "" } else { &body };
+        requests.lock().expect("requests").push(request);
+        let response = format!(
+            "HTTP/1.1 {status}\r\n{extra_headers}Content-Length: {}\r\n\r\n{payload}",
+            body.len()
+        );
+        socket
+            .get_mut()
+            .write_all(response.as_bytes())
+            .await
+            .expect("response");
+    }
+}
+
+fn respond(request: &Request) -> (&'static str, String, String) {
+    let (path, query) = request
+        .target
+        .split_once('?')
+        .unwrap_or((request.target.as_str(), ""));
+    match request.method.as_str() {
+        "POST" if query.starts_with("uploads") => (
+            "200 OK",
+            String::new(),
+            format!(
+                "<InitiateMultipartUploadResult><Bucket>bucket</Bucket><Key>{path}</Key><UploadId>upload-1</UploadId></InitiateMultipartUploadResult>"
+            ),
+        ),
+        "POST" => (
+            "200 OK",
+            String::new(),
+            "<CompleteMultipartUploadResult><ETag>\"done\"</ETag></CompleteMultipartUploadResult>"
+                .to_owned(),
+        ),
+        "PUT" => {
+            let part = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("partNumber="))
+                .unwrap_or("object");
+            (
+                "200 OK",
+                format!("ETag: \"etag-{part}\"\r\n"),
+                String::new(),
+            )
+        }
+        "DELETE" => ("204 No Content", String::new(), String::new()),
+        "GET" | "HEAD" if path.ends_with("/missing") => (
+            "404 Not Found",
+            String::new(),
+            "<Error><Code>NoSuchKey</Code><Message>missing</Message></Error>".to_owned(),
+        ),
+        "GET" | "HEAD" => (
+            "200 OK",
+            "Content-Type: text/plain\r\nLast-Modified: Wed, 21 Oct 2015 07:28:00 GMT\r\n"
+                .to_owned(),
+            "hello".to_owned(),
+        ),
+        _ => ("400 Bad Request", String::new(), String::new()),
+    }
+}
+
+fn client(endpoint: &str) -> Client {
+    Client::from_conf(
+        aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .credentials_provider(Credentials::new("key", "secret", None, None, "test"))
+            .endpoint_url(endpoint)
+            .force_path_style(true)
+            .request_checksum_calculation(RequestChecksumCalculation::WhenRequired)
+            .build(),
+    )
+}
+
+fn chunks(len: usize) -> Vec<Result<Bytes, MulterError>> {
+    let body: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+    body.chunks(64 * 1024)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect()
+}
+
+#[tokio::test]
+async fn small_files_are_put_in_one_request() {
+    let (endpoint, requests) = fake_s3().await;
+    let storage = S3Storage::builder(client(&endpoint), "bucket")
+        .prefix("uploads/")
+        .acl(ObjectCannedAcl::PublicRead)
+        .content_disposition(ContentDisposition::Attachment)
+        .filename(FilenameStrategy::Keep)
+        .build()
+        .expect("storage should build");
+    let multer = Multer::builder()
+        .storage(storage)
+        .build()
+        .expect("config should validate");
+
+    let output = multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+                b"--BOUND\r\nContent-Disposition: form-data; name=\"doc\"; filename*=UTF-8''r%C3%A9sum%C3%A9.txt\r\nContent-Type: text/plain\r\n\r\nhello\r\n--BOUND--\r\n",
+            ))]),
+        )
+        .await
+        .expect("upload should succeed");
+
+    let stored = &output.stored_files[0];
+    assert!(stored.storage_key.starts_with("uploads/"));
+    assert_eq!(stored.size, 5);
+    assert_eq!(stored.path, None);
+
+    let requests = requests.lock().expect("requests").clone();
+    assert_eq!(requests.len(), 1);
+    let put = &requests[0];
+    assert_eq!(put.method, "PUT");
+    assert_eq!(
+        put.target.split('?').next(),
+        Some(format!("/bucket/{}", stored.storage_key).as_str())
+    );
+    assert_eq!(put.body, b"hello");
+    assert_eq!(put.header("content-type"), Some("text/plain"));
+    assert_eq!(put.header("x-amz-acl"), Some("public-read"));
+    assert_eq!(
+        put.header("content-disposition"),
+        Some("attachment; filename=\"r_sum_.txt\"; filename*=UTF-8''r%C3%A9sum%C3%A9.txt")
+    );
+}
+
+#[tokio::test]
+async fn large_files_stream_as_multipart_uploads() {
+    let (endpoint, requests) = fake_s3().await;
+    let storage = S3Storage::builder(client(&endpoint), "bucket")
+        .part_size(MIN_PART_SIZE)
+        .build()
+        .expect("storage should build");
+
+    let len = 2 * MIN_PART_SIZE + 1024;
+    let stored = storage
+        .store(
+            FileMeta::new("upload", Some("big.bin"), "application/octet-stream"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(len))),
+        )
+        .await
+        .expect("upload should succeed");
+    assert_eq!(stored.size, len as u64);
+
+    let requests = requests.lock().expect("requests").clone();
+    let summary: Vec<_> = requests
+        .iter()
+        .map(|request| (request.method.as_str(), request.body.len()))
+        .collect();
+    assert_eq!(
+        summary[..4],
+        [
+            ("POST", 0),
+            ("PUT", MIN_PART_SIZE),
+            ("PUT", MIN_PART_SIZE),
+            ("PUT", 1024),
+        ]
+    );
+    assert!(requests[2].target.contains("partNumber=2"));
+    assert!(requests[3].target.contains("uploadId=upload-1"));
+
+    let complete = &requests[4];
+    assert_eq!(complete.method, "POST");
+    let manifest = String::from_utf8_lossy(&complete.body);
+    for part in 1..=3 {
+        assert!(manifest.contains(&format!("etag-{part}")), "{manifest}");
+    }
+}
+
+#[tokio::test]
+async fn objects_under_the_prefix_can_be_opened_inspected_and_deleted() {
+    let (endpoint, requests) = fake_s3().await;
+    let storage = S3Storage::builder(client(&endpoint), "bucket")
+        .prefix("uploads/")
+        .build()
+        .expect("storage should build");
+
+    let body: Vec<Bytes> = storage
+        .open("uploads/a.txt")
+        .await
+        .expect("open should succeed")
+        .expect("object should exist")
+        .try_collect()
+        .await
+        .expect("body should read");
+    assert_eq!(body.concat(), b"hello");
+    let metadata = storage
+        .metadata("uploads/a.txt")
+        .await
+        .expect("metadata should load")
+        .expect("object should exist");
+    assert_eq!(metadata.size, 5);
+    assert_eq!(metadata.content_type, Some(mime::TEXT_PLAIN));
+    assert!(metadata.modified.is_some());
+    storage
+        .delete("uploads/a.txt")
+        .await
+        .expect("delete should succeed");
+
+    assert!(storage
+        .open("uploads/missing")
+        .await
+        .expect("open should succeed")
+        .is_none());
+    assert!(!storage
+        .exists("uploads/missing")
+        .await
+        .expect("exists should succeed"));
+    // Keys outside the prefix never reach the bucket.
+    assert!(!storage
+        .exists("private/a.txt")
+        .await
+        .expect("exists should succeed"));
+
+    let requests = requests.lock().expect("requests").clone();
+    let summary: Vec<_> = requests
+        .iter()
+        .map(|request| (request.method.as_str(), request.target.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("GET", "/bucket/uploads/a.txt?x-id=GetObject"),
+            ("HEAD", "/bucket/uploads/a.txt"),
+            ("DELETE", "/bucket/uploads/a.txt?x-id=DeleteObject"),
+            ("GET", "/bucket/uploads/missing?x-id=GetObject"),
+            ("HEAD", "/bucket/uploads/missing"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn failed_multipart_uploads_are_aborted() {
+    let (endpoint, requests) = fake_s3().await;
+    let storage = S3Storage::builder(client(&endpoint), "bucket")
+        .part_size(MIN_PART_SIZE)
+        .build()
+        .expect("storage should build");
+
+    let mut body = chunks(MIN_PART_SIZE + 1024);
+    body.push(Err(MulterError::IncompleteStream));
+    storage
+        .store(
+            FileMeta::new("upload", Some("broken.bin"), "application/octet-stream"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(body)),
+        )
+        .await
+        .expect_err("stream error should fail the upload");
+
+    for _ in 0..100 {
+        let aborted = requests.lock().expect("requests").iter().any(|request| {
+            request.method == "DELETE" && request.target.contains("uploadId=upload-1")
+        });
+        if aborted {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("multipart upload was not aborted");
+}
+
+#[test]
+fn builder_rejects_invalid_settings() {
+    let client = client("http://127.0.0.1:9");
+    assert!(matches!(
+        S3Storage::builder(client.clone(), " ").build(),
+        Err(StorageError::Message { .. })
+    ));
+    assert!(S3Storage::builder(client, "bucket")
+        .part_size(MIN_PART_SIZE - 1)
+        .build()
+        .is_err());
+}
@@ -0,0 +1,199 @@
+#![allow(missing_docs)]
+#![cfg(feature = "sqlx-sqlite")]
+
+use std::path::PathBuf;
+
+use bytes::Bytes;
+use futures::{stream, TryStreamExt};
+use multigear::{
+    FileMeta, Multer, MulterError, SqliteStorage, StorageEngine, StorageEngineExt, StorageError,
+    StoreContext,
+};
+use sqlx::Row;
+use uuid::Uuid;
+
+fn temp_db() -> PathBuf {
+    std::env::temp_dir().join(format!("multigear-test-{}.db", Uuid::new_v4()))
+}
+
+fn meta(file_name: &str, size_hint: Option<u64>) -> FileMeta {
+    let mut meta = FileMeta::new("upload", Some(file_name), "application/octet-stream");
+    meta.size_hint = size_hint;
+    meta
+}
+
+fn chunks(body: &[u8]) -> Vec<Result<Bytes, MulterError>> {
+    body.chunks(1000)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect()
+}
+
+#[tokio::test]
+async fn files_are_stored_as_blob_rows() {
+    let path = temp_db();
+    let storage = SqliteStorage::builder(&path)
+        .build()
+        .await
+        .expect("storage should build");
+    let pool = storage.pool().clone();
+    let multer = Multer::builder()
+        .storage(storage)
+        .build()
+        .expect("config should validate");
+
+    let output = multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+                b"--BOUND\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\nContent-Type: text/plain\r\n\r\nhello\r\n--BOUND--\r\n",
+            ))]),
+        )
+        .await
+        .expect("upload should succeed");
+    let stored = &output.stored_files[0];
+    assert_eq!(stored.storage_key, "1");
+    assert_eq!(stored.size, 5);
+    assert_eq!(stored.path, None);
+
+    let row = sqlx::query(
+        "SELECT field_name, file_name, content_type, size, data FROM multigear_files WHERE id = ?1",
+    )
+    .bind(stored.storage_key.parse::<i64>().expect("numeric id"))
+    .fetch_one(&pool)
+    .await
+    .expect("row should exist");
+    assert_eq!(row.get::<String, _>("field_name"), "doc");
+    assert_eq!(
+        row.get::<Option<String>, _>("file_name").as_deref(),
+        Some("a.txt")
+    );
+    assert_eq!(row.get::<String, _>("content_type"), "text/plain");
+    assert_eq!(row.get::<i64, _>("size"), 5);
+    assert_eq!(row.get::<Vec<u8>, _>("data"), b"hello");
+
+    let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+        .fetch_one(&pool)
+        .await
+        .expect("journal mode");
+    assert_eq!(journal_mode, "wal");
+
+    pool.close().await;
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn files_over_the_cap_are_refused() {
+    let path = temp_db();
+    let storage = SqliteStorage::builder(&path)
+        .table("uploads")
+        .max_file_size(2500)
+        .build()
+        .await
+        .expect("storage should build");
+
+    let err = storage
+        .store(
+            meta("big.bin", None),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(&[1; 3000]))),
+        )
+        .await
+        .expect_err("oversized file should fail");
+    assert!(err.to_string().contains("2500"), "{err}");
+
+    storage
+        .store(
+            meta("declared.bin", Some(4000)),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(&[1; 10]))),
+        )
+        .await
+        .expect_err("declared oversized file should fail");
+
+    let stored = storage
+        .store(
+            meta("fits.bin", None),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(&[1; 2500]))),
+        )
+        .await
+        .expect("file at the cap should be stored");
+    assert_eq!(stored.size, 2500);
+
+    let rows: i64 = sqlx::query_scalar("SELECT count(*) FROM uploads")
+        .fetch_one(storage.pool())
+        .await
+        .expect("count rows");
+    assert_eq!(rows, 1);
+
+    storage.pool().close().await;
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn stored_rows_can_be_opened_inspected_and_deleted() {
+    let path = temp_db();
+    let storage = SqliteStorage::builder(&path)
+        .build()
+        .await
+        .expect("storage should build");
+    let body = vec![7u8; 5000];
+    let stored = storage
+        .store(
+            meta("big.bin", None),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(&body))),
+        )
+        .await
+        .expect("store should succeed");
+    let key = stored.storage_key.as_str();
+
+    let metadata = storage
+        .metadata(key)
+        .await
+        .expect("metadata should load")
+        .expect("row should exist");
+    assert_eq!(metadata.size, 5000);
+    assert_eq!(metadata.content_type, Some(mime::APPLICATION_OCTET_STREAM));
+    let read: Vec<Bytes> = storage
+        .open(key)
+        .await
+        .expect("open should succeed")
+        .expect("row should exist")
+        .try_collect()
+        .await
+        .expect("body should read");
+    assert_eq!(read.concat(), body);
+
+    storage.delete(key).await.expect("delete should succeed");
+    assert!(!storage.exists(key).await.expect("exists should succeed"));
+    assert!(storage
+        .open("not-an-id")
+        .await
+        .expect("open should succeed")
+        .is_none());
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn builder_rejects_invalid_settings() {
+    let path = temp_db();
+    assert!(matches!(
+        SqliteStorage::builder(&path)
+            .table("files; --")
+            .build()
+            .await,
+        Err(StorageError::Message { .. })
+    ));
+    assert!(SqliteStorage::builder(&path)
+        .max_file_size(0)
+        .build()
+        .await
+        .is_err());
+    assert!(SqliteStorage::builder(&path)
+        .max_connections(0)
+        .build()
+        .await
+        .is_err());
+    assert!(!path.exists());
+}
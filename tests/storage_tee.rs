@@ -0,0 +1,157 @@
+#![allow(missing_docs)]
+
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use multigear::{
+    BoxStream, FileMeta, MemoryStorage, MulterError, StorageEngine, StorageError, StoreContext,
+    StoredFile, TeeHalf, TeeStorage,
+};
+
+/// Backend that reads one chunk and then fails.
+#[derive(Debug, Clone, Copy)]
+struct FailingStorage;
+
+#[cfg_attr(not(feature = "unsend"), async_trait::async_trait)]
+#[cfg_attr(feature = "unsend", async_trait::async_trait(?Send))]
+impl StorageEngine for FailingStorage {
+    type Output = StoredFile;
+    type Error = StorageError;
+
+    async fn store(
+        &self,
+        _meta: FileMeta,
+        _ctx: &StoreContext,
+        mut stream: BoxStream<'_, Result<Bytes, MulterError>>,
+    ) -> Result<Self::Output, Self::Error> {
+        let _ = stream.next().await;
+        Err(StorageError::new("bucket unavailable"))
+    }
+}
+
+fn chunks(body: &[u8]) -> Vec<Result<Bytes, MulterError>> {
+    body.chunks(3)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect()
+}
+
+#[tokio::test]
+async fn files_are_stored_in_both_backends() {
+    let (first, second) = (MemoryStorage::new(), MemoryStorage::new());
+    let storage = TeeStorage::new(first.clone(), second.clone());
+    let body = b"hello from both sides".repeat(10);
+
+    let file = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(&body))),
+        )
+        .await
+        .expect("store should succeed");
+    assert_eq!(
+        first.get(&file.first.storage_key).await.as_deref(),
+        Some(&body[..])
+    );
+    assert_eq!(
+        second.get(&file.second.storage_key).await.as_deref(),
+        Some(&body[..])
+    );
+}
+
+#[tokio::test]
+async fn aborts_reach_both_backends() {
+    let (first, second) = (MemoryStorage::new(), MemoryStorage::new());
+    let storage = TeeStorage::new(first.clone(), second.clone());
+
+    let file = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(b"hello"))),
+        )
+        .await
+        .expect("store should succeed");
+    storage.abort(file).await.expect("abort should succeed");
+    assert!(first.is_empty().await);
+    assert!(second.is_empty().await);
+}
+
+#[tokio::test]
+async fn partial_failures_hand_the_survivor_to_the_cleanup_hook() {
+    let memory = MemoryStorage::new();
+    let cleaned = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&cleaned);
+    let storage = TeeStorage::new(memory.clone(), FailingStorage).on_partial_failure(
+        move |half: TeeHalf<StoredFile, StoredFile>| {
+            let recorded = Arc::clone(&recorded);
+            async move {
+                if let TeeHalf::First(file) = half {
+                    recorded.lock().unwrap().push(file);
+                }
+                Err(StorageError::new("delete refused"))
+            }
+        },
+    );
+
+    let err = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(b"hello world"))),
+        )
+        .await
+        .expect_err("a failed half should fail the store");
+    assert_eq!(
+        err.to_string(),
+        "second storage failed (cleanup failed: delete refused): bucket unavailable"
+    );
+    let source = std::error::Error::source(&err).expect("the backend error is kept");
+    assert_eq!(source.to_string(), "bucket unavailable");
+
+    let cleaned = cleaned.lock().unwrap().clone();
+    assert_eq!(cleaned.len(), 1);
+    assert_eq!(cleaned[0].size, 11);
+    assert_eq!(
+        memory.get(&cleaned[0].storage_key).await.as_deref(),
+        Some(&b"hello world"[..])
+    );
+}
+
+#[tokio::test]
+async fn survivors_are_kept_without_a_hook() {
+    let memory = MemoryStorage::new();
+    let storage = TeeStorage::new(FailingStorage, memory.clone());
+
+    let err = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(b"hello world"))),
+        )
+        .await
+        .expect_err("a failed half should fail the store");
+    assert_eq!(err.to_string(), "first storage failed: bucket unavailable");
+    assert_eq!(memory.len().await, 1);
+}
+
+#[tokio::test]
+async fn stream_errors_fail_both_backends() {
+    let (first, second) = (MemoryStorage::new(), MemoryStorage::new());
+    let storage = TeeStorage::new(first.clone(), second.clone());
+
+    let mut body = chunks(b"partial");
+    body.push(Err(MulterError::IncompleteStream));
+    let err = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(body)),
+        )
+        .await
+        .expect_err("stream error should fail the store");
+    assert!(err.to_string().starts_with("both storages failed"));
+    assert!(first.is_empty().await);
+    assert!(second.is_empty().await);
+}
@@ -0,0 +1,94 @@
+#![allow(missing_docs)]
+#![cfg(feature = "tempfile")]
+
+use std::path::{Path, PathBuf};
+
+use bytes::Bytes;
+use futures::stream;
+use multigear::{FileMeta, MulterError, StorageEngine, StoreContext, TempfileStorage};
+use uuid::Uuid;
+
+fn temp_root() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("multigear-test-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&root).expect("create temp root");
+    root
+}
+
+fn body(parts: &[&'static [u8]]) -> Vec<Result<Bytes, MulterError>> {
+    parts
+        .iter()
+        .map(|part| Ok(Bytes::from_static(part)))
+        .collect()
+}
+
+fn entries(root: &Path) -> usize {
+    std::fs::read_dir(root).expect("read dir").count()
+}
+
+#[tokio::test]
+async fn dropped_files_are_deleted() {
+    let root = temp_root();
+    let storage = TempfileStorage::in_dir(&root);
+
+    let file = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(body(&[b"hel", b"lo"]))),
+        )
+        .await
+        .expect("store should succeed");
+    assert_eq!(file.stored().size, 5);
+    assert_eq!(file.stored().path.as_deref(), Some(file.path()));
+    assert!(file.path().starts_with(&root));
+    assert_eq!(std::fs::read(file.path()).expect("read"), b"hello");
+
+    drop(file);
+    assert_eq!(entries(&root), 0);
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[tokio::test]
+async fn persisted_files_outlive_the_guard() {
+    let root = temp_root();
+    let storage = TempfileStorage::in_dir(root.join("scratch"));
+    std::fs::create_dir_all(root.join("scratch")).expect("create scratch dir");
+
+    let file = storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(body(&[b"hello"]))),
+        )
+        .await
+        .expect("store should succeed");
+    let target = root.join("kept.txt");
+    let stored = file.persist(&target).await.expect("persist should succeed");
+
+    assert_eq!(stored.path.as_deref(), Some(target.as_path()));
+    assert_eq!(stored.storage_key, target.to_string_lossy());
+    assert_eq!(stored.file_name.as_deref(), Some("a.txt"));
+    assert_eq!(std::fs::read(&target).expect("read"), b"hello");
+    assert_eq!(entries(&root.join("scratch")), 0);
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[tokio::test]
+async fn failed_streams_leave_no_file() {
+    let root = temp_root();
+    let storage = TempfileStorage::in_dir(&root);
+
+    let mut chunks = body(&[b"partial"]);
+    chunks.push(Err(MulterError::IncompleteStream));
+    storage
+        .store(
+            FileMeta::new("upload", Some("a.txt"), "text/plain"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks)),
+        )
+        .await
+        .expect_err("stream error should fail the store");
+
+    assert_eq!(entries(&root), 0);
+    let _ = std::fs::remove_dir_all(root);
+}
@@ -0,0 +1,106 @@
+#![allow(missing_docs)]
+
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use futures::stream;
+use multigear::{
+    FileMeta, MemoryStorage, MulterError, StorageEngine, StoreContext, ThrottledStorage,
+};
+
+fn chunks(count: usize, size: usize) -> Vec<Result<Bytes, MulterError>> {
+    (0..count).map(|_| Ok(Bytes::from(vec![7; size]))).collect()
+}
+
+#[tokio::test]
+async fn per_part_rate_paces_each_file() {
+    let memory = MemoryStorage::new();
+    let storage = ThrottledStorage::builder(memory.clone())
+        .per_part(1000)
+        .burst(100)
+        .build()
+        .expect("storage should build");
+
+    let started = Instant::now();
+    let stored = storage
+        .store(
+            FileMeta::new("upload", Some("a.bin"), "application/octet-stream"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(4, 100))),
+        )
+        .await
+        .expect("store should succeed");
+    // The burst covers the first chunk; the other 300 bytes take 300ms.
+    assert!(started.elapsed() >= Duration::from_millis(280));
+    assert_eq!(
+        memory
+            .get(&stored.storage_key)
+            .await
+            .map(|bytes| bytes.len()),
+        Some(400)
+    );
+}
+
+#[tokio::test]
+async fn global_rate_is_shared_by_concurrent_files() {
+    let storage = ThrottledStorage::builder(MemoryStorage::new())
+        .global(2000)
+        .burst(200)
+        .build()
+        .expect("storage should build");
+
+    let ctx = StoreContext::new();
+    let started = Instant::now();
+    let (first, second) = tokio::join!(
+        storage.store(
+            FileMeta::new("upload", Some("a.bin"), "application/octet-stream"),
+            &ctx,
+            Box::pin(stream::iter(chunks(4, 100)))
+        ),
+        storage.store(
+            FileMeta::new("upload", Some("a.bin"), "application/octet-stream"),
+            &ctx,
+            Box::pin(stream::iter(chunks(4, 100)))
+        ),
+    );
+    first.expect("first store should succeed");
+    second.expect("second store should succeed");
+    // 800 bytes at 2000 B/s, minus the 200-byte burst.
+    assert!(started.elapsed() >= Duration::from_millis(280));
+}
+
+#[tokio::test]
+async fn unthrottled_stores_pass_through() {
+    let storage = ThrottledStorage::builder(MemoryStorage::new())
+        .build()
+        .expect("storage should build");
+
+    let started = Instant::now();
+    let stored = storage
+        .store(
+            FileMeta::new("upload", Some("a.bin"), "application/octet-stream"),
+            &StoreContext::new(),
+            Box::pin(stream::iter(chunks(100, 1000))),
+        )
+        .await
+        .expect("store should succeed");
+    assert_eq!(stored.size, 100_000);
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn builder_rejects_zero_rates() {
+    assert!(ThrottledStorage::builder(MemoryStorage::new())
+        .per_part(0)
+        .build()
+        .is_err());
+    assert!(ThrottledStorage::builder(MemoryStorage::new())
+        .global(0)
+        .build()
+        .is_err());
+    assert!(ThrottledStorage::builder(MemoryStorage::new())
+        .global(10)
+        .burst(0)
+        .build()
+        .is_err());
+}
@@ -0,0 +1,256 @@
+#![allow(missing_docs)]
+
+use bytes::Bytes;
+use futures::stream;
+use multigear::{
+    parser::headers::{parse_content_transfer_encoding, ContentTransferEncoding},
+    Limits, MulterConfig, MulterError, Multipart, ParseError, TransferEncodingPolicy,
+};
+
+#[test]
+fn parses_content_transfer_encoding_tokens() {
+    assert_eq!(
+        parse_content_transfer_encoding(None),
+        ContentTransferEncoding::Identity
+    );
+    assert_eq!(
+        parse_content_transfer_encoding(Some("8bit")),
+        ContentTransferEncoding::Identity
+    );
+    assert_eq!(
+        parse_content_transfer_encoding(Some(" Base64 ")),
+        ContentTransferEncoding::Base64
+    );
+    assert_eq!(
+        parse_content_transfer_encoding(Some("Quoted-Printable")),
+        ContentTransferEncoding::QuotedPrintable
+    );
+    assert_eq!(
+        parse_content_transfer_encoding(Some("x-uuencode")),
+        ContentTransferEncoding::Other("x-uuencode".to_owned())
+    );
+}
+
+#[tokio::test]
+async fn decodes_base64_part_bodies_across_chunks() {
+    // "hello, multipart world!" wrapped across two lines.
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n",
+        "Content-Transfer-Encoding: base64\r\n",
+        "\r\n",
+        "aGVsbG8sIG11bHRp\r\n",
+        "cGFydCB3b3JsZCE=\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"plain\"\r\n",
+        "\r\n",
+        "aGk=\r\n",
+        "--BOUND--\r\n"
+    );
+
+    for chunk_size in [body.len(), 7, 1] {
+        let chunks = body
+            .as_bytes()
+            .chunks(chunk_size)
+            .map(|chunk| Ok::<Bytes, MulterError>(Bytes::copy_from_slice(chunk)))
+            .collect::<Vec<_>>();
+        let mut multipart =
+            Multipart::new("BOUND", stream::iter(chunks)).expect("multipart should initialize");
+
+        let mut first = multipart
+            .next_part()
+            .await
+            .expect("headers should parse")
+            .expect("first part expected");
+        assert_eq!(
+            first.parsed_headers().transfer_encoding,
+            ContentTransferEncoding::Base64
+        );
+        assert_eq!(
+            first.bytes().await.expect("body should decode"),
+            Bytes::from_static(b"hello, multipart world!")
+        );
+
+        let mut second = multipart
+            .next_part()
+            .await
+            .expect("headers should parse")
+            .expect("second part expected");
+        assert_eq!(second.text().await.expect("text"), "aGk=");
+    }
+}
+
+#[tokio::test]
+async fn rejects_invalid_base64_part_bodies() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"doc\"; filename=\"a.bin\"\r\n",
+        "Content-Transfer-Encoding: base64\r\n",
+        "\r\n",
+        "not*base64\r\n",
+        "--BOUND--\r\n"
+    );
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("multipart should initialize");
+
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("part expected");
+    let err = part.bytes().await.expect_err("invalid base64 should fail");
+    assert!(matches!(
+        err,
+        MulterError::Parse(ParseError::Message { .. })
+    ));
+}
+
+#[tokio::test]
+async fn size_limits_apply_to_decoded_base64_bytes() {
+    // 8 encoded bytes decode to 6 bytes ("abcdef").
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"doc\"; filename=\"a.bin\"\r\n",
+        "Content-Transfer-Encoding: base64\r\n",
+        "\r\n",
+        "YWJjZGVm\r\n",
+        "--BOUND--\r\n"
+    );
+
+    for (max_file_size, ok) in [(6, true), (5, false)] {
+        let config = MulterConfig::new().limits(Limits::new().max_file_size(max_file_size));
+        let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+            body.as_bytes(),
+        ))]);
+        let mut multipart =
+            Multipart::with_config("BOUND", input, config).expect("multipart should initialize");
+        let mut part = multipart
+            .next_part()
+            .await
+            .expect("headers should parse")
+            .expect("part expected");
+
+        let result = part.bytes().await;
+        if ok {
+            assert_eq!(
+                result.expect("decoded body fits"),
+                Bytes::from_static(b"abcdef")
+            );
+        } else {
+            assert!(matches!(
+                result,
+                Err(MulterError::FileSizeLimitExceeded {
+                    max_file_size: 5,
+                    ..
+                })
+            ));
+        }
+    }
+}
+
+#[tokio::test]
+async fn decodes_quoted_printable_part_bodies_across_chunks() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"note\"\r\n",
+        "Content-Type: text/plain; charset=utf-8\r\n",
+        "Content-Transfer-Encoding: quoted-printable\r\n",
+        "\r\n",
+        "caf=C3=A9 au lait =\r\n",
+        "is =E2=82=AC3  \r\n",
+        "a=3Db=\r\n",
+        "--BOUND--\r\n"
+    );
+
+    for chunk_size in [body.len(), 5, 1] {
+        let chunks = body
+            .as_bytes()
+            .chunks(chunk_size)
+            .map(|chunk| Ok::<Bytes, MulterError>(Bytes::copy_from_slice(chunk)))
+            .collect::<Vec<_>>();
+        let mut multipart =
+            Multipart::new("BOUND", stream::iter(chunks)).expect("multipart should initialize");
+
+        let mut part = multipart
+            .next_part()
+            .await
+            .expect("headers should parse")
+            .expect("part expected");
+        assert_eq!(
+            part.parsed_headers().transfer_encoding,
+            ContentTransferEncoding::QuotedPrintable
+        );
+        assert_eq!(
+            part.text().await.expect("body should decode"),
+            "caf\u{e9} au lait is \u{20ac}3\r\na=b"
+        );
+    }
+}
+
+#[tokio::test]
+async fn rejects_invalid_quoted_printable_escapes() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"note\"\r\n",
+        "Content-Transfer-Encoding: quoted-printable\r\n",
+        "\r\n",
+        "bad =ZZ escape\r\n",
+        "--BOUND--\r\n"
+    );
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("multipart should initialize");
+
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("part expected");
+    let err = part.bytes().await.expect_err("invalid escape should fail");
+    assert!(matches!(
+        err,
+        MulterError::Parse(ParseError::Message { .. })
+    ));
+}
+
+#[tokio::test]
+async fn unknown_transfer_encodings_pass_through_or_reject_by_policy() {
+    let body = concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"doc\"; filename=\"a.uue\"\r\n",
+        "Content-Transfer-Encoding: x-uuencode\r\n",
+        "\r\n",
+        "begin 644 a\r\n",
+        "--BOUND--\r\n"
+    );
+
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart = Multipart::new("BOUND", input).expect("multipart should initialize");
+    let mut part = multipart
+        .next_part()
+        .await
+        .expect("headers should parse")
+        .expect("part expected");
+    assert_eq!(part.text().await.expect("raw body"), "begin 644 a");
+
+    let config = MulterConfig::new().transfer_encoding_policy(TransferEncodingPolicy::Reject);
+    let input = stream::iter([Ok::<Bytes, MulterError>(Bytes::from_static(
+        body.as_bytes(),
+    ))]);
+    let mut multipart =
+        Multipart::with_config("BOUND", input, config).expect("multipart should initialize");
+    let err = multipart
+        .next_part()
+        .await
+        .expect_err("unknown encoding should be rejected");
+    assert!(matches!(
+        err,
+        MulterError::UnsupportedTransferEncoding { field, encoding }
+            if field == "doc" && encoding == "x-uuencode"
+    ));
+}
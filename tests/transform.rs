@@ -0,0 +1,131 @@
+#![allow(missing_docs)]
+
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use multigear::{
+    BoxStream, FileMeta, MemoryStorage, Multer, MulterError, ParseError, PartTransformer,
+};
+
+/// Uppercases `text/plain` bodies and relabels them.
+struct Shout;
+
+impl PartTransformer for Shout {
+    fn transform<'a>(
+        &self,
+        meta: &mut FileMeta,
+        body: BoxStream<'a, Result<Bytes, MulterError>>,
+    ) -> BoxStream<'a, Result<Bytes, MulterError>> {
+        if meta.content_type != "text/plain" {
+            return body;
+        }
+        meta.content_type = "text/x-shouting".to_owned();
+        Box::pin(body.map(|chunk| chunk.map(|chunk| Bytes::from(chunk.to_ascii_uppercase()))))
+    }
+}
+
+/// Appends a trailer after the body, seeing whatever earlier transformers produced.
+struct Signature;
+
+impl PartTransformer for Signature {
+    fn transform<'a>(
+        &self,
+        _meta: &mut FileMeta,
+        body: BoxStream<'a, Result<Bytes, MulterError>>,
+    ) -> BoxStream<'a, Result<Bytes, MulterError>> {
+        Box::pin(body.chain(stream::once(async {
+            Ok(Bytes::from_static(b" -- signed"))
+        })))
+    }
+}
+
+/// Fails any body containing a NUL byte.
+struct RejectBinary;
+
+impl PartTransformer for RejectBinary {
+    fn transform<'a>(
+        &self,
+        _meta: &mut FileMeta,
+        body: BoxStream<'a, Result<Bytes, MulterError>>,
+    ) -> BoxStream<'a, Result<Bytes, MulterError>> {
+        Box::pin(body.map(|chunk| match chunk {
+            Ok(chunk) if chunk.contains(&0) => {
+                Err(ParseError::new("binary content is not accepted").into())
+            }
+            other => other,
+        }))
+    }
+}
+
+fn body(content_type: &str, payload: &str) -> Vec<Result<Bytes, MulterError>> {
+    let body = format!(
+        "--BOUND\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\nContent-Type: {content_type}\r\n\r\n{payload}\r\n--BOUND--\r\n"
+    );
+    body.into_bytes()
+        .chunks(6)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect()
+}
+
+fn multer(storage: MemoryStorage) -> Multer<MemoryStorage> {
+    Multer::builder()
+        .storage(storage)
+        .transformer(RejectBinary)
+        .transformer(Shout)
+        .transformer(Signature)
+        .build()
+        .expect("config should validate")
+}
+
+#[tokio::test]
+async fn transformers_rewrite_bodies_and_metadata_in_order() {
+    let storage = MemoryStorage::new();
+
+    let output = multer(storage.clone())
+        .parse_and_store(
+            "BOUND",
+            stream::iter(body("text/plain", "quiet words spread over chunks")),
+        )
+        .await
+        .expect("transformed file should be stored");
+
+    let stored = &output.stored_files[0];
+    assert_eq!(stored.content_type.essence_str(), "text/x-shouting");
+    assert_eq!(
+        storage.get(&stored.storage_key).await,
+        Some(Bytes::from_static(
+            b"QUIET WORDS SPREAD OVER CHUNKS -- signed"
+        ))
+    );
+    assert_eq!(stored.size, 40);
+}
+
+#[tokio::test]
+async fn transformers_pass_unhandled_parts_through() {
+    let storage = MemoryStorage::new();
+
+    let output = multer(storage.clone())
+        .parse_and_store("BOUND", stream::iter(body("text/csv", "a,b")))
+        .await
+        .expect("file should be stored");
+
+    let stored = &output.stored_files[0];
+    assert_eq!(stored.content_type.essence_str(), "text/csv");
+    assert_eq!(
+        storage.get(&stored.storage_key).await,
+        Some(Bytes::from_static(b"a,b -- signed"))
+    );
+}
+
+#[tokio::test]
+async fn transformer_errors_fail_the_store() {
+    let storage = MemoryStorage::new();
+
+    let err = multer(storage.clone())
+        .parse_and_store("BOUND", stream::iter(body("text/plain", "nul\0byte")))
+        .await
+        .expect_err("transformer error should fail the upload");
+
+    assert!(matches!(err, MulterError::Parse(_)));
+    assert!(err.to_string().contains("binary content is not accepted"));
+    assert!(storage.is_empty().await);
+}
@@ -0,0 +1,277 @@
+#![allow(missing_docs)]
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use bytes::Bytes;
+use futures::stream;
+use multigear::{
+    validation::ArchiveValidator, FileMeta, MemoryStorage, Multer, MulterError, PartValidator,
+    ValidationError,
+};
+
+struct MagicBytes {
+    content_type: &'static str,
+    magic: &'static [u8],
+}
+
+#[async_trait::async_trait]
+impl PartValidator for MagicBytes {
+    async fn validate(&self, meta: &FileMeta, head: &[u8]) -> Result<(), ValidationError> {
+        if meta.content_type == self.content_type && !head.starts_with(self.magic) {
+            return Err(ValidationError::new(format!(
+                "content does not look like {}",
+                self.content_type
+            )));
+        }
+        Ok(())
+    }
+
+    fn head_len(&self) -> usize {
+        self.magic.len()
+    }
+}
+
+fn body(content_type: &str, payload: &str) -> Vec<u8> {
+    format!(
+        concat!(
+            "--BOUND\r\n",
+            "Content-Disposition: form-data; name=\"doc\"; filename=\"a.pdf\"\r\n",
+            "Content-Type: {}\r\n",
+            "\r\n",
+            "{}\r\n",
+            "--BOUND--\r\n"
+        ),
+        content_type, payload
+    )
+    .into_bytes()
+}
+
+fn pdf_multer(storage: MemoryStorage, calls: Arc<AtomicUsize>) -> Multer<MemoryStorage> {
+    Multer::builder()
+        .storage(storage)
+        .validator(MagicBytes {
+            content_type: "application/pdf",
+            magic: b"%PDF-",
+        })
+        .validator(move |_: &FileMeta, head: &[u8]| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            assert!(head.len() <= multigear::validation::DEFAULT_HEAD_LEN);
+            Ok(())
+        })
+        .build()
+        .expect("config should validate")
+}
+
+#[tokio::test]
+async fn validators_run_before_storage_and_keep_the_full_body() {
+    let storage = MemoryStorage::new();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let multer = pdf_multer(storage.clone(), calls.clone());
+
+    let output = multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from(body(
+                "application/pdf",
+                "%PDF-1.7 body",
+            )))]),
+        )
+        .await
+        .expect("valid PDF should be stored");
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    let stored = storage
+        .get(&output.stored_files[0].storage_key)
+        .await
+        .expect("stored payload should exist");
+    assert_eq!(stored, Bytes::from_static(b"%PDF-1.7 body"));
+}
+
+#[tokio::test]
+async fn first_failing_validator_rejects_the_part() {
+    let storage = MemoryStorage::new();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let multer = pdf_multer(storage.clone(), calls.clone());
+
+    let err = multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from(body(
+                "application/pdf",
+                "MZ not a pdf",
+            )))]),
+        )
+        .await
+        .expect_err("mismatched content should be rejected");
+
+    assert!(matches!(
+        err,
+        MulterError::Validation { field, source }
+            if field == "doc"
+                && source == ValidationError::new("content does not look like application/pdf")
+    ));
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+    assert!(storage.is_empty().await);
+}
+
+fn upload(file_name: &str, payload: &[u8]) -> Vec<u8> {
+    let mut body = format!(
+        "--BOUND\r\nContent-Disposition: form-data; name=\"archive\"; filename=\"{file_name}\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(payload);
+    body.extend_from_slice(b"\r\n--BOUND--\r\n");
+    body
+}
+
+/// Builds a stored zip whose central directory declares the given sizes.
+fn zip(entries: &[(&str, u32)]) -> Vec<u8> {
+    let mut local = Vec::new();
+    let mut central = Vec::new();
+    for (name, declared_size) in entries {
+        let offset = local.len() as u32;
+        local.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        local.extend_from_slice(&[0; 14]);
+        local.extend_from_slice(&0u32.to_le_bytes());
+        local.extend_from_slice(&declared_size.to_le_bytes());
+        local.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        local.extend_from_slice(&0u16.to_le_bytes());
+        local.extend_from_slice(name.as_bytes());
+
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&[0; 16]);
+        central.extend_from_slice(&0u32.to_le_bytes());
+        central.extend_from_slice(&declared_size.to_le_bytes());
+        central.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        central.extend_from_slice(&[0; 12]);
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+    }
+
+    let mut archive = local;
+    let directory_offset = archive.len() as u32;
+    archive.extend_from_slice(&central);
+    archive.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    archive.extend_from_slice(&[0; 4]);
+    archive.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    archive.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    archive.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    archive.extend_from_slice(&directory_offset.to_le_bytes());
+    archive.extend_from_slice(&0u16.to_le_bytes());
+    archive
+}
+
+fn gzip(payload: &[u8]) -> Vec<u8> {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+    encoder.write_all(payload).expect("gzip write");
+    encoder.finish().expect("gzip finish")
+}
+
+async fn store_archive(
+    validator: ArchiveValidator,
+    file_name: &str,
+    payload: &[u8],
+) -> Result<(), MulterError> {
+    let multer = Multer::builder()
+        .storage(MemoryStorage::new())
+        .validator(validator)
+        .build()
+        .expect("config should validate");
+    multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter([Ok::<Bytes, MulterError>(Bytes::from(upload(
+                file_name, payload,
+            )))]),
+        )
+        .await
+        .map(drop)
+}
+
+#[tokio::test]
+async fn archive_validator_checks_declared_zip_sizes_and_entries() {
+    store_archive(
+        ArchiveValidator::new(),
+        "small.zip",
+        &zip(&[("a.txt", 1_000)]),
+    )
+    .await
+    .expect("modest expansion should pass");
+
+    let err = store_archive(
+        ArchiveValidator::new(),
+        "bomb.zip",
+        &zip(&[("a.txt", u32::MAX - 1)]),
+    )
+    .await
+    .expect_err("huge declared expansion should be rejected");
+    assert!(matches!(err, MulterError::Validation { field, .. } if field == "archive"));
+
+    let err = store_archive(
+        ArchiveValidator::new().max_entries(2),
+        "many.zip",
+        &zip(&[("a", 1), ("b", 1), ("c", 1)]),
+    )
+    .await
+    .expect_err("too many entries should be rejected");
+    assert!(matches!(
+        err,
+        MulterError::Validation { source, .. }
+            if source == ValidationError::new("archive has 3 entries, more than 2")
+    ));
+}
+
+#[tokio::test]
+async fn archive_validator_checks_gzip_trailer_size() {
+    let zeros = vec![0u8; 1024 * 1024];
+    let compressed = gzip(&zeros);
+
+    store_archive(
+        ArchiveValidator::new().max_expansion_ratio(u64::MAX),
+        "zeros.gz",
+        &compressed,
+    )
+    .await
+    .expect("an unbounded ratio should pass");
+
+    let err = store_archive(ArchiveValidator::new(), "zeros.gz", &compressed)
+        .await
+        .expect_err("highly compressible gzip should exceed the ratio");
+    assert!(matches!(err, MulterError::Validation { .. }));
+
+    let err = store_archive(
+        ArchiveValidator::new()
+            .max_expansion_ratio(u64::MAX)
+            .max_uncompressed_size(1024),
+        "zeros.gz",
+        &compressed,
+    )
+    .await
+    .expect_err("total size limit should apply");
+    assert!(matches!(err, MulterError::Validation { .. }));
+}
+
+#[tokio::test]
+async fn archive_validator_ignores_other_files_and_caps_scanning() {
+    store_archive(
+        ArchiveValidator::new().scan_limit(4),
+        "notes.txt",
+        b"plain text body",
+    )
+    .await
+    .expect("non-archives should pass");
+
+    let err = store_archive(
+        ArchiveValidator::new().scan_limit(16),
+        "small.zip",
+        &zip(&[("a.txt", 1)]),
+    )
+    .await
+    .expect_err("archives past the scan limit should be rejected");
+    assert!(matches!(err, MulterError::Validation { .. }));
+}
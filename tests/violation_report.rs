@@ -0,0 +1,225 @@
+#![allow(missing_docs)]
+
+use bytes::Bytes;
+use futures::stream;
+use multigear::{
+    DuplicateFilePolicy, FileMeta, MemoryStorage, Multer, MulterError, SelectedField,
+    UnknownFieldPolicy, ValidationError,
+};
+
+fn chunks(body: &str) -> Vec<Result<Bytes, MulterError>> {
+    body.as_bytes()
+        .chunks(7)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect()
+}
+
+fn body() -> Vec<Result<Bytes, MulterError>> {
+    chunks(concat!(
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"docs\"; filename=\"big.txt\"\r\n",
+        "Content-Type: text/plain\r\n",
+        "\r\n",
+        "this body is far too large\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"docs\"; filename=\"run.exe\"\r\n",
+        "Content-Type: application/x-msdownload\r\n",
+        "\r\n",
+        "MZ\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"stray\"\r\n",
+        "\r\n",
+        "unexpected\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"note\"\r\n",
+        "\r\n",
+        "a note longer than allowed\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"docs\"; filename=\"ok.txt\"\r\n",
+        "Content-Type: text/plain\r\n",
+        "\r\n",
+        "fine\r\n",
+        "--BOUND\r\n",
+        "Content-Disposition: form-data; name=\"note\"\r\n",
+        "\r\n",
+        "short\r\n",
+        "--BOUND--\r\n",
+    ))
+}
+
+fn builder(storage: MemoryStorage) -> multigear::MulterBuilder<MemoryStorage> {
+    Multer::builder()
+        .storage(storage)
+        .fields([
+            SelectedField::new("docs").with_allowed_mime_types(["text/plain"]),
+            SelectedField::new("avatar").required(),
+            SelectedField::text("note"),
+        ])
+        .on_unknown_field(UnknownFieldPolicy::Reject)
+        .max_file_size(10)
+        .max_field_size(10)
+}
+
+#[tokio::test]
+async fn violations_are_recorded_and_valid_parts_stored() {
+    let storage = MemoryStorage::new();
+    let multer = builder(storage.clone())
+        .collect_violations(true)
+        .build()
+        .expect("config should validate");
+
+    let output = multer
+        .parse_and_store("BOUND", stream::iter(body()))
+        .await
+        .expect("violations should not fail the request");
+
+    assert_eq!(output.stored_files.len(), 1);
+    assert_eq!(output.stored_files[0].file_name.as_deref(), Some("ok.txt"));
+    assert_eq!(storage.len().await, 1);
+    assert_eq!(
+        output.text_fields,
+        vec![("note".to_owned(), "short".to_owned())]
+    );
+
+    let errors: Vec<_> = output
+        .report
+        .iter()
+        .map(|violation| (violation.field_name.as_deref(), &*violation.error))
+        .collect();
+    assert_eq!(errors.len(), 5, "{errors:?}");
+    assert!(matches!(
+        errors[0],
+        (
+            Some("docs"),
+            MulterError::FileSizeLimitExceeded {
+                max_file_size: 10,
+                ..
+            }
+        )
+    ));
+    assert_eq!(
+        output.report.violations()[0].file_name.as_deref(),
+        Some("big.txt")
+    );
+    assert!(matches!(
+        errors[1],
+        (Some("docs"), MulterError::MimeTypeNotAllowed { .. })
+    ));
+    assert!(matches!(
+        errors[2],
+        (Some("stray"), MulterError::UnexpectedField { .. })
+    ));
+    assert!(matches!(
+        errors[3],
+        (Some("note"), MulterError::FieldSizeLimitExceeded { .. })
+    ));
+    assert!(matches!(
+        errors[4],
+        (Some("avatar"), MulterError::MissingRequiredField { .. })
+    ));
+}
+
+#[tokio::test]
+async fn first_violation_still_fails_by_default() {
+    let storage = MemoryStorage::new();
+    let multer = builder(storage.clone())
+        .build()
+        .expect("config should validate");
+
+    multer
+        .parse_and_store("BOUND", stream::iter(body()))
+        .await
+        .expect_err("oversized file should fail the request");
+    assert!(storage.is_empty().await);
+}
+
+#[tokio::test]
+async fn declared_lengths_and_validators_are_reported() {
+    let storage = MemoryStorage::new();
+    let multer = Multer::builder()
+        .storage(storage.clone())
+        .max_file_size(10)
+        .duplicate_file_policy(DuplicateFilePolicy::Reject)
+        .validator(|meta: &FileMeta, head: &[u8]| {
+            if meta.content_type == "application/pdf" && !head.starts_with(b"%PDF-") {
+                return Err(ValidationError::new("not a pdf"));
+            }
+            Ok(())
+        })
+        .collect_violations(true)
+        .build()
+        .expect("config should validate");
+
+    let output = multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter(chunks(concat!(
+                "--BOUND\r\n",
+                "Content-Disposition: form-data; name=\"a\"; filename=\"a.bin\"\r\n",
+                "Content-Length: 20\r\n",
+                "\r\n",
+                "twenty bytes of body\r\n",
+                "--BOUND\r\n",
+                "Content-Disposition: form-data; name=\"b\"; filename=\"b.pdf\"\r\n",
+                "Content-Type: application/pdf\r\n",
+                "\r\n",
+                "MZ\r\n",
+                "--BOUND\r\n",
+                "Content-Disposition: form-data; name=\"c\"; filename=\"c.txt\"\r\n",
+                "\r\n",
+                "same\r\n",
+                "--BOUND\r\n",
+                "Content-Disposition: form-data; name=\"d\"; filename=\"d.txt\"\r\n",
+                "\r\n",
+                "same\r\n",
+                "--BOUND--\r\n",
+            ))),
+        )
+        .await
+        .expect("violations should not fail the request");
+
+    assert_eq!(output.stored_files.len(), 1);
+    assert_eq!(storage.len().await, 1);
+    let fields: Vec<_> = output
+        .report
+        .iter()
+        .map(|violation| violation.field_name.as_deref())
+        .collect();
+    assert_eq!(fields, [Some("a"), Some("b"), Some("d")]);
+    assert!(matches!(
+        &*output.report.violations()[2].error,
+        MulterError::DuplicateFile { original_field, .. } if original_field == "c"
+    ));
+}
+
+#[tokio::test]
+async fn request_wide_limits_still_abort() {
+    let multer = Multer::builder()
+        .storage(MemoryStorage::new())
+        .max_total_file_size(6)
+        .collect_violations(true)
+        .build()
+        .expect("config should validate");
+
+    let err = multer
+        .parse_and_store(
+            "BOUND",
+            stream::iter(chunks(concat!(
+                "--BOUND\r\n",
+                "Content-Disposition: form-data; name=\"a\"; filename=\"a.txt\"\r\n",
+                "\r\n",
+                "four\r\n",
+                "--BOUND\r\n",
+                "Content-Disposition: form-data; name=\"b\"; filename=\"b.txt\"\r\n",
+                "\r\n",
+                "four\r\n",
+                "--BOUND--\r\n",
+            ))),
+        )
+        .await
+        .expect_err("total file size should fail the request");
+    assert!(
+        matches!(err, MulterError::TotalFileSizeLimitExceeded { .. }),
+        "{err:?}"
+    );
+}